@@ -7,20 +7,63 @@ use std::convert::TryInto;
 use std::error::Error;
 
 pub const SECONDS_PER_MILLISECOND: f64 = 0.001;
+const EVOLVER_NAME_LABEL: &str = "evolverName";
+/// Configurations written before the evolver could be chosen were all evolved with this one.
+const DEFAULT_EVOLVER_NAME: &str = "SecondOrderEuler";
 const MEMORY_LAYOUT_LABEL: &str = "memoryLayout";
 const NUMBER_OF_STEPS_PER_FRAME_LABEL: &str = "numberOfStepsPerFrame";
+const RELATIVE_ERROR_TOLERANCE_LABEL: &str = "relativeErrorTolerance";
+const NUMBER_OF_THREADS_LABEL: &str = "numberOfThreads";
 const DEAD_ZONE_RADIUS_LABEL: &str = "deadZoneRadius";
 const INVERSE_SQUARED_COUPLING_LABEL: &str = "inverseSquaredCoupling";
 const INVERSE_FOURTH_COUPLING_LABEL: &str = "inverseFourthCoupling";
+const BARNES_HUT_OPENING_ANGLE_LABEL: &str = "barnesHutOpeningAngle";
+const SOFTENING_KERNEL_LABEL: &str = "softeningKernel";
+const FORCE_LAW_LABEL: &str = "forceLaw";
+const FORCE_LAW_NAME_LABEL: &str = "lawName";
+const LENGTH_SCALE_LABEL: &str = "lengthScale";
+const ENERGY_SCALE_LABEL: &str = "energyScale";
+const CUTOFF_SEPARATION_LABEL: &str = "cutoffSeparation";
+const SCREENING_LENGTH_LABEL: &str = "screeningLength";
+const INTERACTIONS_LABEL: &str = "interactions";
+const FIRST_SPECIES_LABEL: &str = "firstSpecies";
+const SECOND_SPECIES_LABEL: &str = "secondSpecies";
+pub const SPECIES_LABEL: &str = "species";
+const BOUNDARY_CONDITIONS_LABEL: &str = "boundaryConditions";
+const BOUNDARY_KIND_LABEL: &str = "boundaryKind";
+const EXTERNAL_FIELDS_LABEL: &str = "externalFields";
+const UNIFORM_ACCELERATION_LABEL: &str = "uniformAccelerationInPixelsPerSecondSquared";
+const FIXED_SOURCES_LABEL: &str = "fixedSources";
+const POSITION_IN_PIXELS_LABEL: &str = "positionInPixels";
+const INVERSE_SQUARED_CHARGE_LABEL: &str = "inverseSquaredChargeInDimensionlessUnits";
+const INVERSE_FOURTH_CHARGE_LABEL: &str = "inverseFourthChargeInDimensionlessUnits";
+const HARMONIC_TRAP_LABEL: &str = "harmonicTrap";
+const CENTER_IN_PIXELS_LABEL: &str = "centerInPixels";
+const HORIZONTAL_SPRING_CONSTANT_LABEL: &str = "horizontalSpringConstant";
+const VERTICAL_SPRING_CONSTANT_LABEL: &str = "verticalSpringConstant";
+const HORIZONTAL_COMPONENT_LABEL: &str = "x";
+const VERTICAL_COMPONENT_LABEL: &str = "y";
+pub const DRAG_LABEL: &str = "drag";
+const DRAG_RATE_LABEL: &str = "dragRatePerSecond";
+const TARGET_TEMPERATURE_LABEL: &str = "targetTemperature";
+const RANDOM_SEED_LABEL: &str = "randomSeed";
+pub const GENERATOR_STATE_LABEL: &str = "generatorState";
+const MERGING_RADIUS_LABEL: &str = "mergingRadius";
+const RESTITUTION_COEFFICIENT_LABEL: &str = "restitutionCoefficient";
 const MILLISECONDS_PER_FRAME_LABEL: &str = "millisecondsPerFrame";
 const NUMBER_OF_FRAMES_LABEL: &str = "numberOfFrames";
 const RIGHT_BORDER_COORDINATE_LABEL: &str = "rightBorderCoordinate";
 const UPPER_BORDER_COORDINATE_LABEL: &str = "upperBorderCoordinate";
 const LEFT_BORDER_COORDINATE_LABEL: &str = "leftBorderCoordinate";
 const LOWER_BORDER_COORDINATE_LABEL: &str = "lowerBorderCoordinate";
-const GENERATOR_CONFIGURATIONS_LABEL: &str = "generatorConfigurations";
-const GENERATOR_NAME_LABEL: &str = "generatorName";
-const GENERATOR_CONFIGURATION_LABEL: &str = "generatorConfiguration";
+const FRAMES_PER_CHECKPOINT_LABEL: &str = "framesPerCheckpoint";
+const CHECKPOINT_FILENAME_LABEL: &str = "checkpointFilename";
+const DIAGNOSTICS_FILENAME_LABEL: &str = "diagnosticsFilename";
+const MAXIMUM_RELATIVE_ENERGY_DRIFT_LABEL: &str = "maximumRelativeEnergyDrift";
+const MAXIMUM_SPEED_LABEL: &str = "maximumSpeed";
+pub const GENERATOR_CONFIGURATIONS_LABEL: &str = "generatorConfigurations";
+pub const GENERATOR_NAME_LABEL: &str = "generatorName";
+pub const GENERATOR_CONFIGURATION_LABEL: &str = "generatorConfiguration";
 
 #[derive(Debug)]
 pub struct ConfigurationParseError {
@@ -60,6 +103,39 @@ pub fn parse_str<'a>(
     }
 }
 
+pub fn parse_optional_str<'a>(
+    attribute_label: &str,
+    given_configuration: &'a serde_json::Value,
+) -> Result<Option<&'a str>, Box<dyn std::error::Error>> {
+    match given_configuration.get(attribute_label) {
+        None => Ok(None),
+        Some(_) => Ok(Some(parse_str(attribute_label, given_configuration)?)),
+    }
+}
+
+pub fn parse_bool(
+    attribute_label: &str,
+    given_configuration: &serde_json::Value,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    match given_configuration[attribute_label].as_bool() {
+        Some(parsed_flag) => Ok(parsed_flag),
+        _ => Err(Box::new(ConfigurationParseError::new(&format!(
+            "Could not parse \"{}\" from {}",
+            attribute_label, given_configuration
+        )))),
+    }
+}
+
+pub fn parse_optional_bool(
+    attribute_label: &str,
+    given_configuration: &serde_json::Value,
+) -> Result<Option<bool>, Box<dyn std::error::Error>> {
+    match given_configuration.get(attribute_label) {
+        None => Ok(None),
+        Some(_) => Ok(Some(parse_bool(attribute_label, given_configuration)?)),
+    }
+}
+
 pub fn parse_f64(
     attribute_label: &str,
     given_configuration: &serde_json::Value,
@@ -73,6 +149,18 @@ pub fn parse_f64(
     }
 }
 
+/// This returns None if there is no attribute with the given label, but an error if there is such
+/// an attribute and it cannot be parsed as a number.
+pub fn parse_optional_f64(
+    attribute_label: &str,
+    given_configuration: &serde_json::Value,
+) -> Result<Option<f64>, Box<dyn std::error::Error>> {
+    match given_configuration.get(attribute_label) {
+        None => Ok(None),
+        Some(_) => Ok(Some(parse_f64(attribute_label, given_configuration)?)),
+    }
+}
+
 pub fn parse_i64(
     attribute_label: &str,
     given_configuration: &serde_json::Value,
@@ -93,6 +181,21 @@ pub fn parse_i64_as_usize(
     Ok(parse_i64(attribute_label, given_configuration)?.try_into()?)
 }
 
+/// This returns None if there is no attribute with the given label, but an error if there is such
+/// an attribute and it cannot be parsed as a non-negative integer.
+pub fn parse_optional_i64_as_usize(
+    attribute_label: &str,
+    given_configuration: &serde_json::Value,
+) -> Result<Option<usize>, Box<dyn std::error::Error>> {
+    match given_configuration.get(attribute_label) {
+        None => Ok(None),
+        Some(_) => Ok(Some(parse_i64_as_usize(
+            attribute_label,
+            given_configuration,
+        )?)),
+    }
+}
+
 pub fn parse_i64_as_u32(
     attribute_label: &str,
     given_configuration: &serde_json::Value,
@@ -114,19 +217,608 @@ pub fn parse_i64_as_i32(
     Ok(parse_i64(attribute_label, given_configuration)?.try_into()?)
 }
 
-#[derive(Debug)]
+/// This is how the forces are kept finite as particles get close to each other, over a length of
+/// the dead zone radius. The dead zone switches the forces off within the radius, which gives a
+/// sudden kick as particles cross it, while the Plummer kernel and the spline kernel smoothly
+/// bring the forces down to zero as the separation goes to zero. The Plummer kernel changes the
+/// forces at every separation, while the spline kernel only changes them within the radius. If the
+/// configuration does not give a kernel, the dead zone is used.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SofteningKernel {
+    DeadZone,
+    Plummer,
+    Spline,
+}
+
+pub fn parse_softening_kernel(
+    kernel_name: &str,
+) -> Result<SofteningKernel, Box<dyn std::error::Error>> {
+    match kernel_name {
+        "DeadZone" => Ok(SofteningKernel::DeadZone),
+        "Plummer" => Ok(SofteningKernel::Plummer),
+        "Spline" => Ok(SofteningKernel::Spline),
+        _ => Err(Box::new(ConfigurationParseError::new(&format!(
+            "{} \"{}\" is unknown (expected \"DeadZone\", \"Plummer\", or \"Spline\")",
+            SOFTENING_KERNEL_LABEL, kernel_name
+        )))),
+    }
+}
+
+/// This selects the law of the force between each pair of particles. If the configuration does not
+/// give a law, the inverse-squared and inverse-fourth forces are used, with the couplings of the
+/// configuration and the charges of the particles.
+///
+/// The Lennard-Jones law has a potential energy of 4 * energy_scale * ((length_scale / r)^12 -
+/// (length_scale / r)^6) for every pair, regardless of their charges, and if a cutoff separation
+/// is given, there is no force beyond it and the potential is shifted to be zero there.
+///
+/// The Yukawa law is an inverse-squared force screened over the screening length, with a
+/// potential energy of the inverse-squared coupling times the inverse-squared charges times
+/// exp(-r / screening_length) / r. The inverse-fourth coupling is not used.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ForceLawConfiguration {
+    InverseSquaredAndFourth,
+    LennardJones {
+        length_scale: f64,
+        energy_scale: f64,
+        cutoff_separation: Option<f64>,
+    },
+    Yukawa {
+        screening_length: f64,
+    },
+}
+
+fn parse_positive_f64(
+    attribute_label: &str,
+    given_configuration: &serde_json::Value,
+) -> Result<f64, Box<dyn std::error::Error>> {
+    match parse_optional_positive_f64(attribute_label, given_configuration)? {
+        Some(parsed_number) => Ok(parsed_number),
+        None => Err(Box::new(ConfigurationParseError::new(&format!(
+            "Could not parse \"{}\" from {}",
+            attribute_label, given_configuration
+        )))),
+    }
+}
+
+pub fn parse_force_law_configuration(
+    force_law_configuration: &serde_json::Value,
+) -> Result<ForceLawConfiguration, Box<dyn std::error::Error>> {
+    match parse_str(FORCE_LAW_NAME_LABEL, force_law_configuration)? {
+        "InverseSquaredAndFourth" => Ok(ForceLawConfiguration::InverseSquaredAndFourth),
+        "LennardJones" => Ok(ForceLawConfiguration::LennardJones {
+            length_scale: parse_positive_f64(LENGTH_SCALE_LABEL, force_law_configuration)?,
+            energy_scale: parse_f64(ENERGY_SCALE_LABEL, force_law_configuration)?,
+            cutoff_separation: parse_optional_positive_f64(
+                CUTOFF_SEPARATION_LABEL,
+                force_law_configuration,
+            )?,
+        }),
+        "Yukawa" => Ok(ForceLawConfiguration::Yukawa {
+            screening_length: parse_positive_f64(SCREENING_LENGTH_LABEL, force_law_configuration)?,
+        }),
+        law_name => Err(Box::new(ConfigurationParseError::new(&format!(
+            "{} \"{}\" is unknown (expected \"InverseSquaredAndFourth\", \"LennardJones\", or \
+            \"Yukawa\")",
+            FORCE_LAW_NAME_LABEL, law_name
+        )))),
+    }
+}
+
+/// The interactions table gives the couplings for pairs of species, which are named by the table.
+/// Particles which are not given a species belong to an unnamed species with index 0, and every
+/// pair which the table does not list, including every pair with a particle of the unnamed
+/// species, uses the couplings of the configuration. Each coupling is still multiplied by the
+/// charges of both particles.
+#[derive(Clone, Debug)]
+pub struct SpeciesInteractions {
+    species_names: std::vec::Vec<String>,
+    inverse_squared_couplings: std::vec::Vec<f64>,
+    inverse_fourth_couplings: std::vec::Vec<f64>,
+}
+
+impl SpeciesInteractions {
+    pub fn species_index(
+        &self,
+        species_name: &str,
+    ) -> Result<data_structure::charge::SpeciesIndex, Box<dyn std::error::Error>> {
+        match self
+            .species_names
+            .iter()
+            .position(|known_name| known_name == species_name)
+        {
+            Some(name_index) => Ok(data_structure::charge::SpeciesIndex(name_index + 1)),
+            None => Err(Box::new(ConfigurationParseError::new(&format!(
+                "{} \"{}\" is not in the {} table",
+                SPECIES_LABEL, species_name, INTERACTIONS_LABEL
+            )))),
+        }
+    }
+
+    /// The unnamed species has no name.
+    pub fn species_name(
+        &self,
+        species_index: data_structure::charge::SpeciesIndex,
+    ) -> Option<&str> {
+        match species_index.0 {
+            0 => None,
+            named_index => self
+                .species_names
+                .get(named_index - 1)
+                .map(|species_name| species_name.as_str()),
+        }
+    }
+
+    fn pair_index(
+        &self,
+        first_species: data_structure::charge::SpeciesIndex,
+        second_species: data_structure::charge::SpeciesIndex,
+    ) -> usize {
+        (first_species.0 * (self.species_names.len() + 1)) + second_species.0
+    }
+
+    pub fn inverse_squared_coupling(
+        &self,
+        first_species: data_structure::charge::SpeciesIndex,
+        second_species: data_structure::charge::SpeciesIndex,
+    ) -> f64 {
+        self.inverse_squared_couplings[self.pair_index(first_species, second_species)]
+    }
+
+    pub fn inverse_fourth_coupling(
+        &self,
+        first_species: data_structure::charge::SpeciesIndex,
+        second_species: data_structure::charge::SpeciesIndex,
+    ) -> f64 {
+        self.inverse_fourth_couplings[self.pair_index(first_species, second_species)]
+    }
+}
+
+/// The table is an array of objects which each name two species and give the couplings between
+/// them, where either coupling may be left out to use the coupling of the configuration. The
+/// species are numbered in the order in which they first appear in the table, and the table is
+/// symmetric, so each pair may only be given once.
+pub fn parse_species_interactions(
+    interactions_table: &serde_json::Value,
+    inverse_squared_coupling: f64,
+    inverse_fourth_coupling: f64,
+) -> Result<SpeciesInteractions, Box<dyn std::error::Error>> {
+    let table_entries = match interactions_table.as_array() {
+        Some(parsed_array) => parsed_array,
+        None => {
+            return Err(Box::new(ConfigurationParseError::new(&format!(
+                "Could not parse \"{}\" from {} as a JSON array.",
+                INTERACTIONS_LABEL, interactions_table
+            ))))
+        }
+    };
+    let mut species_names: std::vec::Vec<String> = vec![];
+    for table_entry in table_entries {
+        for species_label in &[FIRST_SPECIES_LABEL, SECOND_SPECIES_LABEL] {
+            let species_name = parse_str(species_label, table_entry)?;
+            if !species_names
+                .iter()
+                .any(|known_name| known_name == species_name)
+            {
+                species_names.push(species_name.to_string());
+            }
+        }
+    }
+
+    let number_of_pairs = (species_names.len() + 1) * (species_names.len() + 1);
+    let mut species_interactions = SpeciesInteractions {
+        species_names,
+        inverse_squared_couplings: vec![inverse_squared_coupling; number_of_pairs],
+        inverse_fourth_couplings: vec![inverse_fourth_coupling; number_of_pairs],
+    };
+    let mut is_pair_given = vec![false; number_of_pairs];
+    for table_entry in table_entries {
+        let first_species =
+            species_interactions.species_index(parse_str(FIRST_SPECIES_LABEL, table_entry)?)?;
+        let second_species =
+            species_interactions.species_index(parse_str(SECOND_SPECIES_LABEL, table_entry)?)?;
+        let pair_index = species_interactions.pair_index(first_species, second_species);
+        if is_pair_given[pair_index] {
+            return Err(Box::new(ConfigurationParseError::new(&format!(
+                "The couplings between {:?} and {:?} are given more than once in {}",
+                species_interactions.species_name(first_species),
+                species_interactions.species_name(second_species),
+                interactions_table
+            ))));
+        }
+        let mirrored_index = species_interactions.pair_index(second_species, first_species);
+        is_pair_given[pair_index] = true;
+        is_pair_given[mirrored_index] = true;
+        if let Some(pair_coupling) =
+            parse_optional_f64(INVERSE_SQUARED_COUPLING_LABEL, table_entry)?
+        {
+            species_interactions.inverse_squared_couplings[pair_index] = pair_coupling;
+            species_interactions.inverse_squared_couplings[mirrored_index] = pair_coupling;
+        }
+        if let Some(pair_coupling) = parse_optional_f64(INVERSE_FOURTH_COUPLING_LABEL, table_entry)?
+        {
+            species_interactions.inverse_fourth_couplings[pair_index] = pair_coupling;
+            species_interactions.inverse_fourth_couplings[mirrored_index] = pair_coupling;
+        }
+    }
+    Ok(species_interactions)
+}
+
+/// Reflecting walls bounce particles back into the box, reversing the component of their velocity
+/// normal to the wall, while periodic boundaries wrap particles round to the opposite side of the
+/// box, with each pair of particles interacting through the nearest images of each other.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BoundaryKind {
+    Reflecting,
+    Periodic,
+}
+
+/// The box is the picture unless the boundary conditions give their own borders, using the same
+/// labels as the picture.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundaryConditions {
+    pub boundary_kind: BoundaryKind,
+    pub right_border_coordinate: f64,
+    pub upper_border_coordinate: f64,
+    pub left_border_coordinate: f64,
+    pub lower_border_coordinate: f64,
+}
+
+pub fn parse_boundary_conditions(
+    boundary_configuration: &serde_json::Value,
+    picture_configuration: &PictureConfiguration,
+) -> Result<BoundaryConditions, Box<dyn std::error::Error>> {
+    let boundary_kind = match parse_str(BOUNDARY_KIND_LABEL, boundary_configuration)? {
+        "Reflecting" => BoundaryKind::Reflecting,
+        "Periodic" => BoundaryKind::Periodic,
+        kind_name => {
+            return Err(Box::new(ConfigurationParseError::new(&format!(
+                "{} \"{}\" is unknown (expected \"Reflecting\" or \"Periodic\")",
+                BOUNDARY_KIND_LABEL, kind_name
+            ))))
+        }
+    };
+    let parse_border = |border_label, picture_border: i32| {
+        parse_optional_f64(border_label, boundary_configuration)
+            .map(|given_border| given_border.unwrap_or(picture_border as f64))
+    };
+    let boundary_conditions = BoundaryConditions {
+        boundary_kind,
+        right_border_coordinate: parse_border(
+            RIGHT_BORDER_COORDINATE_LABEL,
+            picture_configuration.right_border_coordinate,
+        )?,
+        upper_border_coordinate: parse_border(
+            UPPER_BORDER_COORDINATE_LABEL,
+            picture_configuration.upper_border_coordinate,
+        )?,
+        left_border_coordinate: parse_border(
+            LEFT_BORDER_COORDINATE_LABEL,
+            picture_configuration.left_border_coordinate,
+        )?,
+        lower_border_coordinate: parse_border(
+            LOWER_BORDER_COORDINATE_LABEL,
+            picture_configuration.lower_border_coordinate,
+        )?,
+    };
+    if (boundary_conditions.right_border_coordinate <= boundary_conditions.left_border_coordinate)
+        || (boundary_conditions.upper_border_coordinate
+            <= boundary_conditions.lower_border_coordinate)
+    {
+        return Err(Box::new(ConfigurationParseError::new(&format!(
+            "The box of {:?} has no area",
+            boundary_conditions
+        ))));
+    }
+    Ok(boundary_conditions)
+}
+
+/// A fixed source exerts force on the particles through the force law just as a particle of the
+/// unnamed species with the given charges would, but never moves.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FixedSource {
+    pub horizontal_coordinate: f64,
+    pub vertical_coordinate: f64,
+    pub inverse_squared_charge: f64,
+    pub inverse_fourth_charge: f64,
+}
+
+/// The trap pulls each particle towards its center with a force of the spring constant times the
+/// displacement along each axis, independent of the mass of the particle. A negative spring
+/// constant pushes particles away along that axis instead, so that a trap with spring constants of
+/// opposite sign acts as a uniform tidal field.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HarmonicTrap {
+    pub horizontal_center: f64,
+    pub vertical_center: f64,
+    pub horizontal_spring_constant: f64,
+    pub vertical_spring_constant: f64,
+}
+
+/// The uniform acceleration is the same for every particle whatever its mass, as in constant
+/// gravity, and is zero unless given.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExternalFields {
+    pub horizontal_acceleration: f64,
+    pub vertical_acceleration: f64,
+    pub fixed_sources: std::vec::Vec<FixedSource>,
+    pub harmonic_trap: Option<HarmonicTrap>,
+}
+
+fn parse_vector(
+    vector_label: &str,
+    given_configuration: &serde_json::Value,
+) -> Result<(f64, f64), Box<dyn std::error::Error>> {
+    match given_configuration.get(vector_label) {
+        Some(vector_configuration) => Ok((
+            parse_f64(HORIZONTAL_COMPONENT_LABEL, vector_configuration)?,
+            parse_f64(VERTICAL_COMPONENT_LABEL, vector_configuration)?,
+        )),
+        None => Err(Box::new(ConfigurationParseError::new(&format!(
+            "Could not find \"{}\" in {}",
+            vector_label, given_configuration
+        )))),
+    }
+}
+
+pub fn parse_external_fields(
+    fields_configuration: &serde_json::Value,
+) -> Result<ExternalFields, Box<dyn std::error::Error>> {
+    let (horizontal_acceleration, vertical_acceleration) =
+        match fields_configuration.get(UNIFORM_ACCELERATION_LABEL) {
+            Some(_) => parse_vector(UNIFORM_ACCELERATION_LABEL, fields_configuration)?,
+            None => (0.0, 0.0),
+        };
+    let fixed_sources = match fields_configuration.get(FIXED_SOURCES_LABEL) {
+        Some(sources_configuration) => match sources_configuration.as_array() {
+            Some(source_configurations) => source_configurations
+                .iter()
+                .map(|source_configuration| {
+                    let (horizontal_coordinate, vertical_coordinate) =
+                        parse_vector(POSITION_IN_PIXELS_LABEL, source_configuration)?;
+                    Ok(FixedSource {
+                        horizontal_coordinate,
+                        vertical_coordinate,
+                        inverse_squared_charge: parse_f64(
+                            INVERSE_SQUARED_CHARGE_LABEL,
+                            source_configuration,
+                        )?,
+                        inverse_fourth_charge: parse_f64(
+                            INVERSE_FOURTH_CHARGE_LABEL,
+                            source_configuration,
+                        )?,
+                    })
+                })
+                .collect::<Result<std::vec::Vec<FixedSource>, Box<dyn std::error::Error>>>()?,
+            None => {
+                return Err(Box::new(ConfigurationParseError::new(&format!(
+                    "{} must be an array, got {}",
+                    FIXED_SOURCES_LABEL, sources_configuration
+                ))))
+            }
+        },
+        None => vec![],
+    };
+    let harmonic_trap = match fields_configuration.get(HARMONIC_TRAP_LABEL) {
+        Some(trap_configuration) => {
+            let (horizontal_center, vertical_center) =
+                parse_vector(CENTER_IN_PIXELS_LABEL, trap_configuration)?;
+            Some(HarmonicTrap {
+                horizontal_center,
+                vertical_center,
+                horizontal_spring_constant: parse_f64(
+                    HORIZONTAL_SPRING_CONSTANT_LABEL,
+                    trap_configuration,
+                )?,
+                vertical_spring_constant: parse_f64(
+                    VERTICAL_SPRING_CONSTANT_LABEL,
+                    trap_configuration,
+                )?,
+            })
+        }
+        None => None,
+    };
+    Ok(ExternalFields {
+        horizontal_acceleration,
+        vertical_acceleration,
+        fixed_sources,
+        harmonic_trap,
+    })
+}
+
+/// The random kicks of a Langevin thermostat balance the drag so that the kinetic energy per
+/// particle tends to the target temperature, in units where Boltzmann's constant is 1. The kicks
+/// are drawn from a generator started from the seed, so that an evolution can be reproduced. A
+/// checkpoint also gives the state of the generator at its frame, which a resumed evolution starts
+/// the generator from instead, so that it gets the same kicks as the uninterrupted evolution.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ThermostatConfiguration {
+    pub target_temperature: f64,
+    pub random_seed: u64,
+    pub generator_state: Option<[u64; 4]>,
+}
+
+/// The state is written as an array of 4 integers, each of which may be too large for an i64.
+fn parse_generator_state(
+    drag_configuration: &serde_json::Value,
+) -> Result<Option<[u64; 4]>, Box<dyn std::error::Error>> {
+    let given_state = match drag_configuration.get(GENERATOR_STATE_LABEL) {
+        None => return Ok(None),
+        Some(given_state) => given_state,
+    };
+    let parsed_words = given_state
+        .as_array()
+        .filter(|given_words| given_words.len() == 4)
+        .map(|given_words| {
+            given_words
+                .iter()
+                .filter_map(serde_json::Value::as_u64)
+                .collect::<std::vec::Vec<u64>>()
+        });
+    match parsed_words.as_deref() {
+        Some(&[first_word, second_word, third_word, fourth_word]) => {
+            Ok(Some([first_word, second_word, third_word, fourth_word]))
+        }
+        _ => Err(Box::new(ConfigurationParseError::new(&format!(
+            "Could not parse \"{}\" as 4 non-negative integers from {}",
+            GENERATOR_STATE_LABEL, drag_configuration
+        )))),
+    }
+}
+
+/// The drag slows every particle at the same rate per second whatever its mass, so that a particle
+/// with no other force on it loses a factor of e of its speed every 1 / drag rate seconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DragConfiguration {
+    pub drag_rate: f64,
+    pub thermostat: Option<ThermostatConfiguration>,
+}
+
+pub fn parse_drag_configuration(
+    drag_configuration: &serde_json::Value,
+) -> Result<DragConfiguration, Box<dyn std::error::Error>> {
+    let drag_rate = parse_positive_f64(DRAG_RATE_LABEL, drag_configuration)?;
+    let thermostat = match (
+        parse_optional_positive_f64(TARGET_TEMPERATURE_LABEL, drag_configuration)?,
+        drag_configuration.get(RANDOM_SEED_LABEL),
+    ) {
+        (None, None) => None,
+        (Some(target_temperature), Some(_)) => Some(ThermostatConfiguration {
+            target_temperature,
+            random_seed: parse_i64(RANDOM_SEED_LABEL, drag_configuration)?.try_into()?,
+            generator_state: parse_generator_state(drag_configuration)?,
+        }),
+        _ => {
+            return Err(Box::new(ConfigurationParseError::new(&format!(
+                "{} and {} must be given together",
+                TARGET_TEMPERATURE_LABEL, RANDOM_SEED_LABEL
+            ))))
+        }
+    };
+    Ok(DragConfiguration {
+        drag_rate,
+        thermostat,
+    })
+}
+
+/// A spring bond pulls its two particles towards each other when they are further apart than its
+/// rest length and pushes them apart when they are closer, with a force proportional to the
+/// difference, and if it is damped it also resists the particles moving towards or away from each
+/// other. The particles are given by their indices in the order in which they were generated, with
+/// the first index less than the second.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpringBond {
+    pub first_particle_index: usize,
+    pub second_particle_index: usize,
+    pub rest_length: f64,
+    pub stiffness: f64,
+    pub damping_coefficient: f64,
+}
+
+/// A keyframe gives where a particle with scripted motion should be at the given time, counted from
+/// the start of the uninterrupted evolution.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Keyframe {
+    pub time_in_seconds: f64,
+    pub horizontal_coordinate: f64,
+    pub vertical_coordinate: f64,
+}
+
+/// The path goes anticlockwise around the center for a positive angular frequency, starting from
+/// the given phase, with the horizontal and vertical semi-axes along the x and y axes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EllipticalPath {
+    pub horizontal_center: f64,
+    pub vertical_center: f64,
+    pub horizontal_semi_axis: f64,
+    pub vertical_semi_axis: f64,
+    pub angular_frequency: f64,
+    pub initial_phase: f64,
+}
+
+/// A free particle moves under the forces on it, while a fixed particle stays at its position and
+/// a scripted particle follows its keyframes or its elliptical path whatever the forces on it. A
+/// particle which is not free still exerts forces on the other particles.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MotionMode {
+    Free,
+    Fixed {
+        horizontal_coordinate: f64,
+        vertical_coordinate: f64,
+    },
+    /// The particle moves at constant velocity between consecutive keyframes, which are in order
+    /// of time, and stays at the first keyframe before it and at the last keyframe after it.
+    Keyframes(std::vec::Vec<Keyframe>),
+    Ellipse(EllipticalPath),
+}
+
+impl MotionMode {
+    /// This gives the same motion with its time counted from the given time of the original
+    /// motion, for resuming an evolution part of the way through.
+    pub fn after_elapsed_time(&self, elapsed_seconds: f64) -> Self {
+        match self {
+            MotionMode::Keyframes(keyframes) => MotionMode::Keyframes(
+                keyframes
+                    .iter()
+                    .map(|keyframe| Keyframe {
+                        time_in_seconds: keyframe.time_in_seconds - elapsed_seconds,
+                        ..*keyframe
+                    })
+                    .collect(),
+            ),
+            MotionMode::Ellipse(elliptical_path) => MotionMode::Ellipse(EllipticalPath {
+                initial_phase: elliptical_path.initial_phase
+                    + (elliptical_path.angular_frequency * elapsed_seconds),
+                ..*elliptical_path
+            }),
+            unscripted_mode => unscripted_mode.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct EvolutionConfiguration {
     pub dead_zone_radius: f64,
+    pub softening_kernel: SofteningKernel,
+    pub force_law: ForceLawConfiguration,
     pub inverse_squared_coupling: f64,
     pub inverse_fourth_coupling: f64,
+    /// If this is given, the couplings depend on the species of the particles.
+    pub species_interactions: Option<SpeciesInteractions>,
+    /// If this is given, the particles are kept within a box.
+    pub boundary_conditions: Option<BoundaryConditions>,
+    /// If this is given, the particles also feel forces from outside the collection of particles.
+    pub external_fields: Option<ExternalFields>,
+    /// If this is given, the particles are slowed by drag, and possibly kicked by a thermostat.
+    pub drag: Option<DragConfiguration>,
+    /// If this is given, particles which come closer to each other than this merge into a single
+    /// particle.
+    pub merging_radius: Option<f64>,
+    /// If this is given, particles with collision radii bounce off each other when they touch,
+    /// leaving each collision with this fraction of the speed at which they approached each other.
+    pub restitution_coefficient: Option<f64>,
+    /// The bonds are declared in the configurations of the generators of the initial particles, so
+    /// they are filled in once the particles have been generated rather than being parsed with the
+    /// rest of the evolution configuration.
+    pub spring_bonds: std::vec::Vec<SpringBond>,
+    /// The motion modes are also filled in from the generators, with either one for each particle
+    /// in the order in which the particles were generated, or none if every particle is free.
+    pub motion_modes: std::vec::Vec<MotionMode>,
+    /// If this is given, forces are calculated with a Barnes-Hut quadtree rather than by summing
+    /// over every pair of particles, with groups of particles treated as single particles when
+    /// their extent over their distance is less than this angle.
+    pub barnes_hut_opening_angle: Option<f64>,
     pub milliseconds_per_time_slice: u16,
     pub number_of_time_slices: usize,
 }
 
 #[derive(Debug)]
 pub struct EvolverConfiguration<'a> {
+    pub evolver_name: &'a str,
     pub memory_layout: &'a str,
     pub number_of_steps_per_time_slice: u32,
+    pub relative_error_tolerance: Option<f64>,
+    pub number_of_threads: Option<usize>,
 }
 
 #[derive(Debug)]
@@ -143,37 +835,193 @@ pub struct PictureConfiguration {
     pub lower_border_coordinate: i32,
 }
 
+/// If this is given, the state of the evolution is written to the file every given number of
+/// frames, so that the evolution can be resumed from there.
+#[derive(Debug)]
+pub struct CheckpointConfiguration<'a> {
+    pub frames_per_checkpoint: usize,
+    pub checkpoint_filename: &'a str,
+}
+
+/// Each limit which is given is checked for every frame as it is evolved, and the evolution is
+/// stopped at the first frame which breaks one of them. Positions and velocities which are not
+/// finite always stop the evolution, as nothing sensible can follow from them.
+#[derive(Clone, Debug)]
+pub struct WatchdogConfiguration {
+    pub maximum_relative_energy_drift: Option<f64>,
+    pub maximum_speed: Option<f64>,
+}
+
 #[derive(Debug)]
 pub struct ParsedConfiguration<'a> {
     pub evolver_configuration: EvolverConfiguration<'a>,
     pub evolution_configuration: EvolutionConfiguration,
     pub generator_configurations: std::vec::Vec<InitialParticleGeneratorConfiguration<'a>>,
     pub picture_configuration: PictureConfiguration,
+    pub checkpoint_configuration: Option<CheckpointConfiguration<'a>>,
+    /// If this is given, the energy, momentum, and angular momentum of every frame are written to
+    /// the file.
+    pub diagnostics_filename: Option<&'a str>,
+    pub watchdog_configuration: WatchdogConfiguration,
+}
+
+/// The picture is parsed separately from the rest of the configuration so that a saved trajectory
+/// can be drawn again with different settings.
+pub fn parse_picture_configuration(
+    deserialized_configuration: &serde_json::Value,
+) -> Result<PictureConfiguration, Box<dyn std::error::Error>> {
+    Ok(PictureConfiguration {
+        right_border_coordinate: parse_i64_as_i32(
+            RIGHT_BORDER_COORDINATE_LABEL,
+            deserialized_configuration,
+        )?,
+        upper_border_coordinate: parse_i64_as_i32(
+            UPPER_BORDER_COORDINATE_LABEL,
+            deserialized_configuration,
+        )?,
+        left_border_coordinate: parse_i64_as_i32(
+            LEFT_BORDER_COORDINATE_LABEL,
+            deserialized_configuration,
+        )?,
+        lower_border_coordinate: parse_i64_as_i32(
+            LOWER_BORDER_COORDINATE_LABEL,
+            deserialized_configuration,
+        )?,
+    })
+}
+
+fn parse_checkpoint_configuration<'a>(
+    deserialized_configuration: &'a serde_json::Value,
+) -> Result<Option<CheckpointConfiguration<'a>>, Box<dyn std::error::Error>> {
+    let frames_per_checkpoint =
+        parse_optional_i64_as_usize(FRAMES_PER_CHECKPOINT_LABEL, deserialized_configuration)?;
+    match (
+        frames_per_checkpoint,
+        deserialized_configuration.get(CHECKPOINT_FILENAME_LABEL),
+    ) {
+        (None, None) => Ok(None),
+        (Some(0), _) => Err(Box::new(ConfigurationParseError::new(&format!(
+            "{} must be > 0",
+            FRAMES_PER_CHECKPOINT_LABEL
+        )))),
+        (Some(frames_per_checkpoint), Some(_)) => Ok(Some(CheckpointConfiguration {
+            frames_per_checkpoint,
+            checkpoint_filename: parse_str(CHECKPOINT_FILENAME_LABEL, deserialized_configuration)?,
+        })),
+        _ => Err(Box::new(ConfigurationParseError::new(&format!(
+            "{} and {} must be given together",
+            FRAMES_PER_CHECKPOINT_LABEL, CHECKPOINT_FILENAME_LABEL
+        )))),
+    }
+}
+
+fn parse_optional_positive_f64(
+    attribute_label: &str,
+    given_configuration: &serde_json::Value,
+) -> Result<Option<f64>, Box<dyn std::error::Error>> {
+    match parse_optional_f64(attribute_label, given_configuration)? {
+        Some(given_number) if given_number <= 0.0 => Err(Box::new(ConfigurationParseError::new(
+            &format!("{} must be > 0, got {}", attribute_label, given_number),
+        ))),
+        parsed_number => Ok(parsed_number),
+    }
+}
+
+fn parse_watchdog_configuration(
+    deserialized_configuration: &serde_json::Value,
+) -> Result<WatchdogConfiguration, Box<dyn std::error::Error>> {
+    Ok(WatchdogConfiguration {
+        maximum_relative_energy_drift: parse_optional_positive_f64(
+            MAXIMUM_RELATIVE_ENERGY_DRIFT_LABEL,
+            deserialized_configuration,
+        )?,
+        maximum_speed: parse_optional_positive_f64(
+            MAXIMUM_SPEED_LABEL,
+            deserialized_configuration,
+        )?,
+    })
 }
 
 pub fn parse_deserialized_configuration<'a>(
     deserialized_configuration: &'a serde_json::Value,
 ) -> Result<ParsedConfiguration<'a>, Box<dyn std::error::Error>> {
+    let evolver_name = parse_optional_str(EVOLVER_NAME_LABEL, deserialized_configuration)?
+        .unwrap_or(DEFAULT_EVOLVER_NAME);
     let memory_layout = parse_str(MEMORY_LAYOUT_LABEL, &deserialized_configuration)?;
     let number_of_steps_per_time_slice =
         parse_i64_as_u32(NUMBER_OF_STEPS_PER_FRAME_LABEL, &deserialized_configuration)?;
+    let relative_error_tolerance =
+        parse_optional_f64(RELATIVE_ERROR_TOLERANCE_LABEL, deserialized_configuration)?;
+    let number_of_threads =
+        parse_optional_i64_as_usize(NUMBER_OF_THREADS_LABEL, deserialized_configuration)?;
     let dead_zone_radius = parse_f64(DEAD_ZONE_RADIUS_LABEL, &deserialized_configuration)?;
+    let softening_kernel =
+        match parse_optional_str(SOFTENING_KERNEL_LABEL, deserialized_configuration)? {
+            Some(kernel_name) => parse_softening_kernel(kernel_name)?,
+            None => SofteningKernel::DeadZone,
+        };
+    let force_law = match deserialized_configuration.get(FORCE_LAW_LABEL) {
+        Some(force_law_configuration) => parse_force_law_configuration(force_law_configuration)?,
+        None => ForceLawConfiguration::InverseSquaredAndFourth,
+    };
     let inverse_squared_coupling =
         parse_f64(INVERSE_SQUARED_COUPLING_LABEL, &deserialized_configuration)?;
     let inverse_fourth_coupling =
         parse_f64(INVERSE_FOURTH_COUPLING_LABEL, &deserialized_configuration)?;
+    let species_interactions = match deserialized_configuration.get(INTERACTIONS_LABEL) {
+        Some(interactions_table) => Some(parse_species_interactions(
+            interactions_table,
+            inverse_squared_coupling,
+            inverse_fourth_coupling,
+        )?),
+        None => None,
+    };
+    let barnes_hut_opening_angle =
+        parse_optional_f64(BARNES_HUT_OPENING_ANGLE_LABEL, deserialized_configuration)?;
+    if let Some(given_angle) = barnes_hut_opening_angle {
+        if given_angle < 0.0 {
+            return Err(Box::new(ConfigurationParseError::new(&format!(
+                "{} must be a non-negative number, got {}",
+                BARNES_HUT_OPENING_ANGLE_LABEL, given_angle
+            ))));
+        }
+    }
     let milliseconds_per_time_slice =
         parse_i64_as_u16(MILLISECONDS_PER_FRAME_LABEL, &deserialized_configuration)?;
     let number_of_time_slices =
         parse_i64_as_usize(NUMBER_OF_FRAMES_LABEL, &deserialized_configuration)?;
-    let right_border_coordinate =
-        parse_i64_as_i32(RIGHT_BORDER_COORDINATE_LABEL, &deserialized_configuration)?;
-    let upper_border_coordinate =
-        parse_i64_as_i32(UPPER_BORDER_COORDINATE_LABEL, &deserialized_configuration)?;
-    let left_border_coordinate =
-        parse_i64_as_i32(LEFT_BORDER_COORDINATE_LABEL, &deserialized_configuration)?;
-    let lower_border_coordinate =
-        parse_i64_as_i32(LOWER_BORDER_COORDINATE_LABEL, &deserialized_configuration)?;
+    let picture_configuration = parse_picture_configuration(deserialized_configuration)?;
+    let boundary_conditions = match deserialized_configuration.get(BOUNDARY_CONDITIONS_LABEL) {
+        Some(boundary_configuration) => Some(parse_boundary_conditions(
+            boundary_configuration,
+            &picture_configuration,
+        )?),
+        None => None,
+    };
+    let external_fields = match deserialized_configuration.get(EXTERNAL_FIELDS_LABEL) {
+        Some(fields_configuration) => Some(parse_external_fields(fields_configuration)?),
+        None => None,
+    };
+    let drag = match deserialized_configuration.get(DRAG_LABEL) {
+        Some(drag_configuration) => Some(parse_drag_configuration(drag_configuration)?),
+        None => None,
+    };
+    let merging_radius =
+        parse_optional_positive_f64(MERGING_RADIUS_LABEL, deserialized_configuration)?;
+    let restitution_coefficient =
+        parse_optional_f64(RESTITUTION_COEFFICIENT_LABEL, deserialized_configuration)?;
+    if let Some(given_coefficient) = restitution_coefficient {
+        if !(0.0..=1.0).contains(&given_coefficient) {
+            return Err(Box::new(ConfigurationParseError::new(&format!(
+                "{} must be from 0 to 1, got {}",
+                RESTITUTION_COEFFICIENT_LABEL, given_coefficient
+            ))));
+        }
+    }
+    let checkpoint_configuration = parse_checkpoint_configuration(deserialized_configuration)?;
+    let diagnostics_filename =
+        parse_optional_str(DIAGNOSTICS_FILENAME_LABEL, deserialized_configuration)?;
+    let watchdog_configuration = parse_watchdog_configuration(deserialized_configuration)?;
 
     let mut particle_generators: std::vec::Vec<InitialParticleGeneratorConfiguration> = vec![];
     let configuration_objects =
@@ -214,23 +1062,35 @@ pub fn parse_deserialized_configuration<'a>(
     }
     Ok(ParsedConfiguration {
         evolver_configuration: EvolverConfiguration {
+            evolver_name,
             memory_layout: memory_layout,
             number_of_steps_per_time_slice: number_of_steps_per_time_slice,
+            relative_error_tolerance,
+            number_of_threads,
         },
         evolution_configuration: EvolutionConfiguration {
             dead_zone_radius: dead_zone_radius,
+            softening_kernel,
+            force_law,
             inverse_squared_coupling: inverse_squared_coupling,
             inverse_fourth_coupling: inverse_fourth_coupling,
+            species_interactions,
+            boundary_conditions,
+            external_fields,
+            drag,
+            merging_radius,
+            restitution_coefficient,
+            spring_bonds: vec![],
+            motion_modes: vec![],
+            barnes_hut_opening_angle,
             milliseconds_per_time_slice: milliseconds_per_time_slice,
             number_of_time_slices: number_of_time_slices,
         },
         generator_configurations: particle_generators,
-        picture_configuration: PictureConfiguration {
-            right_border_coordinate: right_border_coordinate,
-            upper_border_coordinate: upper_border_coordinate,
-            left_border_coordinate: left_border_coordinate,
-            lower_border_coordinate: lower_border_coordinate,
-        },
+        picture_configuration,
+        checkpoint_configuration,
+        diagnostics_filename,
+        watchdog_configuration,
     })
 }
 
@@ -260,6 +1120,38 @@ mod tests {
             Err(String::from("Did not get an error"))
         }
     }
+
+    #[test]
+    fn check_parse_optional_f64_when_absent() -> Result<(), String> {
+        let configuration_without_attribute = serde_json::json!({ "otherAttribute": 1.0 });
+        match parse_optional_f64("absentAttribute", &configuration_without_attribute) {
+            Ok(None) => Ok(()),
+            unexpected_result => Err(format!("Expected Ok(None), got {:?}", unexpected_result)),
+        }
+    }
+
+    #[test]
+    fn check_parse_optional_f64_when_present() -> Result<(), String> {
+        let configuration_with_attribute = serde_json::json!({ "presentAttribute": 0.25 });
+        let parsed_result = parse_optional_f64("presentAttribute", &configuration_with_attribute);
+        if let Ok(Some(parsed_value)) = parsed_result {
+            if parsed_value == 0.25 {
+                return Ok(());
+            }
+        }
+        Err(format!("Expected Ok(Some(0.25)), got {:?}", parsed_result))
+    }
+
+    #[test]
+    fn check_reject_optional_f64_when_malformed() -> Result<(), String> {
+        let configuration_with_attribute = serde_json::json!({ "presentAttribute": "a quarter" });
+        if parse_optional_f64("presentAttribute", &configuration_with_attribute).is_err() {
+            Ok(())
+        } else {
+            Err(String::from("Did not get an error"))
+        }
+    }
+
     #[test]
     fn check_reject_when_no_generator_name() -> Result<(), String> {
         let nameless_configuration = serde_json::json!(
@@ -368,6 +1260,637 @@ mod tests {
         }
     }
 
+    #[test]
+    fn check_reject_negative_barnes_hut_opening_angle() -> Result<(), String> {
+        let negative_angle_configuration = serde_json::json!(
+            {
+                MEMORY_LAYOUT_LABEL: "VecOfPureStruct",
+                NUMBER_OF_STEPS_PER_FRAME_LABEL: 10,
+                DEAD_ZONE_RADIUS_LABEL: 1.0,
+                INVERSE_SQUARED_COUPLING_LABEL: -1.0,
+                INVERSE_FOURTH_COUPLING_LABEL: 1.0,
+                BARNES_HUT_OPENING_ANGLE_LABEL: -0.5,
+                MILLISECONDS_PER_FRAME_LABEL: 100,
+                NUMBER_OF_FRAMES_LABEL: 40,
+                RIGHT_BORDER_COORDINATE_LABEL: 10,
+                UPPER_BORDER_COORDINATE_LABEL: 10,
+                LEFT_BORDER_COORDINATE_LABEL: -10,
+                LOWER_BORDER_COORDINATE_LABEL: -10,
+                GENERATOR_CONFIGURATIONS_LABEL: []
+            }
+        );
+        let parsing_result = parse_deserialized_configuration(&negative_angle_configuration);
+        if parsing_result.is_err() {
+            Ok(())
+        } else {
+            Err(String::from("Did not get an error"))
+        }
+    }
+
+    #[test]
+    fn check_parse_merging_radius_only_if_positive() -> Result<(), String> {
+        let create_configuration = |merging_radius| {
+            serde_json::json!(
+                {
+                    MEMORY_LAYOUT_LABEL: "VecOfPureStruct",
+                    NUMBER_OF_STEPS_PER_FRAME_LABEL: 10,
+                    DEAD_ZONE_RADIUS_LABEL: 1.0,
+                    INVERSE_SQUARED_COUPLING_LABEL: -1.0,
+                    INVERSE_FOURTH_COUPLING_LABEL: 1.0,
+                    MERGING_RADIUS_LABEL: merging_radius,
+                    MILLISECONDS_PER_FRAME_LABEL: 100,
+                    NUMBER_OF_FRAMES_LABEL: 40,
+                    RIGHT_BORDER_COORDINATE_LABEL: 10,
+                    UPPER_BORDER_COORDINATE_LABEL: 10,
+                    LEFT_BORDER_COORDINATE_LABEL: -10,
+                    LOWER_BORDER_COORDINATE_LABEL: -10,
+                    GENERATOR_CONFIGURATIONS_LABEL: []
+                }
+            )
+        };
+        let positive_configuration = create_configuration(0.25);
+        let parsed_radius = parse_deserialized_configuration(&positive_configuration)
+            .map_err(|parsing_error| format!("Unexpected error: {:?}", parsing_error))?
+            .evolution_configuration
+            .merging_radius;
+        if parsed_radius != Some(0.25) {
+            return Err(format!("Expected Some(0.25), got {:?}", parsed_radius));
+        }
+        if parse_deserialized_configuration(&create_configuration(0.0)).is_ok() {
+            return Err(String::from(
+                "Did not get an error for a merging radius of 0",
+            ));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn check_parse_restitution_coefficient_only_from_zero_to_one() -> Result<(), String> {
+        let create_configuration = |restitution_coefficient| {
+            serde_json::json!(
+                {
+                    MEMORY_LAYOUT_LABEL: "VecOfPureStruct",
+                    NUMBER_OF_STEPS_PER_FRAME_LABEL: 10,
+                    DEAD_ZONE_RADIUS_LABEL: 1.0,
+                    INVERSE_SQUARED_COUPLING_LABEL: -1.0,
+                    INVERSE_FOURTH_COUPLING_LABEL: 1.0,
+                    RESTITUTION_COEFFICIENT_LABEL: restitution_coefficient,
+                    MILLISECONDS_PER_FRAME_LABEL: 100,
+                    NUMBER_OF_FRAMES_LABEL: 40,
+                    RIGHT_BORDER_COORDINATE_LABEL: 10,
+                    UPPER_BORDER_COORDINATE_LABEL: 10,
+                    LEFT_BORDER_COORDINATE_LABEL: -10,
+                    LOWER_BORDER_COORDINATE_LABEL: -10,
+                    GENERATOR_CONFIGURATIONS_LABEL: []
+                }
+            )
+        };
+        let mut failure_messages: std::vec::Vec<String> = vec![];
+        for given_coefficient in [0.0, 0.5, 1.0].iter() {
+            match parse_deserialized_configuration(&create_configuration(*given_coefficient)) {
+                Ok(parsed_configuration) => {
+                    let parsed_coefficient = parsed_configuration
+                        .evolution_configuration
+                        .restitution_coefficient;
+                    if parsed_coefficient != Some(*given_coefficient) {
+                        failure_messages.push(format!(
+                            "Expected Some({}), got {:?}",
+                            given_coefficient, parsed_coefficient
+                        ));
+                    }
+                }
+                Err(parsing_error) => failure_messages.push(format!(
+                    "Unexpected error for {}: {:?}",
+                    given_coefficient, parsing_error
+                )),
+            }
+        }
+        for given_coefficient in [-0.1, 1.1].iter() {
+            if parse_deserialized_configuration(&create_configuration(*given_coefficient)).is_ok() {
+                failure_messages.push(format!("Did not get an error for {}", given_coefficient));
+            }
+        }
+
+        if failure_messages.is_empty() {
+            Ok(())
+        } else {
+            Err(failure_messages.join("\n"))
+        }
+    }
+
+    #[test]
+    fn check_reject_checkpoint_interval_without_filename() -> Result<(), String> {
+        let configuration_without_filename = serde_json::json!({ FRAMES_PER_CHECKPOINT_LABEL: 10 });
+        if parse_checkpoint_configuration(&configuration_without_filename).is_err() {
+            Ok(())
+        } else {
+            Err(String::from("Did not get an error"))
+        }
+    }
+
+    #[test]
+    fn check_reject_zero_frames_per_checkpoint() -> Result<(), String> {
+        let configuration_with_zero_interval = serde_json::json!(
+            {
+                FRAMES_PER_CHECKPOINT_LABEL: 0,
+                CHECKPOINT_FILENAME_LABEL: "checkpoint.json"
+            }
+        );
+        if parse_checkpoint_configuration(&configuration_with_zero_interval).is_err() {
+            Ok(())
+        } else {
+            Err(String::from("Did not get an error"))
+        }
+    }
+
+    #[test]
+    fn check_reject_unknown_softening_kernel() -> Result<(), String> {
+        if parse_softening_kernel("Gaussian").is_err() {
+            Ok(())
+        } else {
+            Err(String::from("Did not get an error"))
+        }
+    }
+
+    #[test]
+    fn check_parse_lennard_jones_without_cutoff() -> Result<(), String> {
+        let parsed_result = parse_force_law_configuration(&serde_json::json!(
+            {
+                FORCE_LAW_NAME_LABEL: "LennardJones",
+                LENGTH_SCALE_LABEL: 0.5,
+                ENERGY_SCALE_LABEL: 2.0,
+            }
+        ));
+        match parsed_result {
+            Ok(ForceLawConfiguration::LennardJones {
+                length_scale,
+                energy_scale,
+                cutoff_separation: None,
+            }) if (length_scale == 0.5) && (energy_scale == 2.0) => Ok(()),
+            unexpected_result => Err(format!(
+                "Expected Lennard-Jones with length scale 0.5, energy scale 2, and no cutoff, got \
+                {:?}",
+                unexpected_result
+            )),
+        }
+    }
+
+    #[test]
+    fn check_reject_invalid_force_laws() -> Result<(), String> {
+        let mut failure_messages: std::vec::Vec<String> = vec![];
+        for invalid_configuration in &[
+            serde_json::json!({ FORCE_LAW_NAME_LABEL: "Morse" }),
+            serde_json::json!({ FORCE_LAW_NAME_LABEL: "Yukawa" }),
+            serde_json::json!({ FORCE_LAW_NAME_LABEL: "Yukawa", SCREENING_LENGTH_LABEL: -1.0 }),
+            serde_json::json!(
+                {
+                    FORCE_LAW_NAME_LABEL: "LennardJones",
+                    LENGTH_SCALE_LABEL: 1.0,
+                    ENERGY_SCALE_LABEL: 1.0,
+                    CUTOFF_SEPARATION_LABEL: 0.0,
+                }
+            ),
+        ] {
+            if parse_force_law_configuration(invalid_configuration).is_ok() {
+                failure_messages.push(format!(
+                    "Did not get an error for {}",
+                    invalid_configuration
+                ));
+            }
+        }
+        if failure_messages.is_empty() {
+            Ok(())
+        } else {
+            Err(failure_messages.join("\n"))
+        }
+    }
+
+    #[test]
+    fn check_parse_interactions_table_with_default_couplings() -> Result<(), String> {
+        let species_interactions = parse_species_interactions(
+            &serde_json::json!([
+                {
+                    FIRST_SPECIES_LABEL: "A",
+                    SECOND_SPECIES_LABEL: "B",
+                    INVERSE_SQUARED_COUPLING_LABEL: -2.0,
+                },
+                {
+                    FIRST_SPECIES_LABEL: "C",
+                    SECOND_SPECIES_LABEL: "A",
+                    INVERSE_SQUARED_COUPLING_LABEL: 3.0,
+                    INVERSE_FOURTH_COUPLING_LABEL: 0.5,
+                },
+            ]),
+            1.0,
+            0.25,
+        )
+        .map_err(|parsing_error| format!("Could not parse: {:?}", parsing_error))?;
+        let species_index = |species_name| {
+            species_interactions
+                .species_index(species_name)
+                .map_err(|parsing_error| format!("No species index: {:?}", parsing_error))
+        };
+        let (a_index, b_index, c_index) = (
+            species_index("A")?,
+            species_index("B")?,
+            species_index("C")?,
+        );
+        let unnamed_index = data_structure::charge::SpeciesIndex(0);
+        let actual_couplings = [
+            (a_index, b_index),
+            (b_index, a_index),
+            (a_index, c_index),
+            (b_index, c_index),
+            (unnamed_index, a_index),
+        ]
+        .iter()
+        .map(|(first_species, second_species)| {
+            (
+                species_interactions.inverse_squared_coupling(*first_species, *second_species),
+                species_interactions.inverse_fourth_coupling(*first_species, *second_species),
+            )
+        })
+        .collect::<std::vec::Vec<(f64, f64)>>();
+        let expected_couplings = vec![
+            (-2.0, 0.25),
+            (-2.0, 0.25),
+            (3.0, 0.5),
+            (1.0, 0.25),
+            (1.0, 0.25),
+        ];
+        if (actual_couplings == expected_couplings)
+            && species_interactions.species_index("D").is_err()
+            && (species_interactions.species_name(c_index) == Some("C"))
+            && species_interactions.species_name(unnamed_index).is_none()
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected couplings {:?}, got {:?} from {:?}",
+                expected_couplings, actual_couplings, species_interactions
+            ))
+        }
+    }
+
+    #[test]
+    fn check_reject_invalid_interactions_tables() -> Result<(), String> {
+        let mut failure_messages: std::vec::Vec<String> = vec![];
+        for invalid_table in &[
+            serde_json::json!({ FIRST_SPECIES_LABEL: "A", SECOND_SPECIES_LABEL: "B" }),
+            serde_json::json!([{ FIRST_SPECIES_LABEL: "A" }]),
+            serde_json::json!([
+                { FIRST_SPECIES_LABEL: "A", SECOND_SPECIES_LABEL: "B" },
+                { FIRST_SPECIES_LABEL: "B", SECOND_SPECIES_LABEL: "A" },
+            ]),
+        ] {
+            if parse_species_interactions(invalid_table, 1.0, 1.0).is_ok() {
+                failure_messages.push(format!("Did not get an error for {}", invalid_table));
+            }
+        }
+        if failure_messages.is_empty() {
+            Ok(())
+        } else {
+            Err(failure_messages.join("\n"))
+        }
+    }
+
+    fn create_test_picture_configuration() -> PictureConfiguration {
+        PictureConfiguration {
+            right_border_coordinate: 10,
+            upper_border_coordinate: 20,
+            left_border_coordinate: -10,
+            lower_border_coordinate: -20,
+        }
+    }
+
+    #[test]
+    fn check_parse_boundary_conditions_with_picture_borders_by_default() -> Result<(), String> {
+        let parsed_result = parse_boundary_conditions(
+            &serde_json::json!(
+                {
+                    BOUNDARY_KIND_LABEL: "Periodic",
+                    LEFT_BORDER_COORDINATE_LABEL: -2.5,
+                }
+            ),
+            &create_test_picture_configuration(),
+        );
+        let expected_conditions = BoundaryConditions {
+            boundary_kind: BoundaryKind::Periodic,
+            right_border_coordinate: 10.0,
+            upper_border_coordinate: 20.0,
+            left_border_coordinate: -2.5,
+            lower_border_coordinate: -20.0,
+        };
+        match parsed_result {
+            Ok(actual_conditions) if actual_conditions == expected_conditions => Ok(()),
+            unexpected_result => Err(format!(
+                "Expected {:?}, got {:?}",
+                expected_conditions, unexpected_result
+            )),
+        }
+    }
+
+    #[test]
+    fn check_reject_invalid_boundary_conditions() -> Result<(), String> {
+        let mut failure_messages: std::vec::Vec<String> = vec![];
+        for invalid_configuration in &[
+            serde_json::json!({}),
+            serde_json::json!({ BOUNDARY_KIND_LABEL: "Absorbing" }),
+            serde_json::json!(
+                {
+                    BOUNDARY_KIND_LABEL: "Reflecting",
+                    RIGHT_BORDER_COORDINATE_LABEL: -10.0,
+                }
+            ),
+        ] {
+            if parse_boundary_conditions(
+                invalid_configuration,
+                &create_test_picture_configuration(),
+            )
+            .is_ok()
+            {
+                failure_messages.push(format!(
+                    "Did not get an error for {}",
+                    invalid_configuration
+                ));
+            }
+        }
+        if failure_messages.is_empty() {
+            Ok(())
+        } else {
+            Err(failure_messages.join("\n"))
+        }
+    }
+
+    #[test]
+    fn check_parse_external_fields() -> Result<(), String> {
+        let parsed_result = parse_external_fields(&serde_json::json!(
+            {
+                FIXED_SOURCES_LABEL: [
+                    {
+                        POSITION_IN_PIXELS_LABEL: { "x": 1.0, "y": -2.0 },
+                        INVERSE_SQUARED_CHARGE_LABEL: 3.0,
+                        INVERSE_FOURTH_CHARGE_LABEL: 0.5,
+                    },
+                ],
+                HARMONIC_TRAP_LABEL: {
+                    CENTER_IN_PIXELS_LABEL: { "x": 0.5, "y": 1.5 },
+                    HORIZONTAL_SPRING_CONSTANT_LABEL: 2.0,
+                    VERTICAL_SPRING_CONSTANT_LABEL: -1.0,
+                },
+            }
+        ));
+        let expected_fields = ExternalFields {
+            horizontal_acceleration: 0.0,
+            vertical_acceleration: 0.0,
+            fixed_sources: vec![FixedSource {
+                horizontal_coordinate: 1.0,
+                vertical_coordinate: -2.0,
+                inverse_squared_charge: 3.0,
+                inverse_fourth_charge: 0.5,
+            }],
+            harmonic_trap: Some(HarmonicTrap {
+                horizontal_center: 0.5,
+                vertical_center: 1.5,
+                horizontal_spring_constant: 2.0,
+                vertical_spring_constant: -1.0,
+            }),
+        };
+        match parsed_result {
+            Ok(actual_fields) if actual_fields == expected_fields => Ok(()),
+            unexpected_result => Err(format!(
+                "Expected {:?}, got {:?}",
+                expected_fields, unexpected_result
+            )),
+        }
+    }
+
+    #[test]
+    fn check_reject_invalid_external_fields() -> Result<(), String> {
+        let mut failure_messages: std::vec::Vec<String> = vec![];
+        for invalid_configuration in &[
+            serde_json::json!({ UNIFORM_ACCELERATION_LABEL: { "x": 1.0 } }),
+            serde_json::json!({ FIXED_SOURCES_LABEL: { "x": 1.0, "y": 1.0 } }),
+            serde_json::json!(
+                {
+                    FIXED_SOURCES_LABEL: [
+                        { POSITION_IN_PIXELS_LABEL: { "x": 1.0, "y": -2.0 } },
+                    ],
+                }
+            ),
+            serde_json::json!(
+                {
+                    HARMONIC_TRAP_LABEL: {
+                        HORIZONTAL_SPRING_CONSTANT_LABEL: 2.0,
+                        VERTICAL_SPRING_CONSTANT_LABEL: 2.0,
+                    },
+                }
+            ),
+        ] {
+            if parse_external_fields(invalid_configuration).is_ok() {
+                failure_messages.push(format!(
+                    "Did not get an error for {}",
+                    invalid_configuration
+                ));
+            }
+        }
+        if failure_messages.is_empty() {
+            Ok(())
+        } else {
+            Err(failure_messages.join("\n"))
+        }
+    }
+
+    #[test]
+    fn check_parse_drag_with_and_without_thermostat() -> Result<(), String> {
+        let parsed_results = [
+            parse_drag_configuration(&serde_json::json!({ DRAG_RATE_LABEL: 0.5 })),
+            parse_drag_configuration(&serde_json::json!(
+                {
+                    DRAG_RATE_LABEL: 2.0,
+                    TARGET_TEMPERATURE_LABEL: 1.5,
+                    RANDOM_SEED_LABEL: 12345,
+                }
+            )),
+            parse_drag_configuration(&serde_json::json!(
+                {
+                    DRAG_RATE_LABEL: 2.0,
+                    TARGET_TEMPERATURE_LABEL: 1.5,
+                    RANDOM_SEED_LABEL: 12345,
+                    GENERATOR_STATE_LABEL: [1, u64::MAX, 0, 9_876_543_210_u64],
+                }
+            )),
+        ];
+        let expected_configurations = [
+            DragConfiguration {
+                drag_rate: 0.5,
+                thermostat: None,
+            },
+            DragConfiguration {
+                drag_rate: 2.0,
+                thermostat: Some(ThermostatConfiguration {
+                    target_temperature: 1.5,
+                    random_seed: 12345,
+                    generator_state: None,
+                }),
+            },
+            DragConfiguration {
+                drag_rate: 2.0,
+                thermostat: Some(ThermostatConfiguration {
+                    target_temperature: 1.5,
+                    random_seed: 12345,
+                    generator_state: Some([1, u64::MAX, 0, 9_876_543_210]),
+                }),
+            },
+        ];
+        let mut failure_messages: std::vec::Vec<String> = vec![];
+        for (parsed_result, expected_configuration) in
+            parsed_results.iter().zip(expected_configurations.iter())
+        {
+            match parsed_result {
+                Ok(actual_configuration) if actual_configuration == expected_configuration => {}
+                unexpected_result => failure_messages.push(format!(
+                    "Expected {:?}, got {:?}",
+                    expected_configuration, unexpected_result
+                )),
+            }
+        }
+        if failure_messages.is_empty() {
+            Ok(())
+        } else {
+            Err(failure_messages.join("\n"))
+        }
+    }
+
+    #[test]
+    fn check_reject_invalid_drag() -> Result<(), String> {
+        let mut failure_messages: std::vec::Vec<String> = vec![];
+        for invalid_configuration in &[
+            serde_json::json!({}),
+            serde_json::json!({ DRAG_RATE_LABEL: 0.0 }),
+            serde_json::json!({ DRAG_RATE_LABEL: 1.0, TARGET_TEMPERATURE_LABEL: 1.0 }),
+            serde_json::json!({ DRAG_RATE_LABEL: 1.0, RANDOM_SEED_LABEL: 1 }),
+            serde_json::json!(
+                {
+                    DRAG_RATE_LABEL: 1.0,
+                    TARGET_TEMPERATURE_LABEL: -1.0,
+                    RANDOM_SEED_LABEL: 1,
+                }
+            ),
+            serde_json::json!(
+                {
+                    DRAG_RATE_LABEL: 1.0,
+                    TARGET_TEMPERATURE_LABEL: 1.0,
+                    RANDOM_SEED_LABEL: -1,
+                }
+            ),
+            serde_json::json!(
+                {
+                    DRAG_RATE_LABEL: 1.0,
+                    TARGET_TEMPERATURE_LABEL: 1.0,
+                    RANDOM_SEED_LABEL: 1,
+                    GENERATOR_STATE_LABEL: [1, 2, 3],
+                }
+            ),
+            serde_json::json!(
+                {
+                    DRAG_RATE_LABEL: 1.0,
+                    TARGET_TEMPERATURE_LABEL: 1.0,
+                    RANDOM_SEED_LABEL: 1,
+                    GENERATOR_STATE_LABEL: [1, 2, 3, -4],
+                }
+            ),
+        ] {
+            if parse_drag_configuration(invalid_configuration).is_ok() {
+                failure_messages.push(format!(
+                    "Did not get an error for {}",
+                    invalid_configuration
+                ));
+            }
+        }
+        if failure_messages.is_empty() {
+            Ok(())
+        } else {
+            Err(failure_messages.join("\n"))
+        }
+    }
+
+    #[test]
+    fn check_reject_non_positive_watchdog_limits() -> Result<(), String> {
+        let mut failure_messages: std::vec::Vec<String> = vec![];
+        for invalid_configuration in &[
+            serde_json::json!({ MAXIMUM_RELATIVE_ENERGY_DRIFT_LABEL: 0.0 }),
+            serde_json::json!({ MAXIMUM_SPEED_LABEL: -1.0 }),
+        ] {
+            if parse_watchdog_configuration(invalid_configuration).is_ok() {
+                failure_messages.push(format!(
+                    "Did not get an error for {}",
+                    invalid_configuration
+                ));
+            }
+        }
+        if failure_messages.is_empty() {
+            Ok(())
+        } else {
+            Err(failure_messages.join("\n"))
+        }
+    }
+
+    #[test]
+    fn check_parse_evolver_name_with_default_when_absent() -> Result<(), String> {
+        let create_configuration = |evolver_name: Option<&str>| {
+            let mut created_configuration = serde_json::json!(
+                {
+                    MEMORY_LAYOUT_LABEL: "VecOfPureStruct",
+                    NUMBER_OF_STEPS_PER_FRAME_LABEL: 10,
+                    DEAD_ZONE_RADIUS_LABEL: 1.0,
+                    INVERSE_SQUARED_COUPLING_LABEL: -1.0,
+                    INVERSE_FOURTH_COUPLING_LABEL: 1.0,
+                    MILLISECONDS_PER_FRAME_LABEL: 100,
+                    NUMBER_OF_FRAMES_LABEL: 40,
+                    RIGHT_BORDER_COORDINATE_LABEL: 10,
+                    UPPER_BORDER_COORDINATE_LABEL: 10,
+                    LEFT_BORDER_COORDINATE_LABEL: -10,
+                    LOWER_BORDER_COORDINATE_LABEL: -10,
+                    GENERATOR_CONFIGURATIONS_LABEL: []
+                }
+            );
+            if let Some(evolver_name) = evolver_name {
+                created_configuration[EVOLVER_NAME_LABEL] = serde_json::json!(evolver_name);
+            }
+            created_configuration
+        };
+        let mut failure_messages: std::vec::Vec<String> = vec![];
+        for (given_name, expected_name) in &[
+            (None, "SecondOrderEuler"),
+            (Some("SecondOrderEuler"), "SecondOrderEuler"),
+            (Some("Leapfrog"), "Leapfrog"),
+        ] {
+            let given_configuration = create_configuration(*given_name);
+            match parse_deserialized_configuration(&given_configuration) {
+                Ok(parsed_configuration) => {
+                    if parsed_configuration.evolver_configuration.evolver_name != *expected_name {
+                        failure_messages.push(format!(
+                            "Given {:?}, expected {}, actually parsed {}",
+                            given_name,
+                            expected_name,
+                            parsed_configuration.evolver_configuration.evolver_name
+                        ));
+                    }
+                }
+                Err(parsing_error) => failure_messages.push(format!(
+                    "Given {:?}, got error {}",
+                    given_name, parsing_error
+                )),
+            }
+        }
+        if failure_messages.is_empty() {
+            Ok(())
+        } else {
+            Err(failure_messages.join("\n"))
+        }
+    }
+
     #[test]
     fn check_parse_valid_single_configuration() -> Result<(), String> {
         let expected_name = "acceptable";
@@ -14,3 +14,8 @@ pub struct InverseFourthChargeUnit(pub f64);
 
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
 pub struct InertialMassUnit(pub f64);
+
+/// This is the index of the species of a particle in the table of interactions between species,
+/// where particles which are not given a species have index 0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpeciesIndex(pub usize);
@@ -2,6 +2,9 @@
 
 /// This trait should allow functions over single elements and over all pairs, and also
 /// offer a means of collecting a transformation into an owning iterator.
+///
+/// The functions for pairs must not capture anything mutably, as implementations may call them
+/// from several threads at once, though never on the same element at the same time.
 pub trait SingleAndPairwiseFinite {
     type MutableElement;
 
@@ -13,14 +16,34 @@ pub trait SingleAndPairwiseFinite {
 
     fn apply_to_every_pair<IntermediateResult, ReadOnlyDerive, FirstMutate, SecondMutate>(
         &mut self,
-        derive_change: &mut ReadOnlyDerive,
-        apply_to_first: &mut FirstMutate,
-        apply_to_second: &mut SecondMutate,
+        derive_change: &ReadOnlyDerive,
+        apply_to_first: &FirstMutate,
+        apply_to_second: &SecondMutate,
     ) where
         IntermediateResult: Sized,
-        ReadOnlyDerive: FnMut(&Self::MutableElement, &Self::MutableElement) -> IntermediateResult,
-        FirstMutate: FnMut(&mut Self::MutableElement, &IntermediateResult) -> (),
-        SecondMutate: FnMut(&mut Self::MutableElement, &IntermediateResult) -> ();
+        ReadOnlyDerive:
+            Fn(&Self::MutableElement, &Self::MutableElement) -> IntermediateResult + Sync,
+        FirstMutate: Fn(&mut Self::MutableElement, &IntermediateResult) -> () + Sync,
+        SecondMutate: Fn(&mut Self::MutableElement, &IntermediateResult) -> () + Sync;
+
+    /// A collection which stores the positions, charges and forces of its elements each in its own
+    /// array can set the force on every element to the sum over every other element of a force
+    /// which depends only on the charges of the pair and their separation, directly over the
+    /// arrays, and return true. The force on the first element of a pair is its separation from the
+    /// second times the given function of the inverse-squared coupling and inverse-fourth coupling
+    /// of the pair, which are the given constants times the charges of both elements, and of the
+    /// square of the separation. Any other collection returns false without changing anything.
+    fn set_forces_from_charges_and_separations<MagnitudeOverSeparation>(
+        &mut self,
+        _inverse_squared_constant: f64,
+        _inverse_fourth_constant: f64,
+        _force_magnitude_over_separation: &MagnitudeOverSeparation,
+    ) -> bool
+    where
+        MagnitudeOverSeparation: Fn(f64, f64, f64) -> f64,
+    {
+        false
+    }
 }
 
 impl<VectorElement> super::collection::SingleAndPairwiseFinite for std::vec::Vec<VectorElement> {
@@ -41,14 +64,15 @@ impl<VectorElement> super::collection::SingleAndPairwiseFinite for std::vec::Vec
 
     fn apply_to_every_pair<IntermediateResult, ReadOnlyDerive, FirstMutate, SecondMutate>(
         &mut self,
-        derive_change: &mut ReadOnlyDerive,
-        apply_to_first: &mut FirstMutate,
-        apply_to_second: &mut SecondMutate,
+        derive_change: &ReadOnlyDerive,
+        apply_to_first: &FirstMutate,
+        apply_to_second: &SecondMutate,
     ) where
         IntermediateResult: Sized,
-        ReadOnlyDerive: FnMut(&Self::MutableElement, &Self::MutableElement) -> IntermediateResult,
-        FirstMutate: FnMut(&mut Self::MutableElement, &IntermediateResult) -> (),
-        SecondMutate: FnMut(&mut Self::MutableElement, &IntermediateResult) -> (),
+        ReadOnlyDerive:
+            Fn(&Self::MutableElement, &Self::MutableElement) -> IntermediateResult + Sync,
+        FirstMutate: Fn(&mut Self::MutableElement, &IntermediateResult) -> () + Sync,
+        SecondMutate: Fn(&mut Self::MutableElement, &IntermediateResult) -> () + Sync,
     {
         let number_of_elements = self.len();
         for first_index in 0..(number_of_elements - 1) {
@@ -60,3 +84,439 @@ impl<VectorElement> super::collection::SingleAndPairwiseFinite for std::vec::Vec
         }
     }
 }
+
+/// This wraps a vector so that the functions over pairs are applied by several threads at once.
+/// The elements are split into two blocks per thread, and the pairs of blocks are scheduled as a
+/// round-robin tournament, so that in each round every thread has exclusive access to its own
+/// pair of blocks. Since the assignment of pairs to rounds depends only on the number of elements
+/// and the number of threads, each element receives its changes in the same order on every run,
+/// so the results are identical bit for bit for a fixed number of threads.
+pub struct MultithreadedVector<VectorElement> {
+    pub elements: std::vec::Vec<VectorElement>,
+    number_of_threads: usize,
+}
+
+pub fn new_multithreaded_vector<VectorElement>(
+    number_of_threads: usize,
+) -> MultithreadedVector<VectorElement> {
+    MultithreadedVector {
+        elements: vec![],
+        number_of_threads: number_of_threads.max(1),
+    }
+}
+
+/// A task covers either all the pairs within a single block (if there is no second block) or all
+/// the pairs of one element from the first block and one from the second block.
+struct BlockPairTask<'a, VectorElement> {
+    first_block_index: usize,
+    first_block: &'a mut [VectorElement],
+    second_block: Option<(usize, &'a mut [VectorElement])>,
+}
+
+fn apply_to_pairs_within_block<
+    VectorElement,
+    IntermediateResult,
+    ReadOnlyDerive,
+    FirstMutate,
+    SecondMutate,
+>(
+    block_elements: &mut [VectorElement],
+    derive_change: &ReadOnlyDerive,
+    apply_to_first: &FirstMutate,
+    apply_to_second: &SecondMutate,
+) where
+    ReadOnlyDerive: Fn(&VectorElement, &VectorElement) -> IntermediateResult,
+    FirstMutate: Fn(&mut VectorElement, &IntermediateResult),
+    SecondMutate: Fn(&mut VectorElement, &IntermediateResult),
+{
+    let number_of_elements = block_elements.len();
+    for first_index in 0..number_of_elements {
+        let (lower_elements, higher_elements) = block_elements.split_at_mut(first_index + 1);
+        let first_element = &mut lower_elements[first_index];
+        for second_element in higher_elements.iter_mut() {
+            let intermediate_result = derive_change(first_element, second_element);
+            apply_to_first(first_element, &intermediate_result);
+            apply_to_second(second_element, &intermediate_result);
+        }
+    }
+}
+
+fn apply_to_pairs_across_blocks<
+    VectorElement,
+    IntermediateResult,
+    ReadOnlyDerive,
+    FirstMutate,
+    SecondMutate,
+>(
+    first_block: &mut [VectorElement],
+    second_block: &mut [VectorElement],
+    derive_change: &ReadOnlyDerive,
+    apply_to_first: &FirstMutate,
+    apply_to_second: &SecondMutate,
+) where
+    ReadOnlyDerive: Fn(&VectorElement, &VectorElement) -> IntermediateResult,
+    FirstMutate: Fn(&mut VectorElement, &IntermediateResult),
+    SecondMutate: Fn(&mut VectorElement, &IntermediateResult),
+{
+    for first_element in first_block.iter_mut() {
+        for second_element in second_block.iter_mut() {
+            let intermediate_result = derive_change(first_element, second_element);
+            apply_to_first(first_element, &intermediate_result);
+            apply_to_second(second_element, &intermediate_result);
+        }
+    }
+}
+
+/// This returns the pairs of block indices for each round, following the circle method for a
+/// round-robin tournament: the last block stays fixed while the others rotate around it. The
+/// first rounds just pair each block with itself, half of the blocks in each round. The lower
+/// index is always first in each pair, matching the order of the single-threaded loop.
+fn schedule_block_pairs(number_of_blocks: usize) -> std::vec::Vec<std::vec::Vec<(usize, usize)>> {
+    let half_number_of_blocks = number_of_blocks / 2;
+    let mut scheduled_rounds: std::vec::Vec<std::vec::Vec<(usize, usize)>> = vec![
+        (0..half_number_of_blocks)
+            .map(|block_index| (block_index, block_index))
+            .collect(),
+        (half_number_of_blocks..number_of_blocks)
+            .map(|block_index| (block_index, block_index))
+            .collect(),
+    ];
+    let number_of_rotating_blocks = number_of_blocks - 1;
+    for round_index in 0..number_of_rotating_blocks {
+        let mut round_pairs = vec![(round_index, number_of_rotating_blocks)];
+        for offset_from_first in 1..half_number_of_blocks {
+            let first_block_index = (round_index + offset_from_first) % number_of_rotating_blocks;
+            let second_block_index = (round_index + number_of_rotating_blocks - offset_from_first)
+                % number_of_rotating_blocks;
+            round_pairs.push((
+                first_block_index.min(second_block_index),
+                first_block_index.max(second_block_index),
+            ));
+        }
+        scheduled_rounds.push(round_pairs);
+    }
+    scheduled_rounds
+}
+
+impl<VectorElement> SingleAndPairwiseFinite for MultithreadedVector<VectorElement>
+where
+    VectorElement: Send,
+{
+    type MutableElement = VectorElement;
+
+    fn get_count(&self) -> usize {
+        self.elements.len()
+    }
+
+    fn apply_to_every_single<T>(&mut self, update_single: &mut T)
+    where
+        T: FnMut(&mut Self::MutableElement),
+    {
+        self.elements.apply_to_every_single(update_single)
+    }
+
+    fn apply_to_every_pair<IntermediateResult, ReadOnlyDerive, FirstMutate, SecondMutate>(
+        &mut self,
+        derive_change: &ReadOnlyDerive,
+        apply_to_first: &FirstMutate,
+        apply_to_second: &SecondMutate,
+    ) where
+        IntermediateResult: Sized,
+        ReadOnlyDerive:
+            Fn(&Self::MutableElement, &Self::MutableElement) -> IntermediateResult + Sync,
+        FirstMutate: Fn(&mut Self::MutableElement, &IntermediateResult) + Sync,
+        SecondMutate: Fn(&mut Self::MutableElement, &IntermediateResult) + Sync,
+    {
+        let number_of_threads = self.number_of_threads;
+        let number_of_blocks = 2 * number_of_threads;
+
+        // If there are too few elements to give every block at least two, the threads would cost
+        // more than they save.
+        if (number_of_threads <= 1) || (self.elements.len() < (2 * number_of_blocks)) {
+            apply_to_pairs_within_block(
+                &mut self.elements,
+                derive_change,
+                apply_to_first,
+                apply_to_second,
+            );
+            return;
+        }
+
+        let number_of_elements = self.elements.len();
+        let mut element_blocks: std::vec::Vec<Option<&mut [VectorElement]>> =
+            std::vec::Vec::with_capacity(number_of_blocks);
+        let mut remaining_elements = &mut self.elements[..];
+        let mut previous_block_end = 0;
+        for block_index in 1..=number_of_blocks {
+            let block_end = (block_index * number_of_elements) / number_of_blocks;
+            let (block_elements, following_elements) =
+                remaining_elements.split_at_mut(block_end - previous_block_end);
+            element_blocks.push(Some(block_elements));
+            remaining_elements = following_elements;
+            previous_block_end = block_end;
+        }
+
+        std::thread::scope(|thread_scope| {
+            let (finished_sender, finished_receiver) = std::sync::mpsc::channel();
+            let task_senders: std::vec::Vec<std::sync::mpsc::Sender<BlockPairTask<VectorElement>>> =
+                (0..number_of_threads)
+                    .map(|_| {
+                        let (task_sender, task_receiver) =
+                            std::sync::mpsc::channel::<BlockPairTask<VectorElement>>();
+                        let finished_sender = finished_sender.clone();
+                        thread_scope.spawn(move || {
+                            for mut received_task in task_receiver {
+                                // A panic is sent back in place of the task, as the main thread
+                                // would otherwise wait for the task forever.
+                                let task_outcome =
+                                    std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                                        match &mut received_task.second_block {
+                                            None => apply_to_pairs_within_block(
+                                                received_task.first_block,
+                                                derive_change,
+                                                apply_to_first,
+                                                apply_to_second,
+                                            ),
+                                            Some((_, second_block)) => {
+                                                apply_to_pairs_across_blocks(
+                                                    received_task.first_block,
+                                                    second_block,
+                                                    derive_change,
+                                                    apply_to_first,
+                                                    apply_to_second,
+                                                )
+                                            }
+                                        }
+                                    }))
+                                    .map(|_| received_task);
+                                if finished_sender.send(task_outcome).is_err() {
+                                    return;
+                                }
+                            }
+                        });
+                        task_sender
+                    })
+                    .collect();
+            drop(finished_sender);
+
+            for round_pairs in schedule_block_pairs(number_of_blocks) {
+                for (task_sender, (first_block_index, second_block_index)) in
+                    task_senders.iter().zip(round_pairs.iter())
+                {
+                    let first_block = element_blocks[*first_block_index]
+                        .take()
+                        .expect("each block is in at most one task per round");
+                    let second_block = if first_block_index == second_block_index {
+                        None
+                    } else {
+                        Some((
+                            *second_block_index,
+                            element_blocks[*second_block_index]
+                                .take()
+                                .expect("each block is in at most one task per round"),
+                        ))
+                    };
+                    task_sender
+                        .send(BlockPairTask {
+                            first_block_index: *first_block_index,
+                            first_block,
+                            second_block,
+                        })
+                        .expect("worker threads run until their task channels are dropped");
+                }
+
+                // The blocks are returned to their places before the next round is handed out.
+                for _ in 0..round_pairs.len() {
+                    // Unwinding drops the task senders, so the other worker threads finish and
+                    // the scope can join them.
+                    let finished_task = match finished_receiver
+                        .recv()
+                        .expect("worker threads return every task they receive")
+                    {
+                        Ok(finished_task) => finished_task,
+                        Err(panic_payload) => std::panic::resume_unwind(panic_payload),
+                    };
+                    element_blocks[finished_task.first_block_index] =
+                        Some(finished_task.first_block);
+                    if let Some((second_block_index, second_block)) = finished_task.second_block {
+                        element_blocks[second_block_index] = Some(second_block);
+                    }
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Each element records the sum of the products of its value with every other value, and the
+    /// list of the other values in the order in which they were applied.
+    #[derive(Clone, Debug, PartialEq)]
+    struct RecordingElement {
+        element_value: i64,
+        product_sum: i64,
+        applied_values: std::vec::Vec<i64>,
+    }
+
+    fn create_recording_elements(number_of_elements: usize) -> std::vec::Vec<RecordingElement> {
+        (0..number_of_elements)
+            .map(|element_index| RecordingElement {
+                element_value: ((element_index * 7919) % 101) as i64,
+                product_sum: 0,
+                applied_values: vec![],
+            })
+            .collect()
+    }
+
+    fn apply_recording_to_every_pair(
+        tested_collection: &mut impl SingleAndPairwiseFinite<MutableElement = RecordingElement>,
+    ) {
+        tested_collection.apply_to_every_pair(
+            &|first_element, second_element| {
+                (first_element.element_value, second_element.element_value)
+            },
+            &|first_element, (_, second_value)| {
+                first_element.product_sum += first_element.element_value * second_value;
+                first_element.applied_values.push(*second_value);
+            },
+            &|second_element, (first_value, _)| {
+                second_element.product_sum += second_element.element_value * first_value;
+                second_element.applied_values.push(*first_value);
+            },
+        );
+    }
+
+    fn run_multithreaded(
+        number_of_threads: usize,
+        number_of_elements: usize,
+    ) -> std::vec::Vec<RecordingElement> {
+        let mut multithreaded_collection = new_multithreaded_vector(number_of_threads);
+        multithreaded_collection.elements = create_recording_elements(number_of_elements);
+        apply_recording_to_every_pair(&mut multithreaded_collection);
+        multithreaded_collection.elements
+    }
+
+    fn check_every_pair_applied_once(
+        number_of_threads: usize,
+        number_of_elements: usize,
+    ) -> Result<(), String> {
+        let mut single_threaded_elements = create_recording_elements(number_of_elements);
+        apply_recording_to_every_pair(&mut single_threaded_elements);
+        let multithreaded_elements = run_multithreaded(number_of_threads, number_of_elements);
+        for (element_index, (expected_element, actual_element)) in single_threaded_elements
+            .iter()
+            .zip(multithreaded_elements.iter())
+            .enumerate()
+        {
+            let mut expected_values = expected_element.applied_values.clone();
+            let mut actual_values = actual_element.applied_values.clone();
+            expected_values.sort_unstable();
+            actual_values.sort_unstable();
+            if (expected_element.product_sum != actual_element.product_sum)
+                || (expected_values != actual_values)
+            {
+                return Err(format!(
+                    "{} threads, element {}: expected {:?}, actual {:?}",
+                    number_of_threads, element_index, expected_element, actual_element
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn check_schedule_covers_every_pair_of_blocks_once() -> Result<(), String> {
+        for number_of_blocks in [2, 4, 6, 8, 64].iter() {
+            let mut pair_counts = vec![vec![0; *number_of_blocks]; *number_of_blocks];
+            for round_pairs in schedule_block_pairs(*number_of_blocks) {
+                let mut block_counts = vec![0; *number_of_blocks];
+                for (first_block_index, second_block_index) in round_pairs {
+                    if first_block_index > second_block_index {
+                        return Err(format!(
+                            "Pair ({}, {}) out of order",
+                            first_block_index, second_block_index
+                        ));
+                    }
+                    pair_counts[first_block_index][second_block_index] += 1;
+                    block_counts[first_block_index] += 1;
+                    if first_block_index != second_block_index {
+                        block_counts[second_block_index] += 1;
+                    }
+                }
+                if block_counts.iter().any(|block_count| *block_count > 1) {
+                    return Err(format!(
+                        "{} blocks: a block appears twice in a round: {:?}",
+                        number_of_blocks, block_counts
+                    ));
+                }
+            }
+            for (first_block_index, counts_for_first) in pair_counts.iter().enumerate() {
+                for (second_block_index, pair_count) in
+                    counts_for_first.iter().enumerate().skip(first_block_index)
+                {
+                    if *pair_count != 1 {
+                        return Err(format!(
+                            "{} blocks: pair ({}, {}) scheduled {} times",
+                            number_of_blocks, first_block_index, second_block_index, pair_count
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn check_single_thread_matches_vector() -> Result<(), String> {
+        check_every_pair_applied_once(1, 50)
+    }
+
+    #[test]
+    fn check_too_few_elements_for_threads_matches_vector() -> Result<(), String> {
+        check_every_pair_applied_once(8, 5)
+    }
+
+    #[test]
+    fn check_several_threads_apply_every_pair_once() -> Result<(), String> {
+        check_every_pair_applied_once(3, 97)?;
+        check_every_pair_applied_once(4, 128)
+    }
+
+    #[test]
+    fn check_panic_in_worker_thread_reaches_caller() -> Result<(), String> {
+        let panic_result = std::panic::catch_unwind(|| {
+            let mut multithreaded_collection = new_multithreaded_vector(4);
+            multithreaded_collection.elements = create_recording_elements(128);
+            multithreaded_collection.apply_to_every_pair(
+                &|first_element: &RecordingElement, second_element: &RecordingElement| {
+                    if first_element.element_value == second_element.element_value {
+                        panic!("Deliberate panic for equal values");
+                    }
+                },
+                &|_, _| {},
+                &|_, _| {},
+            );
+        });
+        match panic_result {
+            Ok(_) => Err(String::from("Expected panic did not reach the caller")),
+            Err(panic_payload) => match panic_payload.downcast_ref::<&str>() {
+                Some(&"Deliberate panic for equal values") => Ok(()),
+                _ => Err(format!("Unexpected panic payload {:?}", panic_payload)),
+            },
+        }
+    }
+
+    #[test]
+    fn check_several_threads_apply_in_same_order_every_time() -> Result<(), String> {
+        let first_run = run_multithreaded(4, 101);
+        for _ in 0..5 {
+            let repeated_run = run_multithreaded(4, 101);
+            if repeated_run != first_run {
+                return Err(String::from("Order of application changed between runs"));
+            }
+        }
+        Ok(())
+    }
+}
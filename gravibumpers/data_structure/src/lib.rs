@@ -5,7 +5,8 @@
 /// of Add and a default unpacking for a trait) and thus has no #[cfg(test)].
 ///
 /// There are public modules (comparison, color) but these exist to provide traits, structs, and
-/// simple utility functions, or utility functions for tests, so also have no #[cfg(test)].
+/// simple utility functions, or utility functions for tests, so also have no #[cfg(test)]. The
+/// exception is the collection module, which schedules the multithreaded pairwise functions.
 pub mod charge;
 pub mod collection;
 pub mod color;
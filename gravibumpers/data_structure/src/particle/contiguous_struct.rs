@@ -1,6 +1,6 @@
 /// This module contains a pure struct implementation of the particle traits, and a
-/// generator for a contiguous vector of them, and a generator for a vector of pointers
-/// to them.
+/// generator for a contiguous vector of them, a generator for a contiguous vector of them
+/// which is processed by several threads, and a generator for a vector of pointers to them.
 
 pub struct MassNormalizedWithForceField {
     particle_description: super::BasicIndividual,
@@ -77,6 +77,10 @@ impl super::CollectionInForceField for VectorOfMassNormalizedWithForceField {
             timestep_over_inertial_mass,
         ));
     }
+
+    fn remove_particle(&mut self, particle_index: usize) {
+        self.0.remove(particle_index);
+    }
 }
 
 pub struct VectorOfMassNormalizedWithForceFieldGenerator {}
@@ -90,6 +94,51 @@ impl super::CollectionInForceFieldGenerator for VectorOfMassNormalizedWithForceF
     }
 }
 
+pub struct MultithreadedVectorOfMassNormalizedWithForceField(
+    pub super::super::collection::MultithreadedVector<MassNormalizedWithForceField>,
+);
+
+impl super::CollectionInForceField for MultithreadedVectorOfMassNormalizedWithForceField {
+    type MutableElement = MassNormalizedWithForceField;
+    type FixedSizeCollection =
+        super::super::collection::MultithreadedVector<MassNormalizedWithForceField>;
+    fn access_mutable_elements(&mut self) -> &mut Self::FixedSizeCollection {
+        &mut self.0
+    }
+
+    fn add_particle(
+        &mut self,
+        particle_to_add: &impl super::IndividualRepresentation,
+        timestep_over_inertial_mass: &super::super::time::OverMassUnit,
+    ) {
+        self.0.elements.push(new_mass_normalized_with_force_field(
+            particle_to_add,
+            timestep_over_inertial_mass,
+        ));
+    }
+
+    fn remove_particle(&mut self, particle_index: usize) {
+        self.0.elements.remove(particle_index);
+    }
+}
+
+pub struct MultithreadedVectorOfMassNormalizedWithForceFieldGenerator {
+    pub number_of_threads: usize,
+}
+
+impl super::CollectionInForceFieldGenerator
+    for MultithreadedVectorOfMassNormalizedWithForceFieldGenerator
+{
+    type MutableElement = MassNormalizedWithForceField;
+    type CreatedCollection = MultithreadedVectorOfMassNormalizedWithForceField;
+
+    fn create_collection(&self) -> Self::CreatedCollection {
+        MultithreadedVectorOfMassNormalizedWithForceField(
+            super::super::collection::new_multithreaded_vector(self.number_of_threads),
+        )
+    }
+}
+
 pub struct VectorOfDynamicBoxedMassNormalizedWithForceField(
     pub std::vec::Vec<std::boxed::Box<dyn super::WritableInForceField>>,
 );
@@ -116,6 +165,10 @@ impl super::CollectionInForceField for VectorOfDynamicBoxedMassNormalizedWithFor
                 timestep_over_inertial_mass: *timestep_over_inertial_mass,
             }));
     }
+
+    fn remove_particle(&mut self, particle_index: usize) {
+        self.0.remove(particle_index);
+    }
 }
 
 pub struct VectorOfDynamicBoxedMassNormalizedWithForceFieldGenerator {}
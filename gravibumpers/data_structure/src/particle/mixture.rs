@@ -33,6 +33,10 @@ impl super::CollectionInForceField for VectorOfDynamicBoxedMassNormalizedStructs
             )
         });
     }
+
+    fn remove_particle(&mut self, particle_index: usize) {
+        self.0.remove(particle_index);
+    }
 }
 
 pub struct VectorOfDynamicBoxedMassNormalizedStructsAndBoxesGenerator {}
@@ -4,6 +4,7 @@ use std::ops::DerefMut;
 
 pub mod contiguous_struct;
 pub mod mixture;
+pub mod struct_of_arrays;
 pub mod struct_of_boxes;
 
 /// The particles have some intrinsic qualities which do not change, unlike their
@@ -13,6 +14,11 @@ pub struct IntrinsicPart {
     pub inertial_mass: super::charge::InertialMassUnit,
     pub inverse_squared_charge: super::charge::InverseSquaredChargeUnit,
     pub inverse_fourth_charge: super::charge::InverseFourthChargeUnit,
+    pub species_index: super::charge::SpeciesIndex,
+    /// Particles only collide if both have radii greater than 0.
+    pub collision_radius: super::position::SeparationUnit,
+    /// Tracers feel the forces from the other particles but exert none on them.
+    pub is_tracer: bool,
     pub color_brightness: super::color::RedGreenBlueTriplet,
 }
 
@@ -91,6 +97,10 @@ pub trait CollectionInForceField {
         particle_to_add: &impl IndividualRepresentation,
         timestep_over_inertial_mass: &super::time::OverMassUnit,
     );
+
+    /// The remaining particles keep their order, so the index of every particle after the removed
+    /// particle goes down by one.
+    fn remove_particle(&mut self, particle_index: usize);
 }
 
 pub trait CollectionInForceFieldGenerator {
@@ -0,0 +1,776 @@
+/// This module contains a collection which stores each component of the particles in its own
+/// contiguous vector, rather than storing a struct per particle, and a generator for it. The
+/// forces which depend only on the charges and separations of the pairs are summed directly over
+/// the vectors: for each particle, the forces from all the later particles are first calculated
+/// into a re-used buffer, in a loop where no pair depends on another so that it can be vectorised,
+/// and then added up in several partial sums, so that the forces agree with those of a vector of
+/// structs only to within rounding, as they are summed in a different order. The functions over
+/// single particles and any other functions over pairs are given a copy of each particle in the
+/// pure struct implementation, gathered from the vectors, and any changes to the variables or the
+/// force are scattered back into the vectors afterwards.
+use super::super::collection::SingleAndPairwiseFinite;
+
+/// The forces on each particle from the later particles are added up in this many separate sums,
+/// so that the additions for consecutive particles do not have to wait for each other.
+const PARTIAL_SUMS_PER_PARTICLE: usize = 4;
+
+pub struct ParticleArraysWithForceField {
+    horizontal_positions: std::vec::Vec<f64>,
+    vertical_positions: std::vec::Vec<f64>,
+    horizontal_velocities: std::vec::Vec<f64>,
+    vertical_velocities: std::vec::Vec<f64>,
+    inverse_squared_charges: std::vec::Vec<f64>,
+    inverse_fourth_charges: std::vec::Vec<f64>,
+    horizontal_forces: std::vec::Vec<f64>,
+    vertical_forces: std::vec::Vec<f64>,
+    inertial_masses: std::vec::Vec<super::super::charge::InertialMassUnit>,
+    species_indices: std::vec::Vec<super::super::charge::SpeciesIndex>,
+    collision_radii: std::vec::Vec<super::super::position::SeparationUnit>,
+    tracer_flags: std::vec::Vec<bool>,
+    color_brightnesses: std::vec::Vec<super::super::color::RedGreenBlueTriplet>,
+    timesteps_over_inertial_mass: std::vec::Vec<f64>,
+    magnitudes_over_separation: std::vec::Vec<f64>,
+    gathered_particles: std::vec::Vec<super::contiguous_struct::MassNormalizedWithForceField>,
+}
+
+impl ParticleArraysWithForceField {
+    fn gather_particle(
+        &self,
+        particle_index: usize,
+    ) -> super::contiguous_struct::MassNormalizedWithForceField {
+        let mut gathered_particle = super::contiguous_struct::new_mass_normalized_with_force_field(
+            &super::BasicIndividual {
+                intrinsic_values: super::IntrinsicPart {
+                    inertial_mass: self.inertial_masses[particle_index],
+                    inverse_squared_charge: super::super::charge::InverseSquaredChargeUnit(
+                        self.inverse_squared_charges[particle_index],
+                    ),
+                    inverse_fourth_charge: super::super::charge::InverseFourthChargeUnit(
+                        self.inverse_fourth_charges[particle_index],
+                    ),
+                    species_index: self.species_indices[particle_index],
+                    collision_radius: self.collision_radii[particle_index],
+                    is_tracer: self.tracer_flags[particle_index],
+                    color_brightness: self.color_brightnesses[particle_index],
+                },
+                variable_values: super::VariablePart {
+                    position_vector: super::super::position::DimensionfulVector {
+                        horizontal_component: super::super::position::HorizontalUnit(
+                            self.horizontal_positions[particle_index],
+                        ),
+                        vertical_component: super::super::position::VerticalUnit(
+                            self.vertical_positions[particle_index],
+                        ),
+                    },
+                    velocity_vector: super::super::velocity::DimensionfulVector {
+                        horizontal_component: super::super::velocity::HorizontalUnit(
+                            self.horizontal_velocities[particle_index],
+                        ),
+                        vertical_component: super::super::velocity::VerticalUnit(
+                            self.vertical_velocities[particle_index],
+                        ),
+                    },
+                },
+            },
+            &super::super::time::OverMassUnit(self.timesteps_over_inertial_mass[particle_index]),
+        );
+        let gathered_force =
+            super::WritableInForceField::write_experienced_force(&mut gathered_particle);
+        gathered_force.horizontal_component =
+            super::super::force::HorizontalUnit(self.horizontal_forces[particle_index]);
+        gathered_force.vertical_component =
+            super::super::force::VerticalUnit(self.vertical_forces[particle_index]);
+        gathered_particle
+    }
+
+    /// Only the variables and the force can be changed through the traits, so only they are
+    /// written back.
+    fn scatter_particle(
+        &mut self,
+        particle_index: usize,
+        gathered_particle: &super::contiguous_struct::MassNormalizedWithForceField,
+    ) {
+        let particle_variables = super::IndividualRepresentation::read_variables(gathered_particle);
+        self.horizontal_positions[particle_index] =
+            particle_variables.position_vector.horizontal_component.0;
+        self.vertical_positions[particle_index] =
+            particle_variables.position_vector.vertical_component.0;
+        self.horizontal_velocities[particle_index] =
+            particle_variables.velocity_vector.horizontal_component.0;
+        self.vertical_velocities[particle_index] =
+            particle_variables.velocity_vector.vertical_component.0;
+        let particle_force = super::ReadOnlyInForceField::read_experienced_force(gathered_particle);
+        self.horizontal_forces[particle_index] = particle_force.horizontal_component.0;
+        self.vertical_forces[particle_index] = particle_force.vertical_component.0;
+    }
+}
+
+impl SingleAndPairwiseFinite for ParticleArraysWithForceField {
+    type MutableElement = super::contiguous_struct::MassNormalizedWithForceField;
+
+    fn get_count(&self) -> usize {
+        self.horizontal_positions.len()
+    }
+
+    fn apply_to_every_single<T>(&mut self, update_single: &mut T)
+    where
+        T: FnMut(&mut Self::MutableElement),
+    {
+        for particle_index in 0..self.get_count() {
+            let mut gathered_particle = self.gather_particle(particle_index);
+            update_single(&mut gathered_particle);
+            self.scatter_particle(particle_index, &gathered_particle);
+        }
+    }
+
+    fn apply_to_every_pair<IntermediateResult, ReadOnlyDerive, FirstMutate, SecondMutate>(
+        &mut self,
+        derive_change: &ReadOnlyDerive,
+        apply_to_first: &FirstMutate,
+        apply_to_second: &SecondMutate,
+    ) where
+        IntermediateResult: Sized,
+        ReadOnlyDerive:
+            Fn(&Self::MutableElement, &Self::MutableElement) -> IntermediateResult + Sync,
+        FirstMutate: Fn(&mut Self::MutableElement, &IntermediateResult) + Sync,
+        SecondMutate: Fn(&mut Self::MutableElement, &IntermediateResult) + Sync,
+    {
+        let mut gathered_particles = std::mem::take(&mut self.gathered_particles);
+        gathered_particles.clear();
+        gathered_particles.extend(
+            (0..self.get_count()).map(|particle_index| self.gather_particle(particle_index)),
+        );
+        gathered_particles.apply_to_every_pair(derive_change, apply_to_first, apply_to_second);
+        for (particle_index, gathered_particle) in gathered_particles.iter().enumerate() {
+            self.scatter_particle(particle_index, gathered_particle);
+        }
+        self.gathered_particles = gathered_particles;
+    }
+
+    fn set_forces_from_charges_and_separations<MagnitudeOverSeparation>(
+        &mut self,
+        inverse_squared_constant: f64,
+        inverse_fourth_constant: f64,
+        force_magnitude_over_separation: &MagnitudeOverSeparation,
+    ) -> bool
+    where
+        MagnitudeOverSeparation: Fn(f64, f64, f64) -> f64,
+    {
+        let number_of_particles = self.get_count();
+        self.horizontal_forces
+            .iter_mut()
+            .for_each(|force_component| {
+                *force_component = 0.0;
+            });
+        self.vertical_forces.iter_mut().for_each(|force_component| {
+            *force_component = 0.0;
+        });
+        self.magnitudes_over_separation
+            .resize(number_of_particles, 0.0);
+        for first_index in 0..number_of_particles {
+            let later_range = (first_index + 1)..number_of_particles;
+            let first_horizontal_position = self.horizontal_positions[first_index];
+            let first_vertical_position = self.vertical_positions[first_index];
+            let first_inverse_squared_coupling =
+                inverse_squared_constant * self.inverse_squared_charges[first_index];
+            let first_inverse_fourth_coupling =
+                inverse_fourth_constant * self.inverse_fourth_charges[first_index];
+            let later_horizontal_positions = &self.horizontal_positions[later_range.clone()];
+            let later_vertical_positions = &self.vertical_positions[later_range.clone()];
+            let magnitudes_over_separation =
+                &mut self.magnitudes_over_separation[later_range.clone()];
+            for (
+                (
+                    (
+                        (magnitude_over_separation, later_horizontal_position),
+                        later_vertical_position,
+                    ),
+                    later_inverse_squared_charge,
+                ),
+                later_inverse_fourth_charge,
+            ) in magnitudes_over_separation
+                .iter_mut()
+                .zip(later_horizontal_positions)
+                .zip(later_vertical_positions)
+                .zip(&self.inverse_squared_charges[later_range.clone()])
+                .zip(&self.inverse_fourth_charges[later_range.clone()])
+            {
+                let horizontal_separation = first_horizontal_position - later_horizontal_position;
+                let vertical_separation = first_vertical_position - later_vertical_position;
+                *magnitude_over_separation = force_magnitude_over_separation(
+                    first_inverse_squared_coupling * later_inverse_squared_charge,
+                    first_inverse_fourth_coupling * later_inverse_fourth_charge,
+                    (horizontal_separation * horizontal_separation)
+                        + (vertical_separation * vertical_separation),
+                );
+            }
+
+            let mut horizontal_partial_sums = [0.0; PARTIAL_SUMS_PER_PARTICLE];
+            let mut vertical_partial_sums = [0.0; PARTIAL_SUMS_PER_PARTICLE];
+            let mut add_block_of_forces =
+                |horizontal_positions: &[f64],
+                 vertical_positions: &[f64],
+                 magnitudes_over_separation: &[f64],
+                 horizontal_forces: &mut [f64],
+                 vertical_forces: &mut [f64]| {
+                    for partial_sum_index in 0..horizontal_positions.len() {
+                        let horizontal_force = (first_horizontal_position
+                            - horizontal_positions[partial_sum_index])
+                            * magnitudes_over_separation[partial_sum_index];
+                        let vertical_force = (first_vertical_position
+                            - vertical_positions[partial_sum_index])
+                            * magnitudes_over_separation[partial_sum_index];
+                        horizontal_partial_sums[partial_sum_index] += horizontal_force;
+                        vertical_partial_sums[partial_sum_index] += vertical_force;
+                        horizontal_forces[partial_sum_index] -= horizontal_force;
+                        vertical_forces[partial_sum_index] -= vertical_force;
+                    }
+                };
+            let mut horizontal_position_blocks =
+                later_horizontal_positions.chunks_exact(PARTIAL_SUMS_PER_PARTICLE);
+            let mut vertical_position_blocks =
+                later_vertical_positions.chunks_exact(PARTIAL_SUMS_PER_PARTICLE);
+            let mut magnitude_blocks =
+                magnitudes_over_separation.chunks_exact(PARTIAL_SUMS_PER_PARTICLE);
+            let mut horizontal_force_blocks = self.horizontal_forces[later_range.clone()]
+                .chunks_exact_mut(PARTIAL_SUMS_PER_PARTICLE);
+            let mut vertical_force_blocks =
+                self.vertical_forces[later_range].chunks_exact_mut(PARTIAL_SUMS_PER_PARTICLE);
+            for (
+                (
+                    ((horizontal_position_block, vertical_position_block), magnitude_block),
+                    horizontal_force_block,
+                ),
+                vertical_force_block,
+            ) in (&mut horizontal_position_blocks)
+                .zip(&mut vertical_position_blocks)
+                .zip(&mut magnitude_blocks)
+                .zip(&mut horizontal_force_blocks)
+                .zip(&mut vertical_force_blocks)
+            {
+                add_block_of_forces(
+                    horizontal_position_block,
+                    vertical_position_block,
+                    magnitude_block,
+                    horizontal_force_block,
+                    vertical_force_block,
+                );
+            }
+            add_block_of_forces(
+                horizontal_position_blocks.remainder(),
+                vertical_position_blocks.remainder(),
+                magnitude_blocks.remainder(),
+                horizontal_force_blocks.into_remainder(),
+                vertical_force_blocks.into_remainder(),
+            );
+            self.horizontal_forces[first_index] += horizontal_partial_sums.iter().sum::<f64>();
+            self.vertical_forces[first_index] += vertical_partial_sums.iter().sum::<f64>();
+        }
+        true
+    }
+}
+
+impl super::CollectionInForceField for ParticleArraysWithForceField {
+    type MutableElement = super::contiguous_struct::MassNormalizedWithForceField;
+    type FixedSizeCollection = Self;
+    fn access_mutable_elements(&mut self) -> &mut Self::FixedSizeCollection {
+        self
+    }
+
+    fn add_particle(
+        &mut self,
+        particle_to_add: &impl super::IndividualRepresentation,
+        timestep_over_inertial_mass: &super::super::time::OverMassUnit,
+    ) {
+        let particle_intrinsics = particle_to_add.read_intrinsics();
+        let particle_variables = particle_to_add.read_variables();
+        self.horizontal_positions
+            .push(particle_variables.position_vector.horizontal_component.0);
+        self.vertical_positions
+            .push(particle_variables.position_vector.vertical_component.0);
+        self.horizontal_velocities
+            .push(particle_variables.velocity_vector.horizontal_component.0);
+        self.vertical_velocities
+            .push(particle_variables.velocity_vector.vertical_component.0);
+        self.inverse_squared_charges
+            .push(particle_intrinsics.inverse_squared_charge.0);
+        self.inverse_fourth_charges
+            .push(particle_intrinsics.inverse_fourth_charge.0);
+        self.horizontal_forces.push(0.0);
+        self.vertical_forces.push(0.0);
+        self.inertial_masses.push(particle_intrinsics.inertial_mass);
+        self.species_indices.push(particle_intrinsics.species_index);
+        self.collision_radii
+            .push(particle_intrinsics.collision_radius);
+        self.tracer_flags.push(particle_intrinsics.is_tracer);
+        self.color_brightnesses
+            .push(particle_intrinsics.color_brightness);
+        self.timesteps_over_inertial_mass
+            .push(timestep_over_inertial_mass.0);
+    }
+
+    fn remove_particle(&mut self, particle_index: usize) {
+        self.horizontal_positions.remove(particle_index);
+        self.vertical_positions.remove(particle_index);
+        self.horizontal_velocities.remove(particle_index);
+        self.vertical_velocities.remove(particle_index);
+        self.inverse_squared_charges.remove(particle_index);
+        self.inverse_fourth_charges.remove(particle_index);
+        self.horizontal_forces.remove(particle_index);
+        self.vertical_forces.remove(particle_index);
+        self.inertial_masses.remove(particle_index);
+        self.species_indices.remove(particle_index);
+        self.collision_radii.remove(particle_index);
+        self.tracer_flags.remove(particle_index);
+        self.color_brightnesses.remove(particle_index);
+        self.timesteps_over_inertial_mass.remove(particle_index);
+    }
+}
+
+pub struct ParticleArraysWithForceFieldGenerator {}
+
+impl super::CollectionInForceFieldGenerator for ParticleArraysWithForceFieldGenerator {
+    type MutableElement = super::contiguous_struct::MassNormalizedWithForceField;
+    type CreatedCollection = ParticleArraysWithForceField;
+
+    fn create_collection(&self) -> Self::CreatedCollection {
+        ParticleArraysWithForceField {
+            horizontal_positions: vec![],
+            vertical_positions: vec![],
+            horizontal_velocities: vec![],
+            vertical_velocities: vec![],
+            inverse_squared_charges: vec![],
+            inverse_fourth_charges: vec![],
+            horizontal_forces: vec![],
+            vertical_forces: vec![],
+            inertial_masses: vec![],
+            species_indices: vec![],
+            collision_radii: vec![],
+            tracer_flags: vec![],
+            color_brightnesses: vec![],
+            timesteps_over_inertial_mass: vec![],
+            magnitudes_over_separation: vec![],
+            gathered_particles: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::CollectionInForceField;
+    use super::super::CollectionInForceFieldGenerator;
+    use super::super::IndividualRepresentation;
+    use super::super::ReadOnlyInForceField;
+    use super::super::WritableInForceField;
+    use super::*;
+
+    /// This is not a multiple of the number of partial sums, so that the remainder of the later
+    /// particles after the full blocks is also covered.
+    const TEST_NUMBER_OF_PARTICLES: usize = 11;
+    const TEST_INVERSE_SQUARED_CONSTANT: f64 = -1.0;
+    const TEST_INVERSE_FOURTH_CONSTANT: f64 = 2.0;
+
+    /// Every component of each test particle depends on its index in a different way, so that
+    /// any mix-up between the vectors shows up.
+    fn create_test_particle(particle_index: usize) -> super::super::BasicIndividual {
+        let index_value = particle_index as f64;
+        super::super::BasicIndividual {
+            intrinsic_values: super::super::IntrinsicPart {
+                inertial_mass: super::super::super::charge::InertialMassUnit(1.0 + index_value),
+                inverse_squared_charge: super::super::super::charge::InverseSquaredChargeUnit(
+                    2.0 - index_value,
+                ),
+                inverse_fourth_charge: super::super::super::charge::InverseFourthChargeUnit(
+                    0.5 * index_value,
+                ),
+                species_index: super::super::super::charge::SpeciesIndex(particle_index % 3),
+                collision_radius: super::super::super::position::SeparationUnit(0.1 * index_value),
+                is_tracer: (particle_index % 4) == 1,
+                color_brightness: super::super::super::color::new_triplet(
+                    super::super::super::color::RedUnit(index_value),
+                    super::super::super::color::GreenUnit(2.0 * index_value),
+                    super::super::super::color::BlueUnit(3.0 * index_value),
+                ),
+            },
+            variable_values: super::super::VariablePart {
+                position_vector: super::super::super::position::DimensionfulVector {
+                    horizontal_component: super::super::super::position::HorizontalUnit(
+                        1.5 * index_value,
+                    ),
+                    vertical_component: super::super::super::position::VerticalUnit(
+                        ((particle_index * particle_index) % 7) as f64,
+                    ),
+                },
+                velocity_vector: super::super::super::velocity::DimensionfulVector {
+                    horizontal_component: super::super::super::velocity::HorizontalUnit(
+                        -index_value,
+                    ),
+                    vertical_component: super::super::super::velocity::VerticalUnit(
+                        0.25 * index_value,
+                    ),
+                },
+            },
+        }
+    }
+
+    fn create_test_timestep_over_inertial_mass(
+        particle_index: usize,
+    ) -> super::super::super::time::OverMassUnit {
+        super::super::super::time::OverMassUnit(1.0 / (1.0 + (particle_index as f64)))
+    }
+
+    fn create_test_collection() -> ParticleArraysWithForceField {
+        let mut test_collection = ParticleArraysWithForceFieldGenerator {}.create_collection();
+        for particle_index in 0..TEST_NUMBER_OF_PARTICLES {
+            test_collection.add_particle(
+                &create_test_particle(particle_index),
+                &create_test_timestep_over_inertial_mass(particle_index),
+            );
+        }
+        test_collection
+    }
+
+    /// This returns a description of every difference between the given particle and the test
+    /// particle for the given index, apart from the variables if they are not to be compared.
+    fn describe_differences_from_test_particle(
+        given_particle: &super::super::contiguous_struct::MassNormalizedWithForceField,
+        particle_index: usize,
+        are_variables_compared: bool,
+    ) -> std::vec::Vec<String> {
+        let expected_particle = create_test_particle(particle_index);
+        let expected_intrinsics = expected_particle.read_intrinsics();
+        let actual_intrinsics = given_particle.read_intrinsics();
+        let mut difference_messages = vec![];
+        if (actual_intrinsics.inertial_mass != expected_intrinsics.inertial_mass)
+            || (actual_intrinsics.inverse_squared_charge
+                != expected_intrinsics.inverse_squared_charge)
+            || (actual_intrinsics.inverse_fourth_charge
+                != expected_intrinsics.inverse_fourth_charge)
+            || (actual_intrinsics.species_index != expected_intrinsics.species_index)
+            || (actual_intrinsics.collision_radius.0 != expected_intrinsics.collision_radius.0)
+            || (actual_intrinsics.is_tracer != expected_intrinsics.is_tracer)
+            || (actual_intrinsics.color_brightness != expected_intrinsics.color_brightness)
+        {
+            difference_messages.push(format!(
+                "particle {}: expected intrinsics {:?}, actual {:?}",
+                particle_index, expected_intrinsics, actual_intrinsics
+            ));
+        }
+        let expected_variables = expected_particle.read_variables();
+        let actual_variables = given_particle.read_variables();
+        if are_variables_compared
+            && ((actual_variables.position_vector.horizontal_component
+                != expected_variables.position_vector.horizontal_component)
+                || (actual_variables.position_vector.vertical_component
+                    != expected_variables.position_vector.vertical_component)
+                || (actual_variables.velocity_vector.horizontal_component
+                    != expected_variables.velocity_vector.horizontal_component)
+                || (actual_variables.velocity_vector.vertical_component
+                    != expected_variables.velocity_vector.vertical_component))
+        {
+            difference_messages.push(format!(
+                "particle {}: expected variables {:?}, actual {:?}",
+                particle_index, expected_variables, actual_variables
+            ));
+        }
+        if given_particle.read_timestep_over_inertial_mass().0
+            != create_test_timestep_over_inertial_mass(particle_index).0
+        {
+            difference_messages.push(format!(
+                "particle {}: expected timestep over inertial mass {:?}, actual {:?}",
+                particle_index,
+                create_test_timestep_over_inertial_mass(particle_index),
+                given_particle.read_timestep_over_inertial_mass()
+            ));
+        }
+        difference_messages
+    }
+
+    fn read_forces(
+        particles_with_forces: &mut impl SingleAndPairwiseFinite<
+            MutableElement = super::super::contiguous_struct::MassNormalizedWithForceField,
+        >,
+    ) -> std::vec::Vec<(f64, f64)> {
+        let mut read_forces = vec![];
+        particles_with_forces.apply_to_every_single(&mut |particle_with_force| {
+            let experienced_force = particle_with_force.read_experienced_force();
+            read_forces.push((
+                experienced_force.horizontal_component.0,
+                experienced_force.vertical_component.0,
+            ));
+        });
+        read_forces
+    }
+
+    /// This is the force magnitude over the separation of the original law without a dead zone.
+    fn test_magnitude_over_separation(
+        inverse_squared_coupling: f64,
+        inverse_fourth_coupling: f64,
+        squared_separation: f64,
+    ) -> f64 {
+        let inverse_squared_separation = 1.0 / squared_separation;
+        let inverse_separation = inverse_squared_separation.sqrt();
+        inverse_separation
+            * inverse_squared_separation
+            * (inverse_squared_coupling + (inverse_fourth_coupling * inverse_squared_separation))
+    }
+
+    /// This sums the forces over every pair in the same way as the force laws do for collections
+    /// which do not sum the forces over their own arrays.
+    fn sum_forces_over_every_pair(
+        particles_with_forces: &mut impl SingleAndPairwiseFinite<
+            MutableElement = super::super::contiguous_struct::MassNormalizedWithForceField,
+        >,
+    ) {
+        particles_with_forces.apply_to_every_pair(
+            &|first_particle, second_particle| {
+                let first_intrinsics = first_particle.read_intrinsics();
+                let second_intrinsics = second_particle.read_intrinsics();
+                let separation_vector = first_particle.read_variables().position_vector
+                    - second_particle.read_variables().position_vector;
+                let magnitude_over_separation = test_magnitude_over_separation(
+                    TEST_INVERSE_SQUARED_CONSTANT
+                        * first_intrinsics.inverse_squared_charge.0
+                        * second_intrinsics.inverse_squared_charge.0,
+                    TEST_INVERSE_FOURTH_CONSTANT
+                        * first_intrinsics.inverse_fourth_charge.0
+                        * second_intrinsics.inverse_fourth_charge.0,
+                    super::super::super::position::square_separation_vector(&separation_vector).0,
+                );
+                super::super::super::force::DimensionfulVector {
+                    horizontal_component: super::super::super::force::HorizontalUnit(
+                        separation_vector.horizontal_component.0 * magnitude_over_separation,
+                    ),
+                    vertical_component: super::super::super::force::VerticalUnit(
+                        separation_vector.vertical_component.0 * magnitude_over_separation,
+                    ),
+                }
+            },
+            &|first_particle, force_on_first| {
+                *first_particle.write_experienced_force() += *force_on_first;
+            },
+            &|second_particle, force_on_first| {
+                *second_particle.write_experienced_force() -= *force_on_first;
+            },
+        );
+    }
+
+    #[test]
+    fn check_gathered_particles_match_added_particles() -> Result<(), String> {
+        let mut test_collection = create_test_collection();
+        let mut difference_messages = vec![];
+        let mut particle_index = 0;
+        test_collection.apply_to_every_single(&mut |gathered_particle| {
+            difference_messages.extend(describe_differences_from_test_particle(
+                gathered_particle,
+                particle_index,
+                true,
+            ));
+            particle_index += 1;
+        });
+        if particle_index != TEST_NUMBER_OF_PARTICLES {
+            difference_messages.push(format!(
+                "Expected {} particles, gathered {}",
+                TEST_NUMBER_OF_PARTICLES, particle_index
+            ));
+        }
+        if difference_messages.is_empty() {
+            Ok(())
+        } else {
+            Err(difference_messages.join("\n"))
+        }
+    }
+
+    #[test]
+    fn check_changes_to_gathered_particles_are_scattered_back() -> Result<(), String> {
+        let mut test_collection = create_test_collection();
+        let mut particle_index = 0;
+        test_collection.apply_to_every_single(&mut |gathered_particle| {
+            let index_value = particle_index as f64;
+            let particle_variables = gathered_particle.write_particle_variables();
+            particle_variables.position_vector.horizontal_component.0 += 10.0;
+            particle_variables.velocity_vector.vertical_component.0 = -index_value;
+            let experienced_force = gathered_particle.write_experienced_force();
+            experienced_force.horizontal_component.0 = 3.0 * index_value;
+            experienced_force.vertical_component.0 = -4.0 * index_value;
+            particle_index += 1;
+        });
+
+        let mut difference_messages = vec![];
+        particle_index = 0;
+        test_collection.apply_to_every_single(&mut |gathered_particle| {
+            let index_value = particle_index as f64;
+            difference_messages.extend(describe_differences_from_test_particle(
+                gathered_particle,
+                particle_index,
+                false,
+            ));
+            let mut expected_variables = *create_test_particle(particle_index).read_variables();
+            expected_variables.position_vector.horizontal_component.0 += 10.0;
+            expected_variables.velocity_vector.vertical_component.0 = -index_value;
+            let actual_variables = gathered_particle.read_variables();
+            let actual_force = gathered_particle.read_experienced_force();
+            if (actual_variables.position_vector.horizontal_component
+                != expected_variables.position_vector.horizontal_component)
+                || (actual_variables.position_vector.vertical_component
+                    != expected_variables.position_vector.vertical_component)
+                || (actual_variables.velocity_vector.horizontal_component
+                    != expected_variables.velocity_vector.horizontal_component)
+                || (actual_variables.velocity_vector.vertical_component
+                    != expected_variables.velocity_vector.vertical_component)
+                || (actual_force.horizontal_component.0 != (3.0 * index_value))
+                || (actual_force.vertical_component.0 != (-4.0 * index_value))
+            {
+                difference_messages.push(format!(
+                    "particle {}: expected variables {:?} and force ({}, {}), actual {:?} and {:?}",
+                    particle_index,
+                    expected_variables,
+                    3.0 * index_value,
+                    -4.0 * index_value,
+                    actual_variables,
+                    actual_force
+                ));
+            }
+            particle_index += 1;
+        });
+        if difference_messages.is_empty() {
+            Ok(())
+        } else {
+            Err(difference_messages.join("\n"))
+        }
+    }
+
+    #[test]
+    fn check_removal_keeps_components_of_each_particle_together() -> Result<(), String> {
+        let removed_index = 3;
+        let mut test_collection = create_test_collection();
+        test_collection.remove_particle(removed_index);
+        let mut difference_messages = vec![];
+        let mut remaining_index = 0;
+        test_collection.apply_to_every_single(&mut |gathered_particle| {
+            let original_index = if remaining_index < removed_index {
+                remaining_index
+            } else {
+                remaining_index + 1
+            };
+            difference_messages.extend(describe_differences_from_test_particle(
+                gathered_particle,
+                original_index,
+                true,
+            ));
+            remaining_index += 1;
+        });
+        if remaining_index != (TEST_NUMBER_OF_PARTICLES - 1) {
+            difference_messages.push(format!(
+                "Expected {} particles, gathered {}",
+                TEST_NUMBER_OF_PARTICLES - 1,
+                remaining_index
+            ));
+        }
+        if difference_messages.is_empty() {
+            Ok(())
+        } else {
+            Err(difference_messages.join("\n"))
+        }
+    }
+
+    #[test]
+    fn check_every_pair_matches_vector_of_structs() -> Result<(), String> {
+        let mut vector_of_structs: std::vec::Vec<_> = (0..TEST_NUMBER_OF_PARTICLES)
+            .map(|particle_index| {
+                super::super::contiguous_struct::new_mass_normalized_with_force_field(
+                    &create_test_particle(particle_index),
+                    &create_test_timestep_over_inertial_mass(particle_index),
+                )
+            })
+            .collect();
+        sum_forces_over_every_pair(&mut vector_of_structs);
+        let mut test_collection = create_test_collection();
+        sum_forces_over_every_pair(&mut test_collection);
+        let vector_forces = read_forces(&mut vector_of_structs);
+        let struct_of_arrays_forces = read_forces(&mut test_collection);
+        if struct_of_arrays_forces == vector_forces {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected {:?}, actual {:?}",
+                vector_forces, struct_of_arrays_forces
+            ))
+        }
+    }
+
+    #[test]
+    fn check_sum_over_arrays_matches_every_pair() -> Result<(), String> {
+        // The sums over the arrays are added up in a different order, so only agree to within
+        // rounding.
+        let relative_tolerance = 1.0e-12;
+        let mut pairwise_collection = create_test_collection();
+        sum_forces_over_every_pair(&mut pairwise_collection);
+        let mut summed_collection = create_test_collection();
+        if !summed_collection.set_forces_from_charges_and_separations(
+            TEST_INVERSE_SQUARED_CONSTANT,
+            TEST_INVERSE_FOURTH_CONSTANT,
+            &test_magnitude_over_separation,
+        ) {
+            return Err(String::from(
+                "Expected the forces to be summed over the arrays",
+            ));
+        }
+        let pairwise_forces = read_forces(&mut pairwise_collection);
+        let summed_forces = read_forces(&mut summed_collection);
+        if pairwise_forces
+            .iter()
+            .zip(summed_forces.iter())
+            .any(|(pairwise_force, summed_force)| {
+                let force_scale = pairwise_force.0.abs() + pairwise_force.1.abs();
+                ((pairwise_force.0 - summed_force.0).abs()
+                    + (pairwise_force.1 - summed_force.1).abs())
+                    > (relative_tolerance * force_scale)
+            })
+        {
+            Err(format!(
+                "Expected {:?} within relative tolerance {}, actual {:?}",
+                pairwise_forces, relative_tolerance, summed_forces
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn check_vector_of_structs_does_not_sum_over_arrays() -> Result<(), String> {
+        let mut vector_of_structs: std::vec::Vec<_> = (0..TEST_NUMBER_OF_PARTICLES)
+            .map(|particle_index| {
+                let mut particle_with_force =
+                    super::super::contiguous_struct::new_mass_normalized_with_force_field(
+                        &create_test_particle(particle_index),
+                        &create_test_timestep_over_inertial_mass(particle_index),
+                    );
+                particle_with_force
+                    .write_experienced_force()
+                    .horizontal_component
+                    .0 = particle_index as f64;
+                particle_with_force
+            })
+            .collect();
+        let initial_forces = read_forces(&mut vector_of_structs);
+        if vector_of_structs.set_forces_from_charges_and_separations(
+            TEST_INVERSE_SQUARED_CONSTANT,
+            TEST_INVERSE_FOURTH_CONSTANT,
+            &test_magnitude_over_separation,
+        ) {
+            return Err(String::from(
+                "Expected the vector of structs to fall back to summing over every pair",
+            ));
+        }
+        let final_forces = read_forces(&mut vector_of_structs);
+        if final_forces == initial_forces {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected forces to be left as {:?}, actual {:?}",
+                initial_forces, final_forces
+            ))
+        }
+    }
+}
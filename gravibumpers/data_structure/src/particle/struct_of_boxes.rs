@@ -83,6 +83,10 @@ impl super::CollectionInForceField for VectorOfDynamicBoxedMassNormalizedBoxesWi
             ),
         ));
     }
+
+    fn remove_particle(&mut self, particle_index: usize) {
+        self.0.remove(particle_index);
+    }
 }
 
 pub struct VectorOfDynamicBoxedMassNormalizedBoxesWithForceFieldGenerator {}
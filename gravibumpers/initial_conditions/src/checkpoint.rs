@@ -0,0 +1,568 @@
+/// This module provides the means to write the state of an evolution to a file every so many
+/// frames, and to read the state back in to resume the evolution. A checkpoint is itself a
+/// configuration, which is a copy of the configuration of the evolution with every particle given
+/// individually by the single generator, along with the index of the frame which the particles
+/// represent. The number of frames is left as the total for the uninterrupted evolution. Any spring
+/// bonds are written with the later particle of each bond, which keeps the indices of the bonded
+/// particles as the particles are written in the same order as they were generated. Any motion
+/// modes are written with their times counted from the start of the uninterrupted evolution, so a
+/// resumed evolution has to count them from the time of the checkpoint. Any thermostat is written
+/// with the state of its random generator at the frame, so that the resumed evolution gets the
+/// same random kicks as the uninterrupted evolution.
+use data_structure::particle::IndividualRepresentation;
+
+const CHECKPOINT_FRAME_INDEX_LABEL: &str = "checkpointFrameIndex";
+const SINGLE_GENERATOR_NAME: &str = "single";
+
+pub fn create_checkpoint_configuration(
+    original_configuration: &serde_json::Value,
+    species_interactions: Option<&configuration_parsing::SpeciesInteractions>,
+    spring_bonds: &[configuration_parsing::SpringBond],
+    motion_modes: &[configuration_parsing::MotionMode],
+    thermostat_generator_state: Option<[u64; 4]>,
+    frame_index: usize,
+    particles_at_frame: impl std::iter::Iterator<Item = impl IndividualRepresentation>,
+) -> serde_json::Value {
+    let mut checkpoint_configuration = original_configuration.clone();
+    checkpoint_configuration[configuration_parsing::GENERATOR_CONFIGURATIONS_LABEL] =
+        serde_json::Value::Array(
+            particles_at_frame
+                .enumerate()
+                .map(|(particle_index, particle_at_frame)| {
+                    let bonds_to_earlier_particles = spring_bonds
+                        .iter()
+                        .filter(|spring_bond| spring_bond.second_particle_index == particle_index)
+                        .copied()
+                        .collect::<std::vec::Vec<_>>();
+                    serde_json::json!(
+                        {
+                            configuration_parsing::GENERATOR_NAME_LABEL: SINGLE_GENERATOR_NAME,
+                            configuration_parsing::GENERATOR_CONFIGURATION_LABEL:
+                                super::single::to_json(
+                                    &particle_at_frame,
+                                    species_interactions,
+                                    &bonds_to_earlier_particles,
+                                    motion_modes
+                                        .get(particle_index)
+                                        .unwrap_or(&configuration_parsing::MotionMode::Free),
+                                )
+                        }
+                    )
+                })
+                .collect(),
+        );
+    if let Some(generator_state) = thermostat_generator_state {
+        checkpoint_configuration[configuration_parsing::DRAG_LABEL]
+            [configuration_parsing::GENERATOR_STATE_LABEL] = serde_json::json!(generator_state);
+    }
+    checkpoint_configuration[CHECKPOINT_FRAME_INDEX_LABEL] = serde_json::json!(frame_index);
+    checkpoint_configuration
+}
+
+/// The checkpoint is written to a temporary file which then replaces any previous checkpoint, so
+/// that an evolution which is stopped while writing still leaves the previous checkpoint intact.
+pub fn write_checkpoint_file(
+    checkpoint_configuration: &serde_json::Value,
+    checkpoint_filename: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let temporary_filename = format!("{}.partial", checkpoint_filename);
+    std::fs::write(
+        &temporary_filename,
+        serde_json::to_string_pretty(checkpoint_configuration)?,
+    )?;
+    std::fs::rename(&temporary_filename, checkpoint_filename)?;
+    Ok(())
+}
+
+/// The frame of the checkpoint becomes the first frame of the resumed evolution, so it has to be
+/// before the last frame of the uninterrupted evolution.
+pub fn parse_frame_index(
+    checkpoint_configuration: &serde_json::Value,
+    number_of_frames: usize,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let frame_index = configuration_parsing::parse_i64_as_usize(
+        CHECKPOINT_FRAME_INDEX_LABEL,
+        checkpoint_configuration,
+    )?;
+    if frame_index >= number_of_frames {
+        return Err(Box::new(
+            configuration_parsing::ConfigurationParseError::new(&format!(
+                "{} is {} but there are only {} frames",
+                CHECKPOINT_FRAME_INDEX_LABEL, frame_index, number_of_frames
+            )),
+        ));
+    }
+    Ok(frame_index)
+}
+
+/// The first frame index is 0 for a new evolution, and the frame index of the checkpoint for a
+/// resumed evolution. The state of the random generator of any thermostat is given for each frame
+/// index by a function, as it is calculated by the time evolution.
+pub struct CheckpointWriter<'a> {
+    original_configuration: &'a serde_json::Value,
+    species_interactions: Option<&'a configuration_parsing::SpeciesInteractions>,
+    spring_bonds: &'a [configuration_parsing::SpringBond],
+    motion_modes: &'a [configuration_parsing::MotionMode],
+    thermostat_generator_state_at_frame: &'a dyn Fn(usize) -> Option<[u64; 4]>,
+    frames_per_checkpoint: usize,
+    checkpoint_filename: &'a str,
+    first_frame_index: usize,
+}
+
+pub fn new_writer<'a>(
+    original_configuration: &'a serde_json::Value,
+    species_interactions: Option<&'a configuration_parsing::SpeciesInteractions>,
+    spring_bonds: &'a [configuration_parsing::SpringBond],
+    motion_modes: &'a [configuration_parsing::MotionMode],
+    thermostat_generator_state_at_frame: &'a dyn Fn(usize) -> Option<[u64; 4]>,
+    checkpoint_configuration: &configuration_parsing::CheckpointConfiguration<'a>,
+    first_frame_index: usize,
+) -> CheckpointWriter<'a> {
+    CheckpointWriter {
+        original_configuration,
+        species_interactions,
+        spring_bonds,
+        motion_modes,
+        thermostat_generator_state_at_frame,
+        frames_per_checkpoint: checkpoint_configuration.frames_per_checkpoint,
+        checkpoint_filename: checkpoint_configuration.checkpoint_filename,
+        first_frame_index,
+    }
+}
+
+/// This passes on the frames of the wrapped sequence unchanged, writing a checkpoint whenever the
+/// index of the frame is a multiple of the number of frames per checkpoint. The first frame is
+/// never written, as it is either the initial conditions or the checkpoint which was resumed.
+pub struct CheckpointingSequence<'a, FrameSequence> {
+    frame_sequence: FrameSequence,
+    checkpoint_writer: Option<&'a CheckpointWriter<'a>>,
+    next_frame_index: usize,
+}
+
+/// Without a writer, the frames are just passed on.
+pub fn write_periodically<'a, FrameSequence>(
+    frame_sequence: FrameSequence,
+    checkpoint_writer: Option<&'a CheckpointWriter<'a>>,
+) -> CheckpointingSequence<'a, FrameSequence> {
+    CheckpointingSequence {
+        frame_sequence,
+        checkpoint_writer,
+        next_frame_index: checkpoint_writer
+            .map(|checkpoint_writer| checkpoint_writer.first_frame_index)
+            .unwrap_or(0),
+    }
+}
+
+impl<'a, FrameSequence, ParticleIterator> std::iter::Iterator
+    for CheckpointingSequence<'a, FrameSequence>
+where
+    FrameSequence: std::iter::Iterator<Item = Result<ParticleIterator, Box<dyn std::error::Error>>>,
+    ParticleIterator: std::iter::Iterator + Clone,
+    ParticleIterator::Item: IndividualRepresentation,
+{
+    type Item = Result<ParticleIterator, Box<dyn std::error::Error>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let time_slice = self.frame_sequence.next()?;
+        let frame_index = self.next_frame_index;
+        self.next_frame_index += 1;
+        let checkpoint_writer = match self.checkpoint_writer {
+            Some(checkpoint_writer)
+                if (frame_index > checkpoint_writer.first_frame_index)
+                    && frame_index.is_multiple_of(checkpoint_writer.frames_per_checkpoint) =>
+            {
+                checkpoint_writer
+            }
+            _ => return Some(time_slice),
+        };
+        let particles_at_frame = match time_slice {
+            Ok(particles_at_frame) => particles_at_frame,
+            Err(evolution_error) => return Some(Err(evolution_error)),
+        };
+        let checkpoint_configuration = create_checkpoint_configuration(
+            checkpoint_writer.original_configuration,
+            checkpoint_writer.species_interactions,
+            checkpoint_writer.spring_bonds,
+            checkpoint_writer.motion_modes,
+            (checkpoint_writer.thermostat_generator_state_at_frame)(frame_index),
+            frame_index,
+            particles_at_frame.clone(),
+        );
+        match write_checkpoint_file(
+            &checkpoint_configuration,
+            checkpoint_writer.checkpoint_filename,
+        ) {
+            Ok(_) => Some(Ok(particles_at_frame)),
+            Err(writing_error) => Some(Err(writing_error)),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.frame_sequence.size_hint()
+    }
+}
+
+impl<'a, FrameSequence, ParticleIterator> std::iter::ExactSizeIterator
+    for CheckpointingSequence<'a, FrameSequence>
+where
+    FrameSequence:
+        std::iter::ExactSizeIterator<Item = Result<ParticleIterator, Box<dyn std::error::Error>>>,
+    ParticleIterator: std::iter::Iterator + Clone,
+    ParticleIterator::Item: IndividualRepresentation,
+{
+    fn len(&self) -> usize {
+        self.frame_sequence.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_particle(particle_index: usize) -> data_structure::particle::BasicIndividual {
+        let particle_number = particle_index as f64;
+        data_structure::particle::BasicIndividual {
+            intrinsic_values: data_structure::particle::IntrinsicPart {
+                inertial_mass: data_structure::charge::InertialMassUnit(1.0 + particle_number),
+                inverse_squared_charge: data_structure::charge::InverseSquaredChargeUnit(-0.5),
+                inverse_fourth_charge: data_structure::charge::InverseFourthChargeUnit(0.25),
+                species_index: data_structure::charge::SpeciesIndex(0),
+                collision_radius: data_structure::position::SeparationUnit(0.0),
+                is_tracer: false,
+                color_brightness: data_structure::color::new_triplet(
+                    data_structure::color::RedUnit(1.0),
+                    data_structure::color::GreenUnit(0.0),
+                    data_structure::color::BlueUnit(0.5),
+                ),
+            },
+            variable_values: data_structure::particle::VariablePart {
+                position_vector: data_structure::position::DimensionfulVector {
+                    horizontal_component: data_structure::position::HorizontalUnit(
+                        particle_number / 3.0,
+                    ),
+                    vertical_component: data_structure::position::VerticalUnit(-0.1),
+                },
+                velocity_vector: data_structure::velocity::DimensionfulVector {
+                    horizontal_component: data_structure::velocity::HorizontalUnit(0.2),
+                    vertical_component: data_structure::velocity::VerticalUnit(
+                        particle_number / 7.0,
+                    ),
+                },
+            },
+        }
+    }
+
+    fn create_test_configuration() -> serde_json::Value {
+        serde_json::json!(
+            {
+                "numberOfFrames": 10,
+                configuration_parsing::GENERATOR_CONFIGURATIONS_LABEL: [
+                    {
+                        configuration_parsing::GENERATOR_NAME_LABEL: "circle",
+                        configuration_parsing::GENERATOR_CONFIGURATION_LABEL: {}
+                    }
+                ]
+            }
+        )
+    }
+
+    #[test]
+    fn check_checkpoint_gives_back_particles_and_frame_index() -> Result<(), String> {
+        let expected_particles: std::vec::Vec<data_structure::particle::BasicIndividual> =
+            (0..3).map(create_test_particle).collect();
+        let checkpoint_configuration = create_checkpoint_configuration(
+            &create_test_configuration(),
+            None,
+            &[],
+            &[],
+            None,
+            4,
+            expected_particles.iter(),
+        );
+
+        let generator_configurations = checkpoint_configuration
+            [configuration_parsing::GENERATOR_CONFIGURATIONS_LABEL]
+            .as_array()
+            .ok_or_else(|| format!("No generators in {}", checkpoint_configuration))?;
+        let mut actual_particles = vec![];
+        for generator_configuration in generator_configurations {
+            if generator_configuration[configuration_parsing::GENERATOR_NAME_LABEL]
+                != SINGLE_GENERATOR_NAME
+            {
+                return Err(format!(
+                    "Expected only single generators, got {}",
+                    generator_configuration
+                ));
+            }
+            actual_particles.extend(
+                super::super::single::from_json(
+                    &generator_configuration[configuration_parsing::GENERATOR_CONFIGURATION_LABEL],
+                    None,
+                )
+                .map_err(|parsing_error| format!("Could not parse: {:?}", parsing_error))?,
+            );
+        }
+        let frame_index = parse_frame_index(&checkpoint_configuration, 10)
+            .map_err(|parsing_error| format!("Could not parse: {:?}", parsing_error))?;
+
+        if (format!("{:?}", actual_particles) == format!("{:?}", expected_particles))
+            && (frame_index == 4)
+            && (checkpoint_configuration["numberOfFrames"] == 10)
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected {:?} at frame 4 of 10, got {}",
+                expected_particles, checkpoint_configuration
+            ))
+        }
+    }
+
+    #[test]
+    fn check_checkpoint_keeps_spring_bonds() -> Result<(), String> {
+        let new_test_bond =
+            |first_particle_index, second_particle_index| configuration_parsing::SpringBond {
+                first_particle_index,
+                second_particle_index,
+                rest_length: 1.0,
+                stiffness: 2.0,
+                damping_coefficient: 0.5,
+            };
+        let expected_bonds = vec![
+            new_test_bond(0, 2),
+            new_test_bond(1, 2),
+            new_test_bond(0, 1),
+        ];
+        let checkpoint_configuration = create_checkpoint_configuration(
+            &create_test_configuration(),
+            None,
+            &expected_bonds,
+            &[],
+            None,
+            4,
+            (0..3).map(create_test_particle),
+        );
+
+        let generator_configurations = checkpoint_configuration
+            [configuration_parsing::GENERATOR_CONFIGURATIONS_LABEL]
+            .as_array()
+            .ok_or_else(|| format!("No generators in {}", checkpoint_configuration))?;
+        let mut actual_bonds = vec![];
+        for (particle_index, generator_configuration) in generator_configurations.iter().enumerate()
+        {
+            actual_bonds.extend(
+                super::super::single::spring_bonds_from_json(
+                    &generator_configuration[configuration_parsing::GENERATOR_CONFIGURATION_LABEL],
+                    particle_index,
+                )
+                .map_err(|parsing_error| format!("Could not parse: {:?}", parsing_error))?,
+            );
+        }
+        let expected_in_order = vec![
+            new_test_bond(0, 1),
+            new_test_bond(0, 2),
+            new_test_bond(1, 2),
+        ];
+        if actual_bonds == expected_in_order {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected {:?}, got {:?} from {}",
+                expected_in_order, actual_bonds, checkpoint_configuration
+            ))
+        }
+    }
+
+    #[test]
+    fn check_reject_frame_index_at_end_of_evolution() -> Result<(), String> {
+        let checkpoint_configuration = create_checkpoint_configuration(
+            &create_test_configuration(),
+            None,
+            &[],
+            &[],
+            None,
+            10,
+            (0..1).map(create_test_particle),
+        );
+        if parse_frame_index(&checkpoint_configuration, 10).is_err() {
+            Ok(())
+        } else {
+            Err(String::from("Did not get an error"))
+        }
+    }
+
+    #[test]
+    fn check_reject_missing_frame_index() -> Result<(), String> {
+        if parse_frame_index(&create_test_configuration(), 10).is_err() {
+            Ok(())
+        } else {
+            Err(String::from("Did not get an error"))
+        }
+    }
+
+    #[test]
+    fn check_checkpoints_written_only_at_multiples_after_first_frame() -> Result<(), String> {
+        let checkpoint_filename = std::env::temp_dir().join(format!(
+            "gravibumpers_checkpoint_test_{}.json",
+            std::process::id()
+        ));
+        let checkpoint_filename = checkpoint_filename
+            .to_str()
+            .ok_or_else(|| String::from("Temporary directory is not valid UTF-8"))?;
+        let original_configuration = create_test_configuration();
+        let checkpoint_writer = new_writer(
+            &original_configuration,
+            None,
+            &[],
+            &[],
+            &|_| None,
+            &configuration_parsing::CheckpointConfiguration {
+                frames_per_checkpoint: 3,
+                checkpoint_filename,
+            },
+            3,
+        );
+
+        // The sequence starts at frame 3, as if resumed from a checkpoint there, so that frame
+        // should not be written again, and the last checkpoint should be from frame 6 of frames 3
+        // to 7.
+        let frame_sequence = (0..5).map(|particle_count| {
+            Ok::<_, Box<dyn std::error::Error>>(
+                (0..particle_count)
+                    .map(create_test_particle)
+                    .collect::<std::vec::Vec<data_structure::particle::BasicIndividual>>()
+                    .into_iter(),
+            )
+        });
+        let _ = std::fs::remove_file(checkpoint_filename);
+        let mut checkpointing_sequence =
+            write_periodically(frame_sequence, Some(&checkpoint_writer));
+        let mut particle_counts = vec![];
+        let mut checkpoint_frame_indices = vec![];
+        for time_slice in &mut checkpointing_sequence {
+            let particles_at_frame = time_slice
+                .map_err(|sequence_error| format!("Sequence error: {:?}", sequence_error))?;
+            particle_counts.push(particles_at_frame.len());
+            checkpoint_frame_indices.push(
+                std::fs::read_to_string(checkpoint_filename)
+                    .ok()
+                    .and_then(|checkpoint_content| {
+                        serde_json::from_str::<serde_json::Value>(&checkpoint_content).ok()
+                    })
+                    .and_then(|checkpoint_configuration| {
+                        checkpoint_configuration[CHECKPOINT_FRAME_INDEX_LABEL].as_u64()
+                    }),
+            );
+        }
+        let _ = std::fs::remove_file(checkpoint_filename);
+
+        if (particle_counts == vec![0, 1, 2, 3, 4])
+            && (checkpoint_frame_indices == vec![None, None, None, Some(6), Some(6)])
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected particle counts [0, 1, 2, 3, 4] and checkpoints [None, None, None, \
+                Some(6), Some(6)], got {:?} and {:?}",
+                particle_counts, checkpoint_frame_indices
+            ))
+        }
+    }
+
+    #[test]
+    fn check_checkpoint_keeps_motion_modes() -> Result<(), String> {
+        let anchored_particle = create_test_particle(1);
+        let expected_modes = vec![
+            configuration_parsing::MotionMode::Free,
+            configuration_parsing::MotionMode::Fixed {
+                horizontal_coordinate: anchored_particle
+                    .variable_values
+                    .position_vector
+                    .horizontal_component
+                    .0,
+                vertical_coordinate: anchored_particle
+                    .variable_values
+                    .position_vector
+                    .vertical_component
+                    .0,
+            },
+            configuration_parsing::MotionMode::Keyframes(vec![configuration_parsing::Keyframe {
+                time_in_seconds: 2.5,
+                horizontal_coordinate: -1.0,
+                vertical_coordinate: 3.0,
+            }]),
+        ];
+        let checkpoint_configuration = create_checkpoint_configuration(
+            &create_test_configuration(),
+            None,
+            &[],
+            &expected_modes,
+            None,
+            4,
+            (0..3).map(create_test_particle),
+        );
+
+        let generator_configurations = checkpoint_configuration
+            [configuration_parsing::GENERATOR_CONFIGURATIONS_LABEL]
+            .as_array()
+            .ok_or_else(|| format!("No generators in {}", checkpoint_configuration))?;
+        let mut actual_modes = vec![];
+        for generator_configuration in generator_configurations {
+            let given_configuration =
+                &generator_configuration[configuration_parsing::GENERATOR_CONFIGURATION_LABEL];
+            let read_particles = super::super::single::from_json(given_configuration, None)
+                .map_err(|parsing_error| format!("Could not parse: {:?}", parsing_error))?;
+            actual_modes.extend(
+                super::super::single::motion_modes_from_json(given_configuration, &read_particles)
+                    .map_err(|parsing_error| format!("Could not parse: {:?}", parsing_error))?,
+            );
+        }
+        if actual_modes == expected_modes {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected {:?}, got {:?} from {}",
+                expected_modes, actual_modes, checkpoint_configuration
+            ))
+        }
+    }
+
+    #[test]
+    fn check_checkpoint_keeps_thermostat_generator_state() -> Result<(), String> {
+        let mut original_configuration = create_test_configuration();
+        original_configuration[configuration_parsing::DRAG_LABEL] = serde_json::json!(
+            {
+                "dragRatePerSecond": 2.0,
+                "targetTemperature": 0.5,
+                "randomSeed": 3,
+            }
+        );
+        let expected_state = [u64::MAX, 0, 12_345_678_901_234_567_890, 7];
+        let checkpoint_configuration = create_checkpoint_configuration(
+            &original_configuration,
+            None,
+            &[],
+            &[],
+            Some(expected_state),
+            4,
+            (0..3).map(create_test_particle),
+        );
+
+        let parsed_drag = configuration_parsing::parse_drag_configuration(
+            &checkpoint_configuration[configuration_parsing::DRAG_LABEL],
+        )
+        .map_err(|parsing_error| format!("Could not parse: {:?}", parsing_error))?;
+        let expected_thermostat = configuration_parsing::ThermostatConfiguration {
+            target_temperature: 0.5,
+            random_seed: 3,
+            generator_state: Some(expected_state),
+        };
+        if parsed_drag.thermostat == Some(expected_thermostat) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected {:?}, got {:?} from {}",
+                expected_thermostat, parsed_drag, checkpoint_configuration
+            ))
+        }
+    }
+}
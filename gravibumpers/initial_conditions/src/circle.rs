@@ -17,9 +17,11 @@ const INVERSE_FOURTH_CHARGE_IN_DIMENSIONLESS_UNITS_LABEL: &str =
 const RED_PIXEL_STRENGTH_LABEL: &str = "redPixelStrength";
 const GREEN_PIXEL_STRENGTH_LABEL: &str = "greenPixelStrength";
 const BLUE_PIXEL_STRENGTH_LABEL: &str = "bluePixelStrength";
+const NEIGHBOURS_ON_EACH_SIDE_LABEL: &str = "neighboursOnEachSide";
 
 pub fn from_json(
     given_configuration: &serde_json::Value,
+    species_interactions: Option<&super::configuration_parsing::SpeciesInteractions>,
 ) -> Result<std::vec::Vec<data_structure::particle::BasicIndividual>, Box<dyn std::error::Error>> {
     let circle_displacement =
         super::parse_position(&given_configuration[COMMON_DISPLACEMENT_IN_PIXELS_LABEL])?;
@@ -53,6 +55,9 @@ pub fn from_json(
         super::configuration_parsing::parse_f64(GREEN_PIXEL_STRENGTH_LABEL, given_configuration)?;
     let blue_brightness =
         super::configuration_parsing::parse_f64(BLUE_PIXEL_STRENGTH_LABEL, given_configuration)?;
+    let species_index = super::parse_species(given_configuration, species_interactions)?;
+    let collision_radius = super::parse_collision_radius(given_configuration)?;
+    let is_tracer = super::parse_tracer_flag(given_configuration)?;
     let common_intrinsics = data_structure::particle::IntrinsicPart {
         inertial_mass: data_structure::charge::InertialMassUnit(inertial_mass),
         inverse_squared_charge: data_structure::charge::InverseSquaredChargeUnit(
@@ -61,6 +66,9 @@ pub fn from_json(
         inverse_fourth_charge: data_structure::charge::InverseFourthChargeUnit(
             inverse_fourth_charge,
         ),
+        species_index,
+        collision_radius,
+        is_tracer,
         color_brightness: data_structure::color::new_triplet(
             data_structure::color::RedUnit(red_brightness),
             data_structure::color::GreenUnit(green_brightness),
@@ -201,6 +209,99 @@ fn particles_from_numbers(
     Ok(circle_particles)
 }
 
+/// The particles are generated first on the positive x axis, then on the negative x axis if there
+/// is an even number of them, and then in pairs above and below the x axis going around from the
+/// positive x axis, so this gives the index in the generated particles of the particle which is the
+/// given number of steps around the circle anticlockwise from the positive x axis.
+fn index_around_circle(steps_around_circle: usize, circle_population: usize) -> usize {
+    let first_pair_index = 2 - (circle_population % 2);
+    let steps_to_negative_axis = circle_population / 2;
+    if steps_around_circle == 0 {
+        0
+    } else if circle_population.is_multiple_of(2) && (steps_around_circle == steps_to_negative_axis)
+    {
+        1
+    } else if steps_around_circle <= steps_to_negative_axis {
+        first_pair_index + (2 * (steps_around_circle - 1))
+    } else {
+        first_pair_index + (2 * (circle_population - steps_around_circle - 1)) + 1
+    }
+}
+
+/// The circle particles should be those which from_json generated from the same configuration, so
+/// that a circle following keyframes or an ellipse keeps its shape without rotating.
+pub fn motion_modes_from_json(
+    given_configuration: &serde_json::Value,
+    circle_particles: &[data_structure::particle::BasicIndividual],
+) -> Result<std::vec::Vec<super::configuration_parsing::MotionMode>, Box<dyn std::error::Error>> {
+    super::parse_motion_modes(
+        given_configuration,
+        &given_configuration[COMMON_DISPLACEMENT_IN_PIXELS_LABEL],
+        circle_particles,
+    )
+}
+
+/// The circle may declare springs bonding each of its particles to its nearest neighbours around
+/// the circle, by default just the one on each side, with the separations of the generated
+/// particles as the rest lengths. Each pair of particles is bonded at most once, so two particles
+/// have a single bond between them. The given index is that which the first particle of the circle
+/// will have once all the particles are generated.
+pub fn spring_bonds_from_json(
+    given_configuration: &serde_json::Value,
+    first_particle_index: usize,
+    circle_particles: &[data_structure::particle::BasicIndividual],
+) -> Result<std::vec::Vec<super::configuration_parsing::SpringBond>, Box<dyn std::error::Error>> {
+    let given_bonds = match given_configuration.get(super::SPRING_BONDS_LABEL) {
+        None => return Ok(vec![]),
+        Some(given_bonds) => given_bonds,
+    };
+    let neighbours_on_each_side = super::configuration_parsing::parse_optional_i64_as_usize(
+        NEIGHBOURS_ON_EACH_SIDE_LABEL,
+        given_bonds,
+    )?
+    .unwrap_or(1);
+    if neighbours_on_each_side == 0 {
+        return Err(Box::new(ConfigurationParseError::new(&format!(
+            "{} must be > 0",
+            NEIGHBOURS_ON_EACH_SIDE_LABEL
+        ))));
+    }
+    let circle_population = circle_particles.len();
+    let mut spring_bonds = vec![];
+    for steps_between_particles in 1..=neighbours_on_each_side.min(circle_population / 2) {
+        for steps_around_circle in 0..circle_population {
+            // The particles opposite each other on a circle with an even number of particles are
+            // the same number of steps apart going either way, so they are only bonded once.
+            if (2 * steps_between_particles == circle_population)
+                && (2 * steps_around_circle >= circle_population)
+            {
+                continue;
+            }
+            let first_index = index_around_circle(steps_around_circle, circle_population);
+            let second_index = index_around_circle(
+                (steps_around_circle + steps_between_particles) % circle_population,
+                circle_population,
+            );
+            let separation_vector = circle_particles[second_index]
+                .variable_values
+                .position_vector
+                - circle_particles[first_index]
+                    .variable_values
+                    .position_vector;
+            spring_bonds.push(super::parse_spring_bond(
+                given_bonds,
+                first_particle_index + first_index.min(second_index),
+                first_particle_index + first_index.max(second_index),
+                separation_vector
+                    .horizontal_component
+                    .0
+                    .hypot(separation_vector.vertical_component.0),
+            )?);
+        }
+    }
+    Ok(spring_bonds)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -210,6 +311,9 @@ mod tests {
             inertial_mass: data_structure::charge::InertialMassUnit(0.01),
             inverse_squared_charge: data_structure::charge::InverseSquaredChargeUnit(0.01),
             inverse_fourth_charge: data_structure::charge::InverseFourthChargeUnit(0.01),
+            species_index: data_structure::charge::SpeciesIndex(0),
+            collision_radius: data_structure::position::SeparationUnit(0.0),
+            is_tracer: false,
             color_brightness: data_structure::color::new_triplet(
                 data_structure::color::RedUnit(0.01),
                 data_structure::color::GreenUnit(0.01),
@@ -243,6 +347,9 @@ mod tests {
             inertial_mass: data_structure::charge::InertialMassUnit(1.9),
             inverse_squared_charge: data_structure::charge::InverseSquaredChargeUnit(2.8),
             inverse_fourth_charge: data_structure::charge::InverseFourthChargeUnit(3.7),
+            species_index: data_structure::charge::SpeciesIndex(0),
+            collision_radius: data_structure::position::SeparationUnit(0.0),
+            is_tracer: false,
             color_brightness: data_structure::color::new_triplet(
                 data_structure::color::RedUnit(4.6),
                 data_structure::color::GreenUnit(5.5),
@@ -326,7 +433,7 @@ mod tests {
                 }
             }
 
-            let parsing_result = from_json(&configuration_without_attribute);
+            let parsing_result = from_json(&configuration_without_attribute, None);
             if !parsing_result.is_err() {
                 failed_cases.push(missing_attribute.to_string());
             }
@@ -372,7 +479,7 @@ mod tests {
                 }
             }
 
-            let parsing_result = from_json(&configuration_without_attribute);
+            let parsing_result = from_json(&configuration_without_attribute, None);
             if !parsing_result.is_err() {
                 failed_cases.push(malformed_attribute.to_string());
             }
@@ -397,7 +504,7 @@ mod tests {
             serde_json::json!(9005.0),
             serde_json::json!(9006.0),
         );
-        let parsing_result = from_json(&configuration_without_population);
+        let parsing_result = from_json(&configuration_without_population, None);
         if parsing_result.is_err() {
             Ok(())
         } else {
@@ -417,7 +524,7 @@ mod tests {
         );
         configuration_with_array_population[TOTAL_PARTICLES_ON_CIRCLE_LABEL] =
             serde_json::json!([9001.0, 9002.0]);
-        let parsing_result = from_json(&configuration_with_array_population);
+        let parsing_result = from_json(&configuration_with_array_population, None);
         if parsing_result.is_err() {
             Ok(())
         } else {
@@ -436,7 +543,7 @@ mod tests {
             serde_json::json!(9006.0),
         );
         test_configuration[TOTAL_PARTICLES_ON_CIRCLE_LABEL] = serde_json::json!(0);
-        let parsing_result = from_json(&test_configuration);
+        let parsing_result = from_json(&test_configuration, None);
         if parsing_result.is_err() {
             Ok(())
         } else {
@@ -455,7 +562,7 @@ mod tests {
             serde_json::json!(9006.0),
         );
         test_configuration[TOTAL_PARTICLES_ON_CIRCLE_LABEL] = serde_json::json!(1);
-        let parsing_result = from_json(&test_configuration);
+        let parsing_result = from_json(&test_configuration, None);
         if parsing_result.is_err() {
             Ok(())
         } else {
@@ -463,6 +570,147 @@ mod tests {
         }
     }
 
+    #[test]
+    fn check_collision_radius_given_to_every_particle_and_negative_rejected() -> Result<(), String>
+    {
+        let mut test_configuration = new_test_configuration(
+            serde_json::json!(0.0),
+            serde_json::json!(0.0),
+            serde_json::json!(0.0),
+            serde_json::json!(0.0),
+            serde_json::json!(2.0),
+            serde_json::json!(0.0),
+        );
+        test_configuration[TOTAL_PARTICLES_ON_CIRCLE_LABEL] = serde_json::json!(3);
+        test_configuration[super::super::COLLISION_RADIUS_IN_PIXELS_LABEL] = serde_json::json!(0.5);
+        let generated_radii: std::vec::Vec<f64> = from_json(&test_configuration, None)
+            .map_err(|parsing_error| format!("Could not parse: {:?}", parsing_error))?
+            .iter()
+            .map(|generated_particle| generated_particle.intrinsic_values.collision_radius.0)
+            .collect();
+        test_configuration[super::super::COLLISION_RADIUS_IN_PIXELS_LABEL] =
+            serde_json::json!(-0.5);
+        let is_negative_radius_rejected = from_json(&test_configuration, None).is_err();
+        if (generated_radii == vec![0.5, 0.5, 0.5]) && is_negative_radius_rejected {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected radii of 0.5 and a negative radius rejected, got {:?} and {}",
+                generated_radii, is_negative_radius_rejected
+            ))
+        }
+    }
+
+    #[test]
+    fn check_tracer_flag_given_to_every_particle_and_non_boolean_rejected() -> Result<(), String> {
+        let mut test_configuration = new_test_configuration(
+            serde_json::json!(0.0),
+            serde_json::json!(0.0),
+            serde_json::json!(0.0),
+            serde_json::json!(0.0),
+            serde_json::json!(2.0),
+            serde_json::json!(0.0),
+        );
+        test_configuration[TOTAL_PARTICLES_ON_CIRCLE_LABEL] = serde_json::json!(3);
+        let default_flags: std::vec::Vec<bool> = from_json(&test_configuration, None)
+            .map_err(|parsing_error| format!("Could not parse: {:?}", parsing_error))?
+            .iter()
+            .map(|generated_particle| generated_particle.intrinsic_values.is_tracer)
+            .collect();
+        test_configuration[super::super::TRACER_LABEL] = serde_json::json!(true);
+        let tracer_flags: std::vec::Vec<bool> = from_json(&test_configuration, None)
+            .map_err(|parsing_error| format!("Could not parse: {:?}", parsing_error))?
+            .iter()
+            .map(|generated_particle| generated_particle.intrinsic_values.is_tracer)
+            .collect();
+        test_configuration[super::super::TRACER_LABEL] = serde_json::json!(1);
+        let is_non_boolean_rejected = from_json(&test_configuration, None).is_err();
+        if (default_flags == vec![false, false, false])
+            && (tracer_flags == vec![true, true, true])
+            && is_non_boolean_rejected
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected no tracers by default, all tracers when flagged and a non-boolean flag \
+                 rejected, got {:?}, {:?} and {}",
+                default_flags, tracer_flags, is_non_boolean_rejected
+            ))
+        }
+    }
+
+    /// On a square, each particle is bonded to the particles on either side of it at the length of
+    /// a side, and with two neighbours on each side also to the particle opposite it at the length
+    /// of a diagonal, which is the second neighbour going either way, so it has to be bonded just
+    /// once. The particle opposite the first is generated second.
+    #[test]
+    fn check_spring_bonds_join_neighbours_once_and_zero_stiffness_rejected() -> Result<(), String> {
+        let mut test_configuration = new_test_configuration(
+            serde_json::json!(1.0),
+            serde_json::json!(-1.0),
+            serde_json::json!(0.0),
+            serde_json::json!(0.0),
+            serde_json::json!(2.0),
+            serde_json::json!(0.0),
+        );
+        test_configuration[TOTAL_PARTICLES_ON_CIRCLE_LABEL] = serde_json::json!(4);
+        test_configuration[super::super::SPRING_BONDS_LABEL] = serde_json::json!({
+            super::super::STIFFNESS_LABEL: 1.5,
+            NEIGHBOURS_ON_EACH_SIDE_LABEL: 2,
+        });
+        let circle_particles = from_json(&test_configuration, None)
+            .map_err(|parsing_error| format!("Could not parse: {:?}", parsing_error))?;
+        let mut bonded_pairs: std::vec::Vec<(usize, usize, f64)> =
+            spring_bonds_from_json(&test_configuration, 3, &circle_particles)
+                .map_err(|parsing_error| format!("Could not parse bonds: {:?}", parsing_error))?
+                .iter()
+                .filter(|spring_bond| {
+                    (spring_bond.stiffness == 1.5) && (spring_bond.damping_coefficient == 0.0)
+                })
+                .map(|spring_bond| {
+                    (
+                        spring_bond.first_particle_index,
+                        spring_bond.second_particle_index,
+                        spring_bond.rest_length,
+                    )
+                })
+                .collect();
+        bonded_pairs.sort_by(|first_pair, second_pair| {
+            (first_pair.0, first_pair.1).cmp(&(second_pair.0, second_pair.1))
+        });
+        let side_length = 2.0 * std::f64::consts::SQRT_2;
+        let expected_pairs = vec![
+            (3, 4, 4.0),
+            (3, 5, side_length),
+            (3, 6, side_length),
+            (4, 5, side_length),
+            (4, 6, side_length),
+            (5, 6, 4.0),
+        ];
+        let are_pairs_correct = (bonded_pairs.len() == expected_pairs.len())
+            && bonded_pairs.iter().zip(expected_pairs.iter()).all(
+                |(bonded_pair, expected_pair)| {
+                    (bonded_pair.0 == expected_pair.0)
+                        && (bonded_pair.1 == expected_pair.1)
+                        && ((bonded_pair.2 - expected_pair.2).abs() < 0.000001)
+                },
+            );
+
+        test_configuration[super::super::SPRING_BONDS_LABEL] = serde_json::json!({
+            super::super::STIFFNESS_LABEL: 0.0,
+        });
+        let is_zero_stiffness_rejected =
+            spring_bonds_from_json(&test_configuration, 3, &circle_particles).is_err();
+        if are_pairs_correct && is_zero_stiffness_rejected {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected bonds {:?} and zero stiffness rejected, got {:?} and {}",
+                expected_pairs, bonded_pairs, is_zero_stiffness_rejected
+            ))
+        }
+    }
+
     #[test]
     fn check_parse_two_points() -> Result<(), String> {
         let test_radius = 2.0;
@@ -480,7 +728,7 @@ mod tests {
         );
         test_configuration[TOTAL_PARTICLES_ON_CIRCLE_LABEL] = serde_json::json!(2);
         let generated_particles =
-            from_json(&test_configuration).expect("Valid configuration should be parsed.");
+            from_json(&test_configuration, None).expect("Valid configuration should be parsed.");
         let expected_particles = vec![
             new_test_particle_at(
                 data_structure::position::HorizontalUnit(
@@ -524,7 +772,7 @@ mod tests {
         );
         test_configuration[TOTAL_PARTICLES_ON_CIRCLE_LABEL] = serde_json::json!(3);
         let generated_particles =
-            from_json(&test_configuration).expect("Valid configuration should be parsed.");
+            from_json(&test_configuration, None).expect("Valid configuration should be parsed.");
         let left_vertical_magnitude = 0.866;
         let left_horizontal_coordinate = data_structure::position::HorizontalUnit(-0.5);
         let expected_particles = vec![
@@ -574,7 +822,7 @@ mod tests {
         );
         test_configuration[TOTAL_PARTICLES_ON_CIRCLE_LABEL] = serde_json::json!(4);
         let generated_particles =
-            from_json(&test_configuration).expect("Valid configuration should be parsed.");
+            from_json(&test_configuration, None).expect("Valid configuration should be parsed.");
         let expected_particles = vec![
             new_test_particle_at(
                 data_structure::position::HorizontalUnit(
@@ -616,4 +864,63 @@ mod tests {
             &new_particle_tolerance(),
         )
     }
+
+    #[test]
+    fn check_motion_modes_keep_circle_shape() -> Result<(), String> {
+        let mut test_configuration = new_test_configuration(
+            serde_json::json!(1.0),
+            serde_json::json!(-1.0),
+            serde_json::json!(0.0),
+            serde_json::json!(0.0),
+            serde_json::json!(2.0),
+            serde_json::json!(0.0),
+        );
+        test_configuration[TOTAL_PARTICLES_ON_CIRCLE_LABEL] = serde_json::json!(4);
+        let circle_particles = from_json(&test_configuration, None)
+            .map_err(|parsing_error| format!("Could not parse: {:?}", parsing_error))?;
+        test_configuration[super::super::MOTION_MODE_LABEL] = serde_json::json!("fixed");
+        let fixed_modes = motion_modes_from_json(&test_configuration, &circle_particles)
+            .map_err(|parsing_error| format!("Could not parse fixed: {:?}", parsing_error))?;
+        test_configuration[super::super::MOTION_MODE_LABEL] = serde_json::json!(
+            {
+                super::super::ELLIPSE_LABEL: {
+                    super::super::CENTER_IN_PIXELS_LABEL: { "x": 5.0, "y": 6.0 },
+                    super::super::HORIZONTAL_SEMI_AXIS_IN_PIXELS_LABEL: 3.0,
+                    super::super::VERTICAL_SEMI_AXIS_IN_PIXELS_LABEL: 1.0,
+                    super::super::ANGULAR_FREQUENCY_IN_RADIANS_PER_SECOND_LABEL: 0.5
+                }
+            }
+        );
+        let elliptical_modes = motion_modes_from_json(&test_configuration, &circle_particles)
+            .map_err(|parsing_error| format!("Could not parse ellipse: {:?}", parsing_error))?;
+
+        let mut expected_fixed_modes = vec![];
+        let mut expected_elliptical_modes = vec![];
+        for circle_particle in &circle_particles {
+            let particle_position = &circle_particle.variable_values.position_vector;
+            expected_fixed_modes.push(configuration_parsing::MotionMode::Fixed {
+                horizontal_coordinate: particle_position.horizontal_component.0,
+                vertical_coordinate: particle_position.vertical_component.0,
+            });
+            expected_elliptical_modes.push(configuration_parsing::MotionMode::Ellipse(
+                configuration_parsing::EllipticalPath {
+                    horizontal_center: 5.0 + (particle_position.horizontal_component.0 - 1.0),
+                    vertical_center: 6.0 + (particle_position.vertical_component.0 + 1.0),
+                    horizontal_semi_axis: 3.0,
+                    vertical_semi_axis: 1.0,
+                    angular_frequency: 0.5,
+                    initial_phase: 0.0,
+                },
+            ));
+        }
+        if (fixed_modes == expected_fixed_modes) && (elliptical_modes == expected_elliptical_modes)
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected {:?} and {:?}, got {:?} and {:?}",
+                expected_fixed_modes, expected_elliptical_modes, fixed_modes, elliptical_modes
+            ))
+        }
+    }
 }
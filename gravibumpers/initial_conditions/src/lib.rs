@@ -4,11 +4,29 @@
 extern crate configuration_parsing;
 extern crate data_structure;
 extern crate serde_json;
+pub mod checkpoint;
 pub mod circle;
 pub mod single;
 
 const HORIZONTAL_LABEL: &str = "x";
 const VERTICAL_LABEL: &str = "y";
+const COLLISION_RADIUS_IN_PIXELS_LABEL: &str = "collisionRadiusInPixels";
+const TRACER_LABEL: &str = "tracer";
+const SPRING_BONDS_LABEL: &str = "springBonds";
+const STIFFNESS_LABEL: &str = "stiffness";
+const DAMPING_COEFFICIENT_LABEL: &str = "dampingCoefficient";
+const MOTION_MODE_LABEL: &str = "motionMode";
+const FREE_MOTION_NAME: &str = "free";
+const FIXED_MOTION_NAME: &str = "fixed";
+const KEYFRAMES_LABEL: &str = "keyframes";
+const TIME_IN_SECONDS_LABEL: &str = "timeInSeconds";
+const POSITION_IN_PIXELS_LABEL: &str = "positionInPixels";
+const ELLIPSE_LABEL: &str = "ellipse";
+const CENTER_IN_PIXELS_LABEL: &str = "centerInPixels";
+const HORIZONTAL_SEMI_AXIS_IN_PIXELS_LABEL: &str = "horizontalSemiAxisInPixels";
+const VERTICAL_SEMI_AXIS_IN_PIXELS_LABEL: &str = "verticalSemiAxisInPixels";
+const ANGULAR_FREQUENCY_IN_RADIANS_PER_SECOND_LABEL: &str = "angularFrequencyInRadiansPerSecond";
+const INITIAL_PHASE_IN_RADIANS_LABEL: &str = "initialPhaseInRadians";
 
 pub fn parse_position(
     given_position: &serde_json::Value,
@@ -31,3 +49,326 @@ pub fn parse_velocity(
         vertical_component: data_structure::velocity::VerticalUnit(vertical_velocity),
     })
 }
+
+/// A generator may give the species of its particles, which must be named in the table of
+/// interactions, and otherwise its particles belong to the unnamed species.
+pub fn parse_species(
+    given_configuration: &serde_json::Value,
+    species_interactions: Option<&configuration_parsing::SpeciesInteractions>,
+) -> Result<data_structure::charge::SpeciesIndex, Box<dyn std::error::Error>> {
+    match (
+        configuration_parsing::parse_optional_str(
+            configuration_parsing::SPECIES_LABEL,
+            given_configuration,
+        )?,
+        species_interactions,
+    ) {
+        (None, _) => Ok(data_structure::charge::SpeciesIndex(0)),
+        (Some(species_name), Some(species_interactions)) => {
+            species_interactions.species_index(species_name)
+        }
+        (Some(species_name), None) => Err(Box::new(
+            configuration_parsing::ConfigurationParseError::new(&format!(
+                "{} \"{}\" is given without a table of interactions",
+                configuration_parsing::SPECIES_LABEL,
+                species_name
+            )),
+        )),
+    }
+}
+
+/// A generator may give a radius to its particles so that they collide with each other, and
+/// otherwise its particles are points which never collide.
+pub fn parse_collision_radius(
+    given_configuration: &serde_json::Value,
+) -> Result<data_structure::position::SeparationUnit, Box<dyn std::error::Error>> {
+    match configuration_parsing::parse_optional_f64(
+        COLLISION_RADIUS_IN_PIXELS_LABEL,
+        given_configuration,
+    )? {
+        None => Ok(data_structure::position::SeparationUnit(0.0)),
+        Some(collision_radius) if collision_radius >= 0.0 => {
+            Ok(data_structure::position::SeparationUnit(collision_radius))
+        }
+        Some(collision_radius) => Err(Box::new(
+            configuration_parsing::ConfigurationParseError::new(&format!(
+                "{} must be >= 0, got {}",
+                COLLISION_RADIUS_IN_PIXELS_LABEL, collision_radius
+            )),
+        )),
+    }
+}
+
+/// A generator may declare spring bonds between the particles which it creates and other
+/// particles. This parses the stiffness of a declared bond, which must be positive, and its
+/// damping coefficient, which is 0 if not given and must not be negative, as a bond with the given
+/// particle indices and rest length.
+/// A generator may make its particles tracers, which feel the forces from the other particles but
+/// exert none, so that many of them can show the flow around the other particles without slowing
+/// the evolution as much as particles which exert forces would. Otherwise its particles are not
+/// tracers.
+pub fn parse_tracer_flag(
+    given_configuration: &serde_json::Value,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    Ok(
+        configuration_parsing::parse_optional_bool(TRACER_LABEL, given_configuration)?
+            .unwrap_or(false),
+    )
+}
+
+fn parse_spring_bond(
+    given_bond: &serde_json::Value,
+    first_particle_index: usize,
+    second_particle_index: usize,
+    rest_length: f64,
+) -> Result<configuration_parsing::SpringBond, Box<dyn std::error::Error>> {
+    let stiffness = configuration_parsing::parse_f64(STIFFNESS_LABEL, given_bond)?;
+    if stiffness <= 0.0 {
+        return Err(Box::new(
+            configuration_parsing::ConfigurationParseError::new(&format!(
+                "{} must be > 0, got {}",
+                STIFFNESS_LABEL, stiffness
+            )),
+        ));
+    }
+    let damping_coefficient =
+        configuration_parsing::parse_optional_f64(DAMPING_COEFFICIENT_LABEL, given_bond)?
+            .unwrap_or(0.0);
+    if damping_coefficient < 0.0 {
+        return Err(Box::new(
+            configuration_parsing::ConfigurationParseError::new(&format!(
+                "{} must be >= 0, got {}",
+                DAMPING_COEFFICIENT_LABEL, damping_coefficient
+            )),
+        ));
+    }
+    Ok(configuration_parsing::SpringBond {
+        first_particle_index,
+        second_particle_index,
+        rest_length,
+        stiffness,
+        damping_coefficient,
+    })
+}
+
+fn parse_keyframes(
+    given_keyframes: &serde_json::Value,
+) -> Result<std::vec::Vec<configuration_parsing::Keyframe>, Box<dyn std::error::Error>> {
+    let given_keyframes = given_keyframes.as_array().ok_or_else(|| {
+        configuration_parsing::ConfigurationParseError::new(&format!(
+            "{} must be an array, got {}",
+            KEYFRAMES_LABEL, given_keyframes
+        ))
+    })?;
+    let mut keyframes: std::vec::Vec<configuration_parsing::Keyframe> = vec![];
+    for given_keyframe in given_keyframes {
+        let time_in_seconds =
+            configuration_parsing::parse_f64(TIME_IN_SECONDS_LABEL, given_keyframe)?;
+        if let Some(previous_keyframe) = keyframes.last() {
+            if time_in_seconds <= previous_keyframe.time_in_seconds {
+                return Err(Box::new(
+                    configuration_parsing::ConfigurationParseError::new(&format!(
+                        "{} must increase from one keyframe to the next, got {} after {}",
+                        TIME_IN_SECONDS_LABEL, time_in_seconds, previous_keyframe.time_in_seconds
+                    )),
+                ));
+            }
+        }
+        let keyframe_position = parse_position(&given_keyframe[POSITION_IN_PIXELS_LABEL])?;
+        keyframes.push(configuration_parsing::Keyframe {
+            time_in_seconds,
+            horizontal_coordinate: keyframe_position.horizontal_component.0,
+            vertical_coordinate: keyframe_position.vertical_component.0,
+        });
+    }
+    if keyframes.is_empty() {
+        return Err(Box::new(
+            configuration_parsing::ConfigurationParseError::new(&format!(
+                "{} must have at least one keyframe",
+                KEYFRAMES_LABEL
+            )),
+        ));
+    }
+    Ok(keyframes)
+}
+
+fn parse_elliptical_path(
+    given_ellipse: &serde_json::Value,
+) -> Result<configuration_parsing::EllipticalPath, Box<dyn std::error::Error>> {
+    let ellipse_center = parse_position(&given_ellipse[CENTER_IN_PIXELS_LABEL])?;
+    let horizontal_semi_axis =
+        configuration_parsing::parse_f64(HORIZONTAL_SEMI_AXIS_IN_PIXELS_LABEL, given_ellipse)?;
+    let vertical_semi_axis =
+        configuration_parsing::parse_f64(VERTICAL_SEMI_AXIS_IN_PIXELS_LABEL, given_ellipse)?;
+    if (horizontal_semi_axis < 0.0) || (vertical_semi_axis < 0.0) {
+        return Err(Box::new(
+            configuration_parsing::ConfigurationParseError::new(&format!(
+                "The semi-axes of {} must be >= 0, got {} and {}",
+                ELLIPSE_LABEL, horizontal_semi_axis, vertical_semi_axis
+            )),
+        ));
+    }
+    Ok(configuration_parsing::EllipticalPath {
+        horizontal_center: ellipse_center.horizontal_component.0,
+        vertical_center: ellipse_center.vertical_component.0,
+        horizontal_semi_axis,
+        vertical_semi_axis,
+        angular_frequency: configuration_parsing::parse_f64(
+            ANGULAR_FREQUENCY_IN_RADIANS_PER_SECOND_LABEL,
+            given_ellipse,
+        )?,
+        initial_phase: configuration_parsing::parse_optional_f64(
+            INITIAL_PHASE_IN_RADIANS_LABEL,
+            given_ellipse,
+        )?
+        .unwrap_or(0.0),
+    })
+}
+
+/// This gives the motion mode of the common displacement of the generator moved by the given
+/// offset, which is how far a particle was generated from the common displacement.
+fn offset_motion_mode(
+    motion_mode: &configuration_parsing::MotionMode,
+    offset_vector: &data_structure::position::DimensionfulVector,
+) -> configuration_parsing::MotionMode {
+    let horizontal_offset = offset_vector.horizontal_component.0;
+    let vertical_offset = offset_vector.vertical_component.0;
+    match motion_mode {
+        configuration_parsing::MotionMode::Keyframes(keyframes) => {
+            configuration_parsing::MotionMode::Keyframes(
+                keyframes
+                    .iter()
+                    .map(|keyframe| configuration_parsing::Keyframe {
+                        horizontal_coordinate: keyframe.horizontal_coordinate + horizontal_offset,
+                        vertical_coordinate: keyframe.vertical_coordinate + vertical_offset,
+                        ..*keyframe
+                    })
+                    .collect(),
+            )
+        }
+        configuration_parsing::MotionMode::Ellipse(elliptical_path) => {
+            configuration_parsing::MotionMode::Ellipse(configuration_parsing::EllipticalPath {
+                horizontal_center: elliptical_path.horizontal_center + horizontal_offset,
+                vertical_center: elliptical_path.vertical_center + vertical_offset,
+                ..*elliptical_path
+            })
+        }
+        unmoved_mode => unmoved_mode.clone(),
+    }
+}
+
+/// A generator may give a motion mode to its particles, and otherwise its particles are free. A
+/// fixed particle stays where it was generated, while the keyframes or the ellipse give the path of
+/// the common displacement of the generator, which each particle follows from where it was
+/// generated relative to the common displacement, so that the generated shape moves as a whole.
+/// There is one motion mode for each of the given particles.
+fn parse_motion_modes(
+    given_configuration: &serde_json::Value,
+    common_displacement: &serde_json::Value,
+    generated_particles: &[data_structure::particle::BasicIndividual],
+) -> Result<std::vec::Vec<configuration_parsing::MotionMode>, Box<dyn std::error::Error>> {
+    let given_mode = match given_configuration.get(MOTION_MODE_LABEL) {
+        None => {
+            return Ok(vec![
+                configuration_parsing::MotionMode::Free;
+                generated_particles.len()
+            ])
+        }
+        Some(given_mode) => given_mode,
+    };
+    let common_motion_mode = if let Some(given_keyframes) = given_mode.get(KEYFRAMES_LABEL) {
+        configuration_parsing::MotionMode::Keyframes(parse_keyframes(given_keyframes)?)
+    } else if let Some(given_ellipse) = given_mode.get(ELLIPSE_LABEL) {
+        configuration_parsing::MotionMode::Ellipse(parse_elliptical_path(given_ellipse)?)
+    } else {
+        match given_mode.as_str() {
+            Some(FREE_MOTION_NAME) => configuration_parsing::MotionMode::Free,
+            Some(FIXED_MOTION_NAME) => configuration_parsing::MotionMode::Fixed {
+                horizontal_coordinate: 0.0,
+                vertical_coordinate: 0.0,
+            },
+            _ => {
+                return Err(Box::new(
+                    configuration_parsing::ConfigurationParseError::new(&format!(
+                        "{} {} is unknown (expected \"{}\", \"{}\", or an object with \"{}\" or \
+                         \"{}\")",
+                        MOTION_MODE_LABEL,
+                        given_mode,
+                        FREE_MOTION_NAME,
+                        FIXED_MOTION_NAME,
+                        KEYFRAMES_LABEL,
+                        ELLIPSE_LABEL
+                    )),
+                ))
+            }
+        }
+    };
+    let common_position = parse_position(common_displacement)?;
+    Ok(generated_particles
+        .iter()
+        .map(|generated_particle| {
+            let particle_position = &generated_particle.variable_values.position_vector;
+            match common_motion_mode {
+                configuration_parsing::MotionMode::Fixed { .. } => {
+                    configuration_parsing::MotionMode::Fixed {
+                        horizontal_coordinate: particle_position.horizontal_component.0,
+                        vertical_coordinate: particle_position.vertical_component.0,
+                    }
+                }
+                _ => {
+                    offset_motion_mode(&common_motion_mode, &(*particle_position - common_position))
+                }
+            }
+        })
+        .collect())
+}
+
+/// This creates the value of the motion mode for a generator of a single particle, for which the
+/// common displacement is the position of the particle, or None if the particle is free. A fixed
+/// particle is taken to be where it is anchored.
+fn motion_mode_to_json(
+    motion_mode: &configuration_parsing::MotionMode,
+) -> Option<serde_json::Value> {
+    match motion_mode {
+        configuration_parsing::MotionMode::Free => None,
+        configuration_parsing::MotionMode::Fixed { .. } => {
+            Some(serde_json::json!(FIXED_MOTION_NAME))
+        }
+        configuration_parsing::MotionMode::Keyframes(keyframes) => Some(serde_json::json!(
+            {
+                KEYFRAMES_LABEL: keyframes
+                    .iter()
+                    .map(|keyframe| {
+                        serde_json::json!(
+                            {
+                                TIME_IN_SECONDS_LABEL: keyframe.time_in_seconds,
+                                POSITION_IN_PIXELS_LABEL:
+                                {
+                                    HORIZONTAL_LABEL: keyframe.horizontal_coordinate,
+                                    VERTICAL_LABEL: keyframe.vertical_coordinate
+                                }
+                            }
+                        )
+                    })
+                    .collect::<std::vec::Vec<serde_json::Value>>()
+            }
+        )),
+        configuration_parsing::MotionMode::Ellipse(elliptical_path) => Some(serde_json::json!(
+            {
+                ELLIPSE_LABEL:
+                {
+                    CENTER_IN_PIXELS_LABEL:
+                    {
+                        HORIZONTAL_LABEL: elliptical_path.horizontal_center,
+                        VERTICAL_LABEL: elliptical_path.vertical_center
+                    },
+                    HORIZONTAL_SEMI_AXIS_IN_PIXELS_LABEL: elliptical_path.horizontal_semi_axis,
+                    VERTICAL_SEMI_AXIS_IN_PIXELS_LABEL: elliptical_path.vertical_semi_axis,
+                    ANGULAR_FREQUENCY_IN_RADIANS_PER_SECOND_LABEL:
+                        elliptical_path.angular_frequency,
+                    INITIAL_PHASE_IN_RADIANS_LABEL: elliptical_path.initial_phase
+                }
+            }
+        )),
+    }
+}
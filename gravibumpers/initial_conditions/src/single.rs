@@ -10,9 +10,12 @@ const INVERSE_FOURTH_CHARGE_IN_DIMENSIONLESS_UNITS_LABEL: &str =
 const RED_PIXEL_STRENGTH_LABEL: &str = "redPixelStrength";
 const GREEN_PIXEL_STRENGTH_LABEL: &str = "greenPixelStrength";
 const BLUE_PIXEL_STRENGTH_LABEL: &str = "bluePixelStrength";
+const BONDED_PARTICLE_INDEX_LABEL: &str = "bondedParticleIndex";
+const REST_LENGTH_IN_PIXELS_LABEL: &str = "restLengthInPixels";
 
 pub fn from_json(
     given_configuration: &serde_json::Value,
+    species_interactions: Option<&super::configuration_parsing::SpeciesInteractions>,
 ) -> Result<std::vec::Vec<data_structure::particle::BasicIndividual>, Box<dyn std::error::Error>> {
     let particle_displacement =
         super::parse_position(&given_configuration[COMMON_DISPLACEMENT_IN_PIXELS_LABEL])?;
@@ -36,6 +39,9 @@ pub fn from_json(
         super::configuration_parsing::parse_f64(GREEN_PIXEL_STRENGTH_LABEL, given_configuration)?;
     let blue_brightness =
         super::configuration_parsing::parse_f64(BLUE_PIXEL_STRENGTH_LABEL, given_configuration)?;
+    let species_index = super::parse_species(given_configuration, species_interactions)?;
+    let collision_radius = super::parse_collision_radius(given_configuration)?;
+    let is_tracer = super::parse_tracer_flag(given_configuration)?;
 
     Ok(vec![data_structure::particle::BasicIndividual {
         intrinsic_values: data_structure::particle::IntrinsicPart {
@@ -46,6 +52,9 @@ pub fn from_json(
             inverse_fourth_charge: data_structure::charge::InverseFourthChargeUnit(
                 inverse_fourth_charge,
             ),
+            species_index,
+            collision_radius,
+            is_tracer,
             color_brightness: data_structure::color::new_triplet(
                 data_structure::color::RedUnit(red_brightness),
                 data_structure::color::GreenUnit(green_brightness),
@@ -59,10 +68,274 @@ pub fn from_json(
     }])
 }
 
+/// The particle may be bonded to particles which were generated before it, given by their indices
+/// in the order of generation, as the particle itself is given the index which it will have once
+/// generated.
+pub fn spring_bonds_from_json(
+    given_configuration: &serde_json::Value,
+    particle_index: usize,
+) -> Result<std::vec::Vec<super::configuration_parsing::SpringBond>, Box<dyn std::error::Error>> {
+    let given_bonds = match given_configuration.get(super::SPRING_BONDS_LABEL) {
+        None => return Ok(vec![]),
+        Some(given_bonds) => given_bonds.as_array().ok_or_else(|| {
+            super::configuration_parsing::ConfigurationParseError::new(&format!(
+                "{} must be an array, got {}",
+                super::SPRING_BONDS_LABEL,
+                given_bonds
+            ))
+        })?,
+    };
+    let mut spring_bonds = vec![];
+    for given_bond in given_bonds {
+        let bonded_particle_index = super::configuration_parsing::parse_i64_as_usize(
+            BONDED_PARTICLE_INDEX_LABEL,
+            given_bond,
+        )?;
+        if bonded_particle_index >= particle_index {
+            return Err(Box::new(
+                super::configuration_parsing::ConfigurationParseError::new(&format!(
+                    "{} must be less than the index {} of the particle, got {}",
+                    BONDED_PARTICLE_INDEX_LABEL, particle_index, bonded_particle_index
+                )),
+            ));
+        }
+        let rest_length =
+            super::configuration_parsing::parse_f64(REST_LENGTH_IN_PIXELS_LABEL, given_bond)?;
+        if rest_length < 0.0 {
+            return Err(Box::new(
+                super::configuration_parsing::ConfigurationParseError::new(&format!(
+                    "{} must be >= 0, got {}",
+                    REST_LENGTH_IN_PIXELS_LABEL, rest_length
+                )),
+            ));
+        }
+        spring_bonds.push(super::parse_spring_bond(
+            given_bond,
+            bonded_particle_index,
+            particle_index,
+            rest_length,
+        )?);
+    }
+    Ok(spring_bonds)
+}
+
+/// The generated particles should be those which from_json generated from the same configuration.
+pub fn motion_modes_from_json(
+    given_configuration: &serde_json::Value,
+    generated_particles: &[data_structure::particle::BasicIndividual],
+) -> Result<std::vec::Vec<super::configuration_parsing::MotionMode>, Box<dyn std::error::Error>> {
+    super::parse_motion_modes(
+        given_configuration,
+        &given_configuration[COMMON_DISPLACEMENT_IN_PIXELS_LABEL],
+        generated_particles,
+    )
+}
+
+/// This creates the configuration from which from_json reproduces the given particle exactly, as
+/// long as every number is finite and the same table of interactions is given. The given bonds
+/// should be those to particles generated before the given particle, so that
+/// spring_bonds_from_json reproduces them, and a fixed particle should be where it is anchored, so
+/// that motion_modes_from_json reproduces its motion mode.
+pub fn to_json(
+    particle_to_represent: &impl data_structure::particle::IndividualRepresentation,
+    species_interactions: Option<&super::configuration_parsing::SpeciesInteractions>,
+    spring_bonds: &[super::configuration_parsing::SpringBond],
+    motion_mode: &super::configuration_parsing::MotionMode,
+) -> serde_json::Value {
+    let particle_intrinsics = particle_to_represent.read_intrinsics();
+    let particle_variables = particle_to_represent.read_variables();
+    let mut particle_configuration = serde_json::json!(
+        {
+            COMMON_DISPLACEMENT_IN_PIXELS_LABEL:
+            {
+                super::HORIZONTAL_LABEL:
+                    particle_variables.position_vector.horizontal_component.0,
+                super::VERTICAL_LABEL: particle_variables.position_vector.vertical_component.0
+            },
+            LINEAR_VELOCITY_IN_PIXELS_PER_SECOND_LABEL:
+            {
+                super::HORIZONTAL_LABEL:
+                    particle_variables.velocity_vector.horizontal_component.0,
+                super::VERTICAL_LABEL: particle_variables.velocity_vector.vertical_component.0
+            },
+            INERTIAL_MASS_IN_MASS_UNITS_LABEL: particle_intrinsics.inertial_mass.0,
+            INVERSE_SQUARED_CHARGE_IN_DIMENSIONLESS_UNITS_LABEL:
+                particle_intrinsics.inverse_squared_charge.0,
+            INVERSE_FOURTH_CHARGE_IN_DIMENSIONLESS_UNITS_LABEL:
+                particle_intrinsics.inverse_fourth_charge.0,
+            RED_PIXEL_STRENGTH_LABEL: particle_intrinsics.color_brightness.get_red().0,
+            GREEN_PIXEL_STRENGTH_LABEL: particle_intrinsics.color_brightness.get_green().0,
+            BLUE_PIXEL_STRENGTH_LABEL: particle_intrinsics.color_brightness.get_blue().0
+        }
+    );
+    if let Some(species_name) = species_interactions.and_then(|species_interactions| {
+        species_interactions.species_name(particle_intrinsics.species_index)
+    }) {
+        particle_configuration[super::configuration_parsing::SPECIES_LABEL] =
+            serde_json::json!(species_name);
+    }
+    if particle_intrinsics.collision_radius.0 != 0.0 {
+        particle_configuration[super::COLLISION_RADIUS_IN_PIXELS_LABEL] =
+            serde_json::json!(particle_intrinsics.collision_radius.0);
+    }
+    if particle_intrinsics.is_tracer {
+        particle_configuration[super::TRACER_LABEL] = serde_json::json!(true);
+    }
+    if !spring_bonds.is_empty() {
+        particle_configuration[super::SPRING_BONDS_LABEL] = spring_bonds
+            .iter()
+            .map(|spring_bond| {
+                serde_json::json!(
+                    {
+                        BONDED_PARTICLE_INDEX_LABEL: spring_bond.first_particle_index,
+                        REST_LENGTH_IN_PIXELS_LABEL: spring_bond.rest_length,
+                        super::STIFFNESS_LABEL: spring_bond.stiffness,
+                        super::DAMPING_COEFFICIENT_LABEL: spring_bond.damping_coefficient
+                    }
+                )
+            })
+            .collect();
+    }
+    if let Some(given_mode) = super::motion_mode_to_json(motion_mode) {
+        particle_configuration[super::MOTION_MODE_LABEL] = given_mode;
+    }
+    particle_configuration
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn check_exact_round_trip_through_text() -> Result<(), String> {
+        // These numbers have no short decimal representation, so any loss of precision in writing
+        // or reading the text would change at least one of them.
+        let original_particle = data_structure::particle::BasicIndividual {
+            intrinsic_values: data_structure::particle::IntrinsicPart {
+                inertial_mass: data_structure::charge::InertialMassUnit(1.0 / 3.0),
+                inverse_squared_charge: data_structure::charge::InverseSquaredChargeUnit(
+                    -0.1 - 0.2,
+                ),
+                inverse_fourth_charge: data_structure::charge::InverseFourthChargeUnit(
+                    2.0_f64.sqrt(),
+                ),
+                species_index: data_structure::charge::SpeciesIndex(0),
+                collision_radius: data_structure::position::SeparationUnit(0.1 + 0.7),
+                is_tracer: true,
+                color_brightness: data_structure::color::new_triplet(
+                    data_structure::color::RedUnit(1.0e-300 / 7.0),
+                    data_structure::color::GreenUnit(0.0),
+                    data_structure::color::BlueUnit(123456.789e10 / 11.0),
+                ),
+            },
+            variable_values: data_structure::particle::VariablePart {
+                position_vector: data_structure::position::DimensionfulVector {
+                    horizontal_component: data_structure::position::HorizontalUnit(
+                        std::f64::consts::PI,
+                    ),
+                    vertical_component: data_structure::position::VerticalUnit(
+                        -std::f64::consts::E * 1.0e-5,
+                    ),
+                },
+                velocity_vector: data_structure::velocity::DimensionfulVector {
+                    horizontal_component: data_structure::velocity::HorizontalUnit(0.7_f64.ln()),
+                    vertical_component: data_structure::velocity::VerticalUnit(f64::MIN_POSITIVE),
+                },
+            },
+        };
+        let written_text = serde_json::to_string(&to_json(
+            &original_particle,
+            None,
+            &[],
+            &configuration_parsing::MotionMode::Free,
+        ))
+        .map_err(|writing_error| format!("Could not write: {:?}", writing_error))?;
+        let read_configuration: serde_json::Value = serde_json::from_str(&written_text)
+            .map_err(|reading_error| format!("Could not read: {:?}", reading_error))?;
+        let read_particles = from_json(&read_configuration, None)
+            .map_err(|parsing_error| format!("Could not parse: {:?}", parsing_error))?;
+        if read_particles.len() != 1 {
+            return Err(format!("Expected 1 particle, got {:?}", read_particles));
+        }
+
+        let original_bits = &original_particle;
+        let read_bits = &read_particles[0];
+        let original_numbers = [
+            original_bits.intrinsic_values.inertial_mass.0,
+            original_bits.intrinsic_values.inverse_squared_charge.0,
+            original_bits.intrinsic_values.inverse_fourth_charge.0,
+            original_bits.intrinsic_values.collision_radius.0,
+            original_bits.intrinsic_values.color_brightness.get_red().0,
+            original_bits
+                .intrinsic_values
+                .color_brightness
+                .get_green()
+                .0,
+            original_bits.intrinsic_values.color_brightness.get_blue().0,
+            original_bits
+                .variable_values
+                .position_vector
+                .horizontal_component
+                .0,
+            original_bits
+                .variable_values
+                .position_vector
+                .vertical_component
+                .0,
+            original_bits
+                .variable_values
+                .velocity_vector
+                .horizontal_component
+                .0,
+            original_bits
+                .variable_values
+                .velocity_vector
+                .vertical_component
+                .0,
+        ];
+        let read_numbers = [
+            read_bits.intrinsic_values.inertial_mass.0,
+            read_bits.intrinsic_values.inverse_squared_charge.0,
+            read_bits.intrinsic_values.inverse_fourth_charge.0,
+            read_bits.intrinsic_values.collision_radius.0,
+            read_bits.intrinsic_values.color_brightness.get_red().0,
+            read_bits.intrinsic_values.color_brightness.get_green().0,
+            read_bits.intrinsic_values.color_brightness.get_blue().0,
+            read_bits
+                .variable_values
+                .position_vector
+                .horizontal_component
+                .0,
+            read_bits
+                .variable_values
+                .position_vector
+                .vertical_component
+                .0,
+            read_bits
+                .variable_values
+                .velocity_vector
+                .horizontal_component
+                .0,
+            read_bits
+                .variable_values
+                .velocity_vector
+                .vertical_component
+                .0,
+        ];
+        if read_bits.intrinsic_values.is_tracer
+            && original_numbers.iter().zip(read_numbers.iter()).all(
+                |(original_number, read_number)| original_number.to_bits() == read_number.to_bits(),
+            )
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected a tracer with {:?}, read {:?} from {}",
+                original_numbers, read_particles, written_text
+            ))
+        }
+    }
+
     #[test]
     fn check_reject_when_missing_attribute() -> Result<(), String> {
         let required_attributes = vec![
@@ -87,7 +360,7 @@ mod tests {
                 }
             }
 
-            let parsing_result = from_json(&configuration_without_attribute);
+            let parsing_result = from_json(&configuration_without_attribute, None);
             if !parsing_result.is_err() {
                 failed_cases.push(missing_attribute.to_string());
             }
@@ -130,7 +403,7 @@ mod tests {
                 }
             }
 
-            let parsing_result = from_json(&configuration_without_attribute);
+            let parsing_result = from_json(&configuration_without_attribute, None);
             if !parsing_result.is_err() {
                 failed_cases.push(malformed_attribute.to_string());
             }
@@ -145,4 +418,220 @@ mod tests {
             )))
         }
     }
+
+    #[test]
+    fn check_species_round_trip_and_unknown_species_rejected() -> Result<(), String> {
+        let species_interactions = configuration_parsing::parse_species_interactions(
+            &serde_json::json!([{ "firstSpecies": "A", "secondSpecies": "B" }]),
+            1.0,
+            1.0,
+        )
+        .map_err(|parsing_error| format!("Could not parse table: {:?}", parsing_error))?;
+        let mut particle_configuration = to_json(
+            &from_json(
+                &serde_json::json!(
+                    {
+                        COMMON_DISPLACEMENT_IN_PIXELS_LABEL: { "x": 1.0, "y": 2.0 },
+                        LINEAR_VELOCITY_IN_PIXELS_PER_SECOND_LABEL: { "x": 0.0, "y": 0.0 },
+                        INERTIAL_MASS_IN_MASS_UNITS_LABEL: 1.0,
+                        INVERSE_SQUARED_CHARGE_IN_DIMENSIONLESS_UNITS_LABEL: 1.0,
+                        INVERSE_FOURTH_CHARGE_IN_DIMENSIONLESS_UNITS_LABEL: 1.0,
+                        RED_PIXEL_STRENGTH_LABEL: 1.0,
+                        GREEN_PIXEL_STRENGTH_LABEL: 1.0,
+                        BLUE_PIXEL_STRENGTH_LABEL: 1.0,
+                        configuration_parsing::SPECIES_LABEL: "B"
+                    }
+                ),
+                Some(&species_interactions),
+            )
+            .map_err(|parsing_error| format!("Could not parse particle: {:?}", parsing_error))?[0],
+            Some(&species_interactions),
+            &[],
+            &configuration_parsing::MotionMode::Free,
+        );
+        let read_particles = from_json(&particle_configuration, Some(&species_interactions))
+            .map_err(|parsing_error| format!("Could not parse: {:?}", parsing_error))?;
+        let expected_index = data_structure::charge::SpeciesIndex(2);
+        if read_particles[0].intrinsic_values.species_index != expected_index {
+            return Err(format!(
+                "Expected {:?}, got {:?} from {}",
+                expected_index, read_particles, particle_configuration
+            ));
+        }
+
+        let is_rejected_without_table = from_json(&particle_configuration, None).is_err();
+        particle_configuration[configuration_parsing::SPECIES_LABEL] = serde_json::json!("C");
+        let is_unknown_species_rejected =
+            from_json(&particle_configuration, Some(&species_interactions)).is_err();
+        if is_rejected_without_table && is_unknown_species_rejected {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected errors without a table ({}) and for unknown species ({})",
+                is_rejected_without_table, is_unknown_species_rejected
+            ))
+        }
+    }
+
+    #[test]
+    fn check_spring_bonds_round_trip_and_bond_to_itself_rejected() -> Result<(), String> {
+        let expected_bonds = vec![
+            configuration_parsing::SpringBond {
+                first_particle_index: 0,
+                second_particle_index: 2,
+                rest_length: 1.5,
+                stiffness: 4.0,
+                damping_coefficient: 0.0,
+            },
+            configuration_parsing::SpringBond {
+                first_particle_index: 1,
+                second_particle_index: 2,
+                rest_length: 0.1 + 0.2,
+                stiffness: 0.1 + 0.7,
+                damping_coefficient: 0.25,
+            },
+        ];
+        let mut particle_configuration = to_json(
+            &from_json(
+                &serde_json::json!(
+                    {
+                        COMMON_DISPLACEMENT_IN_PIXELS_LABEL: { "x": 1.0, "y": 2.0 },
+                        LINEAR_VELOCITY_IN_PIXELS_PER_SECOND_LABEL: { "x": 0.0, "y": 0.0 },
+                        INERTIAL_MASS_IN_MASS_UNITS_LABEL: 1.0,
+                        INVERSE_SQUARED_CHARGE_IN_DIMENSIONLESS_UNITS_LABEL: 1.0,
+                        INVERSE_FOURTH_CHARGE_IN_DIMENSIONLESS_UNITS_LABEL: 1.0,
+                        RED_PIXEL_STRENGTH_LABEL: 1.0,
+                        GREEN_PIXEL_STRENGTH_LABEL: 1.0,
+                        BLUE_PIXEL_STRENGTH_LABEL: 1.0
+                    }
+                ),
+                None,
+            )
+            .map_err(|parsing_error| format!("Could not parse particle: {:?}", parsing_error))?[0],
+            None,
+            &expected_bonds,
+            &configuration_parsing::MotionMode::Free,
+        );
+        let written_text = serde_json::to_string(&particle_configuration)
+            .map_err(|writing_error| format!("Could not write: {:?}", writing_error))?;
+        let read_bonds = spring_bonds_from_json(
+            &serde_json::from_str(&written_text)
+                .map_err(|reading_error| format!("Could not read: {:?}", reading_error))?,
+            2,
+        )
+        .map_err(|parsing_error| format!("Could not parse bonds: {:?}", parsing_error))?;
+        if read_bonds != expected_bonds {
+            return Err(format!(
+                "Expected {:?}, got {:?} from {}",
+                expected_bonds, read_bonds, written_text
+            ));
+        }
+
+        let is_bond_to_itself_rejected =
+            spring_bonds_from_json(&particle_configuration, 1).is_err();
+        particle_configuration[super::super::SPRING_BONDS_LABEL][0]
+            [super::super::DAMPING_COEFFICIENT_LABEL] = serde_json::json!(-0.5);
+        let is_negative_damping_rejected =
+            spring_bonds_from_json(&particle_configuration, 2).is_err();
+        if is_bond_to_itself_rejected && is_negative_damping_rejected {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected errors for a bond to itself ({}) and for negative damping ({})",
+                is_bond_to_itself_rejected, is_negative_damping_rejected
+            ))
+        }
+    }
+
+    #[test]
+    fn check_motion_modes_round_trip_and_unordered_keyframes_rejected() -> Result<(), String> {
+        let particle_configuration = serde_json::json!(
+            {
+                COMMON_DISPLACEMENT_IN_PIXELS_LABEL: { "x": 1.0, "y": 2.0 },
+                LINEAR_VELOCITY_IN_PIXELS_PER_SECOND_LABEL: { "x": 0.0, "y": 0.0 },
+                INERTIAL_MASS_IN_MASS_UNITS_LABEL: 1.0,
+                INVERSE_SQUARED_CHARGE_IN_DIMENSIONLESS_UNITS_LABEL: 1.0,
+                INVERSE_FOURTH_CHARGE_IN_DIMENSIONLESS_UNITS_LABEL: 1.0,
+                RED_PIXEL_STRENGTH_LABEL: 1.0,
+                GREEN_PIXEL_STRENGTH_LABEL: 1.0,
+                BLUE_PIXEL_STRENGTH_LABEL: 1.0
+            }
+        );
+        let generated_particles = from_json(&particle_configuration, None)
+            .map_err(|parsing_error| format!("Could not parse particle: {:?}", parsing_error))?;
+        let expected_modes = vec![
+            configuration_parsing::MotionMode::Free,
+            configuration_parsing::MotionMode::Fixed {
+                horizontal_coordinate: 1.0,
+                vertical_coordinate: 2.0,
+            },
+            configuration_parsing::MotionMode::Keyframes(vec![
+                configuration_parsing::Keyframe {
+                    time_in_seconds: 0.1 + 0.2,
+                    horizontal_coordinate: 1.0 / 3.0,
+                    vertical_coordinate: -2.0,
+                },
+                configuration_parsing::Keyframe {
+                    time_in_seconds: 1.5,
+                    horizontal_coordinate: 4.0,
+                    vertical_coordinate: 2.0_f64.sqrt(),
+                },
+            ]),
+            configuration_parsing::MotionMode::Ellipse(configuration_parsing::EllipticalPath {
+                horizontal_center: -1.0,
+                vertical_center: 0.7_f64.ln(),
+                horizontal_semi_axis: 3.0,
+                vertical_semi_axis: 0.0,
+                angular_frequency: -std::f64::consts::PI,
+                initial_phase: 0.25,
+            }),
+        ];
+        for expected_mode in &expected_modes {
+            let written_text =
+                serde_json::to_string(&to_json(&generated_particles[0], None, &[], expected_mode))
+                    .map_err(|writing_error| format!("Could not write: {:?}", writing_error))?;
+            let read_modes = motion_modes_from_json(
+                &serde_json::from_str(&written_text)
+                    .map_err(|reading_error| format!("Could not read: {:?}", reading_error))?,
+                &generated_particles,
+            )
+            .map_err(|parsing_error| format!("Could not parse modes: {:?}", parsing_error))?;
+            if read_modes != vec![expected_mode.clone()] {
+                return Err(format!(
+                    "Expected {:?}, got {:?} from {}",
+                    expected_mode, read_modes, written_text
+                ));
+            }
+        }
+
+        let mut unordered_configuration = particle_configuration.clone();
+        unordered_configuration[super::super::MOTION_MODE_LABEL] = serde_json::json!(
+            {
+                super::super::KEYFRAMES_LABEL: [
+                    {
+                        super::super::TIME_IN_SECONDS_LABEL: 1.0,
+                        super::super::POSITION_IN_PIXELS_LABEL: { "x": 0.0, "y": 0.0 }
+                    },
+                    {
+                        super::super::TIME_IN_SECONDS_LABEL: 1.0,
+                        super::super::POSITION_IN_PIXELS_LABEL: { "x": 1.0, "y": 0.0 }
+                    }
+                ]
+            }
+        );
+        let is_unordered_rejected =
+            motion_modes_from_json(&unordered_configuration, &generated_particles).is_err();
+        let mut unknown_configuration = particle_configuration;
+        unknown_configuration[super::super::MOTION_MODE_LABEL] = serde_json::json!("orbiting");
+        let is_unknown_rejected =
+            motion_modes_from_json(&unknown_configuration, &generated_particles).is_err();
+        if is_unordered_rejected && is_unknown_rejected {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected errors for unordered keyframes ({}) and an unknown mode ({})",
+                is_unordered_rejected, is_unknown_rejected
+            ))
+        }
+    }
 }
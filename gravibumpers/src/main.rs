@@ -2,23 +2,69 @@ extern crate data_structure;
 extern crate serde_json;
 extern crate visual_representation;
 
+use contiguous_particle_struct::MultithreadedVectorOfMassNormalizedWithForceFieldGenerator;
 use contiguous_particle_struct::VectorOfDynamicBoxedMassNormalizedWithForceFieldGenerator;
 use contiguous_particle_struct::VectorOfMassNormalizedWithForceFieldGenerator;
 use data_structure::particle::contiguous_struct as contiguous_particle_struct;
 use data_structure::particle::mixture::VectorOfDynamicBoxedMassNormalizedStructsAndBoxesGenerator;
+use data_structure::particle::struct_of_arrays::ParticleArraysWithForceFieldGenerator;
 use data_structure::particle::struct_of_boxes as particle_struct_of_boxes;
 use particle_struct_of_boxes::VectorOfDynamicBoxedMassNormalizedBoxesWithForceFieldGenerator;
+use std::io::Write;
+use time_evolution::force_law::PairwiseForceLaw;
 use visual_representation::SequenceAnimator;
 
+const BINARY_TRAJECTORY_FORMAT_NAME: &str = "binary";
+
+const MEMORY_LAYOUT_NAMES: [&str; 6] = [
+    "VecOfPureStruct",
+    "VecOfBoxedStruct",
+    "VecOfDoubleBoxed",
+    "VecOfMixedBoxed",
+    "VecOfPureStructMultithreaded",
+    "StructOfArrays",
+];
+
 fn print_help() -> Result<(), Box<dyn std::error::Error>> {
     println!("GraviBumpers!");
-    println!("The first argument should be the mode. Currently implemented: rgb_demo, read_file");
+    println!(
+        "The first argument should be the mode. Currently implemented: rgb_demo, read_file, \
+        resume, export_trajectory, render, benchmark_layouts"
+    );
     println!("rgb_demo expects 1 further argument: the filename for the output APNG.");
     println!(
         "read_file expects 3 further arguments: the filename of the configuration, then the \
         filename for the output, then a single word to determine if off-screen particles should \
         be drawn on the border (case-insensitive 'yes' or 'true' to draw them, 'no' or 'false' \
-        leave them undrawn)."
+        leave them undrawn). If the configuration has framesPerCheckpoint and checkpointFilename, \
+        the state of the evolution is written to the checkpoint file every framesPerCheckpoint \
+        frames."
+    );
+    println!(
+        "resume expects the same 3 further arguments as read_file, but with the filename of a \
+        checkpoint instead of a configuration. The evolution continues from the frame of the \
+        checkpoint, and the output only has the frames from there on."
+    );
+    println!(
+        "export_trajectory expects 3 further arguments: the filename of the configuration, then \
+        the filename for the output, then the format of the output, either 'csv' for \
+        comma-separated values or 'jsonl' for JSON Lines, which have a row for every particle in \
+        every frame, with the frame index, the time, the particle index, the position, the \
+        velocity, the mass, the charges, and the color, or 'binary' for the compact trajectory \
+        format, which can be read back frame by frame."
+    );
+    println!(
+        "render expects 3 or 4 further arguments: the filename of a trajectory written by \
+        export_trajectory in the binary format, then the filename for the output APNG, then the \
+        same word as for read_file about drawing off-screen particles, then optionally the \
+        filename of a JSON object with picture settings such as rightBorderCoordinate, which \
+        replace those of the configuration saved with the trajectory. The particles are drawn \
+        as they were saved, without evolving them again."
+    );
+    println!(
+        "benchmark_layouts expects 1 further argument: the filename of the configuration. The \
+        time evolution is calculated with every memory layout in turn, ignoring the memoryLayout \
+        of the configuration, and nothing is animated."
     );
     Ok(())
 }
@@ -40,6 +86,9 @@ fn create_rgb_demonstration(
             inertial_mass: data_structure::charge::InertialMassUnit(1.9),
             inverse_squared_charge: data_structure::charge::InverseSquaredChargeUnit(2.8),
             inverse_fourth_charge: data_structure::charge::InverseFourthChargeUnit(3.7),
+            species_index: data_structure::charge::SpeciesIndex(0),
+            collision_radius: data_structure::position::SeparationUnit(0.0),
+            is_tracer: false,
             color_brightness: data_structure::color::new_triplet(
                 data_structure::color::RedUnit(4.6),
                 data_structure::color::GreenUnit(5.5),
@@ -65,65 +114,539 @@ fn create_rgb_demonstration(
             &ignored_particle,
         ));
     }
-    demonstration_animator.animate_sequence(dummy_sequence.into_iter(), 100, output_filename)
+    demonstration_animator.animate_sequence(
+        dummy_sequence
+            .into_iter()
+            .map(Ok::<_, Box<dyn std::error::Error>>),
+        100,
+        output_filename,
+    )
 }
 
-fn evolve_and_animate(
+fn create_particle_animator(
+    picture_configuration: &configuration_parsing::PictureConfiguration,
+    should_draw_offscreen_on_border: bool,
+) -> Result<
+    visual_representation::apng::ApngAnimator<
+        visual_representation::brightness_aggregator::PixelBrightnessAggregator,
+    >,
+    Box<dyn std::error::Error>,
+> {
+    let pixel_brightness_aggregator = visual_representation::brightness_aggregator::new(
+        visual_representation::HorizontalPixelAmount(picture_configuration.right_border_coordinate),
+        visual_representation::VerticalPixelAmount(picture_configuration.upper_border_coordinate),
+        visual_representation::HorizontalPixelAmount(picture_configuration.left_border_coordinate),
+        visual_representation::VerticalPixelAmount(picture_configuration.lower_border_coordinate),
+        should_draw_offscreen_on_border,
+    )?;
+    Ok(visual_representation::apng::new(
+        pixel_brightness_aggregator,
+        1,
+    ))
+}
+
+/// The evolution is either drawn as an APNG, written out as numbers in text or in the binary
+/// trajectory format, or only calculated.
+enum EvolutionOutput<'a> {
+    NoOutput,
+    Animation {
+        output_filename: &'a str,
+        should_draw_offscreen_on_border: bool,
+    },
+    Trajectory {
+        output_filename: &'a str,
+        trajectory_format: visual_representation::text_trajectory::TrajectoryFormat,
+    },
+    BinaryTrajectory {
+        output_filename: &'a str,
+        configuration: &'a serde_json::Value,
+    },
+}
+
+fn report_diagnostics(
     parsed_configuration: &configuration_parsing::ParsedConfiguration,
-    particles_in_time_evolver: &mut impl time_evolution::ParticlesInTimeEvolver,
+    milliseconds_per_time_slice: u16,
+    recorded_diagnostics: &[time_evolution::diagnostics::FrameDiagnostics],
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let (Some(initial_diagnostics), Some(final_diagnostics)) =
+        (recorded_diagnostics.first(), recorded_diagnostics.last())
+    {
+        let largest_relative_drift = recorded_diagnostics
+            .iter()
+            .map(|frame_diagnostics| {
+                time_evolution::diagnostics::relative_energy_drift(
+                    initial_diagnostics,
+                    frame_diagnostics,
+                )
+            })
+            .fold(0.0, f64::max);
+        println!(
+            "Over {} frames, total energy went from {:?} to {:?} (largest relative drift {:?}), \
+            linear momentum went from ({:?}, {:?}) to ({:?}, {:?}), and angular momentum went \
+            from {:?} to {:?}",
+            recorded_diagnostics.len(),
+            initial_diagnostics.total_energy(),
+            final_diagnostics.total_energy(),
+            largest_relative_drift,
+            initial_diagnostics.horizontal_momentum,
+            initial_diagnostics.vertical_momentum,
+            final_diagnostics.horizontal_momentum,
+            final_diagnostics.vertical_momentum,
+            initial_diagnostics.angular_momentum,
+            final_diagnostics.angular_momentum
+        );
+    }
+    if let Some(diagnostics_filename) = parsed_configuration.diagnostics_filename {
+        let mut diagnostics_writer =
+            std::io::BufWriter::new(std::fs::File::create(diagnostics_filename)?);
+        time_evolution::diagnostics::write_time_series(
+            recorded_diagnostics,
+            milliseconds_per_time_slice,
+            &mut diagnostics_writer,
+        )?;
+        diagnostics_writer.flush()?;
+    }
+    Ok(())
+}
+
+fn evolve_and_animate<ParticlesInTimeEvolverImplementation>(
+    parsed_configuration: &configuration_parsing::ParsedConfiguration,
+    particles_in_time_evolver: &mut ParticlesInTimeEvolverImplementation,
     initial_particle_configuration: impl std::iter::ExactSizeIterator<
         Item = impl data_structure::particle::IndividualRepresentation,
     >,
-    should_draw_offscreen_on_border: bool,
-    output_filename: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
+    evolution_output: &EvolutionOutput,
+    checkpoint_writer: Option<&initial_conditions::checkpoint::CheckpointWriter>,
+    first_frame_index: usize,
+) -> Result<std::time::Duration, Box<dyn std::error::Error>>
+where
+    ParticlesInTimeEvolverImplementation: time_evolution::ParticlesInTimeEvolver,
+    ParticlesInTimeEvolverImplementation::ParticleIterator: Clone,
+{
+    // The time slices are only calculated as they are requested, so the time taken to set up the
+    // evolution is small, and the calculation is timed along with whatever consumes the slices.
     let instant_before_evolution = std::time::Instant::now();
     let particle_set_evolution = particles_in_time_evolver.create_time_sequence(
         &parsed_configuration.evolution_configuration,
         initial_particle_configuration,
     )?;
-
-    println!(
-        "Calculation of time evolution took {}ms",
-        instant_before_evolution.elapsed().as_millis()
-    );
-
-    let picture_configuration = &parsed_configuration.picture_configuration;
-    let pixel_brightness_aggregator = visual_representation::brightness_aggregator::new(
-        visual_representation::HorizontalPixelAmount(picture_configuration.right_border_coordinate),
-        visual_representation::VerticalPixelAmount(picture_configuration.upper_border_coordinate),
-        visual_representation::HorizontalPixelAmount(picture_configuration.left_border_coordinate),
-        visual_representation::VerticalPixelAmount(picture_configuration.lower_border_coordinate),
-        should_draw_offscreen_on_border,
+    let force_law = ParticlesInTimeEvolverImplementation::ForceLaw::new_from_configuration(
+        &parsed_configuration.evolution_configuration,
     )?;
-    let particle_animator = visual_representation::apng::new(pixel_brightness_aggregator, 1);
-
-    let instant_before_animation = std::time::Instant::now();
-    particle_animator.animate_sequence(
+    // The frames are checked before they are written to a checkpoint, so that a checkpoint is never
+    // written of a frame which has blown up.
+    let particle_configurations = time_evolution::watchdog::watch_each_frame(
         particle_set_evolution.particle_configurations,
+        &force_law,
+        &parsed_configuration.watchdog_configuration,
+        first_frame_index,
+    );
+    let particle_configurations = initial_conditions::checkpoint::write_periodically(
+        particle_configurations,
+        checkpoint_writer,
+    );
+    let mut recorded_diagnostics = vec![];
+    let particle_configurations = time_evolution::diagnostics::record_each_frame(
+        particle_configurations,
+        &force_law,
+        match evolution_output {
+            EvolutionOutput::NoOutput => None,
+            _ => Some(&mut recorded_diagnostics),
+        },
+    );
+
+    let output_result = match evolution_output {
+        // Without an output file, only the time evolution is being timed.
+        EvolutionOutput::NoOutput => {
+            for time_slice in particle_configurations {
+                let _evolved_particles = time_slice?;
+            }
+            let evolution_duration = instant_before_evolution.elapsed();
+            println!(
+                "Calculation of time evolution took {}ms",
+                evolution_duration.as_millis()
+            );
+            return Ok(evolution_duration);
+        }
+        EvolutionOutput::Animation {
+            output_filename,
+            should_draw_offscreen_on_border,
+        } => {
+            let particle_animator = create_particle_animator(
+                &parsed_configuration.picture_configuration,
+                *should_draw_offscreen_on_border,
+            )?;
+            particle_animator.animate_sequence(
+                particle_configurations,
+                particle_set_evolution.milliseconds_between_configurations,
+                output_filename,
+            )
+        }
+        EvolutionOutput::Trajectory {
+            output_filename,
+            trajectory_format,
+        } => {
+            let trajectory_writer = visual_representation::text_trajectory::new(*trajectory_format);
+            trajectory_writer.animate_sequence(
+                particle_configurations,
+                particle_set_evolution.milliseconds_between_configurations,
+                output_filename,
+            )
+        }
+        EvolutionOutput::BinaryTrajectory {
+            output_filename,
+            configuration,
+        } => trajectory_file::writer::write_sequence_to_file(
+            particle_configurations,
+            configuration,
+            particle_set_evolution.milliseconds_between_configurations,
+            output_filename,
+        ),
+    };
+    // The diagnostics of the frames before a failure are reported before the failure, as they may
+    // show how the evolution went wrong.
+    report_diagnostics(
+        parsed_configuration,
         particle_set_evolution.milliseconds_between_configurations,
-        output_filename,
+        &recorded_diagnostics,
     )?;
+    output_result?;
 
+    let evolution_duration = instant_before_evolution.elapsed();
     println!(
-        "Animation took {}ms",
-        instant_before_animation.elapsed().as_millis()
+        "Calculation of time evolution with output took {}ms",
+        evolution_duration.as_millis()
     );
 
-    Ok(())
+    Ok(evolution_duration)
 }
 
-fn run_from_configuration_file(
+fn evolve_and_animate_given_memory_layout<CollectionElement, CollectionGenerator>(
+    parsed_configuration: &configuration_parsing::ParsedConfiguration,
+    collection_generator: CollectionGenerator,
+    initial_particle_configuration: impl std::iter::ExactSizeIterator<
+        Item = impl data_structure::particle::IndividualRepresentation,
+    >,
+    evolution_output: &EvolutionOutput,
+    checkpoint_writer: Option<&initial_conditions::checkpoint::CheckpointWriter>,
+    first_frame_index: usize,
+) -> Result<std::time::Duration, Box<dyn std::error::Error>>
+where
+    CollectionElement: data_structure::particle::WritableInForceField,
+    CollectionGenerator: data_structure::particle::CollectionInForceFieldGenerator<
+        MutableElement = CollectionElement,
+    >,
+{
+    let evolver_configuration = &parsed_configuration.evolver_configuration;
+    match evolver_configuration.evolver_name {
+        "SecondOrderEuler" => {
+            let mut particles_in_time_evolver =
+                time_evolution::second_order_euler::new_given_memory_strategy(
+                    evolver_configuration.number_of_steps_per_time_slice,
+                    collection_generator,
+                )?;
+            evolve_and_animate(
+                parsed_configuration,
+                &mut particles_in_time_evolver,
+                initial_particle_configuration,
+                evolution_output,
+                checkpoint_writer,
+                first_frame_index,
+            )
+        }
+        "Leapfrog" => {
+            let mut particles_in_time_evolver =
+                time_evolution::leapfrog::new_given_memory_strategy(
+                    evolver_configuration.number_of_steps_per_time_slice,
+                    collection_generator,
+                )?;
+            evolve_and_animate(
+                parsed_configuration,
+                &mut particles_in_time_evolver,
+                initial_particle_configuration,
+                evolution_output,
+                checkpoint_writer,
+                first_frame_index,
+            )
+        }
+        "FourthOrderRungeKutta" => {
+            let mut particles_in_time_evolver =
+                time_evolution::runge_kutta::new_given_memory_strategy(
+                    evolver_configuration.number_of_steps_per_time_slice,
+                    collection_generator,
+                )?;
+            evolve_and_animate(
+                parsed_configuration,
+                &mut particles_in_time_evolver,
+                initial_particle_configuration,
+                evolution_output,
+                checkpoint_writer,
+                first_frame_index,
+            )
+        }
+        "AdaptiveStepDoubling" => {
+            let relative_error_tolerance = evolver_configuration
+                .relative_error_tolerance
+                .ok_or_else(|| {
+                    configuration_parsing::ConfigurationParseError::new(
+                        "Evolver AdaptiveStepDoubling requires relativeErrorTolerance",
+                    )
+                })?;
+            let mut particles_in_time_evolver =
+                time_evolution::adaptive::new_given_memory_strategy(
+                    evolver_configuration.number_of_steps_per_time_slice,
+                    relative_error_tolerance,
+                    collection_generator,
+                )?;
+            let evolution_duration = evolve_and_animate(
+                parsed_configuration,
+                &mut particles_in_time_evolver,
+                initial_particle_configuration,
+                evolution_output,
+                checkpoint_writer,
+                first_frame_index,
+            )?;
+            let step_counts = particles_in_time_evolver.get_step_counts();
+            let total_accepted_steps: u32 = step_counts
+                .iter()
+                .map(|step_count| step_count.accepted_steps)
+                .sum();
+            let total_rejected_steps: u32 = step_counts
+                .iter()
+                .map(|step_count| step_count.rejected_steps)
+                .sum();
+            println!(
+                "Adaptive stepping accepted {} steps and rejected {} steps over {} time slices",
+                total_accepted_steps,
+                total_rejected_steps,
+                step_counts.len()
+            );
+            Ok(evolution_duration)
+        }
+        _ => Err(Box::new(
+            configuration_parsing::ConfigurationParseError::new(&format!(
+                "Evolver name \"{}\" is unknown",
+                evolver_configuration.evolver_name
+            )),
+        )),
+    }
+}
+
+/// The results of a multithreaded evolution depend on the number of threads, so the number can be
+/// fixed in the configuration, and otherwise defaults to the number of available cores.
+fn get_number_of_threads(
+    parsed_configuration: &configuration_parsing::ParsedConfiguration,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    match parsed_configuration.evolver_configuration.number_of_threads {
+        Some(0) => Err(Box::new(
+            configuration_parsing::ConfigurationParseError::new("numberOfThreads must be > 0"),
+        )),
+        Some(number_of_threads) => Ok(number_of_threads),
+        None => Ok(std::thread::available_parallelism()
+            .map(|available_threads| available_threads.get())
+            .unwrap_or(1)),
+    }
+}
+
+/// The spring bonds declared by the generators refer to the particles by their indices in the
+/// initial particle map, so the bonds are kept with the particles, as are the motion modes, one for
+/// each particle in the same order.
+struct GeneratedInitialConditions {
+    initial_particle_map: std::vec::Vec<data_structure::particle::BasicIndividual>,
+    spring_bonds: std::vec::Vec<configuration_parsing::SpringBond>,
+    motion_modes: std::vec::Vec<configuration_parsing::MotionMode>,
+}
+
+fn create_initial_particles(
+    parsed_configuration: &configuration_parsing::ParsedConfiguration,
+) -> Result<GeneratedInitialConditions, Box<dyn std::error::Error>> {
+    let mut initial_particle_map: std::vec::Vec<data_structure::particle::BasicIndividual> = vec![];
+    let mut spring_bonds = vec![];
+    let mut motion_modes = vec![];
+    let species_interactions = parsed_configuration
+        .evolution_configuration
+        .species_interactions
+        .as_ref();
+    for generator_configuration in parsed_configuration.generator_configurations.iter() {
+        let first_particle_index = initial_particle_map.len();
+        let initial_particles_from_configuration = match generator_configuration.generator_name {
+            "single" => {
+                spring_bonds.extend(initial_conditions::single::spring_bonds_from_json(
+                    generator_configuration.generator_configuration,
+                    first_particle_index,
+                )?);
+                let single_particles = initial_conditions::single::from_json(
+                    generator_configuration.generator_configuration,
+                    species_interactions,
+                )?;
+                motion_modes.extend(initial_conditions::single::motion_modes_from_json(
+                    generator_configuration.generator_configuration,
+                    &single_particles,
+                )?);
+                single_particles
+            }
+            "circle" => {
+                let circle_particles = initial_conditions::circle::from_json(
+                    generator_configuration.generator_configuration,
+                    species_interactions,
+                )?;
+                spring_bonds.extend(initial_conditions::circle::spring_bonds_from_json(
+                    generator_configuration.generator_configuration,
+                    first_particle_index,
+                    &circle_particles,
+                )?);
+                motion_modes.extend(initial_conditions::circle::motion_modes_from_json(
+                    generator_configuration.generator_configuration,
+                    &circle_particles,
+                )?);
+                circle_particles
+            }
+            _ => {
+                return Err(Box::new(
+                    configuration_parsing::ConfigurationParseError::new(&format!(
+                        "Generator name \"{}\" is unknown",
+                        generator_configuration.generator_name
+                    )),
+                ))
+            }
+        };
+        initial_particle_map.extend(initial_particles_from_configuration.iter());
+    }
+    Ok(GeneratedInitialConditions {
+        initial_particle_map,
+        spring_bonds,
+        motion_modes,
+    })
+}
+
+fn evolve_and_animate_given_memory_layout_name(
+    memory_layout: &str,
+    parsed_configuration: &configuration_parsing::ParsedConfiguration,
+    initial_particle_map: &[data_structure::particle::BasicIndividual],
+    evolution_output: &EvolutionOutput,
+    checkpoint_writer: Option<&initial_conditions::checkpoint::CheckpointWriter>,
+    first_frame_index: usize,
+) -> Result<std::time::Duration, Box<dyn std::error::Error>> {
+    match memory_layout {
+        "VecOfPureStruct" => evolve_and_animate_given_memory_layout(
+            parsed_configuration,
+            VectorOfMassNormalizedWithForceFieldGenerator {},
+            initial_particle_map.iter(),
+            evolution_output,
+            checkpoint_writer,
+            first_frame_index,
+        ),
+        "VecOfBoxedStruct" => evolve_and_animate_given_memory_layout(
+            parsed_configuration,
+            VectorOfDynamicBoxedMassNormalizedWithForceFieldGenerator {},
+            initial_particle_map.iter(),
+            evolution_output,
+            checkpoint_writer,
+            first_frame_index,
+        ),
+        "VecOfDoubleBoxed" => evolve_and_animate_given_memory_layout(
+            parsed_configuration,
+            VectorOfDynamicBoxedMassNormalizedBoxesWithForceFieldGenerator {},
+            initial_particle_map.iter(),
+            evolution_output,
+            checkpoint_writer,
+            first_frame_index,
+        ),
+        "VecOfMixedBoxed" => evolve_and_animate_given_memory_layout(
+            parsed_configuration,
+            VectorOfDynamicBoxedMassNormalizedStructsAndBoxesGenerator {},
+            initial_particle_map.iter(),
+            evolution_output,
+            checkpoint_writer,
+            first_frame_index,
+        ),
+        "StructOfArrays" => evolve_and_animate_given_memory_layout(
+            parsed_configuration,
+            ParticleArraysWithForceFieldGenerator {},
+            initial_particle_map.iter(),
+            evolution_output,
+            checkpoint_writer,
+            first_frame_index,
+        ),
+        "VecOfPureStructMultithreaded" => evolve_and_animate_given_memory_layout(
+            parsed_configuration,
+            MultithreadedVectorOfMassNormalizedWithForceFieldGenerator {
+                number_of_threads: get_number_of_threads(parsed_configuration)?,
+            },
+            initial_particle_map.iter(),
+            evolution_output,
+            checkpoint_writer,
+            first_frame_index,
+        ),
+        _ => Err(Box::new(
+            configuration_parsing::ConfigurationParseError::new(&format!(
+                "Memory layout \"{}\" is unknown",
+                memory_layout
+            )),
+        )),
+    }
+}
+
+fn run_benchmark_of_memory_layouts(
     command_line_arguments: &[String],
 ) -> Result<(), Box<dyn std::error::Error>> {
     println!("GraviBumpers!");
-    if command_line_arguments.len() != 5 {
+    if command_line_arguments.len() != 3 {
         return print_help();
     }
 
     let input_filename = &command_line_arguments[2];
-    let output_filename = &command_line_arguments[3];
-    let input_for_drawing_offscreen = &command_line_arguments[4];
+    println!(
+        "Benchmarking memory layouts with configuration from {}",
+        input_filename
+    );
+    let configuration_content = std::fs::read_to_string(input_filename)?;
+    let deserialized_configuration: serde_json::Value =
+        serde_json::from_str(&configuration_content)?;
+    let mut parsed_configuration =
+        configuration_parsing::parse_deserialized_configuration(&deserialized_configuration)?;
+    let GeneratedInitialConditions {
+        initial_particle_map,
+        spring_bonds,
+        motion_modes,
+    } = create_initial_particles(&parsed_configuration)?;
+    parsed_configuration.evolution_configuration.spring_bonds = spring_bonds;
+    parsed_configuration.evolution_configuration.motion_modes = motion_modes;
+
+    let mut evolution_durations = vec![];
+    for memory_layout in MEMORY_LAYOUT_NAMES.iter() {
+        println!("Memory layout {}:", memory_layout);
+        evolution_durations.push(evolve_and_animate_given_memory_layout_name(
+            memory_layout,
+            &parsed_configuration,
+            &initial_particle_map,
+            &EvolutionOutput::NoOutput,
+            None,
+            0,
+        )?);
+    }
+
+    println!(
+        "Time evolution of {} particles for {} time slices with evolver {}:",
+        initial_particle_map.len(),
+        parsed_configuration
+            .evolution_configuration
+            .number_of_time_slices,
+        parsed_configuration.evolver_configuration.evolver_name
+    );
+    for (memory_layout, evolution_duration) in
+        MEMORY_LAYOUT_NAMES.iter().zip(evolution_durations.iter())
+    {
+        println!(
+            "{:>30}: {}ms",
+            memory_layout,
+            evolution_duration.as_millis()
+        );
+    }
+    Ok(())
+}
+
+fn parse_should_draw_offscreen_on_border(input_for_drawing_offscreen: &str) -> Option<bool> {
     let should_draw_offscreen_on_border = String::from("yes")
         .eq_ignore_ascii_case(input_for_drawing_offscreen)
         || String::from("true").eq_ignore_ascii_case(input_for_drawing_offscreen);
@@ -131,9 +654,57 @@ fn run_from_configuration_file(
         && !(String::from("no").eq_ignore_ascii_case(input_for_drawing_offscreen)
             || String::from("false").eq_ignore_ascii_case(input_for_drawing_offscreen))
     {
+        return None;
+    }
+    Some(should_draw_offscreen_on_border)
+}
+
+/// The last argument is the format for export_trajectory, and whether to draw off-screen particles
+/// on the border for the modes which animate. The binary format holds the configuration, so that
+/// the trajectory can be rendered later.
+fn parse_evolution_output<'a>(
+    command_line_arguments: &'a [String],
+    deserialized_configuration: &'a serde_json::Value,
+) -> Option<EvolutionOutput<'a>> {
+    let output_filename = &command_line_arguments[3];
+    let last_argument = &command_line_arguments[4];
+    if command_line_arguments[1] == "export_trajectory" {
+        if last_argument == BINARY_TRAJECTORY_FORMAT_NAME {
+            return Some(EvolutionOutput::BinaryTrajectory {
+                output_filename,
+                configuration: deserialized_configuration,
+            });
+        }
+        return visual_representation::text_trajectory::parse_format(last_argument)
+            .ok()
+            .map(|trajectory_format| EvolutionOutput::Trajectory {
+                output_filename,
+                trajectory_format,
+            });
+    }
+
+    parse_should_draw_offscreen_on_border(last_argument).map(|should_draw_offscreen_on_border| {
+        EvolutionOutput::Animation {
+            output_filename,
+            should_draw_offscreen_on_border,
+        }
+    })
+}
+
+/// A checkpoint is a configuration which also has the index of the frame that it represents, so
+/// resuming from one only differs from running from a configuration in skipping the frames before
+/// that index.
+fn run_from_configuration_file(
+    command_line_arguments: &[String],
+    is_resumed_from_checkpoint: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    println!("GraviBumpers!");
+    if command_line_arguments.len() != 5 {
         return print_help();
     }
 
+    let input_filename = &command_line_arguments[2];
+    let output_filename = &command_line_arguments[3];
     println!(
         "Reading configuration from {}, will write to {}",
         input_filename, output_filename
@@ -141,109 +712,162 @@ fn run_from_configuration_file(
 
     let instant_before_configuration = std::time::Instant::now();
 
-    let mut initial_particle_map: std::vec::Vec<data_structure::particle::BasicIndividual> = vec![];
     let configuration_content = std::fs::read_to_string(input_filename)?;
     let deserialized_configuration: serde_json::Value =
         serde_json::from_str(&configuration_content)?;
-    let parsed_configuration =
+    let evolution_output =
+        match parse_evolution_output(command_line_arguments, &deserialized_configuration) {
+            Some(evolution_output) => evolution_output,
+            None => return print_help(),
+        };
+    let mut parsed_configuration =
         configuration_parsing::parse_deserialized_configuration(&deserialized_configuration)?;
-    for generator_configuration in parsed_configuration.generator_configurations.iter() {
-        let initial_particles_from_configuration = match generator_configuration.generator_name {
-            "single" => initial_conditions::single::from_json(
-                generator_configuration.generator_configuration,
+    // A binary trajectory file stores the intrinsic values of the particles once, in its header, so
+    // it cannot hold frames where particles have merged.
+    if let (EvolutionOutput::BinaryTrajectory { .. }, Some(_)) = (
+        &evolution_output,
+        parsed_configuration.evolution_configuration.merging_radius,
+    ) {
+        return Err(Box::new(
+            configuration_parsing::ConfigurationParseError::new(
+                "Binary trajectory output cannot be used with a merging radius",
             ),
-            "circle" => initial_conditions::circle::from_json(
-                generator_configuration.generator_configuration,
-            ),
-            _ => {
-                return Err(Box::new(
-                    configuration_parsing::ConfigurationParseError::new(&format!(
-                        "Generator name \"{}\" is unknown",
-                        generator_configuration.generator_name
-                    )),
-                ))
-            }
-        }?;
-        initial_particle_map.extend(initial_particles_from_configuration.iter());
+        ));
     }
+    let first_frame_index = if is_resumed_from_checkpoint {
+        initial_conditions::checkpoint::parse_frame_index(
+            &deserialized_configuration,
+            parsed_configuration
+                .evolution_configuration
+                .number_of_time_slices,
+        )?
+    } else {
+        0
+    };
+    parsed_configuration
+        .evolution_configuration
+        .number_of_time_slices -= first_frame_index;
+    let GeneratedInitialConditions {
+        initial_particle_map,
+        spring_bonds,
+        motion_modes,
+    } = create_initial_particles(&parsed_configuration)?;
+    parsed_configuration.evolution_configuration.spring_bonds = spring_bonds;
+    // The motion modes count time from the start of the uninterrupted evolution, as the checkpoints
+    // are written with them, while the evolver counts time from the first frame it evolves.
+    let seconds_before_first_frame = (first_frame_index as f64)
+        * (parsed_configuration
+            .evolution_configuration
+            .milliseconds_per_time_slice as f64)
+        * configuration_parsing::SECONDS_PER_MILLISECOND;
+    parsed_configuration.evolution_configuration.motion_modes = motion_modes
+        .iter()
+        .map(|motion_mode| motion_mode.after_elapsed_time(seconds_before_first_frame))
+        .collect();
+    // The random generator of any thermostat is jumped on by the same amount for every time slice,
+    // so its state at any frame follows from its state at the first frame.
+    let thermostat_configuration = parsed_configuration
+        .evolution_configuration
+        .drag
+        .and_then(|drag_configuration| drag_configuration.thermostat);
+    let thermostat_generator_state_at_frame = |frame_index: usize| {
+        thermostat_configuration.map(|thermostat_configuration| {
+            time_evolution::thermostat::generator_state_after_time_slices(
+                &thermostat_configuration,
+                frame_index - first_frame_index,
+            )
+        })
+    };
+    let checkpoint_writer =
+        parsed_configuration
+            .checkpoint_configuration
+            .as_ref()
+            .map(|checkpoint_configuration| {
+                initial_conditions::checkpoint::new_writer(
+                    &deserialized_configuration,
+                    parsed_configuration
+                        .evolution_configuration
+                        .species_interactions
+                        .as_ref(),
+                    &parsed_configuration.evolution_configuration.spring_bonds,
+                    &motion_modes,
+                    &thermostat_generator_state_at_frame,
+                    checkpoint_configuration,
+                    first_frame_index,
+                )
+            });
 
     println!(
         "Reading configuration took {}ms",
         instant_before_configuration.elapsed().as_millis()
     );
 
-    match parsed_configuration.evolver_configuration.memory_layout {
-        "VecOfPureStruct" => {
-            let mut particles_in_time_evolver =
-                time_evolution::second_order_euler::new_given_memory_strategy(
-                    parsed_configuration
-                        .evolver_configuration
-                        .number_of_steps_per_time_slice,
-                    VectorOfMassNormalizedWithForceFieldGenerator {},
-                )?;
-            evolve_and_animate(
-                &parsed_configuration,
-                &mut particles_in_time_evolver,
-                initial_particle_map.iter(),
-                should_draw_offscreen_on_border,
-                output_filename,
-            )
-        }
-        "VecOfBoxedStruct" => {
-            let mut particles_in_time_evolver =
-                time_evolution::second_order_euler::new_given_memory_strategy(
-                    parsed_configuration
-                        .evolver_configuration
-                        .number_of_steps_per_time_slice,
-                    VectorOfDynamicBoxedMassNormalizedWithForceFieldGenerator {},
-                )?;
-            evolve_and_animate(
-                &parsed_configuration,
-                &mut particles_in_time_evolver,
-                initial_particle_map.iter(),
-                should_draw_offscreen_on_border,
-                output_filename,
-            )
-        }
-        "VecOfDoubleBoxed" => {
-            let mut particles_in_time_evolver =
-                time_evolution::second_order_euler::new_given_memory_strategy(
-                    parsed_configuration
-                        .evolver_configuration
-                        .number_of_steps_per_time_slice,
-                    VectorOfDynamicBoxedMassNormalizedBoxesWithForceFieldGenerator {},
-                )?;
-            evolve_and_animate(
-                &parsed_configuration,
-                &mut particles_in_time_evolver,
-                initial_particle_map.iter(),
-                should_draw_offscreen_on_border,
-                output_filename,
-            )
-        }
-        "VecOfMixedBoxed" => {
-            let mut particles_in_time_evolver =
-                time_evolution::second_order_euler::new_given_memory_strategy(
-                    parsed_configuration
-                        .evolver_configuration
-                        .number_of_steps_per_time_slice,
-                    VectorOfDynamicBoxedMassNormalizedStructsAndBoxesGenerator {},
-                )?;
-            evolve_and_animate(
-                &parsed_configuration,
-                &mut particles_in_time_evolver,
-                initial_particle_map.iter(),
-                should_draw_offscreen_on_border,
-                output_filename,
-            )
-        }
-        _ => Err(Box::new(
+    evolve_and_animate_given_memory_layout_name(
+        parsed_configuration.evolver_configuration.memory_layout,
+        &parsed_configuration,
+        &initial_particle_map,
+        &evolution_output,
+        checkpoint_writer.as_ref(),
+        first_frame_index,
+    )?;
+    Ok(())
+}
+
+/// The picture settings are taken from the configuration saved in the trajectory file, with any
+/// top-level attributes of the optional settings file replacing those of the saved configuration.
+fn render_from_trajectory_file(
+    command_line_arguments: &[String],
+) -> Result<(), Box<dyn std::error::Error>> {
+    println!("GraviBumpers!");
+    if (command_line_arguments.len() != 5) && (command_line_arguments.len() != 6) {
+        return print_help();
+    }
+
+    let input_filename = &command_line_arguments[2];
+    let output_filename = &command_line_arguments[3];
+    let should_draw_offscreen_on_border =
+        match parse_should_draw_offscreen_on_border(&command_line_arguments[4]) {
+            Some(should_draw_offscreen_on_border) => should_draw_offscreen_on_border,
+            None => return print_help(),
+        };
+
+    println!(
+        "Reading trajectory from {}, will write to {}",
+        input_filename, output_filename
+    );
+
+    let trajectory_reader = trajectory_file::reader::open_file(input_filename)?;
+    let mut picture_settings = trajectory_reader.get_header().configuration.clone();
+    if let Some(settings_filename) = command_line_arguments.get(5) {
+        let settings_content = std::fs::read_to_string(settings_filename)?;
+        let deserialized_settings: serde_json::Value = serde_json::from_str(&settings_content)?;
+        let replacement_settings = deserialized_settings.as_object().ok_or_else(|| {
             configuration_parsing::ConfigurationParseError::new(&format!(
-                "Memory layout \"{}\" is unknown",
-                parsed_configuration.evolver_configuration.memory_layout
-            )),
-        )),
+                "Could not parse {} as a JSON object",
+                settings_filename
+            ))
+        })?;
+        for (setting_label, setting_value) in replacement_settings {
+            picture_settings[setting_label] = setting_value.clone();
+        }
     }
+    let picture_configuration =
+        configuration_parsing::parse_picture_configuration(&picture_settings)?;
+    let milliseconds_per_frame = trajectory_reader.get_header().milliseconds_per_frame;
+
+    let instant_before_rendering = std::time::Instant::now();
+    let particle_animator =
+        create_particle_animator(&picture_configuration, should_draw_offscreen_on_border)?;
+    particle_animator.animate_sequence(
+        trajectory_reader.into_frame_sequence(),
+        milliseconds_per_frame,
+        output_filename,
+    )?;
+    println!(
+        "Rendering took {}ms",
+        instant_before_rendering.elapsed().as_millis()
+    );
+    Ok(())
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -255,7 +879,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     return match command_line_arguments[1].as_str() {
         "rgb_demo" => create_rgb_demonstration(&command_line_arguments),
-        "read_file" => run_from_configuration_file(&command_line_arguments),
+        "read_file" => run_from_configuration_file(&command_line_arguments, false),
+        "resume" => run_from_configuration_file(&command_line_arguments, true),
+        "export_trajectory" => run_from_configuration_file(&command_line_arguments, false),
+        "render" => render_from_trajectory_file(&command_line_arguments),
+        "benchmark_layouts" => run_benchmark_of_memory_layouts(&command_line_arguments),
         _ => print_help(),
     };
 }
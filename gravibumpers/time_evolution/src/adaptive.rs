@@ -0,0 +1,1244 @@
+/// This module provides an implementation of ParticlesInTimeEvolver which uses the same
+/// second-order Euler step as second_order_euler, but chooses the length of each internal step
+/// so that the error estimated by step doubling stays within a relative tolerance. Each step is
+/// taken once at full length and once as two steps of half the length, and the difference between
+/// the resulting positions, relative to how far the particles moved, estimates the error. Quiet
+/// stretches then need few internal steps while close encounters get many, and every time slice
+/// still ends exactly at the configured interval after the previous one.
+use crate::data_structure::particle::CollectionInForceField;
+use crate::data_structure::particle::CollectionInForceFieldGenerator;
+use crate::data_structure::particle::WritableInForceField;
+use crate::force_law::PairwiseForceLaw;
+
+/// The local error in positions of the second-order Euler step scales with the cube of the step
+/// length, so the step length is scaled by the cube root of the ratio of tolerance to error.
+const ERROR_SCALING_EXPONENT: f64 = 1.0 / 3.0;
+
+/// The new step length is a little shorter than the estimate of the longest acceptable step, so
+/// that fewer steps get rejected.
+const STEP_LENGTH_SAFETY_FACTOR: f64 = 0.9;
+
+const MINIMUM_STEP_LENGTH_FACTOR: f64 = 0.2;
+const MAXIMUM_STEP_LENGTH_FACTOR: f64 = 5.0;
+
+/// If the step length falls below this fraction of a time slice, the evolution is abandoned as the
+/// error cannot be controlled.
+const MINIMUM_FRACTION_OF_TIME_SLICE: f64 = 1.0e-12;
+
+/// This records how many internal steps were taken to reach a time slice from the previous one.
+/// Each accepted step costs three evaluations of the forces, and each rejected step also costs
+/// three evaluations of the forces.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StepCount {
+    pub accepted_steps: u32,
+    pub rejected_steps: u32,
+}
+
+pub struct AdaptiveStepDoubling<
+    CollectionElement,
+    CollectionGenerator,
+    ForceLaw = crate::force_law::WithSpringBonds<
+        crate::force_law::InExternalFields<
+            crate::force_law::MinimumImage<crate::force_law::ConfiguredForceLaw>,
+        >,
+    >,
+> where
+    CollectionElement: WritableInForceField,
+    CollectionGenerator: CollectionInForceFieldGenerator<MutableElement = CollectionElement>,
+    ForceLaw: PairwiseForceLaw,
+{
+    initial_number_of_internal_slices_per_time_slice: u32,
+    relative_error_tolerance: f64,
+    collection_generator: CollectionGenerator,
+    step_counts_per_time_slice: std::rc::Rc<std::cell::RefCell<std::vec::Vec<StepCount>>>,
+
+    phantom_particle_type: std::marker::PhantomData<CollectionElement>,
+    phantom_force_law: std::marker::PhantomData<ForceLaw>,
+}
+
+impl<CollectionElement, CollectionGenerator, ForceLaw>
+    AdaptiveStepDoubling<CollectionElement, CollectionGenerator, ForceLaw>
+where
+    CollectionElement: WritableInForceField,
+    CollectionGenerator: CollectionInForceFieldGenerator<MutableElement = CollectionElement>,
+    ForceLaw: PairwiseForceLaw,
+{
+    /// This returns the numbers of internal steps which were used for each time slice after the
+    /// first which has been evolved so far in the sequence from the most recent call of
+    /// create_time_sequence. The time slices are only evolved as they are requested from the
+    /// sequence.
+    pub fn get_step_counts(&self) -> std::vec::Vec<StepCount> {
+        self.step_counts_per_time_slice.borrow().clone()
+    }
+}
+
+/// This holds what is needed to evolve the particles from one time slice to the next, including
+/// buffers for copies of the particle variables which are re-used for every step, and the numbers
+/// of steps taken so far, which are shared with the evolver which created it.
+pub struct AdaptiveStepDoublingStepper<ForceLaw> {
+    force_law: ForceLaw,
+    initial_number_of_internal_slices_per_time_slice: u32,
+    relative_error_tolerance: f64,
+    nominal_time_interval: data_structure::time::IntervalUnit,
+    variables_at_start: std::vec::Vec<data_structure::particle::VariablePart>,
+    variables_after_single_step: std::vec::Vec<data_structure::particle::VariablePart>,
+    step_counts_per_time_slice: std::rc::Rc<std::cell::RefCell<std::vec::Vec<StepCount>>>,
+    drag_and_thermostat: Option<super::thermostat::DragAndThermostat>,
+}
+
+impl<ForceLaw> AdaptiveStepDoublingStepper<ForceLaw>
+where
+    ForceLaw: PairwiseForceLaw,
+{
+    /// This updates the velocity and position assuming a constant acceleration for the given
+    /// fraction of the nominal time interval which was used to set up the time over mass of each
+    /// particle.
+    fn update_velocity_and_position_for_fraction<T>(
+        nominal_time_interval: &data_structure::time::IntervalUnit,
+        fraction_of_nominal_interval: f64,
+        particle_and_force: &mut T,
+    ) where
+        T: WritableInForceField,
+    {
+        let velocity_difference = data_structure::velocity_change_from_force(
+            particle_and_force.read_experienced_force(),
+            &data_structure::time::OverMassUnit(
+                fraction_of_nominal_interval
+                    * particle_and_force.read_timestep_over_inertial_mass().0,
+            ),
+        );
+        let particle_variables = particle_and_force.write_particle_variables();
+        let average_velocity = data_structure::velocity::sum_with_scaled_other(
+            &particle_variables.velocity_vector,
+            &velocity_difference,
+            0.5,
+        );
+        particle_variables.velocity_vector += velocity_difference;
+        data_structure::increment_position_by_velocity_for_time_interval(
+            &mut particle_variables.position_vector,
+            &average_velocity,
+            &data_structure::time::IntervalUnit(
+                fraction_of_nominal_interval * nominal_time_interval.0,
+            ),
+        );
+    }
+
+    fn take_step<ParticleImplementation, ParticleCollection>(
+        evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+        force_law: &ForceLaw,
+        evolving_particles: &mut ParticleCollection,
+        nominal_time_interval: &data_structure::time::IntervalUnit,
+        fraction_of_nominal_interval: f64,
+    ) where
+        ParticleImplementation: WritableInForceField,
+        ParticleCollection: data_structure::collection::SingleAndPairwiseFinite<
+            MutableElement = ParticleImplementation,
+        >,
+    {
+        super::update_forces(evolution_configuration, force_law, evolving_particles);
+        evolving_particles.apply_to_every_single(&mut |particle_with_force| {
+            Self::update_velocity_and_position_for_fraction(
+                nominal_time_interval,
+                fraction_of_nominal_interval,
+                particle_with_force,
+            )
+        });
+    }
+
+    fn copy_variables<ParticleImplementation, ParticleCollection>(
+        evolving_particles: &mut ParticleCollection,
+        copied_variables: &mut std::vec::Vec<data_structure::particle::VariablePart>,
+    ) where
+        ParticleImplementation: WritableInForceField,
+        ParticleCollection: data_structure::collection::SingleAndPairwiseFinite<
+            MutableElement = ParticleImplementation,
+        >,
+    {
+        copied_variables.clear();
+        evolving_particles.apply_to_every_single(&mut |particle_with_force| {
+            copied_variables.push(*particle_with_force.read_variables());
+        });
+    }
+
+    fn restore_variables<ParticleImplementation, ParticleCollection>(
+        evolving_particles: &mut ParticleCollection,
+        copied_variables: &[data_structure::particle::VariablePart],
+    ) where
+        ParticleImplementation: WritableInForceField,
+        ParticleCollection: data_structure::collection::SingleAndPairwiseFinite<
+            MutableElement = ParticleImplementation,
+        >,
+    {
+        let mut particle_index = 0;
+        evolving_particles.apply_to_every_single(&mut |particle_with_force| {
+            *particle_with_force.write_particle_variables() = copied_variables[particle_index];
+            particle_index += 1;
+        });
+    }
+
+    /// This returns the largest ratio over all the particles of the difference between the
+    /// positions reached by the single step and the pair of half steps to the distance moved by
+    /// the pair of half steps. Particles which did not move at all contribute nothing.
+    fn estimate_relative_error<ParticleImplementation, ParticleCollection>(
+        evolving_particles: &mut ParticleCollection,
+        variables_at_start: &[data_structure::particle::VariablePart],
+        variables_after_single_step: &[data_structure::particle::VariablePart],
+    ) -> f64
+    where
+        ParticleImplementation: WritableInForceField,
+        ParticleCollection: data_structure::collection::SingleAndPairwiseFinite<
+            MutableElement = ParticleImplementation,
+        >,
+    {
+        let mut largest_relative_error: f64 = 0.0;
+        let mut particle_index = 0;
+        evolving_particles.apply_to_every_single(&mut |particle_with_force| {
+            let position_after_half_steps = particle_with_force.read_variables().position_vector;
+            let squared_difference = data_structure::position::square_separation_vector(
+                &(position_after_half_steps
+                    - variables_after_single_step[particle_index].position_vector),
+            );
+            let squared_displacement = data_structure::position::square_separation_vector(
+                &(position_after_half_steps - variables_at_start[particle_index].position_vector),
+            );
+            if squared_difference.0 > 0.0 {
+                let relative_error = if squared_displacement.0 > 0.0 {
+                    (squared_difference.0 / squared_displacement.0).sqrt()
+                } else {
+                    f64::INFINITY
+                };
+                largest_relative_error = largest_relative_error.max(relative_error);
+            }
+            particle_index += 1;
+        });
+        largest_relative_error
+    }
+
+    fn next_fraction_of_nominal_interval(
+        &self,
+        current_fraction_of_nominal_interval: f64,
+        relative_error: f64,
+    ) -> f64 {
+        let scaling_factor = if relative_error > 0.0 {
+            (STEP_LENGTH_SAFETY_FACTOR
+                * (self.relative_error_tolerance / relative_error).powf(ERROR_SCALING_EXPONENT))
+            .clamp(MINIMUM_STEP_LENGTH_FACTOR, MAXIMUM_STEP_LENGTH_FACTOR)
+        } else {
+            MAXIMUM_STEP_LENGTH_FACTOR
+        };
+        scaling_factor * current_fraction_of_nominal_interval
+    }
+
+    /// This evolves the particles through a whole time slice, which is the given number of nominal
+    /// time intervals, taking steps of whatever length keeps the estimated error within tolerance.
+    fn evolve_through_time_slice<EvolvingCollection>(
+        &mut self,
+        evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+        evolving_collection: &mut EvolvingCollection,
+    ) -> Result<StepCount, Box<dyn std::error::Error>>
+    where
+        EvolvingCollection: CollectionInForceField,
+    {
+        let nominal_time_interval = self.nominal_time_interval;
+        let nominal_intervals_per_time_slice =
+            self.initial_number_of_internal_slices_per_time_slice as f64;
+        let mut step_count = StepCount {
+            accepted_steps: 0,
+            rejected_steps: 0,
+        };
+        let mut remaining_fraction = nominal_intervals_per_time_slice;
+        let mut trial_fraction: f64 = 1.0;
+        while remaining_fraction > 0.0 {
+            if trial_fraction < (MINIMUM_FRACTION_OF_TIME_SLICE * nominal_intervals_per_time_slice)
+            {
+                return Err(Box::new(super::EvolutionError::new(&format!(
+                    "Could not keep the relative error within {} even with a step of {} of the \
+                    nominal internal time interval (after {:?} in this time slice)",
+                    self.relative_error_tolerance, trial_fraction, step_count
+                ))));
+            }
+
+            let is_last_step = trial_fraction >= remaining_fraction;
+            let step_fraction = if is_last_step {
+                remaining_fraction
+            } else {
+                trial_fraction
+            };
+
+            let evolving_particles = evolving_collection.access_mutable_elements();
+            Self::copy_variables(evolving_particles, &mut self.variables_at_start);
+            Self::take_step(
+                evolution_configuration,
+                &self.force_law,
+                evolving_particles,
+                &nominal_time_interval,
+                step_fraction,
+            );
+            Self::copy_variables(evolving_particles, &mut self.variables_after_single_step);
+            Self::restore_variables(evolving_particles, &self.variables_at_start);
+
+            let half_step_fraction = 0.5 * step_fraction;
+            Self::take_step(
+                evolution_configuration,
+                &self.force_law,
+                evolving_particles,
+                &nominal_time_interval,
+                half_step_fraction,
+            );
+            Self::take_step(
+                evolution_configuration,
+                &self.force_law,
+                evolving_particles,
+                &nominal_time_interval,
+                half_step_fraction,
+            );
+
+            let relative_error = Self::estimate_relative_error(
+                evolving_particles,
+                &self.variables_at_start,
+                &self.variables_after_single_step,
+            );
+            trial_fraction = self.next_fraction_of_nominal_interval(step_fraction, relative_error);
+            if relative_error <= self.relative_error_tolerance {
+                // The collisions, the drag, the boundaries, and the merging are only applied to
+                // accepted steps, as a particle bouncing, being slowed, kicked, or moved back into
+                // the box after only one of the two ways of taking the step would look like a large
+                // error.
+                let accepted_time_interval =
+                    data_structure::time::IntervalUnit(step_fraction * nominal_time_interval.0);
+                super::collide_touching_particles(
+                    evolution_configuration,
+                    &accepted_time_interval,
+                    evolving_particles,
+                );
+                super::apply_drag_and_thermostat(
+                    &mut self.drag_and_thermostat,
+                    &accepted_time_interval,
+                    evolving_particles,
+                );
+                super::apply_boundary_conditions(evolution_configuration, evolving_particles);
+                super::merge_close_particles(evolution_configuration, evolving_collection)?;
+                step_count.accepted_steps += 1;
+                remaining_fraction = if is_last_step {
+                    0.0
+                } else {
+                    remaining_fraction - step_fraction
+                };
+            } else {
+                step_count.rejected_steps += 1;
+                Self::restore_variables(evolving_particles, &self.variables_at_start);
+            }
+        }
+
+        Ok(step_count)
+    }
+}
+
+impl<ForceLaw> super::TimeSliceStepper for AdaptiveStepDoublingStepper<ForceLaw>
+where
+    ForceLaw: PairwiseForceLaw,
+{
+    fn evolve_to_next_time_slice<EvolvingCollection>(
+        &mut self,
+        evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+        evolving_collection: &mut EvolvingCollection,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        EvolvingCollection: CollectionInForceField,
+    {
+        let step_count =
+            self.evolve_through_time_slice(evolution_configuration, evolving_collection)?;
+        self.step_counts_per_time_slice
+            .borrow_mut()
+            .push(step_count);
+        super::finish_time_slice_of_thermostat(&mut self.drag_and_thermostat);
+        Ok(())
+    }
+}
+
+impl<CollectionElement, CollectionGenerator, ForceLaw> super::ParticlesInTimeEvolver
+    for AdaptiveStepDoubling<CollectionElement, CollectionGenerator, ForceLaw>
+where
+    CollectionElement: WritableInForceField,
+    CollectionGenerator: CollectionInForceFieldGenerator<MutableElement = CollectionElement>,
+    ForceLaw: PairwiseForceLaw,
+{
+    type ForceLaw = ForceLaw;
+    type EmittedParticle = data_structure::particle::BasicIndividual;
+    type ParticleIterator = std::vec::IntoIter<Self::EmittedParticle>;
+    type IteratorIterator = super::LazyTimeSequence<
+        CollectionGenerator::CreatedCollection,
+        AdaptiveStepDoublingStepper<ForceLaw>,
+    >;
+
+    fn create_time_sequence(
+        &mut self,
+        evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+        initial_conditions: impl std::iter::ExactSizeIterator<
+            Item = impl data_structure::particle::IndividualRepresentation,
+        >,
+    ) -> Result<
+        super::ParticleSetEvolution<
+            Self::EmittedParticle,
+            Self::ParticleIterator,
+            Self::IteratorIterator,
+        >,
+        Box<dyn std::error::Error>,
+    > {
+        if evolution_configuration.dead_zone_radius <= 0.0 {
+            return Err(Box::new(super::ParameterError::new(
+                "Dead zone radius must be > 0.",
+            )));
+        }
+        super::reject_prescribed_motion(evolution_configuration)?;
+        let force_law = ForceLaw::new_from_configuration(evolution_configuration)?;
+
+        let seconds_between_configurations = (evolution_configuration.milliseconds_per_time_slice
+            as f64)
+            * configuration_parsing::SECONDS_PER_MILLISECOND;
+
+        // The particles are set up with a nominal internal time interval, and the actual steps are
+        // taken as multiples of it.
+        let time_interval_per_internal_slice = data_structure::time::IntervalUnit(
+            seconds_between_configurations
+                / (self.initial_number_of_internal_slices_per_time_slice as f64),
+        );
+        let evolving_particles = super::create_particles_in_force_field(
+            &self.collection_generator,
+            initial_conditions,
+            &time_interval_per_internal_slice,
+        )?;
+
+        // Any sequence from a previous call keeps its own step counts.
+        self.step_counts_per_time_slice = std::rc::Rc::new(std::cell::RefCell::new(vec![]));
+
+        let drag_and_thermostat =
+            super::thermostat::DragAndThermostat::new_from_configuration(evolution_configuration);
+
+        Ok(super::ParticleSetEvolution {
+            particle_configurations: super::new_lazy_time_sequence(
+                evolving_particles,
+                AdaptiveStepDoublingStepper {
+                    force_law,
+                    initial_number_of_internal_slices_per_time_slice: self
+                        .initial_number_of_internal_slices_per_time_slice,
+                    relative_error_tolerance: self.relative_error_tolerance,
+                    nominal_time_interval: time_interval_per_internal_slice,
+                    variables_at_start: vec![],
+                    variables_after_single_step: vec![],
+                    step_counts_per_time_slice: std::rc::Rc::clone(
+                        &self.step_counts_per_time_slice,
+                    ),
+                    drag_and_thermostat,
+                },
+                evolution_configuration,
+            ),
+            milliseconds_between_configurations: evolution_configuration
+                .milliseconds_per_time_slice,
+        })
+    }
+}
+
+/// The number of internal slices per time slice is used for the length of the first trial step of
+/// each time slice. Each time slice starts afresh from this length so that the evolution from any
+/// time slice depends only on the particles at that time slice.
+pub fn new_given_memory_strategy<CollectionElement, CollectionGenerator>(
+    initial_number_of_internal_slices_per_time_slice: u32,
+    relative_error_tolerance: f64,
+    collection_generator: CollectionGenerator,
+) -> Result<AdaptiveStepDoubling<CollectionElement, CollectionGenerator>, Box<dyn std::error::Error>>
+where
+    CollectionElement: WritableInForceField,
+    CollectionGenerator: CollectionInForceFieldGenerator<MutableElement = CollectionElement>,
+{
+    new_given_memory_strategy_and_force_law(
+        initial_number_of_internal_slices_per_time_slice,
+        relative_error_tolerance,
+        collection_generator,
+    )
+}
+
+/// The force law is created from the evolution configuration given to each call of
+/// create_time_sequence.
+pub fn new_given_memory_strategy_and_force_law<CollectionElement, CollectionGenerator, ForceLaw>(
+    initial_number_of_internal_slices_per_time_slice: u32,
+    relative_error_tolerance: f64,
+    collection_generator: CollectionGenerator,
+) -> Result<
+    AdaptiveStepDoubling<CollectionElement, CollectionGenerator, ForceLaw>,
+    Box<dyn std::error::Error>,
+>
+where
+    CollectionElement: WritableInForceField,
+    CollectionGenerator: CollectionInForceFieldGenerator<MutableElement = CollectionElement>,
+    ForceLaw: PairwiseForceLaw,
+{
+    if initial_number_of_internal_slices_per_time_slice == 0 {
+        Err(Box::new(super::ParameterError::new(
+            "Initial number of internal slices between displayed slices must be > 0.",
+        )))
+    } else if relative_error_tolerance <= 0.0 {
+        Err(Box::new(super::ParameterError::new(
+            "Relative error tolerance must be > 0.",
+        )))
+    } else {
+        Ok(AdaptiveStepDoubling {
+            initial_number_of_internal_slices_per_time_slice,
+            relative_error_tolerance,
+            collection_generator,
+            step_counts_per_time_slice: std::rc::Rc::new(std::cell::RefCell::new(vec![])),
+            phantom_particle_type: std::marker::PhantomData,
+            phantom_force_law: std::marker::PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_functions as evolver_tests;
+    use super::*;
+    use data_structure::particle::contiguous_struct as contiguous_particle_struct;
+    use data_structure::particle::mixture as particle_mixture;
+    use data_structure::particle::struct_of_boxes as particle_struct_of_boxes;
+
+    const TEST_DEAD_ZONE_RADIUS: data_structure::position::SeparationUnit =
+        data_structure::position::SeparationUnit(1.0);
+
+    const TEST_RELATIVE_ERROR_TOLERANCE: f64 = 0.0000001;
+
+    // The steps are chosen to keep the error in positions small, which keeps the energy close to
+    // constant too.
+    const TEST_ENERGY_TOLERANCE: f64 = 0.001;
+
+    fn new_maximally_contiguous_for_test() -> Result<
+        AdaptiveStepDoubling<
+            contiguous_particle_struct::MassNormalizedWithForceField,
+            contiguous_particle_struct::VectorOfMassNormalizedWithForceFieldGenerator,
+        >,
+        String,
+    > {
+        new_given_memory_strategy(
+            10,
+            TEST_RELATIVE_ERROR_TOLERANCE,
+            contiguous_particle_struct::VectorOfMassNormalizedWithForceFieldGenerator {},
+        )
+        .map_err(|construction_error| {
+            format!(
+                "Constructor error in new_maximally_contiguous_for_test: {:?}",
+                construction_error
+            )
+        })
+    }
+
+    fn new_contiguous_pointers_for_test() -> Result<
+        AdaptiveStepDoubling<
+            std::boxed::Box<dyn data_structure::particle::WritableInForceField>,
+            contiguous_particle_struct::VectorOfDynamicBoxedMassNormalizedWithForceFieldGenerator,
+        >,
+        String,
+    > {
+        new_given_memory_strategy(
+            10,
+            TEST_RELATIVE_ERROR_TOLERANCE,
+            contiguous_particle_struct::VectorOfDynamicBoxedMassNormalizedWithForceFieldGenerator {},
+        )
+        .map_err(|construction_error| {
+            format!(
+                "Constructor error in new_contiguous_pointers_for_test: {:?}",
+                construction_error
+            )
+        })
+    }
+
+    fn new_double_boxed_for_test() -> Result<
+        AdaptiveStepDoubling<
+            std::boxed::Box<dyn data_structure::particle::WritableInForceField>,
+            particle_struct_of_boxes::VectorOfDynamicBoxedMassNormalizedBoxesWithForceFieldGenerator,
+        >,
+        String,
+    >{
+        new_given_memory_strategy(
+            10,
+            TEST_RELATIVE_ERROR_TOLERANCE,
+            particle_struct_of_boxes::VectorOfDynamicBoxedMassNormalizedBoxesWithForceFieldGenerator {},
+        )
+        .map_err(|construction_error| {
+            format!(
+                "Constructor error in new_double_boxed_for_test: {:?}",
+                construction_error
+            )
+        })
+    }
+
+    fn new_mixed_boxed_for_test() -> Result<
+        AdaptiveStepDoubling<
+            std::boxed::Box<dyn data_structure::particle::WritableInForceField>,
+            particle_mixture::VectorOfDynamicBoxedMassNormalizedStructsAndBoxesGenerator,
+        >,
+        String,
+    > {
+        new_given_memory_strategy(
+            10,
+            TEST_RELATIVE_ERROR_TOLERANCE,
+            particle_mixture::VectorOfDynamicBoxedMassNormalizedStructsAndBoxesGenerator {},
+        )
+        .map_err(|construction_error| {
+            format!(
+                "Constructor error in new_mixed_boxed_for_test: {:?}",
+                construction_error
+            )
+        })
+    }
+
+    #[test]
+    fn test_single_particle_at_rest_stays_at_rest_with_maximally_contiguous() -> Result<(), String>
+    {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_single_particle_at_rest_stays_at_rest(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_single_particle_at_rest_stays_at_rest_with_contiguous_pointers() -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_single_particle_at_rest_stays_at_rest(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_single_particle_at_rest_stays_at_rest_with_double_boxed() -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_single_particle_at_rest_stays_at_rest(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_single_particle_at_rest_stays_at_rest_with_mixed_boxed() -> Result<(), String> {
+        let mut evolver_implementation = new_mixed_boxed_for_test()?;
+        evolver_tests::test_single_particle_at_rest_stays_at_rest(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_single_particle_at_constant_speed_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_single_particle_at_constant_speed(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_single_particle_at_constant_speed_with_contiguous_pointers() -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_single_particle_at_constant_speed(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_single_particle_at_constant_speed_with_double_boxed() -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_single_particle_at_constant_speed(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_single_particle_at_constant_speed_with_mixed_boxed() -> Result<(), String> {
+        let mut evolver_implementation = new_mixed_boxed_for_test()?;
+        evolver_tests::test_single_particle_at_constant_speed(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_uncharged_particles_do_not_accelerate_with_maximally_contiguous() -> Result<(), String>
+    {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_uncharged_particles_do_not_accelerate(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_uncharged_particles_do_not_accelerate_with_contiguous_pointers() -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_uncharged_particles_do_not_accelerate(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_uncharged_particles_do_not_accelerate_with_double_boxed() -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_uncharged_particles_do_not_accelerate(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_uncharged_particles_do_not_accelerate_with_mixed_boxed() -> Result<(), String> {
+        let mut evolver_implementation = new_mixed_boxed_for_test()?;
+        evolver_tests::test_uncharged_particles_do_not_accelerate(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_immobile_repelling_particles_within_dead_zone_stay_at_rest_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_immobile_repelling_particles_within_dead_zone_stay_at_rest(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+        )
+    }
+
+    #[test]
+    fn test_immobile_repelling_particles_within_dead_zone_stay_at_rest_with_contiguous_pointers(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_immobile_repelling_particles_within_dead_zone_stay_at_rest(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+        )
+    }
+
+    #[test]
+    fn test_immobile_repelling_particles_within_dead_zone_stay_at_rest_with_double_boxed(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_immobile_repelling_particles_within_dead_zone_stay_at_rest(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+        )
+    }
+
+    #[test]
+    fn test_immobile_repelling_particles_within_dead_zone_stay_at_rest_with_mixed_boxed(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_mixed_boxed_for_test()?;
+        evolver_tests::test_immobile_repelling_particles_within_dead_zone_stay_at_rest(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_fourth_critical_escape_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_fourth_critical_escape(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_fourth_critical_escape_with_contiguous_pointers(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_fourth_critical_escape(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_fourth_critical_escape_with_double_boxed(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_fourth_critical_escape(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_fourth_critical_escape_with_mixed_boxed(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_mixed_boxed_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_fourth_critical_escape(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_repelling_inverse_fourth_accelerate_away_equally_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_equal_masses_repelling_inverse_fourth_accelerate_away_equally(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_repelling_inverse_fourth_accelerate_away_equally_with_contiguous_pointers(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_equal_masses_repelling_inverse_fourth_accelerate_away_equally(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_repelling_inverse_fourth_accelerate_away_equally_with_double_boxed(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_equal_masses_repelling_inverse_fourth_accelerate_away_equally(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_repelling_inverse_fourth_accelerate_away_equally_with_mixed_boxed(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_mixed_boxed_for_test()?;
+        evolver_tests::test_equal_masses_repelling_inverse_fourth_accelerate_away_equally(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_square_critical_escape_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_square_critical_escape(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_square_critical_escape_with_contiguous_pointers(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_square_critical_escape(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_square_critical_escape_with_double_boxed(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_square_critical_escape(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_square_critical_escape_with_mixed_boxed(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_mixed_boxed_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_square_critical_escape(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_square_circular_orbit_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_square_circular_orbit(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_square_circular_orbit_with_contiguous_pointers(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_square_circular_orbit(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_square_circular_orbit_with_double_boxed(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_square_circular_orbit(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_square_circular_orbit_with_mixed_boxed(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_mixed_boxed_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_square_circular_orbit(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_triangle_at_cancelling_forces_is_stable_with_maximally_contiguous() -> Result<(), String>
+    {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_triangle_at_cancelling_forces_is_stable(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+        )
+    }
+
+    #[test]
+    fn test_triangle_at_cancelling_forces_is_stable_with_contiguous_pointers() -> Result<(), String>
+    {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_triangle_at_cancelling_forces_is_stable(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+        )
+    }
+
+    #[test]
+    fn test_triangle_at_cancelling_forces_is_stable_with_double_boxed() -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_triangle_at_cancelling_forces_is_stable(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+        )
+    }
+
+    #[test]
+    fn test_triangle_at_cancelling_forces_is_stable_with_mixed_boxed() -> Result<(), String> {
+        let mut evolver_implementation = new_mixed_boxed_for_test()?;
+        evolver_tests::test_triangle_at_cancelling_forces_is_stable(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+        )
+    }
+
+    #[test]
+    fn test_approximate_harmonic_oscillator_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_approximate_harmonic_oscillator(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_approximate_harmonic_oscillator_with_contiguous_pointers() -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_approximate_harmonic_oscillator(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_approximate_harmonic_oscillator_with_double_boxed() -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_approximate_harmonic_oscillator(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_approximate_harmonic_oscillator_with_mixed_boxed() -> Result<(), String> {
+        let mut evolver_implementation = new_mixed_boxed_for_test()?;
+        evolver_tests::test_approximate_harmonic_oscillator(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    /// This creates a pair of particles of unit mass and unit inverse-square charge at the given
+    /// separation along the horizontal axis, moving in opposite vertical directions at the given
+    /// speed.
+    fn create_pair_for_step_count_test(
+        separation: f64,
+        speed: f64,
+    ) -> std::vec::Vec<data_structure::particle::BasicIndividual> {
+        [-0.5, 0.5]
+            .iter()
+            .map(|side| {
+                super::super::test_functions::new_test_particle(
+                    1.0,
+                    1.0,
+                    0.0,
+                    (side * separation, 0.0),
+                    (0.0, 2.0 * side * speed),
+                )
+            })
+            .collect()
+    }
+
+    fn count_accepted_steps_for_pair(
+        relative_error_tolerance: f64,
+        separation: f64,
+        speed: f64,
+    ) -> Result<u32, String> {
+        let evolution_configuration = configuration_parsing::EvolutionConfiguration {
+            inverse_squared_coupling: -1.0,
+            inverse_fourth_coupling: 0.0,
+            milliseconds_per_time_slice: 100,
+            ..super::super::test_functions::create_test_evolution_configuration(6, 0.01)
+        };
+        let mut evolver_implementation = new_given_memory_strategy(
+            10,
+            relative_error_tolerance,
+            contiguous_particle_struct::VectorOfMassNormalizedWithForceFieldGenerator {},
+        )
+        .map_err(|construction_error| format!("Constructor error: {:?}", construction_error))?;
+        let initial_conditions = create_pair_for_step_count_test(separation, speed);
+        let particle_set_evolution = super::super::ParticlesInTimeEvolver::create_time_sequence(
+            &mut evolver_implementation,
+            &evolution_configuration,
+            initial_conditions.iter(),
+        )
+        .map_err(|evolution_error| format!("Evolution error: {:?}", evolution_error))?;
+
+        // The steps are only taken as each time slice is requested.
+        for time_slice in particle_set_evolution.particle_configurations {
+            time_slice
+                .map_err(|evolution_error| format!("Evolution error: {:?}", evolution_error))?;
+        }
+        let step_counts = evolver_implementation.get_step_counts();
+        if step_counts.len() != 5 {
+            return Err(format!(
+                "Expected step counts for 5 time slices, got {:?}",
+                step_counts
+            ));
+        }
+        Ok(step_counts
+            .iter()
+            .map(|step_count| step_count.accepted_steps)
+            .sum())
+    }
+
+    #[test]
+    fn test_reject_zero_tolerance() -> Result<(), String> {
+        match new_given_memory_strategy(
+            10,
+            0.0,
+            contiguous_particle_struct::VectorOfMassNormalizedWithForceFieldGenerator {},
+        ) {
+            Ok(_) => Err(String::from("Expected an error for zero tolerance")),
+            Err(_) => Ok(()),
+        }
+    }
+
+    #[test]
+    fn test_distant_pair_takes_fewer_steps_than_close_pair() -> Result<(), String> {
+        let distant_steps =
+            count_accepted_steps_for_pair(TEST_RELATIVE_ERROR_TOLERANCE, 20.0, 0.1)?;
+        let close_steps = count_accepted_steps_for_pair(TEST_RELATIVE_ERROR_TOLERANCE, 0.2, 0.1)?;
+        if distant_steps < close_steps {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected fewer steps for distant pair ({}) than for close pair ({})",
+                distant_steps, close_steps
+            ))
+        }
+    }
+
+    #[test]
+    fn test_tighter_tolerance_takes_more_steps() -> Result<(), String> {
+        let loose_steps = count_accepted_steps_for_pair(0.0001, 2.0, 0.5)?;
+        let tight_steps = count_accepted_steps_for_pair(0.0000001, 2.0, 0.5)?;
+        if loose_steps < tight_steps {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected fewer steps for loose tolerance ({}) than for tight tolerance ({})",
+                loose_steps, tight_steps
+            ))
+        }
+    }
+
+    #[test]
+    fn test_evolution_resumed_from_time_slice_matches_uninterrupted_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_evolution_resumed_from_time_slice_matches_uninterrupted(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_evolution_with_thermostat_resumed_from_time_slice_matches_uninterrupted_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_evolution_with_thermostat_resumed_from_time_slice_matches_uninterrupted(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_plummer_softened_attraction_passes_through_and_conserves_energy_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_softened_attraction_passes_through_and_conserves_energy(
+            &mut evolver_implementation,
+            configuration_parsing::SofteningKernel::Plummer,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_spline_softened_attraction_passes_through_and_conserves_energy_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_softened_attraction_passes_through_and_conserves_energy(
+            &mut evolver_implementation,
+            configuration_parsing::SofteningKernel::Spline,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_lennard_jones_pair_conserves_energy_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_lennard_jones_pair_conserves_energy(
+            &mut evolver_implementation,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_yukawa_pair_conserves_energy_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_yukawa_pair_conserves_energy(
+            &mut evolver_implementation,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_free_particle_bounces_off_reflecting_walls_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_free_particle_kept_in_box(
+            &mut evolver_implementation,
+            configuration_parsing::BoundaryKind::Reflecting,
+        )
+    }
+
+    #[test]
+    fn test_free_particle_wraps_round_periodic_box_with_maximally_contiguous() -> Result<(), String>
+    {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_free_particle_kept_in_box(
+            &mut evolver_implementation,
+            configuration_parsing::BoundaryKind::Periodic,
+        )
+    }
+
+    #[test]
+    fn test_particle_falls_through_tidal_trap_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_particle_falls_through_tidal_trap(
+            &mut evolver_implementation,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_particle_falls_towards_fixed_source_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_particle_falls_towards_fixed_source(
+            &mut evolver_implementation,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_drag_slows_free_particle_exponentially_with_maximally_contiguous() -> Result<(), String>
+    {
+        // With no forces on the particle, the adaptive steps grow to the whole time slice, and
+        // the drag is only applied at the end of each step.
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_drag_slows_free_particle_exponentially(
+            &mut evolver_implementation,
+            0.05,
+        )
+    }
+
+    #[test]
+    fn test_thermostat_brings_kinetic_temperature_to_target_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_thermostat_brings_kinetic_temperature_to_target(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_thermostat_kicks_repeat_for_same_seed_with_maximally_contiguous() -> Result<(), String>
+    {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_thermostat_kicks_repeat_for_same_seed(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_approaching_particles_merge_at_center_of_mass_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_approaching_particles_merge_at_center_of_mass(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_head_on_collision_keeps_momentum_and_scales_approach_speed_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_head_on_collision_keeps_momentum_and_scales_approach_speed(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_glancing_elastic_collision_deflects_at_right_angles_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_glancing_elastic_collision_deflects_at_right_angles(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_bonded_pair_oscillates_at_spring_frequency_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_bonded_pair_oscillates_at_spring_frequency(
+            &mut evolver_implementation,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_damped_bonded_pair_rings_down_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_damped_bonded_pair_rings_down(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_prescribed_motion_rejected_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_prescribed_motion_rejected(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_tracers_feel_forces_but_exert_none_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_tracers_feel_forces_but_exert_none(&mut evolver_implementation)
+    }
+}
@@ -0,0 +1,779 @@
+/// This module provides a Barnes-Hut calculation of the forces on a collection of particles, as an
+/// alternative to summing the forces over every pair of particles. The particles are sorted into a
+/// quadtree, and each node of the tree which is far enough away from a particle, relative to the
+/// width of the node, is treated as a single particle carrying the total charges of the particles
+/// in the node, for each inverse-power term of the force law. Nodes which are too close are opened
+/// up, down to the leaves, where the forces from the particles are summed exactly. Since a term
+/// with a power higher than two such as the inverse-fourth force falls off so quickly, a node is
+/// also opened whenever any of its particles might be within a few softening lengths of the
+/// particle, whatever the opening angle, so that such a term is always summed exactly over near
+/// neighbours.
+use super::force_law::InversePowerTerm;
+use super::force_law::PairwiseForceLaw;
+use super::zero_force;
+use super::ForceVector;
+use data_structure::particle::BasicIndividual;
+use data_structure::particle::IndividualRepresentation as ParticleRepresentation;
+use data_structure::particle::WritableInForceField;
+use data_structure::position::DimensionfulVector as PositionVector;
+
+const MAXIMUM_PARTICLES_PER_LEAF: usize = 4;
+
+/// Particles at the same position (or close enough that halving the width of the node many times
+/// does not separate them) end up in a leaf together regardless of how many there are.
+const MAXIMUM_DEPTH: u32 = 48;
+
+/// Nodes which might have particles closer than this many softening lengths are always opened for
+/// inverse-power terms with a power higher than two.
+const NEAR_NEIGHBOUR_SOFTENING_LENGTHS: f64 = 4.0;
+
+/// This sets the experienced force of every particle to the sum of the forces from every other
+/// particle as approximated by a Barnes-Hut quadtree with the given opening angle, where the
+/// given inverse-power terms make up the whole of the given force law. An opening angle of zero
+/// opens every node, giving the same forces as summing over every pair. Tracers exert no forces,
+/// so they are left out of the quadtree, and the force on each tracer is found from the quadtree
+/// of the other particles just as for a particle outside of the quadtree.
+pub fn update_forces<ParticleImplementation, ParticleCollection, ForceLaw>(
+    opening_angle: f64,
+    force_law: &ForceLaw,
+    inverse_power_terms: &[InversePowerTerm],
+    particles_with_forces: &mut ParticleCollection,
+) where
+    ParticleImplementation: WritableInForceField,
+    ParticleCollection: data_structure::collection::SingleAndPairwiseFinite<
+        MutableElement = ParticleImplementation,
+    >,
+    ForceLaw: PairwiseForceLaw,
+{
+    let (tracer_particles, source_particles): (std::vec::Vec<_>, std::vec::Vec<_>) =
+        super::copy_time_slice(particles_with_forces)
+            .into_iter()
+            .partition(|particle| particle.intrinsic_values.is_tracer);
+    let particle_tree = Quadtree::new(source_particles, inverse_power_terms);
+    let mut nodes_to_visit = std::vec::Vec::new();
+    let mut source_index = 0;
+    let mut tracer_index = 0;
+    particles_with_forces.apply_to_every_single(&mut |particle_with_force| {
+        *particle_with_force.write_experienced_force() =
+            if particle_with_force.read_intrinsics().is_tracer {
+                tracer_index += 1;
+                particle_tree.force_on_tracer(
+                    force_law,
+                    inverse_power_terms,
+                    opening_angle,
+                    &tracer_particles[tracer_index - 1],
+                    &mut nodes_to_visit,
+                )
+            } else {
+                source_index += 1;
+                particle_tree.force_on_particle(
+                    force_law,
+                    inverse_power_terms,
+                    opening_angle,
+                    source_index - 1,
+                    &mut nodes_to_visit,
+                )
+            };
+    });
+}
+
+/// This represents the particles of a node as a single particle for one kind of force, with the
+/// total charge placed at the centre of the particles weighted by the magnitude of their charges.
+#[derive(Clone, Copy, Debug)]
+struct ChargeCluster {
+    total_charge: f64,
+    total_absolute_charge: f64,
+    charge_centre: PositionVector,
+}
+
+impl ChargeCluster {
+    fn new(charges_and_positions: impl std::iter::Iterator<Item = (f64, PositionVector)>) -> Self {
+        let mut total_charge = 0.0;
+        let mut total_absolute_charge = 0.0;
+        let mut weighted_horizontal_sum = 0.0;
+        let mut weighted_vertical_sum = 0.0;
+        for (particle_charge, particle_position) in charges_and_positions {
+            let absolute_charge = particle_charge.abs();
+            total_charge += particle_charge;
+            total_absolute_charge += absolute_charge;
+            weighted_horizontal_sum += absolute_charge * particle_position.horizontal_component.0;
+            weighted_vertical_sum += absolute_charge * particle_position.vertical_component.0;
+        }
+        let charge_centre = if total_absolute_charge > 0.0 {
+            PositionVector {
+                horizontal_component: data_structure::position::HorizontalUnit(
+                    weighted_horizontal_sum / total_absolute_charge,
+                ),
+                vertical_component: data_structure::position::VerticalUnit(
+                    weighted_vertical_sum / total_absolute_charge,
+                ),
+            }
+        } else {
+            PositionVector {
+                horizontal_component: data_structure::position::HorizontalUnit(0.0),
+                vertical_component: data_structure::position::VerticalUnit(0.0),
+            }
+        };
+        Self {
+            total_charge,
+            total_absolute_charge,
+            charge_centre,
+        }
+    }
+
+    fn is_uncharged(&self) -> bool {
+        self.total_absolute_charge <= 0.0
+    }
+
+    /// This returns true if the cluster is uncharged (so its position does not matter) or if the
+    /// given width is less than the opening angle times the distance from the given position to
+    /// the charge centre of the cluster. For a term with a power higher than two, the distance
+    /// must also be more than the diagonal of the node (as the charge centre can be anywhere in
+    /// the node) plus the near-neighbour number of softening lengths.
+    fn is_far_enough(
+        &self,
+        inverse_power_term: &InversePowerTerm,
+        node_width: f64,
+        opening_angle: f64,
+        position: &PositionVector,
+    ) -> bool {
+        if self.is_uncharged() {
+            return true;
+        }
+        let square_distance =
+            data_structure::position::square_separation_vector(&(*position - self.charge_centre)).0;
+        if inverse_power_term.power_of_separation > 2 {
+            let minimum_distance = (node_width * std::f64::consts::SQRT_2)
+                + (NEAR_NEIGHBOUR_SOFTENING_LENGTHS * inverse_power_term.softening_length);
+            if square_distance <= (minimum_distance * minimum_distance) {
+                return false;
+            }
+        }
+        (node_width * node_width) < (opening_angle * opening_angle * square_distance)
+    }
+
+    /// This returns the force which a particle with the given charge at the given position would
+    /// experience from the cluster if the whole cluster were a single particle at its charge
+    /// centre, for the given inverse-power term.
+    fn force_on_charge(
+        &self,
+        inverse_power_term: &InversePowerTerm,
+        particle_charge: f64,
+        particle_position: &PositionVector,
+    ) -> ForceVector {
+        let separation_vector = *particle_position - self.charge_centre;
+        if self.is_uncharged() {
+            return zero_force();
+        }
+
+        // As for pairs of particles, we include an additional 1/r so that we can multiply the
+        // separation vector directly.
+        let force_magnitude_over_separation = match inverse_power_term.softening_kernel {
+            configuration_parsing::SofteningKernel::DeadZone => {
+                if data_structure::position::SeparationUnit(inverse_power_term.softening_length)
+                    .is_greater_than_square(&separation_vector)
+                {
+                    return zero_force();
+                }
+                let inverse_separation =
+                    data_structure::position::square_separation_vector(&separation_vector)
+                        .to_inverse_square_root()
+                        .get_value();
+                inverse_power_term.coupling_constant
+                    * particle_charge
+                    * self.total_charge
+                    * inverse_separation.powi(inverse_power_term.power_of_separation + 1)
+            }
+            softening_kernel => {
+                inverse_power_term.coupling_constant
+                    * particle_charge
+                    * self.total_charge
+                    * super::softening::force_magnitude_over_separation(
+                        softening_kernel,
+                        inverse_power_term.softening_length,
+                        inverse_power_term.power_of_separation,
+                        data_structure::position::square_separation_vector(&separation_vector).0,
+                    )
+            }
+        };
+        super::scale_separation_to_force(&separation_vector, force_magnitude_over_separation)
+    }
+}
+
+/// Each node covers a contiguous range of the particle indices as ordered by the tree, so that
+/// whether a node contains a particle can be checked from the position of the particle in the
+/// ordering.
+struct QuadtreeNode {
+    first_ordered_index: usize,
+    end_ordered_index: usize,
+    node_width: f64,
+    charge_clusters: std::vec::Vec<ChargeCluster>,
+    child_node_indices: std::vec::Vec<usize>,
+}
+
+impl QuadtreeNode {
+    fn is_leaf(&self) -> bool {
+        self.child_node_indices.is_empty()
+    }
+
+    fn contains_ordered_index(&self, ordered_index: usize) -> bool {
+        (self.first_ordered_index <= ordered_index) && (ordered_index < self.end_ordered_index)
+    }
+}
+
+/// The nodes are stored in a single vector with the root first, and each node refers to its
+/// children by their indices in the vector.
+struct Quadtree {
+    particles: std::vec::Vec<BasicIndividual>,
+    ordered_particle_indices: std::vec::Vec<usize>,
+    ordered_index_of_particle: std::vec::Vec<usize>,
+    tree_nodes: std::vec::Vec<QuadtreeNode>,
+}
+
+impl Quadtree {
+    fn new(
+        particles: std::vec::Vec<BasicIndividual>,
+        inverse_power_terms: &[InversePowerTerm],
+    ) -> Self {
+        let mut ordered_particle_indices: std::vec::Vec<usize> = (0..particles.len()).collect();
+        let mut tree_nodes = std::vec::Vec::new();
+        if !particles.is_empty() {
+            let mut minimum_horizontal = f64::INFINITY;
+            let mut minimum_vertical = f64::INFINITY;
+            let mut maximum_horizontal = f64::NEG_INFINITY;
+            let mut maximum_vertical = f64::NEG_INFINITY;
+            for particle in &particles {
+                let particle_position = &particle.read_variables().position_vector;
+                minimum_horizontal =
+                    minimum_horizontal.min(particle_position.horizontal_component.0);
+                minimum_vertical = minimum_vertical.min(particle_position.vertical_component.0);
+                maximum_horizontal =
+                    maximum_horizontal.max(particle_position.horizontal_component.0);
+                maximum_vertical = maximum_vertical.max(particle_position.vertical_component.0);
+            }
+            let root_width =
+                (maximum_horizontal - minimum_horizontal).max(maximum_vertical - minimum_vertical);
+            Self::add_node(
+                &particles,
+                inverse_power_terms,
+                &mut ordered_particle_indices,
+                0,
+                minimum_horizontal,
+                minimum_vertical,
+                root_width,
+                0,
+                &mut tree_nodes,
+            );
+        }
+
+        let mut ordered_index_of_particle = vec![0; particles.len()];
+        for (ordered_index, particle_index) in ordered_particle_indices.iter().enumerate() {
+            ordered_index_of_particle[*particle_index] = ordered_index;
+        }
+
+        Self {
+            particles,
+            ordered_particle_indices,
+            ordered_index_of_particle,
+            tree_nodes,
+        }
+    }
+
+    /// This adds a node for the particles with the given indices, which all lie in the square
+    /// with the given lower-left corner and width, with a cluster for each inverse-power term, and
+    /// then adds nodes for each non-empty quadrant of the square unless the node is a leaf. The
+    /// given indices are re-ordered so that the indices for each quadrant are contiguous. The
+    /// index of the new node is returned.
+    #[allow(clippy::too_many_arguments)]
+    fn add_node(
+        particles: &[BasicIndividual],
+        inverse_power_terms: &[InversePowerTerm],
+        node_particle_indices: &mut [usize],
+        first_ordered_index: usize,
+        left_border: f64,
+        lower_border: f64,
+        node_width: f64,
+        node_depth: u32,
+        tree_nodes: &mut std::vec::Vec<QuadtreeNode>,
+    ) -> usize {
+        let node_index = tree_nodes.len();
+        tree_nodes.push(QuadtreeNode {
+            first_ordered_index,
+            end_ordered_index: first_ordered_index + node_particle_indices.len(),
+            node_width,
+            charge_clusters: inverse_power_terms
+                .iter()
+                .map(|inverse_power_term| {
+                    ChargeCluster::new(node_particle_indices.iter().map(|particle_index| {
+                        let particle = &particles[*particle_index];
+                        (
+                            (inverse_power_term.charge_of_particle)(particle.read_intrinsics()),
+                            particle.read_variables().position_vector,
+                        )
+                    }))
+                })
+                .collect(),
+            child_node_indices: vec![],
+        });
+
+        if (node_particle_indices.len() <= MAXIMUM_PARTICLES_PER_LEAF)
+            || (node_depth >= MAXIMUM_DEPTH)
+            || (node_width <= 0.0)
+        {
+            return node_index;
+        }
+
+        let half_width = 0.5 * node_width;
+        let horizontal_midpoint = left_border + half_width;
+        let vertical_midpoint = lower_border + half_width;
+        let mut quadrant_particle_indices: [std::vec::Vec<usize>; 4] =
+            [vec![], vec![], vec![], vec![]];
+        for particle_index in node_particle_indices.iter() {
+            let particle_position = &particles[*particle_index].read_variables().position_vector;
+            let is_right = particle_position.horizontal_component.0 >= horizontal_midpoint;
+            let is_upper = particle_position.vertical_component.0 >= vertical_midpoint;
+            quadrant_particle_indices[(is_right as usize) + 2 * (is_upper as usize)]
+                .push(*particle_index);
+        }
+
+        let mut child_node_indices = vec![];
+        let mut quadrant_start = 0;
+        for (quadrant_index, particle_indices_in_quadrant) in
+            quadrant_particle_indices.iter().enumerate()
+        {
+            if particle_indices_in_quadrant.is_empty() {
+                continue;
+            }
+            let quadrant_end = quadrant_start + particle_indices_in_quadrant.len();
+            node_particle_indices[quadrant_start..quadrant_end]
+                .copy_from_slice(particle_indices_in_quadrant);
+            let quadrant_left = if (quadrant_index % 2) == 1 {
+                horizontal_midpoint
+            } else {
+                left_border
+            };
+            let quadrant_lower = if quadrant_index >= 2 {
+                vertical_midpoint
+            } else {
+                lower_border
+            };
+            child_node_indices.push(Self::add_node(
+                particles,
+                inverse_power_terms,
+                &mut node_particle_indices[quadrant_start..quadrant_end],
+                first_ordered_index + quadrant_start,
+                quadrant_left,
+                quadrant_lower,
+                half_width,
+                node_depth + 1,
+                tree_nodes,
+            ));
+            quadrant_start = quadrant_end;
+        }
+        tree_nodes[node_index].child_node_indices = child_node_indices;
+        node_index
+    }
+
+    /// This returns the approximate force on the particle with the given index from every other
+    /// particle. The given vector is used as the stack of nodes still to be visited, so that it
+    /// can be re-used for every particle.
+    fn force_on_particle(
+        &self,
+        force_law: &impl PairwiseForceLaw,
+        inverse_power_terms: &[InversePowerTerm],
+        opening_angle: f64,
+        particle_index: usize,
+        nodes_to_visit: &mut std::vec::Vec<usize>,
+    ) -> ForceVector {
+        if self.tree_nodes.is_empty() {
+            return zero_force();
+        }
+        self.force_on_given_particle(
+            force_law,
+            inverse_power_terms,
+            opening_angle,
+            &self.particles[particle_index],
+            Some(particle_index),
+            nodes_to_visit,
+        )
+    }
+
+    /// This returns the approximate force on the given tracer, which is not in the quadtree, from
+    /// every particle in the quadtree.
+    fn force_on_tracer(
+        &self,
+        force_law: &impl PairwiseForceLaw,
+        inverse_power_terms: &[InversePowerTerm],
+        opening_angle: f64,
+        tracer_particle: &BasicIndividual,
+        nodes_to_visit: &mut std::vec::Vec<usize>,
+    ) -> ForceVector {
+        if self.tree_nodes.is_empty() {
+            return zero_force();
+        }
+        self.force_on_given_particle(
+            force_law,
+            inverse_power_terms,
+            opening_angle,
+            tracer_particle,
+            None,
+            nodes_to_visit,
+        )
+    }
+
+    /// The index is that of the given particle in the quadtree, if it is in the quadtree, so that
+    /// the particle does not exert a force on itself and the nodes containing it are always
+    /// opened.
+    fn force_on_given_particle(
+        &self,
+        force_law: &impl PairwiseForceLaw,
+        inverse_power_terms: &[InversePowerTerm],
+        opening_angle: f64,
+        particle: &BasicIndividual,
+        particle_index: Option<usize>,
+        nodes_to_visit: &mut std::vec::Vec<usize>,
+    ) -> ForceVector {
+        let mut total_force = zero_force();
+        let particle_position = &particle.read_variables().position_vector;
+        let particle_intrinsics = particle.read_intrinsics();
+        let ordered_index =
+            particle_index.map(|particle_index| self.ordered_index_of_particle[particle_index]);
+        nodes_to_visit.clear();
+        nodes_to_visit.push(0);
+        while let Some(node_index) = nodes_to_visit.pop() {
+            let tree_node = &self.tree_nodes[node_index];
+            if tree_node.is_leaf() {
+                for other_index in &self.ordered_particle_indices
+                    [tree_node.first_ordered_index..tree_node.end_ordered_index]
+                {
+                    if Some(*other_index) != particle_index {
+                        total_force += force_law.force_on_first_particle_from_second_particle(
+                            particle,
+                            &self.particles[*other_index],
+                        );
+                    }
+                }
+            } else if !ordered_index
+                .is_some_and(|ordered_index| tree_node.contains_ordered_index(ordered_index))
+                && inverse_power_terms
+                    .iter()
+                    .zip(tree_node.charge_clusters.iter())
+                    .all(|(inverse_power_term, charge_cluster)| {
+                        charge_cluster.is_far_enough(
+                            inverse_power_term,
+                            tree_node.node_width,
+                            opening_angle,
+                            particle_position,
+                        )
+                    })
+            {
+                for (inverse_power_term, charge_cluster) in inverse_power_terms
+                    .iter()
+                    .zip(tree_node.charge_clusters.iter())
+                {
+                    total_force += charge_cluster.force_on_charge(
+                        inverse_power_term,
+                        (inverse_power_term.charge_of_particle)(particle_intrinsics),
+                        particle_position,
+                    );
+                }
+            } else {
+                nodes_to_visit.extend_from_slice(&tree_node.child_node_indices);
+            }
+        }
+        total_force
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_structure::collection::SingleAndPairwiseFinite;
+    use data_structure::particle::contiguous_struct as contiguous_particle_struct;
+    use data_structure::particle::CollectionInForceField;
+    use data_structure::particle::CollectionInForceFieldGenerator;
+    use data_structure::particle::ReadOnlyInForceField;
+
+    const TEST_NUMBER_OF_PARTICLES: usize = 300;
+
+    /// This is a simple linear congruential generator, so that the test particles are scattered
+    /// in the same way on every run without needing an external crate.
+    struct PseudorandomSequence(u64);
+
+    impl PseudorandomSequence {
+        fn next_in_range(&mut self, lower_bound: f64, upper_bound: f64) -> f64 {
+            self.0 = self
+                .0
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            let unit_interval_value = ((self.0 >> 11) as f64) / ((1_u64 << 53) as f64);
+            lower_bound + (unit_interval_value * (upper_bound - lower_bound))
+        }
+    }
+
+    fn create_test_force_law() -> Result<super::super::force_law::InverseSquaredAndFourth, String> {
+        super::super::force_law::InverseSquaredAndFourth::new_from_configuration(
+            &configuration_parsing::EvolutionConfiguration {
+                inverse_squared_coupling: -1.0,
+                inverse_fourth_coupling: 2.0,
+                milliseconds_per_time_slice: 100,
+                ..super::super::test_functions::create_test_evolution_configuration(2, 0.1)
+            },
+        )
+        .map_err(|construction_error| format!("Force law error: {}", construction_error))
+    }
+
+    /// This updates the forces with the Barnes-Hut quadtree for all the inverse-power terms of the
+    /// given force law.
+    fn update_forces_for_law(
+        opening_angle: f64,
+        force_law: &super::super::force_law::InverseSquaredAndFourth,
+        evolving_particles: &mut contiguous_particle_struct::VectorOfMassNormalizedWithForceField,
+    ) -> Result<(), String> {
+        let inverse_power_terms = force_law
+            .inverse_power_terms()
+            .ok_or_else(|| String::from("Expected inverse-power terms"))?;
+        update_forces(
+            opening_angle,
+            force_law,
+            inverse_power_terms,
+            evolving_particles.access_mutable_elements(),
+        );
+        Ok(())
+    }
+
+    /// This scatters particles over a disc with a denser clump off to one side, so that the tree
+    /// has both shallow and deep branches. The inverse-squared charges are all positive, like
+    /// masses, while the inverse-fourth charges have both signs.
+    fn create_scattered_particles(
+        random_sequence: &mut PseudorandomSequence,
+    ) -> contiguous_particle_struct::VectorOfMassNormalizedWithForceField {
+        let mut evolving_particles =
+            contiguous_particle_struct::VectorOfMassNormalizedWithForceFieldGenerator {}
+                .create_collection();
+        for particle_index in 0..TEST_NUMBER_OF_PARTICLES {
+            let (centre_horizontal, centre_vertical, maximum_radius) = if (particle_index % 3) == 0
+            {
+                (30.0, -20.0, 5.0)
+            } else {
+                (0.0, 0.0, 50.0)
+            };
+            let radius = maximum_radius * random_sequence.next_in_range(0.0, 1.0).sqrt();
+            let angle = random_sequence.next_in_range(0.0, 2.0 * std::f64::consts::PI);
+            let test_particle = super::super::test_functions::new_test_particle(
+                1.0,
+                random_sequence.next_in_range(0.5, 2.0),
+                random_sequence.next_in_range(-1.0, 1.0),
+                (
+                    centre_horizontal + (radius * angle.cos()),
+                    centre_vertical + (radius * angle.sin()),
+                ),
+                (0.0, 0.0),
+            );
+            evolving_particles
+                .add_particle(&test_particle, &data_structure::time::OverMassUnit(1.0));
+        }
+        evolving_particles
+    }
+
+    /// This scatters particles over a disc small enough that every particle is within the
+    /// near-neighbour number of dead zone radii of every other particle.
+    fn create_close_particles(
+        random_sequence: &mut PseudorandomSequence,
+    ) -> contiguous_particle_struct::VectorOfMassNormalizedWithForceField {
+        let mut evolving_particles =
+            contiguous_particle_struct::VectorOfMassNormalizedWithForceFieldGenerator {}
+                .create_collection();
+        for _ in 0..(10 * MAXIMUM_PARTICLES_PER_LEAF) {
+            let radius = 0.2 * random_sequence.next_in_range(0.0, 1.0).sqrt();
+            let angle = random_sequence.next_in_range(0.0, 2.0 * std::f64::consts::PI);
+            let test_particle = super::super::test_functions::new_test_particle(
+                1.0,
+                random_sequence.next_in_range(0.5, 2.0),
+                random_sequence.next_in_range(-1.0, 1.0),
+                (radius * angle.cos(), radius * angle.sin()),
+                (0.0, 0.0),
+            );
+            evolving_particles
+                .add_particle(&test_particle, &data_structure::time::OverMassUnit(1.0));
+        }
+        evolving_particles
+    }
+
+    fn read_forces(
+        evolving_particles: &mut contiguous_particle_struct::VectorOfMassNormalizedWithForceField,
+    ) -> std::vec::Vec<ForceVector> {
+        let mut read_forces = vec![];
+        evolving_particles
+            .access_mutable_elements()
+            .apply_to_every_single(&mut |particle_with_force| {
+                read_forces.push(*particle_with_force.read_experienced_force());
+            });
+        read_forces
+    }
+
+    fn force_magnitude(force_vector: &ForceVector) -> f64 {
+        (force_vector.horizontal_component.0 * force_vector.horizontal_component.0
+            + force_vector.vertical_component.0 * force_vector.vertical_component.0)
+            .sqrt()
+    }
+
+    /// This marks every other particle of the given collection as a tracer.
+    fn mark_alternate_particles_as_tracers(
+        given_particles: &contiguous_particle_struct::VectorOfMassNormalizedWithForceField,
+    ) -> contiguous_particle_struct::VectorOfMassNormalizedWithForceField {
+        let mut evolving_particles =
+            contiguous_particle_struct::VectorOfMassNormalizedWithForceFieldGenerator {}
+                .create_collection();
+        for (particle_index, given_particle) in given_particles.0.iter().enumerate() {
+            let mut tracer_particle = given_particle.into_individual_particle();
+            tracer_particle.intrinsic_values.is_tracer = (particle_index % 2) == 1;
+            evolving_particles
+                .add_particle(&tracer_particle, &data_structure::time::OverMassUnit(1.0));
+        }
+        evolving_particles
+    }
+
+    /// This compares the forces from the quadtree with the given opening angle to the exact sums,
+    /// requiring that the difference for each particle is within the given fraction of the root
+    /// mean square of the magnitudes of the exact forces on all the particles (since some
+    /// particles may feel almost no total force from many large cancelling forces).
+    fn compare_to_exact_pairwise_sum(
+        opening_angle: f64,
+        relative_tolerance: f64,
+        mut evolving_particles: contiguous_particle_struct::VectorOfMassNormalizedWithForceField,
+    ) -> Result<(), String> {
+        let force_law = create_test_force_law()?;
+
+        if super::super::has_tracers(evolving_particles.access_mutable_elements()) {
+            super::super::update_forces_from_sources(
+                &force_law,
+                evolving_particles.access_mutable_elements(),
+            );
+        } else {
+            super::super::update_forces_pairwise(
+                &force_law,
+                evolving_particles.access_mutable_elements(),
+            );
+        }
+        let exact_forces = read_forces(&mut evolving_particles);
+        update_forces_for_law(opening_angle, &force_law, &mut evolving_particles)?;
+        let approximate_forces = read_forces(&mut evolving_particles);
+
+        let root_mean_square_force = (exact_forces
+            .iter()
+            .map(|exact_force| force_magnitude(exact_force).powi(2))
+            .sum::<f64>()
+            / (exact_forces.len() as f64))
+            .sqrt();
+        let mut failure_messages = vec![];
+        for (particle_index, (exact_force, approximate_force)) in exact_forces
+            .iter()
+            .zip(approximate_forces.iter())
+            .enumerate()
+        {
+            let mut force_difference = *approximate_force;
+            force_difference -= *exact_force;
+            if force_magnitude(&force_difference) > (relative_tolerance * root_mean_square_force) {
+                failure_messages.push(format!(
+                    "particle {}: expected {:?}, actual {:?}",
+                    particle_index, exact_force, approximate_force
+                ));
+            }
+        }
+        if failure_messages.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Root mean square force {}, {} of {} particles out of tolerance {}: {:?}",
+                root_mean_square_force,
+                failure_messages.len(),
+                exact_forces.len(),
+                relative_tolerance,
+                failure_messages
+            ))
+        }
+    }
+
+    #[test]
+    fn test_zero_opening_angle_matches_pairwise_sum() -> Result<(), String> {
+        compare_to_exact_pairwise_sum(
+            0.0,
+            0.000000001,
+            create_scattered_particles(&mut PseudorandomSequence(0x5eed)),
+        )
+    }
+
+    #[test]
+    fn test_small_opening_angle_close_to_pairwise_sum() -> Result<(), String> {
+        compare_to_exact_pairwise_sum(
+            0.3,
+            0.001,
+            create_scattered_particles(&mut PseudorandomSequence(0x5eed)),
+        )
+    }
+
+    #[test]
+    fn test_typical_opening_angle_close_to_pairwise_sum() -> Result<(), String> {
+        compare_to_exact_pairwise_sum(
+            0.7,
+            0.01,
+            create_scattered_particles(&mut PseudorandomSequence(0x5eed)),
+        )
+    }
+
+    #[test]
+    fn test_zero_opening_angle_matches_pairwise_sum_with_tracers() -> Result<(), String> {
+        compare_to_exact_pairwise_sum(
+            0.0,
+            0.000000001,
+            mark_alternate_particles_as_tracers(&create_scattered_particles(
+                &mut PseudorandomSequence(0x5eed),
+            )),
+        )
+    }
+
+    #[test]
+    fn test_typical_opening_angle_close_to_pairwise_sum_with_tracers() -> Result<(), String> {
+        compare_to_exact_pairwise_sum(
+            0.7,
+            0.01,
+            mark_alternate_particles_as_tracers(&create_scattered_particles(
+                &mut PseudorandomSequence(0x5eed),
+            )),
+        )
+    }
+
+    #[test]
+    fn test_near_neighbours_summed_exactly_whatever_opening_angle() -> Result<(), String> {
+        compare_to_exact_pairwise_sum(
+            10.0,
+            0.000000001,
+            create_close_particles(&mut PseudorandomSequence(0x5eed)),
+        )
+    }
+
+    #[test]
+    fn test_coincident_particles_do_not_recurse_forever() -> Result<(), String> {
+        let force_law = create_test_force_law()?;
+        let mut evolving_particles =
+            contiguous_particle_struct::VectorOfMassNormalizedWithForceFieldGenerator {}
+                .create_collection();
+        let random_particles = create_scattered_particles(&mut PseudorandomSequence(1));
+        let repeated_particle = random_particles.0[0].into_individual_particle();
+        for _ in 0..(2 * MAXIMUM_PARTICLES_PER_LEAF) {
+            evolving_particles
+                .add_particle(&repeated_particle, &data_structure::time::OverMassUnit(1.0));
+        }
+        update_forces_for_law(0.5, &force_law, &mut evolving_particles)?;
+        for particle_force in read_forces(&mut evolving_particles) {
+            if force_magnitude(&particle_force) != 0.0 {
+                return Err(format!(
+                    "Expected no force within dead zone, got {:?}",
+                    particle_force
+                ));
+            }
+        }
+        Ok(())
+    }
+}
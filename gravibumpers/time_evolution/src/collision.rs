@@ -0,0 +1,374 @@
+/// This module provides the collisions of particles which have collision radii, which the evolvers
+/// resolve after each step if the configuration gives a restitution coefficient. A pair of
+/// particles which came into contact during a step, whether they still overlap at the end of the
+/// step or were fast enough to pass through each other, is taken back along its velocities to the
+/// moment in the step when the particles first touched, given an impulse along the line between
+/// their centers which reverses their approach scaled by the restitution coefficient, and then
+/// moved on for the rest of the step with the new velocities. The impulses on the two
+/// particles are equal and opposite, so the total momentum is unchanged, and with a coefficient of
+/// 1 the kinetic energy is unchanged too. Each pair is checked once per step, in the order of the
+/// indices of the particles whatever the memory layout, so a particle which touches two others in
+/// the same step bounces off the one with the lower index first and then off the other with its
+/// new velocity. Tracers never collide, as they exert no force on the other particles.
+use data_structure::collection::SingleAndPairwiseFinite;
+use data_structure::particle::IndividualRepresentation;
+use data_structure::particle::WritableInForceField;
+
+/// The impulse is that which the second particle of the pair receives, and the first receives the
+/// opposite. The time since contact is how long before the end of the step the particles touched.
+struct CollisionImpulse {
+    horizontal_impulse: f64,
+    vertical_impulse: f64,
+    time_since_contact: f64,
+}
+
+/// The particles are taken to have moved in straight lines since they touched, as the forces on
+/// them hardly change the directions of their paths within a step. Particles which overlap at the
+/// end of the step but are moving apart have already bounced, and particles which do not overlap
+/// have only collided if they are moving apart after having passed through each other within the
+/// step. Particles which overlapped for longer than the step are only taken back to the start of
+/// the step.
+fn find_collision_impulse(
+    restitution_coefficient: f64,
+    time_interval: f64,
+    periodic_box_size: Option<(f64, f64)>,
+    first_particle: &impl IndividualRepresentation,
+    second_particle: &impl IndividualRepresentation,
+) -> Option<CollisionImpulse> {
+    let first_intrinsics = first_particle.read_intrinsics();
+    let second_intrinsics = second_particle.read_intrinsics();
+    if (first_intrinsics.collision_radius.0 <= 0.0)
+        || (second_intrinsics.collision_radius.0 <= 0.0)
+        || first_intrinsics.is_tracer
+        || second_intrinsics.is_tracer
+    {
+        return None;
+    }
+    let contact_separation =
+        first_intrinsics.collision_radius.0 + second_intrinsics.collision_radius.0;
+    let first_variables = first_particle.read_variables();
+    let second_variables = second_particle.read_variables();
+    let separation_vector = super::displacement_to_nearest_image(
+        periodic_box_size,
+        &first_variables.position_vector,
+        &second_variables.position_vector,
+    );
+    let separation_components = (
+        separation_vector.horizontal_component.0,
+        separation_vector.vertical_component.0,
+    );
+    let relative_velocity = (
+        second_variables.velocity_vector.horizontal_component.0
+            - first_variables.velocity_vector.horizontal_component.0,
+        second_variables.velocity_vector.vertical_component.0
+            - first_variables.velocity_vector.vertical_component.0,
+    );
+    let squared_separation = (separation_components.0 * separation_components.0)
+        + (separation_components.1 * separation_components.1);
+    let approach_product = (separation_components.0 * relative_velocity.0)
+        + (separation_components.1 * relative_velocity.1);
+    let is_overlapping = squared_separation < (contact_separation * contact_separation);
+    if (is_overlapping && (approach_product >= 0.0))
+        || (!is_overlapping && (approach_product <= 0.0))
+    {
+        return None;
+    }
+
+    // The time since contact is the larger root of
+    // |separation - (relative velocity * time)| = contact separation, which is when the particles
+    // first touched, as the smaller root is when they would have stopped overlapping.
+    let squared_relative_speed =
+        (relative_velocity.0 * relative_velocity.0) + (relative_velocity.1 * relative_velocity.1);
+    let discriminant = (approach_product * approach_product)
+        - (squared_relative_speed
+            * (squared_separation - (contact_separation * contact_separation)));
+    if discriminant <= 0.0 {
+        return None;
+    }
+    let earliest_contact_time = (approach_product + discriminant.sqrt()) / squared_relative_speed;
+    if !is_overlapping && (earliest_contact_time > time_interval) {
+        return None;
+    }
+    let time_since_contact = earliest_contact_time.min(time_interval);
+    let separation_at_contact = (
+        separation_components.0 - (relative_velocity.0 * time_since_contact),
+        separation_components.1 - (relative_velocity.1 * time_since_contact),
+    );
+    let distance_at_contact = ((separation_at_contact.0 * separation_at_contact.0)
+        + (separation_at_contact.1 * separation_at_contact.1))
+        .sqrt();
+    if distance_at_contact <= 0.0 {
+        return None;
+    }
+    let contact_normal = (
+        separation_at_contact.0 / distance_at_contact,
+        separation_at_contact.1 / distance_at_contact,
+    );
+    let normal_speed =
+        (relative_velocity.0 * contact_normal.0) + (relative_velocity.1 * contact_normal.1);
+    if normal_speed >= 0.0 {
+        return None;
+    }
+
+    let first_mass = first_intrinsics.inertial_mass.0;
+    let second_mass = second_intrinsics.inertial_mass.0;
+    let reduced_mass = (first_mass * second_mass) / (first_mass + second_mass);
+    let impulse_magnitude = -(1.0 + restitution_coefficient) * reduced_mass * normal_speed;
+    Some(CollisionImpulse {
+        horizontal_impulse: impulse_magnitude * contact_normal.0,
+        vertical_impulse: impulse_magnitude * contact_normal.1,
+        time_since_contact,
+    })
+}
+
+/// The direction is -1 for the first particle of the pair and 1 for the second. The change in
+/// velocity also applies to the time since contact, which moves the particle to where it would
+/// have been if it had bounced at the moment of contact.
+fn apply_collision_impulse(
+    impulse_direction: f64,
+    particle_in_pair: &mut data_structure::particle::BasicIndividual,
+    collision_impulse: &CollisionImpulse,
+) {
+    let direction_over_mass = impulse_direction / particle_in_pair.intrinsic_values.inertial_mass.0;
+    let velocity_change = (
+        collision_impulse.horizontal_impulse * direction_over_mass,
+        collision_impulse.vertical_impulse * direction_over_mass,
+    );
+    let particle_variables = &mut particle_in_pair.variable_values;
+    particle_variables.velocity_vector.horizontal_component.0 += velocity_change.0;
+    particle_variables.velocity_vector.vertical_component.0 += velocity_change.1;
+    particle_variables.position_vector.horizontal_component.0 +=
+        velocity_change.0 * collision_impulse.time_since_contact;
+    particle_variables.position_vector.vertical_component.0 +=
+        velocity_change.1 * collision_impulse.time_since_contact;
+}
+
+/// The particles are copied out of the collection and the pairs are checked in a plain loop over
+/// the indices, as a multithreaded collection would apply functions over pairs in an order which
+/// depends on its number of threads. Copying the particles is cheap next to the loop over the pairs.
+pub fn resolve_collisions<ParticleImplementation, ParticleCollection>(
+    restitution_coefficient: f64,
+    time_interval: &data_structure::time::IntervalUnit,
+    boundary_conditions: &Option<configuration_parsing::BoundaryConditions>,
+    evolving_particles: &mut ParticleCollection,
+) where
+    ParticleImplementation: WritableInForceField,
+    ParticleCollection: SingleAndPairwiseFinite<MutableElement = ParticleImplementation>,
+{
+    let periodic_box_size = super::get_periodic_box_size(boundary_conditions);
+    let mut time_slice = super::copy_time_slice(evolving_particles);
+    for first_index in 0..time_slice.len() {
+        let (lower_particles, higher_particles) = time_slice.split_at_mut(first_index + 1);
+        let first_particle = &mut lower_particles[first_index];
+        for second_particle in higher_particles.iter_mut() {
+            if let Some(collision_impulse) = find_collision_impulse(
+                restitution_coefficient,
+                time_interval.0,
+                periodic_box_size,
+                first_particle,
+                second_particle,
+            ) {
+                apply_collision_impulse(-1.0, first_particle, &collision_impulse);
+                apply_collision_impulse(1.0, second_particle, &collision_impulse);
+            }
+        }
+    }
+    let mut collided_particles_in_order = time_slice.into_iter();
+    evolving_particles.apply_to_every_single(&mut |particle_with_force| {
+        if let Some(collided_particle) = collided_particles_in_order.next() {
+            *particle_with_force.write_particle_variables() = collided_particle.variable_values;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_structure::particle::contiguous_struct as contiguous_particle_struct;
+    use data_structure::particle::CollectionInForceField;
+    use data_structure::particle::CollectionInForceFieldGenerator;
+
+    /// The middle particle overlaps both of the others, which are both approaching it, by a tenth
+    /// of the sum of the radii, so after the step it has collided with both. Taking the pairs in the
+    /// order of the indices, the first collision swaps the velocities of the equal masses, giving
+    /// the middle particle a velocity of 1, and the heavier particle on the right then bounces it
+    /// back at -5 / 3 while itself moving off at 1 / 3. Taking the other pair first would give
+    /// different velocities. The touching particles are spread out among particles which are far
+    /// away and cannot collide, so that a multithreaded collection splits them into different
+    /// blocks, and would take the pair of the middle and the heavier particle first.
+    fn check_three_touching_particles_collide_in_order_of_indices<CollectionGenerator>(
+        collection_generator: &CollectionGenerator,
+    ) -> Result<(), String>
+    where
+        CollectionGenerator: CollectionInForceFieldGenerator,
+    {
+        let time_interval = data_structure::time::IntervalUnit(0.1);
+        let touching_particles = [(0, 1.0, 0.0, 0.0), (2, 1.0, -0.9, 1.0), (6, 2.0, 0.9, -1.0)];
+        let all_particles = (0..8)
+            .map(|particle_index| {
+                match touching_particles
+                    .iter()
+                    .find(|touching_particle| touching_particle.0 == particle_index)
+                {
+                    Some((_, inertial_mass, horizontal_position, horizontal_velocity)) => {
+                        let mut touching_particle = super::super::test_functions::new_test_particle(
+                            *inertial_mass,
+                            0.0,
+                            0.0,
+                            (*horizontal_position, 0.0),
+                            (*horizontal_velocity, 0.0),
+                        );
+                        touching_particle.intrinsic_values.collision_radius =
+                            data_structure::position::SeparationUnit(0.5);
+                        touching_particle
+                    }
+                    None => super::super::test_functions::new_test_particle(
+                        1.0,
+                        0.0,
+                        0.0,
+                        (10.0 + (particle_index as f64), 10.0),
+                        (0.0, 0.0),
+                    ),
+                }
+            })
+            .collect::<std::vec::Vec<_>>();
+        let mut evolving_particles = super::super::create_particles_in_force_field(
+            collection_generator,
+            all_particles.into_iter(),
+            &time_interval,
+        )
+        .map_err(|setup_error| format!("Set-up error: {:?}", setup_error))?;
+        resolve_collisions(
+            1.0,
+            &time_interval,
+            &None,
+            evolving_particles.access_mutable_elements(),
+        );
+
+        let expected_velocities = [-5.0 / 3.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0 / 3.0, 0.0];
+        let actual_velocities =
+            super::super::copy_time_slice(evolving_particles.access_mutable_elements())
+                .iter()
+                .map(|collided_particle| {
+                    collided_particle
+                        .variable_values
+                        .velocity_vector
+                        .horizontal_component
+                        .0
+                })
+                .collect::<std::vec::Vec<f64>>();
+        if (actual_velocities.len() == expected_velocities.len())
+            && actual_velocities
+                .iter()
+                .zip(expected_velocities.iter())
+                .all(|(actual_velocity, expected_velocity)| {
+                    (actual_velocity - expected_velocity).abs() < 1.0e-12
+                })
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected horizontal velocities {:?}, got {:?}",
+                expected_velocities, actual_velocities
+            ))
+        }
+    }
+
+    /// The first pair meets head on with a relative movement in the step ten times the sum of the
+    /// radii, so by the end of the step the particles have passed through each other, having
+    /// touched 0.04 seconds into the step. The equal masses swap velocities at that moment and
+    /// move apart for the remaining 0.06 seconds. The second pair also passes each other, but
+    /// too far apart to touch, so it is left alone.
+    #[test]
+    fn check_fast_pair_does_not_pass_through_each_other() -> Result<(), String> {
+        let time_interval = data_structure::time::IntervalUnit(0.1);
+        let particles_at_end_of_step = [
+            ((0.5, 0.0), (10.0, 0.0)),
+            ((-0.5, 0.0), (-10.0, 0.0)),
+            ((0.5, 5.0), (10.0, 0.0)),
+            ((-0.5, 5.3), (-10.0, 0.0)),
+        ];
+        let all_particles = particles_at_end_of_step
+            .iter()
+            .map(|(particle_position, particle_velocity)| {
+                let mut fast_particle = super::super::test_functions::new_test_particle(
+                    1.0,
+                    0.0,
+                    0.0,
+                    *particle_position,
+                    *particle_velocity,
+                );
+                fast_particle.intrinsic_values.collision_radius =
+                    data_structure::position::SeparationUnit(0.1);
+                fast_particle
+            })
+            .collect::<std::vec::Vec<_>>();
+        let mut evolving_particles = super::super::create_particles_in_force_field(
+            &contiguous_particle_struct::VectorOfMassNormalizedWithForceFieldGenerator {},
+            all_particles.into_iter(),
+            &time_interval,
+        )
+        .map_err(|setup_error| format!("Set-up error: {:?}", setup_error))?;
+        resolve_collisions(
+            1.0,
+            &time_interval,
+            &None,
+            evolving_particles.access_mutable_elements(),
+        );
+
+        let expected_horizontal_variables =
+            [(-0.7, -10.0), (0.7, 10.0), (0.5, 10.0), (-0.5, -10.0)];
+        let actual_horizontal_variables =
+            super::super::copy_time_slice(evolving_particles.access_mutable_elements())
+                .iter()
+                .map(|collided_particle| {
+                    (
+                        collided_particle
+                            .variable_values
+                            .position_vector
+                            .horizontal_component
+                            .0,
+                        collided_particle
+                            .variable_values
+                            .velocity_vector
+                            .horizontal_component
+                            .0,
+                    )
+                })
+                .collect::<std::vec::Vec<(f64, f64)>>();
+        if (actual_horizontal_variables.len() == expected_horizontal_variables.len())
+            && actual_horizontal_variables
+                .iter()
+                .zip(expected_horizontal_variables.iter())
+                .all(|(actual_variables, expected_variables)| {
+                    ((actual_variables.0 - expected_variables.0).abs() < 1.0e-12)
+                        && ((actual_variables.1 - expected_variables.1).abs() < 1.0e-12)
+                })
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected horizontal positions and velocities {:?}, got {:?}",
+                expected_horizontal_variables, actual_horizontal_variables
+            ))
+        }
+    }
+
+    #[test]
+    fn check_three_touching_particles_collide_in_order_of_indices_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        check_three_touching_particles_collide_in_order_of_indices(
+            &contiguous_particle_struct::VectorOfMassNormalizedWithForceFieldGenerator {},
+        )
+    }
+
+    #[test]
+    fn check_three_touching_particles_collide_in_order_of_indices_with_multithreaded(
+    ) -> Result<(), String> {
+        check_three_touching_particles_collide_in_order_of_indices(
+            &contiguous_particle_struct::MultithreadedVectorOfMassNormalizedWithForceFieldGenerator {
+                number_of_threads: 2,
+            },
+        )
+    }
+}
@@ -0,0 +1,382 @@
+/// This module provides functions for calculating the quantities which should be conserved by the
+/// time evolution (the total energy, the linear momentum, and the angular momentum about the
+/// origin) for each time slice, so that the accuracy of an evolution can be checked.
+use super::force_law::PairwiseForceLaw;
+use super::ParticleRepresentation;
+
+const FRAME_INDEX_LABEL: &str = "frameIndex";
+const TIME_IN_SECONDS_LABEL: &str = "timeInSeconds";
+const KINETIC_ENERGY_LABEL: &str = "kineticEnergy";
+const POTENTIAL_ENERGY_LABEL: &str = "potentialEnergy";
+const TOTAL_ENERGY_LABEL: &str = "totalEnergy";
+const HORIZONTAL_MOMENTUM_LABEL: &str = "horizontalMomentum";
+const VERTICAL_MOMENTUM_LABEL: &str = "verticalMomentum";
+const ANGULAR_MOMENTUM_LABEL: &str = "angularMomentum";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameDiagnostics {
+    pub kinetic_energy: f64,
+    pub potential_energy: f64,
+    pub horizontal_momentum: f64,
+    pub vertical_momentum: f64,
+    pub angular_momentum: f64,
+}
+
+impl FrameDiagnostics {
+    pub fn total_energy(&self) -> f64 {
+        self.kinetic_energy + self.potential_energy
+    }
+}
+
+/// This sums over every pair of particles for the potential energy, so takes as long as updating
+/// the forces by summing over every pair. The potential energy includes that of each particle in
+/// any external fields of the force law and that of any spring bonds between the particles.
+/// Tracers are left out, as they exert no forces, so they take no part in conserving the energy
+/// and momenta of the other particles.
+pub fn calculate_for_time_slice(
+    force_law: &impl PairwiseForceLaw,
+    particles_at_time_slice: &[impl ParticleRepresentation],
+) -> FrameDiagnostics {
+    let mut frame_diagnostics = FrameDiagnostics {
+        kinetic_energy: 0.0,
+        potential_energy: force_law.spring_bond_potential_energy(particles_at_time_slice),
+        horizontal_momentum: 0.0,
+        vertical_momentum: 0.0,
+        angular_momentum: 0.0,
+    };
+    let source_particles: std::vec::Vec<_> = particles_at_time_slice
+        .iter()
+        .filter(|current_particle| !current_particle.read_intrinsics().is_tracer)
+        .collect();
+    for (particle_index, current_particle) in source_particles.iter().enumerate() {
+        let inertial_mass = current_particle.read_intrinsics().inertial_mass.0;
+        let current_variables = current_particle.read_variables();
+        let horizontal_position = current_variables.position_vector.horizontal_component.0;
+        let vertical_position = current_variables.position_vector.vertical_component.0;
+        let horizontal_velocity = current_variables.velocity_vector.horizontal_component.0;
+        let vertical_velocity = current_variables.velocity_vector.vertical_component.0;
+        frame_diagnostics.kinetic_energy += 0.5
+            * inertial_mass
+            * ((horizontal_velocity * horizontal_velocity)
+                + (vertical_velocity * vertical_velocity));
+        frame_diagnostics.horizontal_momentum += inertial_mass * horizontal_velocity;
+        frame_diagnostics.vertical_momentum += inertial_mass * vertical_velocity;
+        frame_diagnostics.angular_momentum += inertial_mass
+            * ((horizontal_position * vertical_velocity)
+                - (vertical_position * horizontal_velocity));
+        frame_diagnostics.potential_energy +=
+            force_law.external_potential_energy_of_particle(*current_particle);
+        for other_particle in source_particles[(particle_index + 1)..].iter() {
+            frame_diagnostics.potential_energy +=
+                force_law.potential_energy_of_pair(*current_particle, *other_particle);
+        }
+    }
+    frame_diagnostics
+}
+
+/// In two dimensions each particle has two degrees of freedom, so at equilibrium the mean kinetic
+/// energy per particle is the temperature, in units where Boltzmann's constant is 1.
+pub fn kinetic_temperature(particles_at_time_slice: &[impl ParticleRepresentation]) -> f64 {
+    if particles_at_time_slice.is_empty() {
+        return 0.0;
+    }
+    let total_kinetic_energy: f64 = particles_at_time_slice
+        .iter()
+        .map(|current_particle| {
+            let velocity_vector = &current_particle.read_variables().velocity_vector;
+            0.5 * current_particle.read_intrinsics().inertial_mass.0
+                * ((velocity_vector.horizontal_component.0
+                    * velocity_vector.horizontal_component.0)
+                    + (velocity_vector.vertical_component.0 * velocity_vector.vertical_component.0))
+        })
+        .sum();
+    total_kinetic_energy / (particles_at_time_slice.len() as f64)
+}
+
+/// The drift is relative to the sum of the magnitudes of the initial kinetic and potential
+/// energies rather than to the initial total energy, since the total energy of a bound system can
+/// be zero or close to it while both parts are large. If both parts were initially zero, the
+/// absolute drift is given instead.
+pub fn relative_energy_drift(
+    initial_diagnostics: &FrameDiagnostics,
+    current_diagnostics: &FrameDiagnostics,
+) -> f64 {
+    let absolute_drift =
+        (current_diagnostics.total_energy() - initial_diagnostics.total_energy()).abs();
+    let energy_scale =
+        initial_diagnostics.kinetic_energy.abs() + initial_diagnostics.potential_energy.abs();
+    if energy_scale > 0.0 {
+        absolute_drift / energy_scale
+    } else {
+        absolute_drift
+    }
+}
+
+/// This passes on the frames of the wrapped sequence unchanged, appending the diagnostics of each
+/// frame to the given vector as the frame is passed on.
+pub struct DiagnosedSequence<'a, FrameSequence, ForceLaw> {
+    frame_sequence: FrameSequence,
+    force_law: &'a ForceLaw,
+    recorded_diagnostics: Option<&'a mut std::vec::Vec<FrameDiagnostics>>,
+}
+
+/// Without a vector to record into, the frames are just passed on, so that timing the evolution is
+/// not slowed down by calculating the potential energy of every pair of particles.
+pub fn record_each_frame<'a, FrameSequence, ForceLaw>(
+    frame_sequence: FrameSequence,
+    force_law: &'a ForceLaw,
+    recorded_diagnostics: Option<&'a mut std::vec::Vec<FrameDiagnostics>>,
+) -> DiagnosedSequence<'a, FrameSequence, ForceLaw> {
+    DiagnosedSequence {
+        frame_sequence,
+        force_law,
+        recorded_diagnostics,
+    }
+}
+
+impl<'a, FrameSequence, ForceLaw, ParticleIterator> std::iter::Iterator
+    for DiagnosedSequence<'a, FrameSequence, ForceLaw>
+where
+    ForceLaw: PairwiseForceLaw,
+    FrameSequence: std::iter::Iterator<Item = Result<ParticleIterator, Box<dyn std::error::Error>>>,
+    ParticleIterator: std::iter::Iterator + Clone,
+    ParticleIterator::Item: ParticleRepresentation,
+{
+    type Item = Result<ParticleIterator, Box<dyn std::error::Error>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let time_slice = self.frame_sequence.next()?;
+        if let (Some(recorded_diagnostics), Ok(particles_at_time_slice)) =
+            (self.recorded_diagnostics.as_mut(), &time_slice)
+        {
+            let copied_particles: std::vec::Vec<data_structure::particle::BasicIndividual> =
+                particles_at_time_slice
+                    .clone()
+                    .map(|particle_representation| {
+                        data_structure::particle::create_individual_from_representation(
+                            &particle_representation,
+                        )
+                    })
+                    .collect();
+            recorded_diagnostics.push(calculate_for_time_slice(self.force_law, &copied_particles));
+        }
+        Some(time_slice)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.frame_sequence.size_hint()
+    }
+}
+
+impl<'a, FrameSequence, ForceLaw, ParticleIterator> std::iter::ExactSizeIterator
+    for DiagnosedSequence<'a, FrameSequence, ForceLaw>
+where
+    ForceLaw: PairwiseForceLaw,
+    FrameSequence:
+        std::iter::ExactSizeIterator<Item = Result<ParticleIterator, Box<dyn std::error::Error>>>,
+    ParticleIterator: std::iter::Iterator + Clone,
+    ParticleIterator::Item: ParticleRepresentation,
+{
+    fn len(&self) -> usize {
+        self.frame_sequence.len()
+    }
+}
+
+/// The time series is written as comma-separated values with a header row, using the debug
+/// formatting of f64 so that the numbers read back exactly.
+pub fn write_time_series(
+    recorded_diagnostics: &[FrameDiagnostics],
+    milliseconds_per_time_slice: u16,
+    output_writer: &mut impl std::io::Write,
+) -> Result<(), Box<dyn std::error::Error>> {
+    writeln!(
+        output_writer,
+        "{},{},{},{},{},{},{},{}",
+        FRAME_INDEX_LABEL,
+        TIME_IN_SECONDS_LABEL,
+        KINETIC_ENERGY_LABEL,
+        POTENTIAL_ENERGY_LABEL,
+        TOTAL_ENERGY_LABEL,
+        HORIZONTAL_MOMENTUM_LABEL,
+        VERTICAL_MOMENTUM_LABEL,
+        ANGULAR_MOMENTUM_LABEL
+    )?;
+    let seconds_per_time_slice =
+        (milliseconds_per_time_slice as f64) * configuration_parsing::SECONDS_PER_MILLISECOND;
+    for (frame_index, frame_diagnostics) in recorded_diagnostics.iter().enumerate() {
+        writeln!(
+            output_writer,
+            "{},{:?},{:?},{:?},{:?},{:?},{:?},{:?}",
+            frame_index,
+            (frame_index as f64) * seconds_per_time_slice,
+            frame_diagnostics.kinetic_energy,
+            frame_diagnostics.potential_energy,
+            frame_diagnostics.total_energy(),
+            frame_diagnostics.horizontal_momentum,
+            frame_diagnostics.vertical_momentum,
+            frame_diagnostics.angular_momentum
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_test_particle(
+        inertial_mass: f64,
+        horizontal_position: f64,
+        vertical_position: f64,
+        horizontal_velocity: f64,
+        vertical_velocity: f64,
+    ) -> data_structure::particle::BasicIndividual {
+        super::super::test_functions::new_test_particle(
+            inertial_mass,
+            2.0,
+            3.0,
+            (horizontal_position, vertical_position),
+            (horizontal_velocity, vertical_velocity),
+        )
+    }
+
+    fn new_test_force_law(
+        dead_zone_radius: f64,
+    ) -> Result<super::super::force_law::InverseSquaredAndFourth, String> {
+        super::super::force_law::InverseSquaredAndFourth::new_from_configuration(
+            &configuration_parsing::EvolutionConfiguration {
+                inverse_squared_coupling: -1.0,
+                inverse_fourth_coupling: 0.5,
+                milliseconds_per_time_slice: 100,
+                ..super::super::test_functions::create_test_evolution_configuration(
+                    3,
+                    dead_zone_radius,
+                )
+            },
+        )
+        .map_err(|construction_error| format!("Force law error: {}", construction_error))
+    }
+
+    fn compare_diagnostics(
+        expected_diagnostics: &FrameDiagnostics,
+        actual_diagnostics: &FrameDiagnostics,
+    ) -> Result<(), String> {
+        let expected_values = [
+            expected_diagnostics.kinetic_energy,
+            expected_diagnostics.potential_energy,
+            expected_diagnostics.horizontal_momentum,
+            expected_diagnostics.vertical_momentum,
+            expected_diagnostics.angular_momentum,
+        ];
+        let actual_values = [
+            actual_diagnostics.kinetic_energy,
+            actual_diagnostics.potential_energy,
+            actual_diagnostics.horizontal_momentum,
+            actual_diagnostics.vertical_momentum,
+            actual_diagnostics.angular_momentum,
+        ];
+        if expected_values
+            .iter()
+            .zip(actual_values.iter())
+            .all(|(expected_value, actual_value)| {
+                data_structure::comparison::within_relative_tolerance(
+                    *expected_value,
+                    *actual_value,
+                    0.000001,
+                )
+            })
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected {:?}, actually {:?}",
+                expected_diagnostics, actual_diagnostics
+            ))
+        }
+    }
+
+    #[test]
+    fn check_diagnostics_of_pair() -> Result<(), String> {
+        let test_particles = vec![
+            new_test_particle(2.0, 0.0, 1.0, 3.0, 0.0),
+            new_test_particle(1.0, 2.0, 1.0, 0.0, -4.0),
+        ];
+        // The separation is 2, so the inverse-square part is -1 * 2 * 2 / 2 and the inverse-fourth
+        // part is 0.5 * 3 * 3 / (3 * 8).
+        let expected_diagnostics = FrameDiagnostics {
+            kinetic_energy: 9.0 + 8.0,
+            potential_energy: -2.0 + 0.1875,
+            horizontal_momentum: 6.0,
+            vertical_momentum: -4.0,
+            angular_momentum: -6.0 - 8.0,
+        };
+        compare_diagnostics(
+            &expected_diagnostics,
+            &calculate_for_time_slice(&new_test_force_law(0.1)?, &test_particles),
+        )
+    }
+
+    #[test]
+    fn check_potential_is_constant_within_dead_zone() -> Result<(), String> {
+        let force_law = new_test_force_law(1.0)?;
+        let first_particle = new_test_particle(1.0, 0.0, 0.0, 0.0, 0.0);
+        let on_edge = force_law
+            .potential_energy_of_pair(&first_particle, &new_test_particle(1.0, 1.0, 0.0, 0.0, 0.0));
+        let within_dead_zone = force_law.potential_energy_of_pair(
+            &first_particle,
+            &new_test_particle(1.0, 0.0, 0.25, 0.0, 0.0),
+        );
+        if within_dead_zone == on_edge {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected {} within the dead zone, actually {}",
+                on_edge, within_dead_zone
+            ))
+        }
+    }
+
+    #[test]
+    fn check_recorded_sequence_is_unchanged() -> Result<(), String> {
+        let force_law = new_test_force_law(0.1)?;
+        let test_frames = vec![
+            vec![
+                new_test_particle(1.0, 0.0, 0.0, 1.0, 0.0),
+                new_test_particle(1.0, 1.0, 0.0, 0.0, 0.0),
+            ],
+            vec![
+                new_test_particle(1.0, 0.5, 0.0, 1.0, 0.0),
+                new_test_particle(1.0, 1.5, 0.0, 0.0, 1.0),
+            ],
+        ];
+        let frame_sequence = test_frames.clone().into_iter().map(
+            |test_frame| -> Result<_, Box<dyn std::error::Error>> { Ok(test_frame.into_iter()) },
+        );
+        let mut recorded_diagnostics = vec![];
+        let passed_frames: std::vec::Vec<std::vec::Vec<data_structure::particle::BasicIndividual>> =
+            record_each_frame(frame_sequence, &force_law, Some(&mut recorded_diagnostics))
+                .map(|time_slice| time_slice.map(|particles| particles.collect()))
+                .collect::<Result<_, _>>()
+                .map_err(|frame_error| format!("Unexpected error: {}", frame_error))?;
+        // The particles do not implement PartialEq, but their debug text is exact.
+        if format!("{:?}", passed_frames) != format!("{:?}", test_frames) {
+            return Err(format!(
+                "Expected {:?}, passed on {:?}",
+                test_frames, passed_frames
+            ));
+        }
+        if recorded_diagnostics.len() != test_frames.len() {
+            return Err(format!(
+                "Expected {} recorded diagnostics, actually {:?}",
+                test_frames.len(),
+                recorded_diagnostics
+            ));
+        }
+        for (test_frame, frame_diagnostics) in test_frames.iter().zip(recorded_diagnostics.iter()) {
+            compare_diagnostics(
+                &calculate_for_time_slice(&force_law, test_frame),
+                frame_diagnostics,
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,1780 @@
+/// This module provides the trait for the law of the force which each pair of particles exerts on
+/// each other, so that the evolvers can be used with any such law, along with the original law of
+/// a sum of an inverse-squared force and an inverse-fourth force between the charges of the
+/// particles, the Lennard-Jones law, and the Yukawa law, with their parameters and softening given
+/// by the evolution configuration. ConfiguredForceLaw is whichever of these the configuration
+/// selects, MinimumImage wraps a law to use the nearest images of the particles under periodic
+/// boundary conditions, InExternalFields wraps a law to add the external fields of the
+/// configuration, and WithSpringBonds wraps a law to add the spring bonds between particles.
+use super::ForceVector;
+use super::ParticleRepresentation;
+use configuration_parsing::ForceLawConfiguration;
+use configuration_parsing::SofteningKernel;
+
+/// The force on the second particle from the first is always taken to be the negative of the
+/// force on the first particle from the second, so that momentum is conserved. The law must be
+/// Sync as the forces may be calculated for many pairs in parallel.
+pub trait PairwiseForceLaw: Sync {
+    /// This creates the law with the parameters given by the configuration, returning an error if
+    /// the configuration is not valid for the law.
+    fn new_from_configuration(
+        evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+    ) -> Result<Self, Box<dyn std::error::Error>>
+    where
+        Self: Sized;
+
+    fn force_on_first_particle_from_second_particle(
+        &self,
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+    ) -> ForceVector;
+
+    /// The potential energy for the pair is the integral of the force felt by one of the particles
+    /// from their separation out to infinity, so that it is zero for particles infinitely far
+    /// apart.
+    fn potential_energy_of_pair(
+        &self,
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+    ) -> f64;
+
+    /// A law which is entirely a sum of inverse-power terms can give them here, so that the
+    /// Barnes-Hut approximation can treat distant clusters of particles as single particles. The
+    /// forces of any other law are always summed over every pair.
+    fn inverse_power_terms(&self) -> Option<&[InversePowerTerm]> {
+        None
+    }
+
+    /// A law whose forces between the particles are those of the original law with the same
+    /// couplings for every pair gives that law here, so that collections which store each
+    /// component of the particles in its own array can sum the forces directly over the arrays.
+    fn uniform_inverse_squared_and_fourth(&self) -> Option<&InverseSquaredAndFourth> {
+        None
+    }
+
+    /// A law which includes fields from outside the collection of particles returns true here, so
+    /// that the evolvers only add the external force to each particle when there is one.
+    fn has_external_fields(&self) -> bool {
+        false
+    }
+
+    fn external_force_on_particle(&self, _particle: &impl ParticleRepresentation) -> ForceVector {
+        super::zero_force()
+    }
+
+    /// This is the potential energy of the particle in the external fields, which is counted once
+    /// per particle rather than once per pair.
+    fn external_potential_energy_of_particle(
+        &self,
+        _particle: &impl ParticleRepresentation,
+    ) -> f64 {
+        0.0
+    }
+
+    /// A law which includes bonds between particles given by their indices returns true here, so
+    /// that the evolvers only collect the particles in order for the bond forces when there are
+    /// bonds.
+    fn has_spring_bonds(&self) -> bool {
+        false
+    }
+
+    /// The forces of the bonds are added to the given forces, which are in the same order as the
+    /// particles.
+    fn add_spring_bond_forces(
+        &self,
+        _particles: &[impl ParticleRepresentation],
+        _bond_forces: &mut [ForceVector],
+    ) {
+    }
+
+    fn spring_bond_potential_energy(&self, _particles: &[impl ParticleRepresentation]) -> f64 {
+        0.0
+    }
+}
+
+/// This describes a force going as the coupling constant times a charge of each particle over the
+/// separation to some power, softened by the given kernel, where the charges of a cluster of
+/// particles add up.
+#[derive(Clone, Copy, Debug)]
+pub struct InversePowerTerm {
+    pub coupling_constant: f64,
+    pub power_of_separation: i32,
+    pub softening_kernel: SofteningKernel,
+    pub softening_length: f64,
+    pub charge_of_particle: fn(&data_structure::particle::IntrinsicPart) -> f64,
+}
+
+fn get_inverse_squared_charge(
+    particle_intrinsics: &data_structure::particle::IntrinsicPart,
+) -> f64 {
+    particle_intrinsics.inverse_squared_charge.0
+}
+
+fn get_inverse_fourth_charge(particle_intrinsics: &data_structure::particle::IntrinsicPart) -> f64 {
+    particle_intrinsics.inverse_fourth_charge.0
+}
+
+/// This is the original law of the evolvers, using the couplings, dead zone radius, and softening
+/// kernel of the evolution configuration. If the configuration has a table of interactions between
+/// species, the couplings of each pair come from the table, and the Barnes-Hut approximation
+/// cannot be used, as the charges of a cluster of particles of different species do not simply add
+/// up.
+#[derive(Clone, Debug)]
+pub struct InverseSquaredAndFourth {
+    dead_zone_radius: f64,
+    softening_kernel: SofteningKernel,
+    inverse_squared_coupling: f64,
+    inverse_fourth_coupling: f64,
+    species_interactions: Option<configuration_parsing::SpeciesInteractions>,
+    inverse_power_terms: [InversePowerTerm; 2],
+}
+
+impl InverseSquaredAndFourth {
+    fn couplings_of_pair(
+        &self,
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+    ) -> (f64, f64) {
+        match &self.species_interactions {
+            Some(species_interactions) => {
+                let first_species = first_particle.read_intrinsics().species_index;
+                let second_species = second_particle.read_intrinsics().species_index;
+                (
+                    species_interactions.inverse_squared_coupling(first_species, second_species),
+                    species_interactions.inverse_fourth_coupling(first_species, second_species),
+                )
+            }
+            None => (self.inverse_squared_coupling, self.inverse_fourth_coupling),
+        }
+    }
+
+    /// This returns the magnitude of the force divided by the separation, so that the separation
+    /// vector can be multiplied directly, given the couplings of the pair including the charges of
+    /// both particles and the square of the separation.
+    fn force_magnitude_over_separation(
+        &self,
+        inverse_squared_coupling: f64,
+        inverse_fourth_coupling: f64,
+        squared_separation: f64,
+    ) -> f64 {
+        match self.softening_kernel {
+            SofteningKernel::DeadZone => dead_zone_force_magnitude_over_separation(
+                self.dead_zone_radius * self.dead_zone_radius,
+                inverse_squared_coupling,
+                inverse_fourth_coupling,
+                squared_separation,
+            ),
+            softening_kernel => softened_force_magnitude_over_separation(
+                softening_kernel,
+                self.dead_zone_radius,
+                inverse_squared_coupling,
+                inverse_fourth_coupling,
+                squared_separation,
+            ),
+        }
+    }
+
+    /// This sets the forces directly over the arrays of a collection which stores each component
+    /// of the particles in its own array, returning false if the collection does not. The kernel
+    /// is chosen before the loop over the pairs, so that the loop can be vectorised.
+    pub fn set_forces_over_arrays(
+        &self,
+        particles_with_forces: &mut impl data_structure::collection::SingleAndPairwiseFinite,
+    ) -> bool {
+        match self.softening_kernel {
+            SofteningKernel::DeadZone => {
+                let squared_dead_zone_radius = self.dead_zone_radius * self.dead_zone_radius;
+                particles_with_forces.set_forces_from_charges_and_separations(
+                    self.inverse_squared_coupling,
+                    self.inverse_fourth_coupling,
+                    &|inverse_squared_coupling, inverse_fourth_coupling, squared_separation| {
+                        dead_zone_force_magnitude_over_separation(
+                            squared_dead_zone_radius,
+                            inverse_squared_coupling,
+                            inverse_fourth_coupling,
+                            squared_separation,
+                        )
+                    },
+                )
+            }
+            softening_kernel => {
+                let softening_length = self.dead_zone_radius;
+                particles_with_forces.set_forces_from_charges_and_separations(
+                    self.inverse_squared_coupling,
+                    self.inverse_fourth_coupling,
+                    &|inverse_squared_coupling, inverse_fourth_coupling, squared_separation| {
+                        softened_force_magnitude_over_separation(
+                            softening_kernel,
+                            softening_length,
+                            inverse_squared_coupling,
+                            inverse_fourth_coupling,
+                            squared_separation,
+                        )
+                    },
+                )
+            }
+        }
+    }
+}
+
+/// We combine the sum of the two kinds of force with an additional 1/r so that we can multiply the
+/// separation vector directly.
+fn dead_zone_force_magnitude_over_separation(
+    squared_dead_zone_radius: f64,
+    inverse_squared_coupling: f64,
+    inverse_fourth_coupling: f64,
+    squared_separation: f64,
+) -> f64 {
+    let inverse_separation = data_structure::position::SquaredSeparationUnit(squared_separation)
+        .to_inverse_square_root();
+
+    let inverse_squared_separation =
+        inverse_separation.get_value() * inverse_separation.get_value();
+    let inverse_squared_force = inverse_squared_coupling * inverse_squared_separation;
+    let inverse_fourth_force =
+        inverse_fourth_coupling * inverse_squared_separation * inverse_squared_separation;
+    let unsoftened_magnitude_over_separation =
+        (inverse_squared_force + inverse_fourth_force) * inverse_separation.get_value();
+
+    // The magnitude is calculated even within the dead zone and then discarded, as a branch around
+    // the calculation would stop loops over many pairs being vectorised.
+    if squared_dead_zone_radius > squared_separation {
+        0.0
+    } else {
+        unsoftened_magnitude_over_separation
+    }
+}
+
+fn softened_force_magnitude_over_separation(
+    softening_kernel: SofteningKernel,
+    softening_length: f64,
+    inverse_squared_coupling: f64,
+    inverse_fourth_coupling: f64,
+    squared_separation: f64,
+) -> f64 {
+    (inverse_squared_coupling
+        * super::softening::force_magnitude_over_separation(
+            softening_kernel,
+            softening_length,
+            2,
+            squared_separation,
+        ))
+        + (inverse_fourth_coupling
+            * super::softening::force_magnitude_over_separation(
+                softening_kernel,
+                softening_length,
+                4,
+                squared_separation,
+            ))
+}
+
+impl PairwiseForceLaw for InverseSquaredAndFourth {
+    fn new_from_configuration(
+        evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        if evolution_configuration.force_law != ForceLawConfiguration::InverseSquaredAndFourth {
+            return Err(Box::new(super::ParameterError::new(&format!(
+                "Cannot create the inverse-squared and inverse-fourth law from {:?}",
+                evolution_configuration.force_law
+            ))));
+        }
+        if evolution_configuration.species_interactions.is_some()
+            && evolution_configuration.barnes_hut_opening_angle.is_some()
+        {
+            return Err(Box::new(super::ParameterError::new(
+                "The Barnes-Hut approximation cannot be used with a table of interactions",
+            )));
+        }
+        let dead_zone_radius = evolution_configuration.dead_zone_radius;
+        let softening_kernel = evolution_configuration.softening_kernel;
+        let inverse_squared_coupling = evolution_configuration.inverse_squared_coupling;
+        let inverse_fourth_coupling = evolution_configuration.inverse_fourth_coupling;
+        Ok(Self {
+            dead_zone_radius,
+            softening_kernel,
+            inverse_squared_coupling,
+            inverse_fourth_coupling,
+            species_interactions: evolution_configuration.species_interactions.clone(),
+            inverse_power_terms: [
+                InversePowerTerm {
+                    coupling_constant: inverse_squared_coupling,
+                    power_of_separation: 2,
+                    softening_kernel,
+                    softening_length: dead_zone_radius,
+                    charge_of_particle: get_inverse_squared_charge,
+                },
+                InversePowerTerm {
+                    coupling_constant: inverse_fourth_coupling,
+                    power_of_separation: 4,
+                    softening_kernel,
+                    softening_length: dead_zone_radius,
+                    charge_of_particle: get_inverse_fourth_charge,
+                },
+            ],
+        })
+    }
+
+    fn force_on_first_particle_from_second_particle(
+        &self,
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+    ) -> ForceVector {
+        let separation_vector = first_particle.read_variables().position_vector
+            - second_particle.read_variables().position_vector;
+        let (inverse_squared_constant, inverse_fourth_constant) =
+            self.couplings_of_pair(first_particle, second_particle);
+        let inverse_squared_coupling = inverse_squared_constant
+            * first_particle.read_intrinsics().inverse_squared_charge.0
+            * second_particle.read_intrinsics().inverse_squared_charge.0;
+        let inverse_fourth_coupling = inverse_fourth_constant
+            * first_particle.read_intrinsics().inverse_fourth_charge.0
+            * second_particle.read_intrinsics().inverse_fourth_charge.0;
+        let force_magnitude_over_separation = self.force_magnitude_over_separation(
+            inverse_squared_coupling,
+            inverse_fourth_coupling,
+            data_structure::position::square_separation_vector(&separation_vector).0,
+        );
+        super::scale_separation_to_force(&separation_vector, force_magnitude_over_separation)
+    }
+
+    fn potential_energy_of_pair(
+        &self,
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+    ) -> f64 {
+        let squared_separation = data_structure::position::square_separation_vector(
+            &(first_particle.read_variables().position_vector
+                - second_particle.read_variables().position_vector),
+        )
+        .0;
+        let (inverse_squared_constant, inverse_fourth_constant) =
+            self.couplings_of_pair(first_particle, second_particle);
+
+        let inverse_fourth_part = inverse_fourth_constant
+            * first_particle.read_intrinsics().inverse_fourth_charge.0
+            * second_particle.read_intrinsics().inverse_fourth_charge.0
+            * super::softening::potential_per_coupling(
+                self.softening_kernel,
+                self.dead_zone_radius,
+                4,
+                squared_separation,
+            );
+        let inverse_square_part = inverse_squared_constant
+            * first_particle.read_intrinsics().inverse_squared_charge.0
+            * second_particle.read_intrinsics().inverse_squared_charge.0
+            * super::softening::potential_per_coupling(
+                self.softening_kernel,
+                self.dead_zone_radius,
+                2,
+                squared_separation,
+            );
+        inverse_fourth_part + inverse_square_part
+    }
+
+    fn inverse_power_terms(&self) -> Option<&[InversePowerTerm]> {
+        Some(&self.inverse_power_terms)
+    }
+
+    fn uniform_inverse_squared_and_fourth(&self) -> Option<&InverseSquaredAndFourth> {
+        match self.species_interactions {
+            Some(_) => None,
+            None => Some(self),
+        }
+    }
+}
+
+/// Laws which are not a single inverse power of the separation are softened by evaluating the
+/// unsoftened force and potential at an effective separation. The dead zone does not let the
+/// effective separation go below the dead zone radius, with no force within it, so the potential
+/// is constant there. The Plummer kernel adds the square of the softening length to the square of
+/// the separation. The spline kernel is only defined for inverse powers.
+#[derive(Clone, Copy, Debug)]
+enum SeparationSoftening {
+    DeadZone(f64),
+    Plummer(f64),
+}
+
+impl SeparationSoftening {
+    fn new(
+        evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        if evolution_configuration.barnes_hut_opening_angle.is_some() {
+            return Err(Box::new(super::ParameterError::new(&format!(
+                "The Barnes-Hut approximation cannot be used with {:?}",
+                evolution_configuration.force_law
+            ))));
+        }
+        match evolution_configuration.softening_kernel {
+            SofteningKernel::DeadZone => Ok(SeparationSoftening::DeadZone(
+                evolution_configuration.dead_zone_radius,
+            )),
+            SofteningKernel::Plummer => Ok(SeparationSoftening::Plummer(
+                evolution_configuration.dead_zone_radius,
+            )),
+            SofteningKernel::Spline => Err(Box::new(super::ParameterError::new(&format!(
+                "The spline softening kernel cannot be used with {:?}",
+                evolution_configuration.force_law
+            )))),
+        }
+    }
+
+    /// This returns the effective separation, or None if there is no force at the given squared
+    /// separation.
+    fn effective_separation_for_force(&self, squared_separation: f64) -> Option<f64> {
+        match self {
+            SeparationSoftening::DeadZone(dead_zone_radius) => {
+                if squared_separation < (dead_zone_radius * dead_zone_radius) {
+                    None
+                } else {
+                    Some(squared_separation.sqrt())
+                }
+            }
+            SeparationSoftening::Plummer(softening_length) => {
+                Some((squared_separation + (softening_length * softening_length)).sqrt())
+            }
+        }
+    }
+
+    fn effective_separation_for_potential(&self, squared_separation: f64) -> f64 {
+        match self {
+            SeparationSoftening::DeadZone(dead_zone_radius) => {
+                squared_separation.sqrt().max(*dead_zone_radius)
+            }
+            SeparationSoftening::Plummer(softening_length) => {
+                (squared_separation + (softening_length * softening_length)).sqrt()
+            }
+        }
+    }
+
+    /// The force is given as a function of the effective separation, positive for repulsion, and
+    /// it acts along the separation as the effective separation only depends on the separation.
+    fn force_on_first_particle_from_second_particle(
+        &self,
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+        force_at_separation: impl Fn(f64) -> f64,
+    ) -> ForceVector {
+        let separation_vector = first_particle.read_variables().position_vector
+            - second_particle.read_variables().position_vector;
+        match self.effective_separation_for_force(
+            data_structure::position::square_separation_vector(&separation_vector).0,
+        ) {
+            Some(effective_separation) => super::scale_separation_to_force(
+                &separation_vector,
+                force_at_separation(effective_separation) / effective_separation,
+            ),
+            None => super::zero_force(),
+        }
+    }
+
+    fn potential_energy_of_pair(
+        &self,
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+        potential_at_separation: impl Fn(f64) -> f64,
+    ) -> f64 {
+        potential_at_separation(
+            self.effective_separation_for_potential(
+                data_structure::position::square_separation_vector(
+                    &(first_particle.read_variables().position_vector
+                        - second_particle.read_variables().position_vector),
+                )
+                .0,
+            ),
+        )
+    }
+}
+
+/// This has the same potential for every pair of particles, regardless of their charges, with a
+/// repulsive core within about the length scale and a well of depth equal to the energy scale at
+/// 2^(1/6) times the length scale. With a cutoff, the potential is shifted by its value at the
+/// cutoff so that it goes to zero there, and there is no force beyond the cutoff.
+#[derive(Clone, Debug)]
+pub struct LennardJones {
+    separation_softening: SeparationSoftening,
+    length_scale: f64,
+    energy_scale: f64,
+    cutoff_separation: Option<f64>,
+    potential_at_cutoff: f64,
+}
+
+impl LennardJones {
+    fn unshifted_potential(&self, separation: f64) -> f64 {
+        let sixth_power_ratio = (self.length_scale / separation).powi(6);
+        4.0 * self.energy_scale * ((sixth_power_ratio * sixth_power_ratio) - sixth_power_ratio)
+    }
+
+    fn is_beyond_cutoff(&self, separation: f64) -> bool {
+        match self.cutoff_separation {
+            Some(cutoff_separation) => separation >= cutoff_separation,
+            None => false,
+        }
+    }
+
+    fn force_at_separation(&self, separation: f64) -> f64 {
+        if self.is_beyond_cutoff(separation) {
+            return 0.0;
+        }
+        let sixth_power_ratio = (self.length_scale / separation).powi(6);
+        24.0 * self.energy_scale
+            * ((2.0 * sixth_power_ratio * sixth_power_ratio) - sixth_power_ratio)
+            / separation
+    }
+
+    fn potential_at_separation(&self, separation: f64) -> f64 {
+        if self.is_beyond_cutoff(separation) {
+            0.0
+        } else {
+            self.unshifted_potential(separation) - self.potential_at_cutoff
+        }
+    }
+}
+
+impl PairwiseForceLaw for LennardJones {
+    fn new_from_configuration(
+        evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        match evolution_configuration.force_law {
+            ForceLawConfiguration::LennardJones {
+                length_scale,
+                energy_scale,
+                cutoff_separation,
+            } => {
+                if evolution_configuration.species_interactions.is_some() {
+                    return Err(Box::new(super::ParameterError::new(
+                        "The Lennard-Jones law has no couplings to take from a table of \
+                        interactions",
+                    )));
+                }
+                let mut created_law = Self {
+                    separation_softening: SeparationSoftening::new(evolution_configuration)?,
+                    length_scale,
+                    energy_scale,
+                    cutoff_separation,
+                    potential_at_cutoff: 0.0,
+                };
+                if let Some(cutoff_separation) = cutoff_separation {
+                    created_law.potential_at_cutoff =
+                        created_law.unshifted_potential(cutoff_separation);
+                }
+                Ok(created_law)
+            }
+            other_law => Err(Box::new(super::ParameterError::new(&format!(
+                "Cannot create the Lennard-Jones law from {:?}",
+                other_law
+            )))),
+        }
+    }
+
+    fn force_on_first_particle_from_second_particle(
+        &self,
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+    ) -> ForceVector {
+        self.separation_softening
+            .force_on_first_particle_from_second_particle(
+                first_particle,
+                second_particle,
+                |separation| self.force_at_separation(separation),
+            )
+    }
+
+    fn potential_energy_of_pair(
+        &self,
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+    ) -> f64 {
+        self.separation_softening.potential_energy_of_pair(
+            first_particle,
+            second_particle,
+            |separation| self.potential_at_separation(separation),
+        )
+    }
+}
+
+/// This is an inverse-squared force between the inverse-squared charges of the particles which is
+/// exponentially screened beyond the screening length. If the configuration has a table of
+/// interactions between species, the coupling of each pair is its inverse-squared coupling from
+/// the table.
+#[derive(Clone, Debug)]
+pub struct Yukawa {
+    separation_softening: SeparationSoftening,
+    coupling_constant: f64,
+    species_interactions: Option<configuration_parsing::SpeciesInteractions>,
+    screening_length: f64,
+}
+
+impl Yukawa {
+    fn coupling_of_pair(
+        &self,
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+    ) -> f64 {
+        let coupling_constant = match &self.species_interactions {
+            Some(species_interactions) => species_interactions.inverse_squared_coupling(
+                first_particle.read_intrinsics().species_index,
+                second_particle.read_intrinsics().species_index,
+            ),
+            None => self.coupling_constant,
+        };
+        coupling_constant
+            * first_particle.read_intrinsics().inverse_squared_charge.0
+            * second_particle.read_intrinsics().inverse_squared_charge.0
+    }
+}
+
+impl PairwiseForceLaw for Yukawa {
+    fn new_from_configuration(
+        evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        match evolution_configuration.force_law {
+            ForceLawConfiguration::Yukawa { screening_length } => Ok(Self {
+                separation_softening: SeparationSoftening::new(evolution_configuration)?,
+                coupling_constant: evolution_configuration.inverse_squared_coupling,
+                species_interactions: evolution_configuration.species_interactions.clone(),
+                screening_length,
+            }),
+            other_law => Err(Box::new(super::ParameterError::new(&format!(
+                "Cannot create the Yukawa law from {:?}",
+                other_law
+            )))),
+        }
+    }
+
+    fn force_on_first_particle_from_second_particle(
+        &self,
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+    ) -> ForceVector {
+        let pair_coupling = self.coupling_of_pair(first_particle, second_particle);
+        self.separation_softening
+            .force_on_first_particle_from_second_particle(
+                first_particle,
+                second_particle,
+                |separation| {
+                    pair_coupling
+                        * (-separation / self.screening_length).exp()
+                        * ((1.0 / (separation * separation))
+                            + (1.0 / (self.screening_length * separation)))
+                },
+            )
+    }
+
+    fn potential_energy_of_pair(
+        &self,
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+    ) -> f64 {
+        let pair_coupling = self.coupling_of_pair(first_particle, second_particle);
+        self.separation_softening.potential_energy_of_pair(
+            first_particle,
+            second_particle,
+            |separation| pair_coupling * (-separation / self.screening_length).exp() / separation,
+        )
+    }
+}
+
+/// This is the default law of the evolvers, so that the law can be chosen in the configuration
+/// without choosing between different types of evolver.
+#[derive(Clone, Debug)]
+pub enum ConfiguredForceLaw {
+    InverseSquaredAndFourth(InverseSquaredAndFourth),
+    LennardJones(LennardJones),
+    Yukawa(Yukawa),
+}
+
+impl PairwiseForceLaw for ConfiguredForceLaw {
+    fn new_from_configuration(
+        evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        match evolution_configuration.force_law {
+            ForceLawConfiguration::InverseSquaredAndFourth => {
+                Ok(ConfiguredForceLaw::InverseSquaredAndFourth(
+                    InverseSquaredAndFourth::new_from_configuration(evolution_configuration)?,
+                ))
+            }
+            ForceLawConfiguration::LennardJones { .. } => Ok(ConfiguredForceLaw::LennardJones(
+                LennardJones::new_from_configuration(evolution_configuration)?,
+            )),
+            ForceLawConfiguration::Yukawa { .. } => Ok(ConfiguredForceLaw::Yukawa(
+                Yukawa::new_from_configuration(evolution_configuration)?,
+            )),
+        }
+    }
+
+    fn force_on_first_particle_from_second_particle(
+        &self,
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+    ) -> ForceVector {
+        match self {
+            ConfiguredForceLaw::InverseSquaredAndFourth(force_law) => force_law
+                .force_on_first_particle_from_second_particle(first_particle, second_particle),
+            ConfiguredForceLaw::LennardJones(force_law) => force_law
+                .force_on_first_particle_from_second_particle(first_particle, second_particle),
+            ConfiguredForceLaw::Yukawa(force_law) => force_law
+                .force_on_first_particle_from_second_particle(first_particle, second_particle),
+        }
+    }
+
+    fn potential_energy_of_pair(
+        &self,
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+    ) -> f64 {
+        match self {
+            ConfiguredForceLaw::InverseSquaredAndFourth(force_law) => {
+                force_law.potential_energy_of_pair(first_particle, second_particle)
+            }
+            ConfiguredForceLaw::LennardJones(force_law) => {
+                force_law.potential_energy_of_pair(first_particle, second_particle)
+            }
+            ConfiguredForceLaw::Yukawa(force_law) => {
+                force_law.potential_energy_of_pair(first_particle, second_particle)
+            }
+        }
+    }
+
+    fn inverse_power_terms(&self) -> Option<&[InversePowerTerm]> {
+        match self {
+            ConfiguredForceLaw::InverseSquaredAndFourth(force_law) => {
+                force_law.inverse_power_terms()
+            }
+            ConfiguredForceLaw::LennardJones(force_law) => force_law.inverse_power_terms(),
+            ConfiguredForceLaw::Yukawa(force_law) => force_law.inverse_power_terms(),
+        }
+    }
+
+    fn uniform_inverse_squared_and_fourth(&self) -> Option<&InverseSquaredAndFourth> {
+        match self {
+            ConfiguredForceLaw::InverseSquaredAndFourth(force_law) => {
+                force_law.uniform_inverse_squared_and_fourth()
+            }
+            ConfiguredForceLaw::LennardJones(_) | ConfiguredForceLaw::Yukawa(_) => None,
+        }
+    }
+}
+
+/// With periodic boundary conditions, each particle interacts with the nearest image of every other
+/// particle, which is only correct if the force is negligible beyond half the width and half the
+/// height of the box. Without periodic boundary conditions, the wrapped law is used unchanged. The
+/// Barnes-Hut approximation cannot be used with periodic boundary conditions, as the quadtree does
+/// not know about the images.
+#[derive(Clone, Debug)]
+pub struct MinimumImage<ForceLaw> {
+    force_law: ForceLaw,
+    periodic_box_size: Option<(f64, f64)>,
+}
+
+impl<ForceLaw> MinimumImage<ForceLaw> {
+    /// This returns a copy of the second particle moved by whole box sizes to be as close as
+    /// possible to the first particle.
+    fn nearest_image_of_second_particle(
+        box_size: (f64, f64),
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+    ) -> data_structure::particle::BasicIndividual {
+        let mut second_image =
+            data_structure::particle::create_individual_from_representation(second_particle);
+        let first_position = &first_particle.read_variables().position_vector;
+        let image_position = &mut second_image.variable_values.position_vector;
+        let (box_width, box_height) = box_size;
+        image_position.horizontal_component.0 -= box_width
+            * ((image_position.horizontal_component.0 - first_position.horizontal_component.0)
+                / box_width)
+                .round();
+        image_position.vertical_component.0 -= box_height
+            * ((image_position.vertical_component.0 - first_position.vertical_component.0)
+                / box_height)
+                .round();
+        second_image
+    }
+}
+
+impl<ForceLaw> PairwiseForceLaw for MinimumImage<ForceLaw>
+where
+    ForceLaw: PairwiseForceLaw,
+{
+    fn new_from_configuration(
+        evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let periodic_box_size = match evolution_configuration.boundary_conditions {
+            Some(configuration_parsing::BoundaryConditions {
+                boundary_kind: configuration_parsing::BoundaryKind::Periodic,
+                right_border_coordinate,
+                upper_border_coordinate,
+                left_border_coordinate,
+                lower_border_coordinate,
+            }) => Some((
+                right_border_coordinate - left_border_coordinate,
+                upper_border_coordinate - lower_border_coordinate,
+            )),
+            _ => None,
+        };
+        if periodic_box_size.is_some() && evolution_configuration.barnes_hut_opening_angle.is_some()
+        {
+            return Err(Box::new(super::ParameterError::new(
+                "The Barnes-Hut approximation cannot be used with periodic boundary conditions",
+            )));
+        }
+        Ok(Self {
+            force_law: ForceLaw::new_from_configuration(evolution_configuration)?,
+            periodic_box_size,
+        })
+    }
+
+    fn force_on_first_particle_from_second_particle(
+        &self,
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+    ) -> ForceVector {
+        match self.periodic_box_size {
+            Some(box_size) => self.force_law.force_on_first_particle_from_second_particle(
+                first_particle,
+                &Self::nearest_image_of_second_particle(box_size, first_particle, second_particle),
+            ),
+            None => self
+                .force_law
+                .force_on_first_particle_from_second_particle(first_particle, second_particle),
+        }
+    }
+
+    fn potential_energy_of_pair(
+        &self,
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+    ) -> f64 {
+        match self.periodic_box_size {
+            Some(box_size) => self.force_law.potential_energy_of_pair(
+                first_particle,
+                &Self::nearest_image_of_second_particle(box_size, first_particle, second_particle),
+            ),
+            None => self
+                .force_law
+                .potential_energy_of_pair(first_particle, second_particle),
+        }
+    }
+
+    fn inverse_power_terms(&self) -> Option<&[InversePowerTerm]> {
+        self.force_law.inverse_power_terms()
+    }
+
+    /// The nearest images are not known to the arrays, so the forces are only summed over the
+    /// arrays without periodic boundary conditions.
+    fn uniform_inverse_squared_and_fourth(&self) -> Option<&InverseSquaredAndFourth> {
+        match self.periodic_box_size {
+            Some(_) => None,
+            None => self.force_law.uniform_inverse_squared_and_fourth(),
+        }
+    }
+
+    fn has_external_fields(&self) -> bool {
+        self.force_law.has_external_fields()
+    }
+
+    fn external_force_on_particle(&self, particle: &impl ParticleRepresentation) -> ForceVector {
+        self.force_law.external_force_on_particle(particle)
+    }
+
+    fn external_potential_energy_of_particle(&self, particle: &impl ParticleRepresentation) -> f64 {
+        self.force_law
+            .external_potential_energy_of_particle(particle)
+    }
+
+    fn has_spring_bonds(&self) -> bool {
+        self.force_law.has_spring_bonds()
+    }
+
+    fn add_spring_bond_forces(
+        &self,
+        particles: &[impl ParticleRepresentation],
+        bond_forces: &mut [ForceVector],
+    ) {
+        self.force_law
+            .add_spring_bond_forces(particles, bond_forces)
+    }
+
+    fn spring_bond_potential_energy(&self, particles: &[impl ParticleRepresentation]) -> f64 {
+        self.force_law.spring_bond_potential_energy(particles)
+    }
+}
+
+/// The fixed sources exert force on the particles through the wrapped law, so they interact with
+/// the nearest image of each particle if the wrapped law uses minimum images. The fields are added
+/// to the forces of the particles after the forces between the particles have been summed, even if
+/// those were approximated by a Barnes-Hut quadtree, so the fixed sources are never approximated.
+#[derive(Clone, Debug)]
+pub struct InExternalFields<ForceLaw> {
+    force_law: ForceLaw,
+    external_fields: Option<configuration_parsing::ExternalFields>,
+    fixed_sources: std::vec::Vec<data_structure::particle::BasicIndividual>,
+}
+
+impl<ForceLaw> InExternalFields<ForceLaw> {
+    /// The mass and color of a fixed source do not matter, as it never moves and is not drawn.
+    fn create_fixed_source_particle(
+        fixed_source: &configuration_parsing::FixedSource,
+    ) -> data_structure::particle::BasicIndividual {
+        data_structure::particle::BasicIndividual {
+            intrinsic_values: data_structure::particle::IntrinsicPart {
+                inertial_mass: data_structure::charge::InertialMassUnit(1.0),
+                inverse_squared_charge: data_structure::charge::InverseSquaredChargeUnit(
+                    fixed_source.inverse_squared_charge,
+                ),
+                inverse_fourth_charge: data_structure::charge::InverseFourthChargeUnit(
+                    fixed_source.inverse_fourth_charge,
+                ),
+                species_index: data_structure::charge::SpeciesIndex(0),
+                collision_radius: data_structure::position::SeparationUnit(0.0),
+                is_tracer: false,
+                color_brightness: data_structure::color::new_triplet(
+                    data_structure::color::RedUnit(0.0),
+                    data_structure::color::GreenUnit(0.0),
+                    data_structure::color::BlueUnit(0.0),
+                ),
+            },
+            variable_values: data_structure::particle::VariablePart {
+                position_vector: data_structure::position::DimensionfulVector {
+                    horizontal_component: data_structure::position::HorizontalUnit(
+                        fixed_source.horizontal_coordinate,
+                    ),
+                    vertical_component: data_structure::position::VerticalUnit(
+                        fixed_source.vertical_coordinate,
+                    ),
+                },
+                velocity_vector: data_structure::velocity::DimensionfulVector {
+                    horizontal_component: data_structure::velocity::HorizontalUnit(0.0),
+                    vertical_component: data_structure::velocity::VerticalUnit(0.0),
+                },
+            },
+        }
+    }
+}
+
+impl<ForceLaw> PairwiseForceLaw for InExternalFields<ForceLaw>
+where
+    ForceLaw: PairwiseForceLaw,
+{
+    fn new_from_configuration(
+        evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let fixed_sources = match &evolution_configuration.external_fields {
+            Some(external_fields) => external_fields
+                .fixed_sources
+                .iter()
+                .map(Self::create_fixed_source_particle)
+                .collect(),
+            None => vec![],
+        };
+        Ok(Self {
+            force_law: ForceLaw::new_from_configuration(evolution_configuration)?,
+            external_fields: evolution_configuration.external_fields.clone(),
+            fixed_sources,
+        })
+    }
+
+    fn force_on_first_particle_from_second_particle(
+        &self,
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+    ) -> ForceVector {
+        self.force_law
+            .force_on_first_particle_from_second_particle(first_particle, second_particle)
+    }
+
+    fn potential_energy_of_pair(
+        &self,
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+    ) -> f64 {
+        self.force_law
+            .potential_energy_of_pair(first_particle, second_particle)
+    }
+
+    fn inverse_power_terms(&self) -> Option<&[InversePowerTerm]> {
+        self.force_law.inverse_power_terms()
+    }
+
+    fn uniform_inverse_squared_and_fourth(&self) -> Option<&InverseSquaredAndFourth> {
+        self.force_law.uniform_inverse_squared_and_fourth()
+    }
+
+    fn has_external_fields(&self) -> bool {
+        self.external_fields.is_some() || self.force_law.has_external_fields()
+    }
+
+    fn external_force_on_particle(&self, particle: &impl ParticleRepresentation) -> ForceVector {
+        let mut external_force = self.force_law.external_force_on_particle(particle);
+        let external_fields = match &self.external_fields {
+            Some(external_fields) => external_fields,
+            None => return external_force,
+        };
+        let inertial_mass = particle.read_intrinsics().inertial_mass.0;
+        external_force.horizontal_component.0 +=
+            inertial_mass * external_fields.horizontal_acceleration;
+        external_force.vertical_component.0 +=
+            inertial_mass * external_fields.vertical_acceleration;
+        for fixed_source in &self.fixed_sources {
+            external_force += self
+                .force_law
+                .force_on_first_particle_from_second_particle(particle, fixed_source);
+        }
+        if let Some(harmonic_trap) = &external_fields.harmonic_trap {
+            let particle_position = &particle.read_variables().position_vector;
+            external_force.horizontal_component.0 -= harmonic_trap.horizontal_spring_constant
+                * (particle_position.horizontal_component.0 - harmonic_trap.horizontal_center);
+            external_force.vertical_component.0 -= harmonic_trap.vertical_spring_constant
+                * (particle_position.vertical_component.0 - harmonic_trap.vertical_center);
+        }
+        external_force
+    }
+
+    fn external_potential_energy_of_particle(&self, particle: &impl ParticleRepresentation) -> f64 {
+        let mut potential_energy = self
+            .force_law
+            .external_potential_energy_of_particle(particle);
+        let external_fields = match &self.external_fields {
+            Some(external_fields) => external_fields,
+            None => return potential_energy,
+        };
+        let particle_position = &particle.read_variables().position_vector;
+        potential_energy -= particle.read_intrinsics().inertial_mass.0
+            * ((external_fields.horizontal_acceleration
+                * particle_position.horizontal_component.0)
+                + (external_fields.vertical_acceleration * particle_position.vertical_component.0));
+        for fixed_source in &self.fixed_sources {
+            potential_energy += self
+                .force_law
+                .potential_energy_of_pair(particle, fixed_source);
+        }
+        if let Some(harmonic_trap) = &external_fields.harmonic_trap {
+            let horizontal_displacement =
+                particle_position.horizontal_component.0 - harmonic_trap.horizontal_center;
+            let vertical_displacement =
+                particle_position.vertical_component.0 - harmonic_trap.vertical_center;
+            potential_energy += 0.5
+                * ((harmonic_trap.horizontal_spring_constant
+                    * horizontal_displacement
+                    * horizontal_displacement)
+                    + (harmonic_trap.vertical_spring_constant
+                        * vertical_displacement
+                        * vertical_displacement));
+        }
+        potential_energy
+    }
+
+    fn has_spring_bonds(&self) -> bool {
+        self.force_law.has_spring_bonds()
+    }
+
+    fn add_spring_bond_forces(
+        &self,
+        particles: &[impl ParticleRepresentation],
+        bond_forces: &mut [ForceVector],
+    ) {
+        self.force_law
+            .add_spring_bond_forces(particles, bond_forces)
+    }
+
+    fn spring_bond_potential_energy(&self, particles: &[impl ParticleRepresentation]) -> f64 {
+        self.force_law.spring_bond_potential_energy(particles)
+    }
+}
+
+/// The bonds are given by the evolution configuration as filled in from the generators of the
+/// initial particles, so they refer to particles by their indices in the collection, which is why
+/// they cannot be used with merging, which removes particles from the collection. With periodic
+/// boundary conditions, each bond pulls on the nearest image of its other particle. The bond forces
+/// are added after the forces of the wrapped law have been summed, so they are never approximated.
+/// A tracer feels the pull of its bonds but exerts none, so a bond between two tracers does
+/// nothing, and a bond with a tracer has no potential energy, as the energy of a tracer is not part
+/// of the energy of the other particles.
+#[derive(Clone, Debug)]
+pub struct WithSpringBonds<ForceLaw> {
+    force_law: ForceLaw,
+    spring_bonds: std::vec::Vec<configuration_parsing::SpringBond>,
+    periodic_box_size: Option<(f64, f64)>,
+}
+
+impl<ForceLaw> WithSpringBonds<ForceLaw> {
+    /// This returns the force on the second particle of the bond, and the first particle feels the
+    /// opposite force. Particles at the same position feel no force from their bond, as it has no
+    /// direction.
+    fn force_on_second_particle(
+        &self,
+        spring_bond: &configuration_parsing::SpringBond,
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+    ) -> ForceVector {
+        let first_variables = first_particle.read_variables();
+        let second_variables = second_particle.read_variables();
+        let separation_vector = super::displacement_to_nearest_image(
+            self.periodic_box_size,
+            &first_variables.position_vector,
+            &second_variables.position_vector,
+        );
+        let separation_components = (
+            separation_vector.horizontal_component.0,
+            separation_vector.vertical_component.0,
+        );
+        let separation = ((separation_components.0 * separation_components.0)
+            + (separation_components.1 * separation_components.1))
+            .sqrt();
+        if separation <= 0.0 {
+            return super::zero_force();
+        }
+        let bond_direction = (
+            separation_components.0 / separation,
+            separation_components.1 / separation,
+        );
+        let separating_speed = ((second_variables.velocity_vector.horizontal_component.0
+            - first_variables.velocity_vector.horizontal_component.0)
+            * bond_direction.0)
+            + ((second_variables.velocity_vector.vertical_component.0
+                - first_variables.velocity_vector.vertical_component.0)
+                * bond_direction.1);
+        let pulling_magnitude = (spring_bond.stiffness * (separation - spring_bond.rest_length))
+            + (spring_bond.damping_coefficient * separating_speed);
+        ForceVector {
+            horizontal_component: data_structure::force::HorizontalUnit(
+                -pulling_magnitude * bond_direction.0,
+            ),
+            vertical_component: data_structure::force::VerticalUnit(
+                -pulling_magnitude * bond_direction.1,
+            ),
+        }
+    }
+}
+
+impl<ForceLaw> PairwiseForceLaw for WithSpringBonds<ForceLaw>
+where
+    ForceLaw: PairwiseForceLaw,
+{
+    fn new_from_configuration(
+        evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        if !evolution_configuration.spring_bonds.is_empty()
+            && evolution_configuration.merging_radius.is_some()
+        {
+            return Err(Box::new(super::ParameterError::new(
+                "Spring bonds cannot be used with a merging radius",
+            )));
+        }
+        Ok(Self {
+            force_law: ForceLaw::new_from_configuration(evolution_configuration)?,
+            spring_bonds: evolution_configuration.spring_bonds.clone(),
+            periodic_box_size: super::get_periodic_box_size(
+                &evolution_configuration.boundary_conditions,
+            ),
+        })
+    }
+
+    fn force_on_first_particle_from_second_particle(
+        &self,
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+    ) -> ForceVector {
+        self.force_law
+            .force_on_first_particle_from_second_particle(first_particle, second_particle)
+    }
+
+    fn potential_energy_of_pair(
+        &self,
+        first_particle: &impl ParticleRepresentation,
+        second_particle: &impl ParticleRepresentation,
+    ) -> f64 {
+        self.force_law
+            .potential_energy_of_pair(first_particle, second_particle)
+    }
+
+    fn inverse_power_terms(&self) -> Option<&[InversePowerTerm]> {
+        self.force_law.inverse_power_terms()
+    }
+
+    fn uniform_inverse_squared_and_fourth(&self) -> Option<&InverseSquaredAndFourth> {
+        self.force_law.uniform_inverse_squared_and_fourth()
+    }
+
+    fn has_external_fields(&self) -> bool {
+        self.force_law.has_external_fields()
+    }
+
+    fn external_force_on_particle(&self, particle: &impl ParticleRepresentation) -> ForceVector {
+        self.force_law.external_force_on_particle(particle)
+    }
+
+    fn external_potential_energy_of_particle(&self, particle: &impl ParticleRepresentation) -> f64 {
+        self.force_law
+            .external_potential_energy_of_particle(particle)
+    }
+
+    fn has_spring_bonds(&self) -> bool {
+        !self.spring_bonds.is_empty() || self.force_law.has_spring_bonds()
+    }
+
+    fn add_spring_bond_forces(
+        &self,
+        particles: &[impl ParticleRepresentation],
+        bond_forces: &mut [ForceVector],
+    ) {
+        self.force_law
+            .add_spring_bond_forces(particles, bond_forces);
+        for spring_bond in &self.spring_bonds {
+            let first_particle = &particles[spring_bond.first_particle_index];
+            let second_particle = &particles[spring_bond.second_particle_index];
+            let force_on_second =
+                self.force_on_second_particle(spring_bond, first_particle, second_particle);
+            if !second_particle.read_intrinsics().is_tracer {
+                bond_forces[spring_bond.first_particle_index] -= force_on_second;
+            }
+            if !first_particle.read_intrinsics().is_tracer {
+                bond_forces[spring_bond.second_particle_index] += force_on_second;
+            }
+        }
+    }
+
+    /// The damping takes energy out of the bonds but has no potential energy of its own.
+    fn spring_bond_potential_energy(&self, particles: &[impl ParticleRepresentation]) -> f64 {
+        let mut potential_energy = self.force_law.spring_bond_potential_energy(particles);
+        for spring_bond in self.spring_bonds.iter().filter(|spring_bond| {
+            !particles[spring_bond.first_particle_index]
+                .read_intrinsics()
+                .is_tracer
+                && !particles[spring_bond.second_particle_index]
+                    .read_intrinsics()
+                    .is_tracer
+        }) {
+            let separation_vector = super::displacement_to_nearest_image(
+                self.periodic_box_size,
+                &particles[spring_bond.first_particle_index]
+                    .read_variables()
+                    .position_vector,
+                &particles[spring_bond.second_particle_index]
+                    .read_variables()
+                    .position_vector,
+            );
+            let stretch = separation_vector
+                .horizontal_component
+                .0
+                .hypot(separation_vector.vertical_component.0)
+                - spring_bond.rest_length;
+            potential_energy += 0.5 * spring_bond.stiffness * stretch * stretch;
+        }
+        potential_energy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_structure::particle::contiguous_struct as contiguous_particle_struct;
+
+    const TEST_RELATIVE_TOLERANCE: f64 = 0.000001;
+
+    fn new_test_particle(
+        horizontal_position: f64,
+        inverse_squared_charge: f64,
+        inverse_fourth_charge: f64,
+    ) -> data_structure::particle::BasicIndividual {
+        super::super::test_functions::new_test_particle(
+            1.0,
+            inverse_squared_charge,
+            inverse_fourth_charge,
+            (horizontal_position, 0.0),
+            (0.0, 0.0),
+        )
+    }
+
+    fn new_test_configuration(
+        softening_kernel: SofteningKernel,
+    ) -> configuration_parsing::EvolutionConfiguration {
+        configuration_parsing::EvolutionConfiguration {
+            softening_kernel,
+            inverse_squared_coupling: -1.5,
+            inverse_fourth_coupling: 0.25,
+            milliseconds_per_time_slice: 100,
+            ..super::super::test_functions::create_test_evolution_configuration(11, 0.5)
+        }
+    }
+
+    /// The force along the separation should be minus the derivative of the potential with respect
+    /// to the separation, which is checked by a central difference.
+    fn check_force_is_gradient_of_potential(
+        softening_kernel: SofteningKernel,
+    ) -> Result<(), String> {
+        let force_law = InverseSquaredAndFourth::new_from_configuration(&new_test_configuration(
+            softening_kernel,
+        ))
+        .map_err(|construction_error| format!("Force law error: {}", construction_error))?;
+        let fixed_particle = new_test_particle(0.0, 2.0, -3.0);
+        let difference_step = 0.000001;
+        let mut failure_messages: std::vec::Vec<String> = vec![];
+        for tested_separation in &[0.75, 1.0, 2.5] {
+            let expected_force = (force_law.potential_energy_of_pair(
+                &new_test_particle(tested_separation - difference_step, 0.5, 1.5),
+                &fixed_particle,
+            ) - force_law.potential_energy_of_pair(
+                &new_test_particle(tested_separation + difference_step, 0.5, 1.5),
+                &fixed_particle,
+            )) / (2.0 * difference_step);
+            let actual_force = force_law
+                .force_on_first_particle_from_second_particle(
+                    &new_test_particle(*tested_separation, 0.5, 1.5),
+                    &fixed_particle,
+                )
+                .horizontal_component
+                .0;
+            if !data_structure::comparison::within_relative_tolerance(
+                expected_force,
+                actual_force,
+                TEST_RELATIVE_TOLERANCE,
+            ) {
+                failure_messages.push(format!(
+                    "Separation {}: expected force {}, actual {}",
+                    tested_separation, expected_force, actual_force
+                ));
+            }
+        }
+        if failure_messages.is_empty() {
+            Ok(())
+        } else {
+            Err(failure_messages.join("\n"))
+        }
+    }
+
+    #[test]
+    fn check_dead_zone_force_is_gradient_of_potential() -> Result<(), String> {
+        check_force_is_gradient_of_potential(SofteningKernel::DeadZone)
+    }
+
+    #[test]
+    fn check_plummer_force_is_gradient_of_potential() -> Result<(), String> {
+        check_force_is_gradient_of_potential(SofteningKernel::Plummer)
+    }
+
+    /// The species table makes A attract B but repel C with the same strength as the coupling of
+    /// the configuration, which is used for every pair without a species.
+    #[test]
+    fn check_species_couplings_give_force_and_energy() -> Result<(), String> {
+        let uniform_configuration = configuration_parsing::EvolutionConfiguration {
+            inverse_squared_coupling: 1.0,
+            inverse_fourth_coupling: 0.0,
+            ..new_test_configuration(SofteningKernel::DeadZone)
+        };
+        let species_interactions = configuration_parsing::parse_species_interactions(
+            &serde_json::json!([
+                { "firstSpecies": "A", "secondSpecies": "B", "inverseSquaredCoupling": -1.0 },
+                { "firstSpecies": "C", "secondSpecies": "A", "inverseSquaredCoupling": 1.0 },
+            ]),
+            uniform_configuration.inverse_squared_coupling,
+            uniform_configuration.inverse_fourth_coupling,
+        )
+        .map_err(|parsing_error| format!("Could not parse: {:?}", parsing_error))?;
+        let new_species_particle = |horizontal_position, species_name| {
+            let mut species_particle = new_test_particle(horizontal_position, 1.0, 1.0);
+            species_particle.intrinsic_values.species_index = species_interactions
+                .species_index(species_name)
+                .map_err(|parsing_error| format!("No species: {:?}", parsing_error))?;
+            Ok::<_, String>(species_particle)
+        };
+        let (a_particle, b_particle, c_particle) = (
+            new_species_particle(2.0, "A")?,
+            new_species_particle(0.0, "B")?,
+            new_species_particle(0.0, "C")?,
+        );
+        let uniform_law =
+            InverseSquaredAndFourth::new_from_configuration(&uniform_configuration)
+                .map_err(|construction_error| format!("Force law error: {}", construction_error))?;
+        let species_law = InverseSquaredAndFourth::new_from_configuration(
+            &configuration_parsing::EvolutionConfiguration {
+                species_interactions: Some(species_interactions.clone()),
+                ..uniform_configuration.clone()
+            },
+        )
+        .map_err(|construction_error| format!("Force law error: {}", construction_error))?;
+        let unnamed_particle = new_test_particle(0.0, 1.0, 1.0);
+
+        let repulsive_force = uniform_law
+            .force_on_first_particle_from_second_particle(&a_particle, &unnamed_particle)
+            .horizontal_component
+            .0;
+        let repulsive_energy = uniform_law.potential_energy_of_pair(&a_particle, &unnamed_particle);
+        let actual_results = [&b_particle, &c_particle, &unnamed_particle]
+            .iter()
+            .map(|other_particle| {
+                (
+                    species_law
+                        .force_on_first_particle_from_second_particle(&a_particle, *other_particle)
+                        .horizontal_component
+                        .0,
+                    species_law.potential_energy_of_pair(&a_particle, *other_particle),
+                )
+            })
+            .collect::<std::vec::Vec<(f64, f64)>>();
+        let expected_results = vec![
+            (-repulsive_force, -repulsive_energy),
+            (repulsive_force, repulsive_energy),
+            (repulsive_force, repulsive_energy),
+        ];
+        if (repulsive_force > 0.0) && (actual_results == expected_results) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected forces and energies {:?}, actual {:?}",
+                expected_results, actual_results
+            ))
+        }
+    }
+
+    /// Two particles near opposite sides of a periodic box should interact across the border as if
+    /// the second were shifted by the width of the box, with equal and opposite forces.
+    #[test]
+    fn check_periodic_forces_use_nearest_image_symmetrically() -> Result<(), String> {
+        let unbounded_configuration = new_test_configuration(SofteningKernel::DeadZone);
+        let periodic_configuration = configuration_parsing::EvolutionConfiguration {
+            boundary_conditions: Some(configuration_parsing::BoundaryConditions {
+                boundary_kind: configuration_parsing::BoundaryKind::Periodic,
+                right_border_coordinate: 5.0,
+                upper_border_coordinate: 5.0,
+                left_border_coordinate: -5.0,
+                lower_border_coordinate: -5.0,
+            }),
+            ..unbounded_configuration.clone()
+        };
+        let unbounded_law = MinimumImage::<InverseSquaredAndFourth>::new_from_configuration(
+            &unbounded_configuration,
+        )
+        .map_err(|construction_error| format!("Force law error: {}", construction_error))?;
+        let periodic_law = MinimumImage::<InverseSquaredAndFourth>::new_from_configuration(
+            &periodic_configuration,
+        )
+        .map_err(|construction_error| format!("Force law error: {}", construction_error))?;
+        let mut first_particle = new_test_particle(4.5, 2.0, -3.0);
+        first_particle
+            .variable_values
+            .position_vector
+            .vertical_component
+            .0 = -4.75;
+        let mut second_particle = new_test_particle(-4.25, 0.5, 1.5);
+        second_particle
+            .variable_values
+            .position_vector
+            .vertical_component
+            .0 = 4.5;
+        let mut second_image = second_particle;
+        second_image
+            .variable_values
+            .position_vector
+            .horizontal_component
+            .0 += 10.0;
+        second_image
+            .variable_values
+            .position_vector
+            .vertical_component
+            .0 -= 10.0;
+
+        let expected_force = unbounded_law
+            .force_on_first_particle_from_second_particle(&first_particle, &second_image);
+        let force_on_first = periodic_law
+            .force_on_first_particle_from_second_particle(&first_particle, &second_particle);
+        let force_on_second = periodic_law
+            .force_on_first_particle_from_second_particle(&second_particle, &first_particle);
+        let expected_energy =
+            unbounded_law.potential_energy_of_pair(&first_particle, &second_image);
+        let energies = [
+            periodic_law.potential_energy_of_pair(&first_particle, &second_particle),
+            periodic_law.potential_energy_of_pair(&second_particle, &first_particle),
+        ];
+        let is_as_expected = |actual_value, expected_value| {
+            data_structure::comparison::within_relative_tolerance(
+                expected_value,
+                actual_value,
+                TEST_RELATIVE_TOLERANCE,
+            )
+        };
+        if is_as_expected(
+            force_on_first.horizontal_component.0,
+            expected_force.horizontal_component.0,
+        ) && is_as_expected(
+            force_on_first.vertical_component.0,
+            expected_force.vertical_component.0,
+        ) && is_as_expected(
+            force_on_second.horizontal_component.0,
+            -expected_force.horizontal_component.0,
+        ) && is_as_expected(
+            force_on_second.vertical_component.0,
+            -expected_force.vertical_component.0,
+        ) && energies
+            .iter()
+            .all(|actual_energy| is_as_expected(*actual_energy, expected_energy))
+            && (expected_energy != 0.0)
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected force {:?} and energy {}, got forces {:?} and {:?} and energies {:?}",
+                expected_force, expected_energy, force_on_first, force_on_second, energies
+            ))
+        }
+    }
+
+    /// Every part of the external fields is switched on, with the particle placed off every axis of
+    /// symmetry, so that each component of the force is checked against a central difference of the
+    /// potential energy along that axis.
+    #[test]
+    fn check_external_force_is_gradient_of_external_potential() -> Result<(), String> {
+        let fields_configuration = configuration_parsing::EvolutionConfiguration {
+            external_fields: Some(configuration_parsing::ExternalFields {
+                horizontal_acceleration: 0.5,
+                vertical_acceleration: -2.0,
+                fixed_sources: vec![configuration_parsing::FixedSource {
+                    horizontal_coordinate: -1.0,
+                    vertical_coordinate: 0.5,
+                    inverse_squared_charge: 2.0,
+                    inverse_fourth_charge: 1.0,
+                }],
+                harmonic_trap: Some(configuration_parsing::HarmonicTrap {
+                    horizontal_center: 0.25,
+                    vertical_center: -0.75,
+                    horizontal_spring_constant: 1.5,
+                    vertical_spring_constant: -0.5,
+                }),
+            }),
+            ..new_test_configuration(SofteningKernel::DeadZone)
+        };
+        let force_law = InExternalFields::<InverseSquaredAndFourth>::new_from_configuration(
+            &fields_configuration,
+        )
+        .map_err(|construction_error| format!("Force law error: {}", construction_error))?;
+        let new_displaced_particle = |horizontal_displacement, vertical_displacement| {
+            let mut displaced_particle = new_test_particle(1.0 + horizontal_displacement, 0.5, 1.5);
+            displaced_particle.intrinsic_values.inertial_mass =
+                data_structure::charge::InertialMassUnit(3.0);
+            displaced_particle
+                .variable_values
+                .position_vector
+                .vertical_component
+                .0 = 1.5 + vertical_displacement;
+            displaced_particle
+        };
+        let difference_step = 0.000001;
+        let expected_horizontal_force = (force_law
+            .external_potential_energy_of_particle(&new_displaced_particle(-difference_step, 0.0))
+            - force_law.external_potential_energy_of_particle(&new_displaced_particle(
+                difference_step,
+                0.0,
+            )))
+            / (2.0 * difference_step);
+        let expected_vertical_force = (force_law
+            .external_potential_energy_of_particle(&new_displaced_particle(0.0, -difference_step))
+            - force_law.external_potential_energy_of_particle(&new_displaced_particle(
+                0.0,
+                difference_step,
+            )))
+            / (2.0 * difference_step);
+        let actual_force = force_law.external_force_on_particle(&new_displaced_particle(0.0, 0.0));
+        if force_law.has_external_fields()
+            && data_structure::comparison::within_relative_tolerance(
+                expected_horizontal_force,
+                actual_force.horizontal_component.0,
+                TEST_RELATIVE_TOLERANCE,
+            )
+            && data_structure::comparison::within_relative_tolerance(
+                expected_vertical_force,
+                actual_force.vertical_component.0,
+                TEST_RELATIVE_TOLERANCE,
+            )
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected force ({}, {}), actual {:?}",
+                expected_horizontal_force, expected_vertical_force, actual_force
+            ))
+        }
+    }
+
+    /// The particles are 1.5 apart through the periodic border, so the bond of rest length 1 is
+    /// stretched by 0.5, and the second particle is moving towards the first at 1, so with a
+    /// stiffness of 2 and a damping coefficient of 0.5 the bond pulls with 1 and the damping
+    /// pushes back with 0.5. The bond cannot be used with merging, as merging changes the indices
+    /// of the particles.
+    #[test]
+    fn check_spring_bond_pulls_nearest_image_with_damping() -> Result<(), String> {
+        let bonded_configuration = configuration_parsing::EvolutionConfiguration {
+            boundary_conditions: Some(configuration_parsing::BoundaryConditions {
+                boundary_kind: configuration_parsing::BoundaryKind::Periodic,
+                right_border_coordinate: 5.0,
+                upper_border_coordinate: 5.0,
+                left_border_coordinate: -5.0,
+                lower_border_coordinate: -5.0,
+            }),
+            spring_bonds: vec![configuration_parsing::SpringBond {
+                first_particle_index: 0,
+                second_particle_index: 2,
+                rest_length: 1.0,
+                stiffness: 2.0,
+                damping_coefficient: 0.5,
+            }],
+            ..new_test_configuration(SofteningKernel::DeadZone)
+        };
+        let force_law = WithSpringBonds::<InverseSquaredAndFourth>::new_from_configuration(
+            &bonded_configuration,
+        )
+        .map_err(|construction_error| format!("Force law error: {}", construction_error))?;
+        let mut test_particles = vec![
+            new_test_particle(4.5, 1.0, 1.0),
+            new_test_particle(0.0, 1.0, 1.0),
+            new_test_particle(-4.0, 1.0, 1.0),
+        ];
+        test_particles[2]
+            .variable_values
+            .velocity_vector
+            .horizontal_component
+            .0 = -1.0;
+        let mut bond_forces = vec![super::super::zero_force(); test_particles.len()];
+        force_law.add_spring_bond_forces(&test_particles, &mut bond_forces);
+        let actual_forces: std::vec::Vec<(f64, f64)> = bond_forces
+            .iter()
+            .map(|bond_force| {
+                (
+                    bond_force.horizontal_component.0,
+                    bond_force.vertical_component.0,
+                )
+            })
+            .collect();
+        let actual_energy = force_law.spring_bond_potential_energy(&test_particles);
+        let is_merging_rejected =
+            WithSpringBonds::<InverseSquaredAndFourth>::new_from_configuration(
+                &configuration_parsing::EvolutionConfiguration {
+                    merging_radius: Some(0.5),
+                    ..bonded_configuration.clone()
+                },
+            )
+            .is_err();
+        let expected_forces = vec![(0.5, 0.0), (0.0, 0.0), (-0.5, 0.0)];
+        let is_force_correct = actual_forces.iter().zip(expected_forces.iter()).all(
+            |(actual_force, expected_force)| {
+                data_structure::comparison::within_relative_tolerance(
+                    expected_force.0,
+                    actual_force.0,
+                    TEST_RELATIVE_TOLERANCE,
+                ) && data_structure::comparison::within_relative_tolerance(
+                    expected_force.1,
+                    actual_force.1,
+                    TEST_RELATIVE_TOLERANCE,
+                )
+            },
+        );
+        if force_law.has_spring_bonds()
+            && is_force_correct
+            && data_structure::comparison::within_relative_tolerance(
+                0.25,
+                actual_energy,
+                TEST_RELATIVE_TOLERANCE,
+            )
+            && is_merging_rejected
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected forces {:?} and energy 0.25 with merging rejected, actual {:?}, {} and {}",
+                expected_forces, actual_forces, actual_energy, is_merging_rejected
+            ))
+        }
+    }
+
+    /// This law pulls every pair of particles together with a force proportional to their
+    /// separation, ignoring their charges, so that it can only come from the law given to the
+    /// evolver.
+    struct HarmonicAttraction {
+        stiffness: f64,
+    }
+
+    impl PairwiseForceLaw for HarmonicAttraction {
+        fn new_from_configuration(
+            _evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+        ) -> Result<Self, Box<dyn std::error::Error>> {
+            Ok(Self {
+                stiffness: 0.5 * std::f64::consts::PI * std::f64::consts::PI,
+            })
+        }
+
+        fn force_on_first_particle_from_second_particle(
+            &self,
+            first_particle: &impl ParticleRepresentation,
+            second_particle: &impl ParticleRepresentation,
+        ) -> ForceVector {
+            super::super::scale_separation_to_force(
+                &(first_particle.read_variables().position_vector
+                    - second_particle.read_variables().position_vector),
+                -self.stiffness,
+            )
+        }
+
+        fn potential_energy_of_pair(
+            &self,
+            first_particle: &impl ParticleRepresentation,
+            second_particle: &impl ParticleRepresentation,
+        ) -> f64 {
+            0.5 * self.stiffness
+                * data_structure::position::square_separation_vector(
+                    &(first_particle.read_variables().position_vector
+                        - second_particle.read_variables().position_vector),
+                )
+                .0
+        }
+    }
+
+    #[test]
+    fn check_evolver_uses_given_force_law() -> Result<(), String> {
+        let mut evolver_implementation =
+            super::super::second_order_euler::new_given_memory_strategy_and_force_law::<
+                _,
+                _,
+                HarmonicAttraction,
+            >(
+                100,
+                contiguous_particle_struct::VectorOfMassNormalizedWithForceFieldGenerator {},
+            )
+            .map_err(|construction_error| format!("Constructor error: {:?}", construction_error))?;
+        // The uncharged particles would never move under the default law. With unit masses and
+        // this stiffness, the period of the oscillation is 2 seconds, so after 1 second (the last
+        // of the 11 time slices of 100 milliseconds) the particles have swapped sides.
+        let initial_conditions = [
+            new_test_particle(-1.0, 0.0, 0.0),
+            new_test_particle(1.0, 0.0, 0.0),
+        ];
+        let particle_set_evolution = super::super::ParticlesInTimeEvolver::create_time_sequence(
+            &mut evolver_implementation,
+            &new_test_configuration(SofteningKernel::DeadZone),
+            initial_conditions.iter(),
+        )
+        .map_err(|evolution_error| format!("Evolution error: {:?}", evolution_error))?;
+        let final_time_slice = particle_set_evolution
+            .particle_configurations
+            .last()
+            .ok_or_else(|| String::from("Expected time slices"))?
+            .map_err(|evolution_error| format!("Evolution error: {:?}", evolution_error))?;
+        let final_positions: std::vec::Vec<f64> = final_time_slice
+            .map(|evolved_particle| {
+                evolved_particle
+                    .read_variables()
+                    .position_vector
+                    .horizontal_component
+                    .0
+            })
+            .collect();
+        if (final_positions.len() == 2)
+            && ((final_positions[0] - 1.0).abs() < 0.01)
+            && ((final_positions[1] + 1.0).abs() < 0.01)
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected the particles to have swapped sides, actual positions {:?}",
+                final_positions
+            ))
+        }
+    }
+}
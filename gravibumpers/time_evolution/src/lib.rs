@@ -2,10 +2,27 @@
 /// sequences of collections of particles.
 extern crate configuration_parsing;
 extern crate data_structure;
+pub mod adaptive;
+pub mod barnes_hut;
+pub mod collision;
+pub mod diagnostics;
+pub mod force_law;
+pub mod leapfrog;
+pub mod merging;
+pub mod prescribed_motion;
+pub mod runge_kutta;
 pub mod second_order_euler;
+pub mod softening;
 pub mod test_functions;
+pub mod thermostat;
+pub mod watchdog;
 use data_structure::force::DimensionfulVector as ForceVector;
+use data_structure::particle::CollectionInForceField;
+use data_structure::particle::CollectionInForceFieldGenerator;
 use data_structure::particle::IndividualRepresentation as ParticleRepresentation;
+use data_structure::particle::WritableInForceField;
+use data_structure::position::DimensionfulVector as PositionVector;
+use force_law::PairwiseForceLaw;
 use std::error::Error;
 
 #[derive(Debug)]
@@ -58,20 +75,27 @@ impl std::fmt::Display for ParameterError {
     }
 }
 
+/// Each time slice is given as a result because the evolution may be calculated lazily, so it might
+/// only be discovered that the evolution cannot continue when a later time slice is requested.
 pub struct ParticleSetEvolution<InnerItem, InnerIterator, OuterIterator>
 where
     InnerItem: ParticleRepresentation,
     InnerIterator: std::iter::ExactSizeIterator<Item = InnerItem>,
-    OuterIterator: std::iter::ExactSizeIterator<Item = InnerIterator>,
+    OuterIterator: std::iter::ExactSizeIterator<Item = Result<InnerIterator, Box<dyn Error>>>,
 {
     pub particle_configurations: OuterIterator,
     pub milliseconds_between_configurations: u16,
 }
 
 pub trait ParticlesInTimeEvolver {
+    /// The force law is created from the evolution configuration given to create_time_sequence,
+    /// and can be created in the same way to calculate the potential energy of the time slices.
+    type ForceLaw: PairwiseForceLaw;
     type EmittedParticle: ParticleRepresentation;
     type ParticleIterator: std::iter::ExactSizeIterator<Item = Self::EmittedParticle>;
-    type IteratorIterator: std::iter::ExactSizeIterator<Item = Self::ParticleIterator>;
+    type IteratorIterator: std::iter::ExactSizeIterator<
+        Item = Result<Self::ParticleIterator, Box<dyn std::error::Error>>,
+    >;
 
     fn create_time_sequence(
         &mut self,
@@ -83,41 +107,544 @@ pub trait ParticlesInTimeEvolver {
     >;
 }
 
-fn force_on_first_particle_from_second_particle(
+/// This is implemented by each method of numerically solving the equations of motion, holding
+/// whatever the method needs to carry over from one time slice to the next. The whole collection is
+/// given rather than just its elements so that particles can be merged after any step.
+pub trait TimeSliceStepper {
+    fn evolve_to_next_time_slice<EvolvingCollection>(
+        &mut self,
+        evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+        evolving_collection: &mut EvolvingCollection,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        EvolvingCollection: CollectionInForceField;
+}
+
+/// This iterator only evolves the particles to the next time slice when that time slice is
+/// requested, so only the current state of the particles is held in memory rather than every time
+/// slice. The first time slice is the initial conditions. If evolving to a time slice fails, the
+/// error is given in place of that time slice and there are no further time slices.
+pub struct LazyTimeSequence<EvolvingCollection, Stepper>
+where
+    EvolvingCollection: CollectionInForceField,
+    Stepper: TimeSliceStepper,
+{
+    evolving_particles: EvolvingCollection,
+    time_slice_stepper: Stepper,
+    evolution_configuration: configuration_parsing::EvolutionConfiguration,
+    number_of_emitted_time_slices: usize,
+}
+
+pub fn new_lazy_time_sequence<EvolvingCollection, Stepper>(
+    evolving_particles: EvolvingCollection,
+    time_slice_stepper: Stepper,
     evolution_configuration: &configuration_parsing::EvolutionConfiguration,
-    first_particle: &impl ParticleRepresentation,
-    second_particle: &impl ParticleRepresentation,
-) -> ForceVector {
-    let separation_vector = first_particle.read_variables().position_vector
-        - second_particle.read_variables().position_vector;
-    if data_structure::position::SeparationUnit(evolution_configuration.dead_zone_radius)
-        .is_greater_than_square(&separation_vector)
-    {
-        return ForceVector {
-            horizontal_component: data_structure::force::HorizontalUnit(0.0),
-            vertical_component: data_structure::force::VerticalUnit(0.0),
+) -> LazyTimeSequence<EvolvingCollection, Stepper>
+where
+    EvolvingCollection: CollectionInForceField,
+    Stepper: TimeSliceStepper,
+{
+    LazyTimeSequence {
+        evolving_particles,
+        time_slice_stepper,
+        evolution_configuration: evolution_configuration.clone(),
+        number_of_emitted_time_slices: 0,
+    }
+}
+
+impl<EvolvingCollection, Stepper> std::iter::Iterator
+    for LazyTimeSequence<EvolvingCollection, Stepper>
+where
+    EvolvingCollection: CollectionInForceField,
+    Stepper: TimeSliceStepper,
+{
+    type Item = Result<
+        std::vec::IntoIter<data_structure::particle::BasicIndividual>,
+        Box<dyn std::error::Error>,
+    >;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let time_slice_index = self.number_of_emitted_time_slices;
+        if time_slice_index >= self.evolution_configuration.number_of_time_slices {
+            return None;
+        }
+
+        if time_slice_index > 0 {
+            let step_result = self.time_slice_stepper.evolve_to_next_time_slice(
+                &self.evolution_configuration,
+                &mut self.evolving_particles,
+            );
+            if let Err(step_error) = step_result {
+                self.number_of_emitted_time_slices =
+                    self.evolution_configuration.number_of_time_slices;
+                return Some(Err(Box::new(EvolutionError::new(&format!(
+                    "Time slice {}: {}",
+                    time_slice_index, step_error
+                )))));
+            }
+        }
+
+        self.number_of_emitted_time_slices += 1;
+        Some(Ok(copy_time_slice(
+            self.evolving_particles.access_mutable_elements(),
+        )
+        .into_iter()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let number_of_remaining_time_slices = self.len();
+        (
+            number_of_remaining_time_slices,
+            Some(number_of_remaining_time_slices),
+        )
+    }
+}
+
+impl<EvolvingCollection, Stepper> std::iter::ExactSizeIterator
+    for LazyTimeSequence<EvolvingCollection, Stepper>
+where
+    EvolvingCollection: CollectionInForceField,
+    Stepper: TimeSliceStepper,
+{
+    fn len(&self) -> usize {
+        self.evolution_configuration.number_of_time_slices - self.number_of_emitted_time_slices
+    }
+}
+
+/// This sets up the collection of particles in the memory layout given by the generator, with the
+/// time interval for each internal step divided by each particle's inertial mass, returning an
+/// error listing every initial particle which could not be set up (for example due to zero mass).
+fn create_particles_in_force_field<CollectionGenerator>(
+    collection_generator: &CollectionGenerator,
+    initial_conditions: impl std::iter::ExactSizeIterator<Item = impl ParticleRepresentation>,
+    time_interval_per_internal_slice: &data_structure::time::IntervalUnit,
+) -> Result<CollectionGenerator::CreatedCollection, Box<dyn std::error::Error>>
+where
+    CollectionGenerator: CollectionInForceFieldGenerator,
+{
+    let mut evolving_particles = collection_generator.create_collection();
+    let mut initial_condition_errors: std::vec::Vec<(usize, Box<dyn std::error::Error>)> = vec![];
+    for (initial_particle_index, initial_particle) in initial_conditions.enumerate() {
+        match data_structure::time::divide_time_by_mass(
+            time_interval_per_internal_slice,
+            &initial_particle.read_intrinsics().inertial_mass,
+        ) {
+            Ok(time_over_mass) => {
+                evolving_particles.add_particle(&initial_particle, &time_over_mass)
+            }
+            Err(initial_condition_error) => {
+                initial_condition_errors.push((initial_particle_index, initial_condition_error))
+            }
         };
     }
 
-    let inverse_separation = data_structure::position::square_separation_vector(&separation_vector)
-        .to_inverse_square_root();
-
-    let inverse_squared_separation =
-        inverse_separation.get_value() * inverse_separation.get_value();
-    let inverse_squared_force = evolution_configuration.inverse_squared_coupling
-        * first_particle.read_intrinsics().inverse_squared_charge.0
-        * second_particle.read_intrinsics().inverse_squared_charge.0
-        * inverse_squared_separation;
-    let inverse_fourth_force = evolution_configuration.inverse_fourth_coupling
-        * first_particle.read_intrinsics().inverse_fourth_charge.0
-        * second_particle.read_intrinsics().inverse_fourth_charge.0
-        * inverse_squared_separation
-        * inverse_squared_separation;
-
-    // We combine the sum of the two kinds of force with an additional 1/r so that we can multiply
-    // the separation vector directly.
-    let force_magnitude_over_separation =
-        (inverse_squared_force + inverse_fourth_force) * inverse_separation.get_value();
+    if !initial_condition_errors.is_empty() {
+        return Err(Box::new(EvolutionError::new(&format!(
+            "The following initial particles could not be set up for time evolution: {:?}",
+            initial_condition_errors
+        ))));
+    }
+
+    Ok(evolving_particles)
+}
+
+/// This sets the experienced force of every particle to the sum of the forces from every other
+/// particle, evaluated at the current positions, either summing over every pair or approximating
+/// with a Barnes-Hut quadtree if the configuration gives an opening angle and the force law is made
+/// up of inverse-power terms, and then adds the force from any external fields of the force law.
+/// Tracers feel the forces from the other particles but exert none.
+fn update_forces<ParticleImplementation, ParticleCollection, ForceLaw>(
+    evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+    force_law: &ForceLaw,
+    particles_with_forces: &mut ParticleCollection,
+) where
+    ParticleImplementation: WritableInForceField,
+    ParticleCollection: data_structure::collection::SingleAndPairwiseFinite<
+        MutableElement = ParticleImplementation,
+    >,
+    ForceLaw: PairwiseForceLaw,
+{
+    match (
+        evolution_configuration.barnes_hut_opening_angle,
+        force_law.inverse_power_terms(),
+    ) {
+        (Some(opening_angle), Some(inverse_power_terms)) => barnes_hut::update_forces(
+            opening_angle,
+            force_law,
+            inverse_power_terms,
+            particles_with_forces,
+        ),
+        _ if has_tracers(particles_with_forces) => {
+            update_forces_from_sources(force_law, particles_with_forces)
+        }
+        _ => update_forces_pairwise(force_law, particles_with_forces),
+    }
+    if force_law.has_external_fields() {
+        particles_with_forces.apply_to_every_single(&mut |particle_with_force| {
+            let external_force = force_law.external_force_on_particle(particle_with_force);
+            *particle_with_force.write_experienced_force() += external_force;
+        });
+    }
+    if force_law.has_spring_bonds() {
+        add_spring_bond_forces(force_law, particles_with_forces);
+    }
+}
+
+/// The bonds refer to the particles by their indices, so the particles are copied in order to give
+/// the force law random access to them, and the bond forces are added back to the particles in the
+/// same order.
+fn add_spring_bond_forces<ParticleImplementation, ParticleCollection, ForceLaw>(
+    force_law: &ForceLaw,
+    particles_with_forces: &mut ParticleCollection,
+) where
+    ParticleImplementation: WritableInForceField,
+    ParticleCollection: data_structure::collection::SingleAndPairwiseFinite<
+        MutableElement = ParticleImplementation,
+    >,
+    ForceLaw: PairwiseForceLaw,
+{
+    let mut copied_particles = vec![];
+    particles_with_forces.apply_to_every_single(&mut |particle_with_force| {
+        copied_particles.push(
+            data_structure::particle::create_individual_from_representation(particle_with_force),
+        );
+    });
+    let mut bond_forces = vec![zero_force(); copied_particles.len()];
+    force_law.add_spring_bond_forces(&copied_particles, &mut bond_forces);
+    let mut bond_forces_in_order = bond_forces.into_iter();
+    particles_with_forces.apply_to_every_single(&mut |particle_with_force| {
+        if let Some(bond_force) = bond_forces_in_order.next() {
+            *particle_with_force.write_experienced_force() += bond_force;
+        }
+    });
+}
+
+fn has_tracers<ParticleImplementation, ParticleCollection>(
+    particles_with_forces: &mut ParticleCollection,
+) -> bool
+where
+    ParticleImplementation: WritableInForceField,
+    ParticleCollection: data_structure::collection::SingleAndPairwiseFinite<
+        MutableElement = ParticleImplementation,
+    >,
+{
+    let mut has_tracer = false;
+    particles_with_forces.apply_to_every_single(&mut |particle_with_force| {
+        has_tracer |= particle_with_force.read_intrinsics().is_tracer;
+    });
+    has_tracer
+}
+
+/// This sets the experienced force of every particle to the exact sum of the forces from every
+/// other particle which is not a tracer. Only the pairs of sources, which are the particles which
+/// are not tracers, and the pairs of a source with a tracer are visited, so the tracers add a cost
+/// proportional to the number of sources for each tracer rather than to the number of particles.
+fn update_forces_from_sources<ParticleImplementation, ParticleCollection, ForceLaw>(
+    force_law: &ForceLaw,
+    particles_with_forces: &mut ParticleCollection,
+) where
+    ParticleImplementation: WritableInForceField,
+    ParticleCollection: data_structure::collection::SingleAndPairwiseFinite<
+        MutableElement = ParticleImplementation,
+    >,
+    ForceLaw: PairwiseForceLaw,
+{
+    let time_slice = copy_time_slice(particles_with_forces);
+    let source_indices: std::vec::Vec<usize> = time_slice
+        .iter()
+        .enumerate()
+        .filter(|(_, particle)| !particle.intrinsic_values.is_tracer)
+        .map(|(particle_index, _)| particle_index)
+        .collect();
+    let mut summed_forces = vec![zero_force(); time_slice.len()];
+    for (position_in_sources, first_index) in source_indices.iter().enumerate() {
+        for second_index in &source_indices[(position_in_sources + 1)..] {
+            let force_on_first = force_law.force_on_first_particle_from_second_particle(
+                &time_slice[*first_index],
+                &time_slice[*second_index],
+            );
+            summed_forces[*first_index] += force_on_first;
+            summed_forces[*second_index] -= force_on_first;
+        }
+    }
+    for (particle_index, tracer_particle) in time_slice
+        .iter()
+        .enumerate()
+        .filter(|(_, particle)| particle.intrinsic_values.is_tracer)
+    {
+        for source_index in &source_indices {
+            summed_forces[particle_index] += force_law
+                .force_on_first_particle_from_second_particle(
+                    tracer_particle,
+                    &time_slice[*source_index],
+                );
+        }
+    }
+    let mut summed_forces_in_order = summed_forces.into_iter();
+    particles_with_forces.apply_to_every_single(&mut |particle_with_force| {
+        if let Some(summed_force) = summed_forces_in_order.next() {
+            *particle_with_force.write_experienced_force() = summed_force;
+        }
+    });
+}
+
+/// This sets the experienced force of every particle to the exact sum of the forces from every
+/// other particle. The forces of the original law with the same couplings for every pair are summed
+/// directly over the arrays of a collection which stores each component in its own array.
+fn update_forces_pairwise<ParticleImplementation, ParticleCollection, ForceLaw>(
+    force_law: &ForceLaw,
+    particles_with_forces: &mut ParticleCollection,
+) where
+    ParticleImplementation: WritableInForceField,
+    ParticleCollection: data_structure::collection::SingleAndPairwiseFinite<
+        MutableElement = ParticleImplementation,
+    >,
+    ForceLaw: PairwiseForceLaw,
+{
+    if let Some(uniform_law) = force_law.uniform_inverse_squared_and_fourth() {
+        if uniform_law.set_forces_over_arrays(particles_with_forces) {
+            return;
+        }
+    }
+    // First all the forces must be set to zero so that we can aggregate the pairwise forces.
+    particles_with_forces.apply_to_every_single(&mut |particle_with_force| {
+        let force_on_particle = particle_with_force.write_experienced_force();
+        force_on_particle.horizontal_component = data_structure::force::HorizontalUnit(0.0);
+        force_on_particle.vertical_component = data_structure::force::VerticalUnit(0.0);
+    });
+    particles_with_forces.apply_to_every_pair(
+        &|first_particle, second_particle| {
+            force_law.force_on_first_particle_from_second_particle(first_particle, second_particle)
+        },
+        &|first_particle, force_on_first| {
+            *first_particle.write_experienced_force() += *force_on_first;
+        },
+        &|second_particle, force_on_first| {
+            *second_particle.write_experienced_force() -= *force_on_first;
+        },
+    )
+}
+
+/// This brings back into the box of the boundary conditions every particle which has left it during
+/// a step, if the configuration gives boundary conditions. It should be called after every step
+/// which moves the particles.
+fn apply_boundary_conditions<ParticleImplementation, ParticleCollection>(
+    evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+    evolving_particles: &mut ParticleCollection,
+) where
+    ParticleImplementation: WritableInForceField,
+    ParticleCollection: data_structure::collection::SingleAndPairwiseFinite<
+        MutableElement = ParticleImplementation,
+    >,
+{
+    let boundary_conditions = match &evolution_configuration.boundary_conditions {
+        Some(boundary_conditions) => boundary_conditions,
+        None => return,
+    };
+    evolving_particles.apply_to_every_single(&mut |particle_with_force| {
+        let particle_variables = particle_with_force.write_particle_variables();
+        apply_boundary_along_axis(
+            boundary_conditions.boundary_kind,
+            boundary_conditions.left_border_coordinate,
+            boundary_conditions.right_border_coordinate,
+            &mut particle_variables.position_vector.horizontal_component.0,
+            &mut particle_variables.velocity_vector.horizontal_component.0,
+        );
+        apply_boundary_along_axis(
+            boundary_conditions.boundary_kind,
+            boundary_conditions.lower_border_coordinate,
+            boundary_conditions.upper_border_coordinate,
+            &mut particle_variables.position_vector.vertical_component.0,
+            &mut particle_variables.velocity_vector.vertical_component.0,
+        );
+    });
+}
+
+/// This slows the particles by the drag for the given time, and kicks them if there is a
+/// thermostat, if the configuration gave drag. It should be called after every step.
+fn apply_drag_and_thermostat<ParticleImplementation, ParticleCollection>(
+    drag_and_thermostat: &mut Option<thermostat::DragAndThermostat>,
+    time_interval: &data_structure::time::IntervalUnit,
+    evolving_particles: &mut ParticleCollection,
+) where
+    ParticleImplementation: WritableInForceField,
+    ParticleCollection: data_structure::collection::SingleAndPairwiseFinite<
+        MutableElement = ParticleImplementation,
+    >,
+{
+    if let Some(drag_and_thermostat) = drag_and_thermostat {
+        drag_and_thermostat.apply_for_time_interval(time_interval, evolving_particles);
+    }
+}
+
+/// This moves the random generator of any thermostat on to the next time slice. It should be
+/// called at the end of every time slice.
+fn finish_time_slice_of_thermostat(
+    drag_and_thermostat: &mut Option<thermostat::DragAndThermostat>,
+) {
+    if let Some(drag_and_thermostat) = drag_and_thermostat {
+        drag_and_thermostat.finish_time_slice();
+    }
+}
+
+/// This bounces apart every pair of touching particles which have collision radii, if the
+/// configuration gives a restitution coefficient. It should be called after every step, straight
+/// after the particles have been moved, so before they are brought back into the box of any
+/// boundary conditions.
+fn collide_touching_particles<ParticleImplementation, ParticleCollection>(
+    evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+    time_interval: &data_structure::time::IntervalUnit,
+    evolving_particles: &mut ParticleCollection,
+) where
+    ParticleImplementation: WritableInForceField,
+    ParticleCollection: data_structure::collection::SingleAndPairwiseFinite<
+        MutableElement = ParticleImplementation,
+    >,
+{
+    if let Some(restitution_coefficient) = evolution_configuration.restitution_coefficient {
+        collision::resolve_collisions(
+            restitution_coefficient,
+            time_interval,
+            &evolution_configuration.boundary_conditions,
+            evolving_particles,
+        );
+    }
+}
+
+/// Only the second-order Euler evolver honours the motion modes of the particles, so the other
+/// evolvers call this to reject a configuration which prescribes the motion of any particle rather
+/// than let the particle move freely.
+fn reject_prescribed_motion(
+    evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if prescribed_motion::has_prescribed_motion(&evolution_configuration.motion_modes) {
+        Err(Box::new(ParameterError::new(
+            "Only the second-order Euler evolver can prescribe the motion of particles",
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// This merges every group of particles within the merging radius of each other into a single
+/// particle, if the configuration gives a merging radius. It should be called after every step,
+/// once the particles are back in the box of any boundary conditions.
+fn merge_close_particles<EvolvingCollection>(
+    evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+    evolving_collection: &mut EvolvingCollection,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    EvolvingCollection: CollectionInForceField,
+{
+    match evolution_configuration.merging_radius {
+        Some(merging_radius) => merging::merge_particles_within_radius(
+            merging_radius,
+            &evolution_configuration.boundary_conditions,
+            evolving_collection,
+        ),
+        None => Ok(()),
+    }
+}
+
+/// This gives the width and height of the box if the boundary conditions are periodic.
+fn get_periodic_box_size(
+    boundary_conditions: &Option<configuration_parsing::BoundaryConditions>,
+) -> Option<(f64, f64)> {
+    match boundary_conditions {
+        Some(configuration_parsing::BoundaryConditions {
+            boundary_kind: configuration_parsing::BoundaryKind::Periodic,
+            right_border_coordinate,
+            upper_border_coordinate,
+            left_border_coordinate,
+            lower_border_coordinate,
+        }) => Some((
+            right_border_coordinate - left_border_coordinate,
+            upper_border_coordinate - lower_border_coordinate,
+        )),
+        _ => None,
+    }
+}
+
+/// With periodic boundary conditions, this is the displacement to the nearest image of the second
+/// position, in the same way as for the forces.
+fn displacement_to_nearest_image(
+    periodic_box_size: Option<(f64, f64)>,
+    first_position: &PositionVector,
+    second_position: &PositionVector,
+) -> PositionVector {
+    let mut displacement_vector = *second_position - *first_position;
+    if let Some((box_width, box_height)) = periodic_box_size {
+        displacement_vector.horizontal_component.0 -=
+            box_width * (displacement_vector.horizontal_component.0 / box_width).round();
+        displacement_vector.vertical_component.0 -=
+            box_height * (displacement_vector.vertical_component.0 / box_height).round();
+    }
+    displacement_vector
+}
+
+/// A particle which has gone past a reflecting wall is mirrored back by the distance which it went
+/// past the wall, and then moves away from the wall. A particle so fast that it would still be
+/// outside the box after being mirrored is left at the far wall.
+fn apply_boundary_along_axis(
+    boundary_kind: configuration_parsing::BoundaryKind,
+    lower_border: f64,
+    upper_border: f64,
+    position_component: &mut f64,
+    velocity_component: &mut f64,
+) {
+    match boundary_kind {
+        configuration_parsing::BoundaryKind::Reflecting => {
+            if *position_component < lower_border {
+                *position_component = (2.0 * lower_border - *position_component).min(upper_border);
+                *velocity_component = velocity_component.abs();
+            } else if *position_component > upper_border {
+                *position_component = (2.0 * upper_border - *position_component).max(lower_border);
+                *velocity_component = -velocity_component.abs();
+            }
+        }
+        configuration_parsing::BoundaryKind::Periodic => {
+            if (*position_component < lower_border) || (*position_component >= upper_border) {
+                *position_component = lower_border
+                    + (*position_component - lower_border).rem_euclid(upper_border - lower_border);
+            }
+        }
+    }
+}
+
+/// This copies the current state of every particle into a new vector, dropping the force and the
+/// time over mass, for emission as a time slice.
+fn copy_time_slice<ParticleImplementation, ParticleCollection>(
+    evolving_particles: &mut ParticleCollection,
+) -> std::vec::Vec<data_structure::particle::BasicIndividual>
+where
+    ParticleImplementation: WritableInForceField,
+    ParticleCollection: data_structure::collection::SingleAndPairwiseFinite<
+        MutableElement = ParticleImplementation,
+    >,
+{
+    let mut time_slice_without_force =
+        std::vec::Vec::<data_structure::particle::BasicIndividual>::with_capacity(
+            evolving_particles.get_count(),
+        );
+    evolving_particles.apply_to_every_single(&mut |particle_with_force| {
+        time_slice_without_force.push(particle_with_force.into_individual_particle());
+    });
+    time_slice_without_force
+}
+
+fn zero_force() -> ForceVector {
+    ForceVector {
+        horizontal_component: data_structure::force::HorizontalUnit(0.0),
+        vertical_component: data_structure::force::VerticalUnit(0.0),
+    }
+}
+
+/// Force laws which give the magnitude of a central force divided by the separation can use this
+/// to multiply the separation vector directly.
+fn scale_separation_to_force(
+    separation_vector: &data_structure::position::DimensionfulVector,
+    force_magnitude_over_separation: f64,
+) -> ForceVector {
     ForceVector {
         horizontal_component: data_structure::force::HorizontalUnit(
             separation_vector.horizontal_component.0 * force_magnitude_over_separation,
@@ -127,3 +654,239 @@ fn force_on_first_particle_from_second_particle(
         ),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_structure::collection::SingleAndPairwiseFinite;
+    use data_structure::particle::contiguous_struct as contiguous_particle_struct;
+    use data_structure::particle::ReadOnlyInForceField;
+
+    /// This stepper does not change the particles, but counts how many time slices it has been
+    /// asked to evolve, and fails for the time slice with the given index, if any.
+    struct CountingStepper {
+        number_of_evolved_time_slices: usize,
+        failing_time_slice_index: Option<usize>,
+    }
+
+    impl TimeSliceStepper for CountingStepper {
+        fn evolve_to_next_time_slice<EvolvingCollection>(
+            &mut self,
+            _evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+            _evolving_collection: &mut EvolvingCollection,
+        ) -> Result<(), Box<dyn std::error::Error>>
+        where
+            EvolvingCollection: CollectionInForceField,
+        {
+            self.number_of_evolved_time_slices += 1;
+            if Some(self.number_of_evolved_time_slices) == self.failing_time_slice_index {
+                Err(Box::new(EvolutionError::new("Expected failure")))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    fn new_test_sequence(
+        number_of_time_slices: usize,
+        failing_time_slice_index: Option<usize>,
+    ) -> Result<
+        LazyTimeSequence<
+            contiguous_particle_struct::VectorOfMassNormalizedWithForceField,
+            CountingStepper,
+        >,
+        String,
+    > {
+        let test_particle =
+            test_functions::new_test_particle(1.0, 1.0, 1.0, (1.0, 2.0), (3.0, 4.0));
+        let evolving_particles = create_particles_in_force_field(
+            &contiguous_particle_struct::VectorOfMassNormalizedWithForceFieldGenerator {},
+            vec![test_particle].into_iter(),
+            &data_structure::time::IntervalUnit(1.0),
+        )
+        .map_err(|setup_error| format!("Set-up error: {:?}", setup_error))?;
+        let evolution_configuration = configuration_parsing::EvolutionConfiguration {
+            inverse_squared_coupling: 1.0,
+            inverse_fourth_coupling: 1.0,
+            ..super::test_functions::create_test_evolution_configuration(number_of_time_slices, 1.0)
+        };
+        Ok(new_lazy_time_sequence(
+            evolving_particles,
+            CountingStepper {
+                number_of_evolved_time_slices: 0,
+                failing_time_slice_index,
+            },
+            &evolution_configuration,
+        ))
+    }
+
+    #[test]
+    fn check_time_slices_are_only_evolved_when_requested() -> Result<(), String> {
+        let mut time_sequence = new_test_sequence(5, None)?;
+        let mut failure_messages: std::vec::Vec<String> = vec![];
+        for expected_number_of_evolved_time_slices in 0..5 {
+            let expected_remaining_length = 5 - expected_number_of_evolved_time_slices;
+            if time_sequence.len() != expected_remaining_length {
+                failure_messages.push(format!(
+                    "Expected length {}, actual length {}",
+                    expected_remaining_length,
+                    time_sequence.len()
+                ));
+            }
+            match time_sequence.next() {
+                Some(Ok(time_slice)) if time_slice.len() == 1 => {}
+                _ => failure_messages.push(format!(
+                    "Expected a time slice with 1 particle at index {}",
+                    expected_number_of_evolved_time_slices
+                )),
+            }
+            let actual_number_of_evolved_time_slices = time_sequence
+                .time_slice_stepper
+                .number_of_evolved_time_slices;
+            if actual_number_of_evolved_time_slices != expected_number_of_evolved_time_slices {
+                failure_messages.push(format!(
+                    "Expected {} evolved time slices, actually {}",
+                    expected_number_of_evolved_time_slices, actual_number_of_evolved_time_slices
+                ));
+            }
+        }
+        if time_sequence.next().is_some() || (time_sequence.len() != 0) {
+            failure_messages.push(String::from("Expected the sequence to have finished"));
+        }
+
+        if failure_messages.is_empty() {
+            Ok(())
+        } else {
+            Err(failure_messages.join("\n"))
+        }
+    }
+
+    #[test]
+    fn check_failed_time_slice_ends_sequence() -> Result<(), String> {
+        let mut time_sequence = new_test_sequence(5, Some(2))?;
+        for time_slice_index in 0..2 {
+            if !matches!(time_sequence.next(), Some(Ok(_))) {
+                return Err(format!(
+                    "Expected time slice {} to succeed",
+                    time_slice_index
+                ));
+            }
+        }
+        if !matches!(time_sequence.next(), Some(Err(_))) {
+            return Err(String::from("Expected time slice 2 to fail"));
+        }
+        if time_sequence.next().is_some() || (time_sequence.len() != 0) {
+            return Err(String::from(
+                "Expected no further time slices after the failure",
+            ));
+        }
+        Ok(())
+    }
+
+    /// This sums the forces between particles on a grid spaced more closely than the softening
+    /// length in one direction, with charges of both signs, and returns the bits of the forces in
+    /// order.
+    fn sum_grid_forces<CollectionGenerator>(
+        collection_generator: &CollectionGenerator,
+        softening_kernel: configuration_parsing::SofteningKernel,
+    ) -> Result<(bool, std::vec::Vec<(f64, f64)>), String>
+    where
+        CollectionGenerator: CollectionInForceFieldGenerator,
+    {
+        let grid_particles = (0..25).map(|particle_index| {
+            test_functions::new_test_particle(
+                1.0,
+                0.5 + (0.1 * (particle_index as f64)),
+                -1.0 + (0.13 * (particle_index as f64)),
+                (
+                    0.37 * ((particle_index % 5) as f64),
+                    0.29 * ((particle_index / 5) as f64),
+                ),
+                (0.0, 0.0),
+            )
+        });
+        let mut evolving_particles = create_particles_in_force_field(
+            collection_generator,
+            grid_particles.collect::<std::vec::Vec<_>>().into_iter(),
+            &data_structure::time::IntervalUnit(1.0),
+        )
+        .map_err(|setup_error| format!("Set-up error: {:?}", setup_error))?;
+        let force_law = force_law::InverseSquaredAndFourth::new_from_configuration(
+            &configuration_parsing::EvolutionConfiguration {
+                softening_kernel,
+                inverse_squared_coupling: -1.0,
+                inverse_fourth_coupling: 0.5,
+                ..super::test_functions::create_test_evolution_configuration(1, 0.3)
+            },
+        )
+        .map_err(|construction_error| format!("Force law error: {:?}", construction_error))?;
+        let is_summed_over_arrays = evolving_particles
+            .access_mutable_elements()
+            .set_forces_from_charges_and_separations(0.0, 0.0, &|_, _, _| 0.0);
+        update_forces_pairwise(&force_law, evolving_particles.access_mutable_elements());
+        let mut force_components = vec![];
+        evolving_particles
+            .access_mutable_elements()
+            .apply_to_every_single(&mut |particle_with_force| {
+                let experienced_force = particle_with_force.read_experienced_force();
+                force_components.push((
+                    experienced_force.horizontal_component.0,
+                    experienced_force.vertical_component.0,
+                ));
+            });
+        Ok((is_summed_over_arrays, force_components))
+    }
+
+    #[test]
+    fn check_forces_summed_over_arrays_match_vector_of_structs() -> Result<(), String> {
+        // The sums over the arrays are added up in a different order, so only agree to within
+        // rounding.
+        let relative_tolerance = 1.0e-12;
+        let mut failed_kernels = vec![];
+        for softening_kernel in &[
+            configuration_parsing::SofteningKernel::DeadZone,
+            configuration_parsing::SofteningKernel::Plummer,
+            configuration_parsing::SofteningKernel::Spline,
+        ] {
+            let (is_vector_summed_over_arrays, vector_forces) = sum_grid_forces(
+                &contiguous_particle_struct::VectorOfMassNormalizedWithForceFieldGenerator {},
+                *softening_kernel,
+            )?;
+            let (is_struct_of_arrays_summed_over_arrays, struct_of_arrays_forces) = sum_grid_forces(
+                &data_structure::particle::struct_of_arrays::ParticleArraysWithForceFieldGenerator {},
+                *softening_kernel,
+            )?;
+            if is_vector_summed_over_arrays
+                || !is_struct_of_arrays_summed_over_arrays
+                || (vector_forces.len() != struct_of_arrays_forces.len())
+                || vector_forces
+                    .iter()
+                    .zip(struct_of_arrays_forces.iter())
+                    .any(|(vector_force, struct_of_arrays_force)| {
+                        let force_scale = vector_force.0.abs() + vector_force.1.abs();
+                        ((vector_force.0 - struct_of_arrays_force.0).abs()
+                            + (vector_force.1 - struct_of_arrays_force.1).abs())
+                            > (relative_tolerance * force_scale)
+                    })
+            {
+                failed_kernels.push(format!(
+                    "{:?}: summed over arrays {} and {}, forces {:?} and {:?}",
+                    softening_kernel,
+                    is_vector_summed_over_arrays,
+                    is_struct_of_arrays_summed_over_arrays,
+                    vector_forces,
+                    struct_of_arrays_forces
+                ));
+            }
+        }
+        if failed_kernels.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected only the struct of arrays to sum over its arrays, with the same forces \
+                 as the vector within a relative tolerance of {}: {:?}",
+                relative_tolerance, failed_kernels
+            ))
+        }
+    }
+}
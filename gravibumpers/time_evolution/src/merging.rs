@@ -0,0 +1,480 @@
+/// This module provides the merging of particles which come closer to each other than the merging
+/// radius of the configuration. Particles are merged in groups: if a particle is within the radius
+/// of two others, all three merge, even if those two are further apart than the radius. Each group
+/// is replaced by a single particle at its center of mass, with the total mass, momentum, charges,
+/// and color brightness of the group, the species of its most massive particle, and the collision
+/// radius which gives it the total area of the group. The kinetic energy of the motion within the
+/// group and the potential energy between its particles are lost, as are the angular momentum of
+/// the motion within the group about its center of mass. The merged particles are removed from the
+/// collection, keeping the order of the others, and the new particles are added after them.
+/// Tracers never merge, as they exert no force on the other particles.
+use data_structure::collection::SingleAndPairwiseFinite;
+use data_structure::force::DimensionfulVector as ForceVector;
+use data_structure::particle::BasicIndividual;
+use data_structure::particle::CollectionInForceField;
+use data_structure::particle::ReadOnlyInForceField;
+use data_structure::particle::WritableInForceField;
+
+/// The force is kept so that evolvers which re-use the force from the end of the previous step,
+/// like the leapfrog, start the next step with the merged particle feeling the sum of the forces
+/// on its parts, in which the forces between the parts cancel.
+struct ParticleWithForce {
+    particle_description: BasicIndividual,
+    experienced_force: ForceVector,
+    timestep_over_inertial_mass: data_structure::time::OverMassUnit,
+}
+
+/// This follows the links from the given particle to the first particle of its group, shortening
+/// the links on the way.
+fn find_group_root(group_links: &mut [usize], particle_index: usize) -> usize {
+    let mut current_index = particle_index;
+    while group_links[current_index] != current_index {
+        group_links[current_index] = group_links[group_links[current_index]];
+        current_index = group_links[current_index];
+    }
+    current_index
+}
+
+fn join_groups(group_links: &mut [usize], first_index: usize, second_index: usize) {
+    let first_root = find_group_root(group_links, first_index);
+    let second_root = find_group_root(group_links, second_index);
+    group_links[first_root.max(second_root)] = first_root.min(second_root);
+}
+
+/// The time over mass of the merged particle is found from the time over mass and the mass of the
+/// first particle of the group, since every particle was set up with the same time interval.
+fn merge_group(
+    particles_with_forces: &[ParticleWithForce],
+    group_indices: &[usize],
+    periodic_box_size: Option<(f64, f64)>,
+    boundary_conditions: &Option<configuration_parsing::BoundaryConditions>,
+) -> Result<ParticleWithForce, Box<dyn std::error::Error>> {
+    let first_particle = &particles_with_forces[group_indices[0]];
+    let first_position = first_particle
+        .particle_description
+        .variable_values
+        .position_vector;
+    let mut merged_particle = ParticleWithForce {
+        particle_description: first_particle.particle_description,
+        experienced_force: super::zero_force(),
+        timestep_over_inertial_mass: first_particle.timestep_over_inertial_mass,
+    };
+    let mut total_mass = 0.0;
+    let mut heaviest_mass = f64::NEG_INFINITY;
+    let mut mass_weighted_displacement = (0.0, 0.0);
+    let mut total_momentum = (0.0, 0.0);
+    let mut total_inverse_squared_charge = 0.0;
+    let mut total_inverse_fourth_charge = 0.0;
+    let mut total_squared_collision_radius = 0.0;
+    let mut total_color_brightness = data_structure::color::new_triplet(
+        data_structure::color::RedUnit(0.0),
+        data_structure::color::GreenUnit(0.0),
+        data_structure::color::BlueUnit(0.0),
+    );
+    for group_index in group_indices {
+        let group_particle = &particles_with_forces[*group_index];
+        let particle_intrinsics = &group_particle.particle_description.intrinsic_values;
+        let particle_variables = &group_particle.particle_description.variable_values;
+        let particle_mass = particle_intrinsics.inertial_mass.0;
+        let displacement_from_first = super::displacement_to_nearest_image(
+            periodic_box_size,
+            &first_position,
+            &particle_variables.position_vector,
+        );
+        total_mass += particle_mass;
+        if particle_mass > heaviest_mass {
+            heaviest_mass = particle_mass;
+            merged_particle
+                .particle_description
+                .intrinsic_values
+                .species_index = particle_intrinsics.species_index;
+        }
+        mass_weighted_displacement.0 +=
+            particle_mass * displacement_from_first.horizontal_component.0;
+        mass_weighted_displacement.1 +=
+            particle_mass * displacement_from_first.vertical_component.0;
+        total_momentum.0 +=
+            particle_mass * particle_variables.velocity_vector.horizontal_component.0;
+        total_momentum.1 += particle_mass * particle_variables.velocity_vector.vertical_component.0;
+        total_inverse_squared_charge += particle_intrinsics.inverse_squared_charge.0;
+        total_inverse_fourth_charge += particle_intrinsics.inverse_fourth_charge.0;
+        total_squared_collision_radius +=
+            particle_intrinsics.collision_radius.0 * particle_intrinsics.collision_radius.0;
+        total_color_brightness += particle_intrinsics.color_brightness;
+        merged_particle.experienced_force += group_particle.experienced_force;
+    }
+
+    merged_particle.timestep_over_inertial_mass = data_structure::time::divide_time_by_mass(
+        &data_structure::time::IntervalUnit(
+            first_particle.timestep_over_inertial_mass.0
+                * first_particle
+                    .particle_description
+                    .intrinsic_values
+                    .inertial_mass
+                    .0,
+        ),
+        &data_structure::charge::InertialMassUnit(total_mass),
+    )?;
+    let merged_intrinsics = &mut merged_particle.particle_description.intrinsic_values;
+    merged_intrinsics.inertial_mass = data_structure::charge::InertialMassUnit(total_mass);
+    merged_intrinsics.inverse_squared_charge =
+        data_structure::charge::InverseSquaredChargeUnit(total_inverse_squared_charge);
+    merged_intrinsics.inverse_fourth_charge =
+        data_structure::charge::InverseFourthChargeUnit(total_inverse_fourth_charge);
+    merged_intrinsics.collision_radius =
+        data_structure::position::SeparationUnit(total_squared_collision_radius.sqrt());
+    merged_intrinsics.color_brightness = total_color_brightness;
+    let merged_variables = &mut merged_particle.particle_description.variable_values;
+    merged_variables.position_vector.horizontal_component.0 =
+        first_position.horizontal_component.0 + (mass_weighted_displacement.0 / total_mass);
+    merged_variables.position_vector.vertical_component.0 =
+        first_position.vertical_component.0 + (mass_weighted_displacement.1 / total_mass);
+    merged_variables.velocity_vector.horizontal_component.0 = total_momentum.0 / total_mass;
+    merged_variables.velocity_vector.vertical_component.0 = total_momentum.1 / total_mass;
+
+    // With periodic boundary conditions, the center of mass can be outside the box if the group
+    // straddles a border.
+    if let Some(boundary_conditions) = boundary_conditions {
+        super::apply_boundary_along_axis(
+            boundary_conditions.boundary_kind,
+            boundary_conditions.left_border_coordinate,
+            boundary_conditions.right_border_coordinate,
+            &mut merged_variables.position_vector.horizontal_component.0,
+            &mut merged_variables.velocity_vector.horizontal_component.0,
+        );
+        super::apply_boundary_along_axis(
+            boundary_conditions.boundary_kind,
+            boundary_conditions.lower_border_coordinate,
+            boundary_conditions.upper_border_coordinate,
+            &mut merged_variables.position_vector.vertical_component.0,
+            &mut merged_variables.velocity_vector.vertical_component.0,
+        );
+    }
+    Ok(merged_particle)
+}
+
+/// This replaces every group of particles within the merging radius of each other by a single
+/// particle, returning an error if a group has a total mass of zero.
+pub fn merge_particles_within_radius<EvolvingCollection>(
+    merging_radius: f64,
+    boundary_conditions: &Option<configuration_parsing::BoundaryConditions>,
+    evolving_collection: &mut EvolvingCollection,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    EvolvingCollection: CollectionInForceField,
+{
+    let mut particles_with_forces = vec![];
+    evolving_collection
+        .access_mutable_elements()
+        .apply_to_every_single(&mut |particle_with_force| {
+            particles_with_forces.push(ParticleWithForce {
+                particle_description: particle_with_force.into_individual_particle(),
+                experienced_force: *particle_with_force.read_experienced_force(),
+                timestep_over_inertial_mass: *particle_with_force
+                    .read_timestep_over_inertial_mass(),
+            });
+        });
+
+    let periodic_box_size = super::get_periodic_box_size(boundary_conditions);
+    let squared_merging_radius = merging_radius * merging_radius;
+    let number_of_particles = particles_with_forces.len();
+    let mut group_links: std::vec::Vec<usize> = (0..number_of_particles).collect();
+    let mut has_close_pair = false;
+    for (first_index, first_particle) in particles_with_forces.iter().enumerate() {
+        if first_particle
+            .particle_description
+            .intrinsic_values
+            .is_tracer
+        {
+            continue;
+        }
+        let first_position = &first_particle
+            .particle_description
+            .variable_values
+            .position_vector;
+        for (second_index, second_particle) in particles_with_forces
+            .iter()
+            .enumerate()
+            .skip(first_index + 1)
+            .filter(|(_, second_particle)| {
+                !second_particle
+                    .particle_description
+                    .intrinsic_values
+                    .is_tracer
+            })
+        {
+            let separation_vector = super::displacement_to_nearest_image(
+                periodic_box_size,
+                first_position,
+                &second_particle
+                    .particle_description
+                    .variable_values
+                    .position_vector,
+            );
+            if data_structure::position::square_separation_vector(&separation_vector).0
+                < squared_merging_radius
+            {
+                join_groups(&mut group_links, first_index, second_index);
+                has_close_pair = true;
+            }
+        }
+    }
+    if !has_close_pair {
+        return Ok(());
+    }
+
+    let mut group_members: std::vec::Vec<std::vec::Vec<usize>> = vec![vec![]; number_of_particles];
+    for particle_index in 0..number_of_particles {
+        let group_root = find_group_root(&mut group_links, particle_index);
+        group_members[group_root].push(particle_index);
+    }
+    let mut merged_particles = vec![];
+    let mut removed_indices = vec![];
+    for group_indices in group_members
+        .iter()
+        .filter(|group_indices| group_indices.len() > 1)
+    {
+        merged_particles.push(merge_group(
+            &particles_with_forces,
+            group_indices,
+            periodic_box_size,
+            boundary_conditions,
+        )?);
+        removed_indices.extend_from_slice(group_indices);
+    }
+
+    removed_indices.sort_unstable();
+    for removed_index in removed_indices.iter().rev() {
+        evolving_collection.remove_particle(*removed_index);
+    }
+    for merged_particle in merged_particles.iter() {
+        evolving_collection.add_particle(
+            &merged_particle.particle_description,
+            &merged_particle.timestep_over_inertial_mass,
+        );
+    }
+    let evolving_particles = evolving_collection.access_mutable_elements();
+    let first_merged_index = evolving_particles.get_count() - merged_particles.len();
+    let mut particle_index = 0;
+    evolving_particles.apply_to_every_single(&mut |particle_with_force| {
+        if particle_index >= first_merged_index {
+            *particle_with_force.write_experienced_force() =
+                merged_particles[particle_index - first_merged_index].experienced_force;
+        }
+        particle_index += 1;
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use data_structure::particle::contiguous_struct as contiguous_particle_struct;
+    use data_structure::particle::CollectionInForceFieldGenerator;
+
+    fn new_test_particle(
+        inertial_mass: f64,
+        species_index: usize,
+        horizontal_position: f64,
+        vertical_position: f64,
+        horizontal_velocity: f64,
+    ) -> BasicIndividual {
+        let mut test_particle = super::super::test_functions::new_test_particle(
+            inertial_mass,
+            1.0,
+            0.5,
+            (horizontal_position, vertical_position),
+            (horizontal_velocity, 0.0),
+        );
+        test_particle.intrinsic_values.species_index =
+            data_structure::charge::SpeciesIndex(species_index);
+        test_particle.intrinsic_values.color_brightness = data_structure::color::new_triplet(
+            data_structure::color::RedUnit(1.0),
+            data_structure::color::GreenUnit(2.0),
+            data_structure::color::BlueUnit(3.0),
+        );
+        test_particle
+    }
+
+    /// Every particle is set up with a time over mass of 0.5 / its mass, and a force of its index.
+    fn merge_test_particles(
+        test_particles: &[BasicIndividual],
+        boundary_conditions: &Option<configuration_parsing::BoundaryConditions>,
+    ) -> Result<std::vec::Vec<ParticleWithForce>, String> {
+        let mut evolving_collection =
+            contiguous_particle_struct::VectorOfMassNormalizedWithForceFieldGenerator {}
+                .create_collection();
+        for test_particle in test_particles {
+            evolving_collection.add_particle(
+                test_particle,
+                &data_structure::time::OverMassUnit(
+                    0.5 / test_particle.intrinsic_values.inertial_mass.0,
+                ),
+            );
+        }
+        let mut particle_index = 0.0;
+        evolving_collection
+            .access_mutable_elements()
+            .apply_to_every_single(&mut |particle_with_force| {
+                particle_with_force
+                    .write_experienced_force()
+                    .horizontal_component = data_structure::force::HorizontalUnit(particle_index);
+                particle_index += 1.0;
+            });
+        merge_particles_within_radius(0.5, boundary_conditions, &mut evolving_collection)
+            .map_err(|merging_error| format!("Merging error: {:?}", merging_error))?;
+        let mut merged_particles = vec![];
+        evolving_collection
+            .access_mutable_elements()
+            .apply_to_every_single(&mut |particle_with_force| {
+                merged_particles.push(ParticleWithForce {
+                    particle_description: particle_with_force.into_individual_particle(),
+                    experienced_force: *particle_with_force.read_experienced_force(),
+                    timestep_over_inertial_mass: *particle_with_force
+                        .read_timestep_over_inertial_mass(),
+                });
+            });
+        Ok(merged_particles)
+    }
+
+    /// The second and fourth particles are within the merging radius of the third but not of each
+    /// other, so all three should merge, while the first and fifth particles are left alone.
+    #[test]
+    fn check_chain_of_close_particles_merges_into_one() -> Result<(), String> {
+        let test_particles = [
+            new_test_particle(1.0, 0, -5.0, 0.0, 1.0),
+            new_test_particle(1.0, 0, 0.0, 0.0, 3.0),
+            new_test_particle(2.0, 1, 0.4, 0.0, -1.0),
+            new_test_particle(1.0, 0, 0.8, 0.0, 1.0),
+            new_test_particle(1.0, 0, 5.0, 0.0, 1.0),
+        ];
+        let merged_particles = merge_test_particles(&test_particles, &None)?;
+        if merged_particles.len() != 3 {
+            return Err(format!(
+                "Expected 3 particles, got {}",
+                merged_particles.len()
+            ));
+        }
+        let mut failure_messages: std::vec::Vec<String> = vec![];
+        for (particle_index, expected_position) in [(0, -5.0), (1, 5.0)].iter() {
+            let actual_position = merged_particles[*particle_index]
+                .particle_description
+                .variable_values
+                .position_vector
+                .horizontal_component
+                .0;
+            if actual_position != *expected_position {
+                failure_messages.push(format!(
+                    "Expected particle {} to stay at {}, got {}",
+                    particle_index, expected_position, actual_position
+                ));
+            }
+        }
+        let merged_particle = &merged_particles[2];
+        let merged_intrinsics = &merged_particle.particle_description.intrinsic_values;
+        let merged_variables = &merged_particle.particle_description.variable_values;
+        let expected_and_actual = [
+            ("mass", 4.0, merged_intrinsics.inertial_mass.0),
+            (
+                "inverse-squared charge",
+                3.0,
+                merged_intrinsics.inverse_squared_charge.0,
+            ),
+            (
+                "inverse-fourth charge",
+                1.5,
+                merged_intrinsics.inverse_fourth_charge.0,
+            ),
+            ("red", 3.0, merged_intrinsics.color_brightness.get_red().0),
+            ("blue", 9.0, merged_intrinsics.color_brightness.get_blue().0),
+            (
+                "horizontal position",
+                0.4,
+                merged_variables.position_vector.horizontal_component.0,
+            ),
+            (
+                "horizontal velocity",
+                0.5,
+                merged_variables.velocity_vector.horizontal_component.0,
+            ),
+            (
+                "force",
+                6.0,
+                merged_particle.experienced_force.horizontal_component.0,
+            ),
+            (
+                "time over mass",
+                0.125,
+                merged_particle.timestep_over_inertial_mass.0,
+            ),
+        ];
+        for (quantity_name, expected_value, actual_value) in expected_and_actual.iter() {
+            if !data_structure::comparison::within_relative_tolerance(
+                *expected_value,
+                *actual_value,
+                1.0e-10,
+            ) {
+                failure_messages.push(format!(
+                    "Expected merged {} {}, got {}",
+                    quantity_name, expected_value, actual_value
+                ));
+            }
+        }
+        if merged_intrinsics.species_index != data_structure::charge::SpeciesIndex(1) {
+            failure_messages.push(format!(
+                "Expected the species of the heaviest particle, got {:?}",
+                merged_intrinsics.species_index
+            ));
+        }
+
+        if failure_messages.is_empty() {
+            Ok(())
+        } else {
+            Err(failure_messages.join("\n"))
+        }
+    }
+
+    /// The particles are on either side of the left and right borders of a periodic box, so they
+    /// are close through the border, and their center of mass is just past the right border, so
+    /// should be wrapped round to just inside the left border.
+    #[test]
+    fn check_particles_merge_through_periodic_border() -> Result<(), String> {
+        let test_particles = [
+            new_test_particle(1.0, 0, 9.9, 1.0, 0.0),
+            new_test_particle(3.0, 0, -9.9, 1.0, 0.0),
+        ];
+        let boundary_conditions = Some(configuration_parsing::BoundaryConditions {
+            boundary_kind: configuration_parsing::BoundaryKind::Periodic,
+            right_border_coordinate: 10.0,
+            upper_border_coordinate: 10.0,
+            left_border_coordinate: -10.0,
+            lower_border_coordinate: -10.0,
+        });
+        let merged_particles = merge_test_particles(&test_particles, &boundary_conditions)?;
+        let merged_positions: std::vec::Vec<f64> = merged_particles
+            .iter()
+            .map(|merged_particle| {
+                merged_particle
+                    .particle_description
+                    .variable_values
+                    .position_vector
+                    .horizontal_component
+                    .0
+            })
+            .collect();
+        if (merged_positions.len() == 1)
+            && data_structure::comparison::within_relative_tolerance(
+                -9.95,
+                merged_positions[0],
+                1.0e-10,
+            )
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected a single particle at -9.95, got particles at {:?}",
+                merged_positions
+            ))
+        }
+    }
+}
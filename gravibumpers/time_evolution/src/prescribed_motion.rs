@@ -0,0 +1,154 @@
+/// This module provides the positions and velocities of particles whose motion is prescribed by
+/// their motion modes rather than found from the forces on them. The particles still exert forces
+/// on the other particles, as the forces are calculated from the positions of every particle before
+/// any particle is moved. Only the second-order Euler evolver honours the motion modes, and it puts
+/// every particle with a prescribed motion back on its path after any collisions, drag, and
+/// boundaries, so a particle with a prescribed motion bounces other particles off as if it had its
+/// own mass but is not itself moved by them.
+use configuration_parsing::MotionMode;
+use data_structure::collection::SingleAndPairwiseFinite;
+use data_structure::particle::WritableInForceField;
+
+fn new_variables(
+    position_components: (f64, f64),
+    velocity_components: (f64, f64),
+) -> data_structure::particle::VariablePart {
+    data_structure::particle::VariablePart {
+        position_vector: data_structure::position::DimensionfulVector {
+            horizontal_component: data_structure::position::HorizontalUnit(position_components.0),
+            vertical_component: data_structure::position::VerticalUnit(position_components.1),
+        },
+        velocity_vector: data_structure::velocity::DimensionfulVector {
+            horizontal_component: data_structure::velocity::HorizontalUnit(velocity_components.0),
+            vertical_component: data_structure::velocity::VerticalUnit(velocity_components.1),
+        },
+    }
+}
+
+/// A time exactly at a keyframe is taken to be at the start of the segment after the keyframe.
+fn variables_between_keyframes(
+    keyframes: &[configuration_parsing::Keyframe],
+    time_in_seconds: f64,
+) -> Option<data_structure::particle::VariablePart> {
+    let at_rest = |keyframe: &configuration_parsing::Keyframe| {
+        new_variables(
+            (keyframe.horizontal_coordinate, keyframe.vertical_coordinate),
+            (0.0, 0.0),
+        )
+    };
+    match keyframes
+        .iter()
+        .position(|keyframe| keyframe.time_in_seconds > time_in_seconds)
+    {
+        Some(0) => keyframes.first().map(at_rest),
+        Some(later_index) => {
+            let earlier_keyframe = &keyframes[later_index - 1];
+            let later_keyframe = &keyframes[later_index];
+            let segment_duration =
+                later_keyframe.time_in_seconds - earlier_keyframe.time_in_seconds;
+            let segment_velocity = (
+                (later_keyframe.horizontal_coordinate - earlier_keyframe.horizontal_coordinate)
+                    / segment_duration,
+                (later_keyframe.vertical_coordinate - earlier_keyframe.vertical_coordinate)
+                    / segment_duration,
+            );
+            let time_into_segment = time_in_seconds - earlier_keyframe.time_in_seconds;
+            Some(new_variables(
+                (
+                    earlier_keyframe.horizontal_coordinate
+                        + (segment_velocity.0 * time_into_segment),
+                    earlier_keyframe.vertical_coordinate + (segment_velocity.1 * time_into_segment),
+                ),
+                segment_velocity,
+            ))
+        }
+        None => keyframes.last().map(at_rest),
+    }
+}
+
+fn variables_on_ellipse(
+    elliptical_path: &configuration_parsing::EllipticalPath,
+    time_in_seconds: f64,
+) -> data_structure::particle::VariablePart {
+    let current_phase =
+        (elliptical_path.angular_frequency * time_in_seconds) + elliptical_path.initial_phase;
+    new_variables(
+        (
+            elliptical_path.horizontal_center
+                + (elliptical_path.horizontal_semi_axis * current_phase.cos()),
+            elliptical_path.vertical_center
+                + (elliptical_path.vertical_semi_axis * current_phase.sin()),
+        ),
+        (
+            -elliptical_path.horizontal_semi_axis
+                * elliptical_path.angular_frequency
+                * current_phase.sin(),
+            elliptical_path.vertical_semi_axis
+                * elliptical_path.angular_frequency
+                * current_phase.cos(),
+        ),
+    )
+}
+
+/// This returns the position and velocity which the motion mode prescribes at the given time, or
+/// None if the particle is free.
+pub fn prescribed_variables(
+    motion_mode: &MotionMode,
+    time_in_seconds: f64,
+) -> Option<data_structure::particle::VariablePart> {
+    match motion_mode {
+        MotionMode::Free => None,
+        MotionMode::Fixed {
+            horizontal_coordinate,
+            vertical_coordinate,
+        } => Some(new_variables(
+            (*horizontal_coordinate, *vertical_coordinate),
+            (0.0, 0.0),
+        )),
+        MotionMode::Keyframes(keyframes) => variables_between_keyframes(keyframes, time_in_seconds),
+        MotionMode::Ellipse(elliptical_path) => {
+            Some(variables_on_ellipse(elliptical_path, time_in_seconds))
+        }
+    }
+}
+
+pub fn has_prescribed_motion(motion_modes: &[MotionMode]) -> bool {
+    motion_modes
+        .iter()
+        .any(|motion_mode| *motion_mode != MotionMode::Free)
+}
+
+/// This puts the particle where its motion mode prescribes at the given time, returning false
+/// without changing the particle if it is free or has no motion mode.
+pub fn impose_on_particle(
+    motion_mode: Option<&MotionMode>,
+    time_in_seconds: f64,
+    particle_in_force_field: &mut impl WritableInForceField,
+) -> bool {
+    match motion_mode.and_then(|motion_mode| prescribed_variables(motion_mode, time_in_seconds)) {
+        Some(prescribed_variables) => {
+            *particle_in_force_field.write_particle_variables() = prescribed_variables;
+            true
+        }
+        None => false,
+    }
+}
+
+/// The motion modes are in the same order as the particles.
+pub fn impose_on_every_particle<ParticleImplementation, ParticleCollection>(
+    motion_modes: &[MotionMode],
+    time_in_seconds: f64,
+    evolving_particles: &mut ParticleCollection,
+) where
+    ParticleImplementation: WritableInForceField,
+    ParticleCollection: SingleAndPairwiseFinite<MutableElement = ParticleImplementation>,
+{
+    let mut motion_modes_in_order = motion_modes.iter();
+    evolving_particles.apply_to_every_single(&mut |particle_in_force_field| {
+        impose_on_particle(
+            motion_modes_in_order.next(),
+            time_in_seconds,
+            particle_in_force_field,
+        );
+    });
+}
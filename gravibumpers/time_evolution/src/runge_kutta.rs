@@ -0,0 +1,946 @@
+/// This module provides an implementation of ParticlesInTimeEvolver which uses the classic
+/// fourth-order Runge-Kutta method to numerically solve the equations of motion. It evaluates the
+/// forces four times per internal time slice, so is more expensive per step than the other
+/// implementations, but its error per step is much smaller, which suits close encounters where
+/// accuracy matters more than conserving the energy over very long times.
+use crate::data_structure::collection::SingleAndPairwiseFinite;
+use crate::data_structure::particle::CollectionInForceField;
+use crate::data_structure::particle::CollectionInForceFieldGenerator;
+use crate::data_structure::particle::IndividualRepresentation;
+use crate::data_structure::particle::WritableInForceField;
+use crate::force_law::PairwiseForceLaw;
+
+/// The weights of the four stages in the final combination, which is divided by their sum of 6.
+const STAGE_WEIGHTS: [f64; 4] = [1.0, 2.0, 2.0, 1.0];
+
+/// The fractions of the whole time interval from the start of the internal slice at which the
+/// second, third, and fourth stages are evaluated.
+const NEXT_STAGE_FRACTIONS: [f64; 3] = [0.5, 0.5, 1.0];
+
+/// Each stage of the method provides an estimate of the velocity (which is the rate of change of
+/// the position) and of the change in velocity over the whole time interval. These are accumulated
+/// with the stage weights for the final combination.
+#[derive(Clone, Copy, Debug)]
+struct WeightedStageSums {
+    velocity_sum: data_structure::velocity::DimensionfulVector,
+    velocity_change_sum: data_structure::velocity::DimensionfulVector,
+}
+
+fn new_zero_sums() -> WeightedStageSums {
+    WeightedStageSums {
+        velocity_sum: data_structure::velocity::DimensionfulVector {
+            horizontal_component: data_structure::velocity::HorizontalUnit(0.0),
+            vertical_component: data_structure::velocity::VerticalUnit(0.0),
+        },
+        velocity_change_sum: data_structure::velocity::DimensionfulVector {
+            horizontal_component: data_structure::velocity::HorizontalUnit(0.0),
+            vertical_component: data_structure::velocity::VerticalUnit(0.0),
+        },
+    }
+}
+
+pub struct FourthOrderRungeKutta<
+    CollectionElement,
+    CollectionGenerator,
+    ForceLaw = crate::force_law::WithSpringBonds<
+        crate::force_law::InExternalFields<
+            crate::force_law::MinimumImage<crate::force_law::ConfiguredForceLaw>,
+        >,
+    >,
+> where
+    CollectionElement: WritableInForceField,
+    CollectionGenerator: CollectionInForceFieldGenerator<MutableElement = CollectionElement>,
+    ForceLaw: PairwiseForceLaw,
+{
+    number_of_internal_slices_per_time_slice: u32,
+    collection_generator: CollectionGenerator,
+
+    phantom_particle_type: std::marker::PhantomData<CollectionElement>,
+    phantom_force_law: std::marker::PhantomData<ForceLaw>,
+}
+
+/// This holds what is needed to evolve the particles from one time slice to the next, including
+/// buffers for the state at the start of each internal slice and the weighted sums of the stages,
+/// which are re-used for every internal slice.
+pub struct FourthOrderRungeKuttaStepper<ForceLaw> {
+    force_law: ForceLaw,
+    number_of_internal_slices_per_time_slice: u32,
+    time_interval_per_internal_slice: data_structure::time::IntervalUnit,
+    states_at_start: std::vec::Vec<data_structure::particle::VariablePart>,
+    weighted_sums: std::vec::Vec<WeightedStageSums>,
+    drag_and_thermostat: Option<super::thermostat::DragAndThermostat>,
+}
+
+impl<ForceLaw> FourthOrderRungeKuttaStepper<ForceLaw>
+where
+    ForceLaw: PairwiseForceLaw,
+{
+    /// This adds the estimates of the current stage (which needs the forces to have been updated to
+    /// the current positions) to the weighted sums, then sets the particle to the state from which
+    /// the next stage should be evaluated, or to the final combination if there is no next stage.
+    fn accumulate_stage_and_prepare_next<T>(
+        time_difference_per_internal_slice: &data_structure::time::IntervalUnit,
+        stage_weight: f64,
+        next_stage_fraction: Option<f64>,
+        state_at_start: &data_structure::particle::VariablePart,
+        weighted_sums: &mut WeightedStageSums,
+        particle_and_force: &mut T,
+    ) where
+        T: WritableInForceField,
+    {
+        let velocity_change = data_structure::velocity_change_from_force(
+            particle_and_force.read_experienced_force(),
+            particle_and_force.read_timestep_over_inertial_mass(),
+        );
+        let particle_variables = particle_and_force.write_particle_variables();
+        let current_velocity = particle_variables.velocity_vector;
+        weighted_sums.velocity_sum = data_structure::velocity::sum_with_scaled_other(
+            &weighted_sums.velocity_sum,
+            &current_velocity,
+            stage_weight,
+        );
+        weighted_sums.velocity_change_sum = data_structure::velocity::sum_with_scaled_other(
+            &weighted_sums.velocity_change_sum,
+            &velocity_change,
+            stage_weight,
+        );
+
+        let (velocity_for_position, velocity_change_fraction, time_fraction) =
+            match next_stage_fraction {
+                Some(time_fraction) => (current_velocity, velocity_change, time_fraction),
+                None => (
+                    weighted_sums.velocity_sum,
+                    weighted_sums.velocity_change_sum,
+                    1.0 / 6.0,
+                ),
+            };
+        *particle_variables = *state_at_start;
+        particle_variables.velocity_vector = data_structure::velocity::sum_with_scaled_other(
+            &state_at_start.velocity_vector,
+            &velocity_change_fraction,
+            time_fraction,
+        );
+        data_structure::increment_position_by_velocity_for_time_interval(
+            &mut particle_variables.position_vector,
+            &velocity_for_position,
+            &data_structure::time::IntervalUnit(
+                time_fraction * time_difference_per_internal_slice.0,
+            ),
+        );
+    }
+}
+
+impl<ForceLaw> super::TimeSliceStepper for FourthOrderRungeKuttaStepper<ForceLaw>
+where
+    ForceLaw: PairwiseForceLaw,
+{
+    fn evolve_to_next_time_slice<EvolvingCollection>(
+        &mut self,
+        evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+        evolving_collection: &mut EvolvingCollection,
+    ) -> Result<(), Box<dyn std::error::Error>>
+    where
+        EvolvingCollection: CollectionInForceField,
+    {
+        let time_interval_per_internal_slice = &self.time_interval_per_internal_slice;
+        let states_at_start = &mut self.states_at_start;
+        let weighted_sums = &mut self.weighted_sums;
+
+        for _ in 0..self.number_of_internal_slices_per_time_slice {
+            // The number of particles can go down between steps if particles merge.
+            let evolving_particles = evolving_collection.access_mutable_elements();
+            let number_of_particles = evolving_particles.get_count();
+            states_at_start.clear();
+            evolving_particles.apply_to_every_single(&mut |particle_with_force| {
+                states_at_start.push(*particle_with_force.read_variables());
+            });
+            weighted_sums.clear();
+            weighted_sums.resize(number_of_particles, new_zero_sums());
+
+            for (stage_index, stage_weight) in STAGE_WEIGHTS.iter().enumerate() {
+                super::update_forces(evolution_configuration, &self.force_law, evolving_particles);
+                let next_stage_fraction = NEXT_STAGE_FRACTIONS.get(stage_index).copied();
+                let mut particle_index = 0;
+                evolving_particles.apply_to_every_single(&mut |particle_with_force| {
+                    Self::accumulate_stage_and_prepare_next(
+                        time_interval_per_internal_slice,
+                        *stage_weight,
+                        next_stage_fraction,
+                        &states_at_start[particle_index],
+                        &mut weighted_sums[particle_index],
+                        particle_with_force,
+                    );
+                    particle_index += 1;
+                });
+            }
+            super::collide_touching_particles(
+                evolution_configuration,
+                time_interval_per_internal_slice,
+                evolving_particles,
+            );
+            super::apply_drag_and_thermostat(
+                &mut self.drag_and_thermostat,
+                time_interval_per_internal_slice,
+                evolving_particles,
+            );
+            super::apply_boundary_conditions(evolution_configuration, evolving_particles);
+            super::merge_close_particles(evolution_configuration, evolving_collection)?;
+        }
+        super::finish_time_slice_of_thermostat(&mut self.drag_and_thermostat);
+        Ok(())
+    }
+}
+
+impl<CollectionElement, CollectionGenerator, ForceLaw> super::ParticlesInTimeEvolver
+    for FourthOrderRungeKutta<CollectionElement, CollectionGenerator, ForceLaw>
+where
+    CollectionElement: WritableInForceField,
+    CollectionGenerator: CollectionInForceFieldGenerator<MutableElement = CollectionElement>,
+    ForceLaw: PairwiseForceLaw,
+{
+    type ForceLaw = ForceLaw;
+    type EmittedParticle = data_structure::particle::BasicIndividual;
+    type ParticleIterator = std::vec::IntoIter<Self::EmittedParticle>;
+    type IteratorIterator = super::LazyTimeSequence<
+        CollectionGenerator::CreatedCollection,
+        FourthOrderRungeKuttaStepper<ForceLaw>,
+    >;
+
+    fn create_time_sequence(
+        &mut self,
+        evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+        initial_conditions: impl std::iter::ExactSizeIterator<
+            Item = impl data_structure::particle::IndividualRepresentation,
+        >,
+    ) -> Result<
+        super::ParticleSetEvolution<
+            Self::EmittedParticle,
+            Self::ParticleIterator,
+            Self::IteratorIterator,
+        >,
+        Box<dyn std::error::Error>,
+    > {
+        if evolution_configuration.dead_zone_radius <= 0.0 {
+            return Err(Box::new(super::ParameterError::new(
+                "Dead zone radius must be > 0.",
+            )));
+        }
+        super::reject_prescribed_motion(evolution_configuration)?;
+        let force_law = ForceLaw::new_from_configuration(evolution_configuration)?;
+
+        let seconds_between_configurations = (evolution_configuration.milliseconds_per_time_slice
+            as f64)
+            * configuration_parsing::SECONDS_PER_MILLISECOND;
+
+        // The calculation uses a smaller time interval than the output time difference between the
+        // configurations.
+        let time_interval_per_internal_slice = data_structure::time::IntervalUnit(
+            seconds_between_configurations / (self.number_of_internal_slices_per_time_slice as f64),
+        );
+        let evolving_particles = super::create_particles_in_force_field(
+            &self.collection_generator,
+            initial_conditions,
+            &time_interval_per_internal_slice,
+        )?;
+
+        let drag_and_thermostat =
+            super::thermostat::DragAndThermostat::new_from_configuration(evolution_configuration);
+
+        Ok(super::ParticleSetEvolution {
+            particle_configurations: super::new_lazy_time_sequence(
+                evolving_particles,
+                FourthOrderRungeKuttaStepper {
+                    force_law,
+                    number_of_internal_slices_per_time_slice: self
+                        .number_of_internal_slices_per_time_slice,
+                    time_interval_per_internal_slice,
+                    states_at_start: vec![],
+                    weighted_sums: vec![],
+                    drag_and_thermostat,
+                },
+                evolution_configuration,
+            ),
+            milliseconds_between_configurations: evolution_configuration
+                .milliseconds_per_time_slice,
+        })
+    }
+}
+
+pub fn new_given_memory_strategy<CollectionElement, CollectionGenerator>(
+    number_of_internal_slices_per_time_slice: u32,
+    collection_generator: CollectionGenerator,
+) -> Result<FourthOrderRungeKutta<CollectionElement, CollectionGenerator>, Box<dyn std::error::Error>>
+where
+    CollectionElement: WritableInForceField,
+    CollectionGenerator: CollectionInForceFieldGenerator<MutableElement = CollectionElement>,
+{
+    new_given_memory_strategy_and_force_law(
+        number_of_internal_slices_per_time_slice,
+        collection_generator,
+    )
+}
+
+/// The force law is created from the evolution configuration given to each call of
+/// create_time_sequence.
+pub fn new_given_memory_strategy_and_force_law<CollectionElement, CollectionGenerator, ForceLaw>(
+    number_of_internal_slices_per_time_slice: u32,
+    collection_generator: CollectionGenerator,
+) -> Result<
+    FourthOrderRungeKutta<CollectionElement, CollectionGenerator, ForceLaw>,
+    Box<dyn std::error::Error>,
+>
+where
+    CollectionElement: WritableInForceField,
+    CollectionGenerator: CollectionInForceFieldGenerator<MutableElement = CollectionElement>,
+    ForceLaw: PairwiseForceLaw,
+{
+    if number_of_internal_slices_per_time_slice == 0 {
+        Err(Box::new(super::ParameterError::new(
+            "Number of internal slices between displayed slices must be > 0.",
+        )))
+    } else {
+        Ok(FourthOrderRungeKutta {
+            number_of_internal_slices_per_time_slice,
+            collection_generator,
+            phantom_particle_type: std::marker::PhantomData,
+            phantom_force_law: std::marker::PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_functions as evolver_tests;
+    use super::*;
+    use data_structure::particle::contiguous_struct as contiguous_particle_struct;
+    use data_structure::particle::mixture as particle_mixture;
+    use data_structure::particle::struct_of_boxes as particle_struct_of_boxes;
+
+    const TEST_DEAD_ZONE_RADIUS: data_structure::position::SeparationUnit =
+        data_structure::position::SeparationUnit(1.0);
+
+    // The fourth-order Runge-Kutta method is accurate enough for each step that the energy checks
+    // can use a much tighter tolerance than the default.
+    const TEST_ENERGY_TOLERANCE: f64 = 0.000001;
+
+    fn new_maximally_contiguous_for_test() -> Result<
+        FourthOrderRungeKutta<
+            contiguous_particle_struct::MassNormalizedWithForceField,
+            contiguous_particle_struct::VectorOfMassNormalizedWithForceFieldGenerator,
+        >,
+        String,
+    > {
+        new_given_memory_strategy(
+            100,
+            contiguous_particle_struct::VectorOfMassNormalizedWithForceFieldGenerator {},
+        )
+        .map_err(|construction_error| {
+            format!(
+                "Constructor error in new_maximally_contiguous_for_test: {:?}",
+                construction_error
+            )
+        })
+    }
+
+    fn new_contiguous_pointers_for_test() -> Result<
+        FourthOrderRungeKutta<
+            std::boxed::Box<dyn data_structure::particle::WritableInForceField>,
+            contiguous_particle_struct::VectorOfDynamicBoxedMassNormalizedWithForceFieldGenerator,
+        >,
+        String,
+    > {
+        new_given_memory_strategy(
+            100,
+            contiguous_particle_struct::VectorOfDynamicBoxedMassNormalizedWithForceFieldGenerator {},
+        )
+        .map_err(|construction_error| {
+            format!(
+                "Constructor error in new_contiguous_pointers_for_test: {:?}",
+                construction_error
+            )
+        })
+    }
+
+    fn new_double_boxed_for_test() -> Result<
+        FourthOrderRungeKutta<
+            std::boxed::Box<dyn data_structure::particle::WritableInForceField>,
+            particle_struct_of_boxes::VectorOfDynamicBoxedMassNormalizedBoxesWithForceFieldGenerator,
+        >,
+        String,
+    >{
+        new_given_memory_strategy(
+            100,
+            particle_struct_of_boxes::VectorOfDynamicBoxedMassNormalizedBoxesWithForceFieldGenerator {},
+        )
+        .map_err(|construction_error| {
+            format!(
+                "Constructor error in new_double_boxed_for_test: {:?}",
+                construction_error
+            )
+        })
+    }
+
+    fn new_mixed_boxed_for_test() -> Result<
+        FourthOrderRungeKutta<
+            std::boxed::Box<dyn data_structure::particle::WritableInForceField>,
+            particle_mixture::VectorOfDynamicBoxedMassNormalizedStructsAndBoxesGenerator,
+        >,
+        String,
+    > {
+        new_given_memory_strategy(
+            100,
+            particle_mixture::VectorOfDynamicBoxedMassNormalizedStructsAndBoxesGenerator {},
+        )
+        .map_err(|construction_error| {
+            format!(
+                "Constructor error in new_mixed_boxed_for_test: {:?}",
+                construction_error
+            )
+        })
+    }
+
+    #[test]
+    fn test_single_particle_at_rest_stays_at_rest_with_maximally_contiguous() -> Result<(), String>
+    {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_single_particle_at_rest_stays_at_rest(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_single_particle_at_rest_stays_at_rest_with_contiguous_pointers() -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_single_particle_at_rest_stays_at_rest(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_single_particle_at_rest_stays_at_rest_with_double_boxed() -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_single_particle_at_rest_stays_at_rest(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_single_particle_at_rest_stays_at_rest_with_mixed_boxed() -> Result<(), String> {
+        let mut evolver_implementation = new_mixed_boxed_for_test()?;
+        evolver_tests::test_single_particle_at_rest_stays_at_rest(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_single_particle_at_constant_speed_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_single_particle_at_constant_speed(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_single_particle_at_constant_speed_with_contiguous_pointers() -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_single_particle_at_constant_speed(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_single_particle_at_constant_speed_with_double_boxed() -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_single_particle_at_constant_speed(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_single_particle_at_constant_speed_with_mixed_boxed() -> Result<(), String> {
+        let mut evolver_implementation = new_mixed_boxed_for_test()?;
+        evolver_tests::test_single_particle_at_constant_speed(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_uncharged_particles_do_not_accelerate_with_maximally_contiguous() -> Result<(), String>
+    {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_uncharged_particles_do_not_accelerate(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_uncharged_particles_do_not_accelerate_with_contiguous_pointers() -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_uncharged_particles_do_not_accelerate(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_uncharged_particles_do_not_accelerate_with_double_boxed() -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_uncharged_particles_do_not_accelerate(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_uncharged_particles_do_not_accelerate_with_mixed_boxed() -> Result<(), String> {
+        let mut evolver_implementation = new_mixed_boxed_for_test()?;
+        evolver_tests::test_uncharged_particles_do_not_accelerate(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_immobile_repelling_particles_within_dead_zone_stay_at_rest_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_immobile_repelling_particles_within_dead_zone_stay_at_rest(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+        )
+    }
+
+    #[test]
+    fn test_immobile_repelling_particles_within_dead_zone_stay_at_rest_with_contiguous_pointers(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_immobile_repelling_particles_within_dead_zone_stay_at_rest(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+        )
+    }
+
+    #[test]
+    fn test_immobile_repelling_particles_within_dead_zone_stay_at_rest_with_double_boxed(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_immobile_repelling_particles_within_dead_zone_stay_at_rest(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+        )
+    }
+
+    #[test]
+    fn test_immobile_repelling_particles_within_dead_zone_stay_at_rest_with_mixed_boxed(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_mixed_boxed_for_test()?;
+        evolver_tests::test_immobile_repelling_particles_within_dead_zone_stay_at_rest(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_fourth_critical_escape_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_fourth_critical_escape(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_fourth_critical_escape_with_contiguous_pointers(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_fourth_critical_escape(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_fourth_critical_escape_with_double_boxed(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_fourth_critical_escape(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_fourth_critical_escape_with_mixed_boxed(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_mixed_boxed_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_fourth_critical_escape(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_repelling_inverse_fourth_accelerate_away_equally_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_equal_masses_repelling_inverse_fourth_accelerate_away_equally(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_repelling_inverse_fourth_accelerate_away_equally_with_contiguous_pointers(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_equal_masses_repelling_inverse_fourth_accelerate_away_equally(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_repelling_inverse_fourth_accelerate_away_equally_with_double_boxed(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_equal_masses_repelling_inverse_fourth_accelerate_away_equally(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_repelling_inverse_fourth_accelerate_away_equally_with_mixed_boxed(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_mixed_boxed_for_test()?;
+        evolver_tests::test_equal_masses_repelling_inverse_fourth_accelerate_away_equally(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_square_critical_escape_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_square_critical_escape(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_square_critical_escape_with_contiguous_pointers(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_square_critical_escape(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_square_critical_escape_with_double_boxed(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_square_critical_escape(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_square_critical_escape_with_mixed_boxed(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_mixed_boxed_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_square_critical_escape(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_square_circular_orbit_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_square_circular_orbit(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_square_circular_orbit_with_contiguous_pointers(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_square_circular_orbit(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_square_circular_orbit_with_double_boxed(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_square_circular_orbit(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_square_circular_orbit_with_mixed_boxed(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_mixed_boxed_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_square_circular_orbit(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_triangle_at_cancelling_forces_is_stable_with_maximally_contiguous() -> Result<(), String>
+    {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_triangle_at_cancelling_forces_is_stable(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+        )
+    }
+
+    #[test]
+    fn test_triangle_at_cancelling_forces_is_stable_with_contiguous_pointers() -> Result<(), String>
+    {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_triangle_at_cancelling_forces_is_stable(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+        )
+    }
+
+    #[test]
+    fn test_triangle_at_cancelling_forces_is_stable_with_double_boxed() -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_triangle_at_cancelling_forces_is_stable(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+        )
+    }
+
+    #[test]
+    fn test_triangle_at_cancelling_forces_is_stable_with_mixed_boxed() -> Result<(), String> {
+        let mut evolver_implementation = new_mixed_boxed_for_test()?;
+        evolver_tests::test_triangle_at_cancelling_forces_is_stable(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+        )
+    }
+
+    #[test]
+    fn test_approximate_harmonic_oscillator_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_approximate_harmonic_oscillator(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_approximate_harmonic_oscillator_with_contiguous_pointers() -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_approximate_harmonic_oscillator(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_approximate_harmonic_oscillator_with_double_boxed() -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_approximate_harmonic_oscillator(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_approximate_harmonic_oscillator_with_mixed_boxed() -> Result<(), String> {
+        let mut evolver_implementation = new_mixed_boxed_for_test()?;
+        evolver_tests::test_approximate_harmonic_oscillator(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            TEST_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_evolution_resumed_from_time_slice_matches_uninterrupted_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_evolution_resumed_from_time_slice_matches_uninterrupted(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_evolution_with_thermostat_resumed_from_time_slice_matches_uninterrupted_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_evolution_with_thermostat_resumed_from_time_slice_matches_uninterrupted(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_plummer_softened_attraction_passes_through_and_conserves_energy_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_softened_attraction_passes_through_and_conserves_energy(
+            &mut evolver_implementation,
+            configuration_parsing::SofteningKernel::Plummer,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_spline_softened_attraction_passes_through_and_conserves_energy_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_softened_attraction_passes_through_and_conserves_energy(
+            &mut evolver_implementation,
+            configuration_parsing::SofteningKernel::Spline,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_lennard_jones_pair_conserves_energy_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_lennard_jones_pair_conserves_energy(
+            &mut evolver_implementation,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_yukawa_pair_conserves_energy_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_yukawa_pair_conserves_energy(
+            &mut evolver_implementation,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_free_particle_bounces_off_reflecting_walls_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_free_particle_kept_in_box(
+            &mut evolver_implementation,
+            configuration_parsing::BoundaryKind::Reflecting,
+        )
+    }
+
+    #[test]
+    fn test_free_particle_wraps_round_periodic_box_with_maximally_contiguous() -> Result<(), String>
+    {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_free_particle_kept_in_box(
+            &mut evolver_implementation,
+            configuration_parsing::BoundaryKind::Periodic,
+        )
+    }
+
+    #[test]
+    fn test_particle_falls_through_tidal_trap_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_particle_falls_through_tidal_trap(
+            &mut evolver_implementation,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_particle_falls_towards_fixed_source_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_particle_falls_towards_fixed_source(
+            &mut evolver_implementation,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_drag_slows_free_particle_exponentially_with_maximally_contiguous() -> Result<(), String>
+    {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_drag_slows_free_particle_exponentially(
+            &mut evolver_implementation,
+            0.01,
+        )
+    }
+
+    #[test]
+    fn test_thermostat_brings_kinetic_temperature_to_target_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_thermostat_brings_kinetic_temperature_to_target(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_thermostat_kicks_repeat_for_same_seed_with_maximally_contiguous() -> Result<(), String>
+    {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_thermostat_kicks_repeat_for_same_seed(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_approaching_particles_merge_at_center_of_mass_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_approaching_particles_merge_at_center_of_mass(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_head_on_collision_keeps_momentum_and_scales_approach_speed_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_head_on_collision_keeps_momentum_and_scales_approach_speed(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_glancing_elastic_collision_deflects_at_right_angles_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_glancing_elastic_collision_deflects_at_right_angles(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_bonded_pair_oscillates_at_spring_frequency_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_bonded_pair_oscillates_at_spring_frequency(
+            &mut evolver_implementation,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_damped_bonded_pair_rings_down_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_damped_bonded_pair_rings_down(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_prescribed_motion_rejected_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_prescribed_motion_rejected(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_tracers_feel_forces_but_exert_none_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_tracers_feel_forces_but_exert_none(&mut evolver_implementation)
+    }
+}
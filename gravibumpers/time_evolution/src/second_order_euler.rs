@@ -1,100 +1,65 @@
 /// This module provides an implementation of ParticlesInTimeEvolver which uses the Euler method to
 /// second order on the positions (assuming a constant force over the timestep) to numerically solve
-/// the equations of motion.
+/// the equations of motion. It is the evolver which honours the motion modes of the particles, so
+/// fixed and scripted particles follow their prescribed motion while still exerting forces.
+use crate::data_structure::collection::SingleAndPairwiseFinite;
 use crate::data_structure::particle::CollectionInForceField;
 use crate::data_structure::particle::CollectionInForceFieldGenerator;
 use crate::data_structure::particle::WritableInForceField;
+use crate::force_law::PairwiseForceLaw;
 
-pub struct SecondOrderEuler<CollectionElement, CollectionGenerator>
-where
+pub struct SecondOrderEuler<
+    CollectionElement,
+    CollectionGenerator,
+    ForceLaw = crate::force_law::WithSpringBonds<
+        crate::force_law::InExternalFields<
+            crate::force_law::MinimumImage<crate::force_law::ConfiguredForceLaw>,
+        >,
+    >,
+> where
     CollectionElement: WritableInForceField,
     CollectionGenerator: CollectionInForceFieldGenerator<MutableElement = CollectionElement>,
+    ForceLaw: PairwiseForceLaw,
 {
     number_of_internal_slices_per_time_slice: u32,
     collection_generator: CollectionGenerator,
 
     phantom_particle_type: std::marker::PhantomData<CollectionElement>,
+    phantom_force_law: std::marker::PhantomData<ForceLaw>,
+}
+
+/// This holds what is needed to evolve the particles from one time slice to the next.
+pub struct SecondOrderEulerStepper<ForceLaw> {
+    force_law: ForceLaw,
+    number_of_internal_slices_per_time_slice: u32,
+    time_interval_per_internal_slice: data_structure::time::IntervalUnit,
+    drag_and_thermostat: Option<super::thermostat::DragAndThermostat>,
+    motion_modes: std::vec::Vec<configuration_parsing::MotionMode>,
+    completed_internal_slices: u64,
 }
 
-impl<CollectionElement, CollectionGenerator>
-    SecondOrderEuler<CollectionElement, CollectionGenerator>
+impl<ForceLaw> SecondOrderEulerStepper<ForceLaw>
 where
-    CollectionElement: WritableInForceField,
-    CollectionGenerator: CollectionInForceFieldGenerator<MutableElement = CollectionElement>,
+    ForceLaw: PairwiseForceLaw,
 {
-    fn create_particles_in_force_field(
-        &self,
-        initial_conditions: impl std::iter::ExactSizeIterator<
-            Item = impl data_structure::particle::IndividualRepresentation,
-        >,
-        time_interval_per_internal_slice: &data_structure::time::IntervalUnit,
-    ) -> Result<CollectionGenerator::CreatedCollection, Box<dyn std::error::Error>> {
-        let mut evolving_particles = self.collection_generator.create_collection();
-        let mut initial_condition_errors: std::vec::Vec<(usize, Box<dyn std::error::Error>)> =
-            vec![];
-        for (initial_particle_index, initial_particle) in initial_conditions.enumerate() {
-            match data_structure::time::divide_time_by_mass(
-                time_interval_per_internal_slice,
-                &initial_particle.read_intrinsics().inertial_mass,
-            ) {
-                Ok(time_over_mass) => {
-                    evolving_particles.add_particle(&initial_particle, &time_over_mass)
-                }
-                Err(initial_condition_error) => {
-                    initial_condition_errors.push((initial_particle_index, initial_condition_error))
-                }
-            };
-        }
-
-        if !initial_condition_errors.is_empty() {
-            return Err(Box::new(super::EvolutionError::new(&format!(
-                "The following initial particles could not be set up for time evolution: {:?}",
-                initial_condition_errors
-            ))));
-        }
-
-        Ok(evolving_particles)
-    }
-
-    fn update_forces<ParticleImplementation, ParticleCollection>(
-        evolution_configuration: &configuration_parsing::EvolutionConfiguration,
-        particles_with_forces: &mut ParticleCollection,
-    ) where
-        ParticleImplementation: WritableInForceField,
-        ParticleCollection: data_structure::collection::SingleAndPairwiseFinite<
-            MutableElement = ParticleImplementation,
-        >,
-    {
-        // First all the forces must be set to zero so that we can aggregate the pairwise forces.
-        particles_with_forces.apply_to_every_single(&mut |particle_with_force| {
-            let mut force_on_particle = particle_with_force.write_experienced_force();
-            force_on_particle.horizontal_component = data_structure::force::HorizontalUnit(0.0);
-            force_on_particle.vertical_component = data_structure::force::VerticalUnit(0.0);
-        });
-        particles_with_forces.apply_to_every_pair(
-            &mut |first_particle, second_particle| {
-                super::force_on_first_particle_from_second_particle(
-                    evolution_configuration,
-                    first_particle,
-                    second_particle,
-                )
-            },
-            &mut |first_particle, force_on_first| {
-                *first_particle.write_experienced_force() += *force_on_first;
-            },
-            &mut |second_particle, force_on_first| {
-                *second_particle.write_experienced_force() -= *force_on_first;
-            },
-        )
-    }
-
-    /// This updates the velocity and position assuming a constant acceleration for the time interval.
+    /// This updates the velocity and position assuming a constant acceleration for the time interval,
+    /// unless the motion mode of the particle prescribes where it should be at the end of the
+    /// interval.
     fn update_velocity_and_position<T>(
         time_difference_per_internal_slice: &data_structure::time::IntervalUnit,
+        motion_mode: Option<&configuration_parsing::MotionMode>,
+        time_at_end_of_slice: f64,
         particle_and_force: &mut T,
     ) where
         T: WritableInForceField,
     {
+        if super::prescribed_motion::impose_on_particle(
+            motion_mode,
+            time_at_end_of_slice,
+            particle_and_force,
+        ) {
+            return;
+        }
         let velocity_difference = data_structure::velocity_change_from_force(
             particle_and_force.read_experienced_force(),
             particle_and_force.read_timestep_over_inertial_mass(),
@@ -112,67 +77,78 @@ where
             &time_difference_per_internal_slice,
         );
     }
+}
 
-    fn evolve_particle_configuration<ParticleImplementation, ParticleCollection>(
+impl<ForceLaw> super::TimeSliceStepper for SecondOrderEulerStepper<ForceLaw>
+where
+    ForceLaw: PairwiseForceLaw,
+{
+    fn evolve_to_next_time_slice<EvolvingCollection>(
+        &mut self,
         evolution_configuration: &configuration_parsing::EvolutionConfiguration,
-        evolving_particles: &mut ParticleCollection,
-        number_of_internal_slices_per_time_slice: u32,
-        time_interval_per_internal_slice: &data_structure::time::IntervalUnit,
-    ) -> std::vec::Vec<std::vec::IntoIter<data_structure::particle::BasicIndividual>>
+        evolving_collection: &mut EvolvingCollection,
+    ) -> Result<(), Box<dyn std::error::Error>>
     where
-        ParticleImplementation: WritableInForceField,
-        ParticleCollection: data_structure::collection::SingleAndPairwiseFinite<
-            MutableElement = ParticleImplementation,
-        >,
+        EvolvingCollection: CollectionInForceField,
     {
-        let mut evaluations_at_time_slices: std::vec::Vec<
-            std::vec::IntoIter<data_structure::particle::BasicIndividual>,
-        > = std::vec::Vec::with_capacity(evolution_configuration.number_of_time_slices);
-
-        let mut initial_time_slice_without_force =
-            std::vec::Vec::<data_structure::particle::BasicIndividual>::with_capacity(
-                evolving_particles.get_count(),
-            );
-        evolving_particles.apply_to_every_single(&mut |particle_with_force| {
-            initial_time_slice_without_force.push(particle_with_force.into_individual_particle());
-        });
-        evaluations_at_time_slices.push(initial_time_slice_without_force.into_iter());
-
-        for _ in 1..evolution_configuration.number_of_time_slices {
-            for _ in 0..number_of_internal_slices_per_time_slice {
-                Self::update_forces(evolution_configuration, evolving_particles);
-
-                evolving_particles.apply_to_every_single(&mut |particle_with_force| {
-                    Self::update_velocity_and_position(
-                        time_interval_per_internal_slice,
-                        particle_with_force,
-                    )
-                });
-            }
+        let time_interval_per_internal_slice = &self.time_interval_per_internal_slice;
+        let motion_modes = &self.motion_modes;
+        let has_prescribed_motion = super::prescribed_motion::has_prescribed_motion(motion_modes);
+        for _ in 0..self.number_of_internal_slices_per_time_slice {
+            self.completed_internal_slices += 1;
+            let time_at_end_of_slice =
+                (self.completed_internal_slices as f64) * time_interval_per_internal_slice.0;
+            let evolving_particles = evolving_collection.access_mutable_elements();
+            super::update_forces(evolution_configuration, &self.force_law, evolving_particles);
 
-            let mut current_time_slice_without_force =
-                std::vec::Vec::<data_structure::particle::BasicIndividual>::with_capacity(
-                    evolving_particles.get_count(),
-                );
+            let mut motion_modes_in_order = motion_modes.iter();
             evolving_particles.apply_to_every_single(&mut |particle_with_force| {
-                current_time_slice_without_force
-                    .push(particle_with_force.into_individual_particle());
+                Self::update_velocity_and_position(
+                    time_interval_per_internal_slice,
+                    motion_modes_in_order.next(),
+                    time_at_end_of_slice,
+                    particle_with_force,
+                )
             });
-            evaluations_at_time_slices.push(current_time_slice_without_force.into_iter());
+            super::collide_touching_particles(
+                evolution_configuration,
+                time_interval_per_internal_slice,
+                evolving_particles,
+            );
+            super::apply_drag_and_thermostat(
+                &mut self.drag_and_thermostat,
+                time_interval_per_internal_slice,
+                evolving_particles,
+            );
+            super::apply_boundary_conditions(evolution_configuration, evolving_particles);
+            if has_prescribed_motion {
+                super::prescribed_motion::impose_on_every_particle(
+                    motion_modes,
+                    time_at_end_of_slice,
+                    evolving_particles,
+                );
+            }
+            super::merge_close_particles(evolution_configuration, evolving_collection)?;
         }
-        evaluations_at_time_slices
+        super::finish_time_slice_of_thermostat(&mut self.drag_and_thermostat);
+        Ok(())
     }
 }
 
-impl<CollectionElement, CollectionGenerator> super::ParticlesInTimeEvolver
-    for SecondOrderEuler<CollectionElement, CollectionGenerator>
+impl<CollectionElement, CollectionGenerator, ForceLaw> super::ParticlesInTimeEvolver
+    for SecondOrderEuler<CollectionElement, CollectionGenerator, ForceLaw>
 where
     CollectionElement: WritableInForceField,
     CollectionGenerator: CollectionInForceFieldGenerator<MutableElement = CollectionElement>,
+    ForceLaw: PairwiseForceLaw,
 {
+    type ForceLaw = ForceLaw;
     type EmittedParticle = data_structure::particle::BasicIndividual;
     type ParticleIterator = std::vec::IntoIter<Self::EmittedParticle>;
-    type IteratorIterator = std::vec::IntoIter<Self::ParticleIterator>;
+    type IteratorIterator = super::LazyTimeSequence<
+        CollectionGenerator::CreatedCollection,
+        SecondOrderEulerStepper<ForceLaw>,
+    >;
 
     fn create_time_sequence(
         &mut self,
@@ -193,14 +169,21 @@ where
                 "Dead zone radius must be > 0.",
             )));
         }
-
-        if evolution_configuration.number_of_time_slices < 1 {
-            return Ok(super::ParticleSetEvolution {
-                particle_configurations: vec![].into_iter(),
-                milliseconds_between_configurations: evolution_configuration
-                    .milliseconds_per_time_slice,
-            });
+        if super::prescribed_motion::has_prescribed_motion(&evolution_configuration.motion_modes) {
+            if evolution_configuration.motion_modes.len() != initial_conditions.len() {
+                return Err(Box::new(super::ParameterError::new(&format!(
+                    "There are {} motion modes for {} particles",
+                    evolution_configuration.motion_modes.len(),
+                    initial_conditions.len()
+                ))));
+            }
+            if evolution_configuration.merging_radius.is_some() {
+                return Err(Box::new(super::ParameterError::new(
+                    "Motion modes cannot be used with a merging radius",
+                )));
+            }
         }
+        let force_law = ForceLaw::new_from_configuration(evolution_configuration)?;
 
         let seconds_between_configurations = (evolution_configuration.milliseconds_per_time_slice
             as f64)
@@ -211,19 +194,29 @@ where
         let time_interval_per_internal_slice = data_structure::time::IntervalUnit(
             seconds_between_configurations / (self.number_of_internal_slices_per_time_slice as f64),
         );
-        let mut evolving_particles = self.create_particles_in_force_field(
+        let evolving_particles = super::create_particles_in_force_field(
+            &self.collection_generator,
             initial_conditions,
             &time_interval_per_internal_slice,
         )?;
-        let time_slices_without_forces = Self::evolve_particle_configuration(
-            evolution_configuration,
-            evolving_particles.access_mutable_elements(),
-            self.number_of_internal_slices_per_time_slice,
-            &time_interval_per_internal_slice,
-        );
+
+        let drag_and_thermostat =
+            super::thermostat::DragAndThermostat::new_from_configuration(evolution_configuration);
 
         Ok(super::ParticleSetEvolution {
-            particle_configurations: time_slices_without_forces.into_iter(),
+            particle_configurations: super::new_lazy_time_sequence(
+                evolving_particles,
+                SecondOrderEulerStepper {
+                    force_law,
+                    number_of_internal_slices_per_time_slice: self
+                        .number_of_internal_slices_per_time_slice,
+                    time_interval_per_internal_slice,
+                    drag_and_thermostat,
+                    motion_modes: evolution_configuration.motion_modes.clone(),
+                    completed_internal_slices: 0,
+                },
+                evolution_configuration,
+            ),
             milliseconds_between_configurations: evolution_configuration
                 .milliseconds_per_time_slice,
         })
@@ -237,6 +230,26 @@ pub fn new_given_memory_strategy<CollectionElement, CollectionGenerator>(
 where
     CollectionElement: WritableInForceField,
     CollectionGenerator: CollectionInForceFieldGenerator<MutableElement = CollectionElement>,
+{
+    new_given_memory_strategy_and_force_law(
+        number_of_internal_slices_per_time_slice,
+        collection_generator,
+    )
+}
+
+/// The force law is created from the evolution configuration given to each call of
+/// create_time_sequence.
+pub fn new_given_memory_strategy_and_force_law<CollectionElement, CollectionGenerator, ForceLaw>(
+    number_of_internal_slices_per_time_slice: u32,
+    collection_generator: CollectionGenerator,
+) -> Result<
+    SecondOrderEuler<CollectionElement, CollectionGenerator, ForceLaw>,
+    Box<dyn std::error::Error>,
+>
+where
+    CollectionElement: WritableInForceField,
+    CollectionGenerator: CollectionInForceFieldGenerator<MutableElement = CollectionElement>,
+    ForceLaw: PairwiseForceLaw,
 {
     if number_of_internal_slices_per_time_slice == 0 {
         Err(Box::new(super::ParameterError::new(
@@ -247,6 +260,7 @@ where
             number_of_internal_slices_per_time_slice: number_of_internal_slices_per_time_slice,
             collection_generator: collection_generator,
             phantom_particle_type: std::marker::PhantomData,
+            phantom_force_law: std::marker::PhantomData,
         })
     }
 }
@@ -256,8 +270,11 @@ mod tests {
     use super::super::test_functions as evolver_tests;
     use super::*;
     use data_structure::particle::contiguous_struct as contiguous_particle_struct;
+    use data_structure::particle::struct_of_arrays as particle_struct_of_arrays;
     use data_structure::particle::struct_of_boxes as particle_struct_of_boxes;
 
+    const TEST_NUMBER_OF_THREADS: usize = 4;
+
     const TEST_DEAD_ZONE_RADIUS: data_structure::position::SeparationUnit =
         data_structure::position::SeparationUnit(1.0);
 
@@ -318,6 +335,46 @@ mod tests {
         })
     }
 
+    fn new_multithreaded_for_test() -> Result<
+        SecondOrderEuler<
+            contiguous_particle_struct::MassNormalizedWithForceField,
+            contiguous_particle_struct::MultithreadedVectorOfMassNormalizedWithForceFieldGenerator,
+        >,
+        String,
+    > {
+        new_given_memory_strategy(
+            100,
+            contiguous_particle_struct::MultithreadedVectorOfMassNormalizedWithForceFieldGenerator {
+                number_of_threads: TEST_NUMBER_OF_THREADS,
+            },
+        )
+        .map_err(|construction_error| {
+            format!(
+                "Constructor error in new_multithreaded_for_test: {:?}",
+                construction_error
+            )
+        })
+    }
+
+    fn new_struct_of_arrays_for_test() -> Result<
+        SecondOrderEuler<
+            contiguous_particle_struct::MassNormalizedWithForceField,
+            particle_struct_of_arrays::ParticleArraysWithForceFieldGenerator,
+        >,
+        String,
+    > {
+        new_given_memory_strategy(
+            100,
+            particle_struct_of_arrays::ParticleArraysWithForceFieldGenerator {},
+        )
+        .map_err(|construction_error| {
+            format!(
+                "Constructor error in new_struct_of_arrays_for_test: {:?}",
+                construction_error
+            )
+        })
+    }
+
     #[test]
     fn test_single_particle_at_rest_stays_at_rest_with_maximally_contiguous() -> Result<(), String>
     {
@@ -411,6 +468,7 @@ mod tests {
         evolver_tests::test_equal_masses_attracting_inverse_fourth_critical_escape(
             &mut evolver_implementation,
             &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
         )
     }
 
@@ -421,6 +479,7 @@ mod tests {
         evolver_tests::test_equal_masses_attracting_inverse_fourth_critical_escape(
             &mut evolver_implementation,
             &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
         )
     }
 
@@ -431,6 +490,7 @@ mod tests {
         evolver_tests::test_equal_masses_attracting_inverse_fourth_critical_escape(
             &mut evolver_implementation,
             &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
         )
     }
 
@@ -441,6 +501,7 @@ mod tests {
         evolver_tests::test_equal_masses_repelling_inverse_fourth_accelerate_away_equally(
             &mut evolver_implementation,
             &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
         )
     }
 
@@ -451,6 +512,7 @@ mod tests {
         evolver_tests::test_equal_masses_repelling_inverse_fourth_accelerate_away_equally(
             &mut evolver_implementation,
             &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
         )
     }
 
@@ -461,6 +523,7 @@ mod tests {
         evolver_tests::test_equal_masses_repelling_inverse_fourth_accelerate_away_equally(
             &mut evolver_implementation,
             &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
         )
     }
 
@@ -471,6 +534,7 @@ mod tests {
         evolver_tests::test_equal_masses_attracting_inverse_square_critical_escape(
             &mut evolver_implementation,
             &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
         )
     }
 
@@ -481,6 +545,7 @@ mod tests {
         evolver_tests::test_equal_masses_attracting_inverse_square_critical_escape(
             &mut evolver_implementation,
             &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
         )
     }
 
@@ -491,6 +556,7 @@ mod tests {
         evolver_tests::test_equal_masses_attracting_inverse_square_critical_escape(
             &mut evolver_implementation,
             &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
         )
     }
 
@@ -501,6 +567,7 @@ mod tests {
         evolver_tests::test_equal_masses_attracting_inverse_square_circular_orbit(
             &mut evolver_implementation,
             &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
         )
     }
 
@@ -511,6 +578,7 @@ mod tests {
         evolver_tests::test_equal_masses_attracting_inverse_square_circular_orbit(
             &mut evolver_implementation,
             &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
         )
     }
 
@@ -521,6 +589,7 @@ mod tests {
         evolver_tests::test_equal_masses_attracting_inverse_square_circular_orbit(
             &mut evolver_implementation,
             &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
         )
     }
 
@@ -559,6 +628,7 @@ mod tests {
         evolver_tests::test_approximate_harmonic_oscillator(
             &mut evolver_implementation,
             &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
         )
     }
 
@@ -568,6 +638,7 @@ mod tests {
         evolver_tests::test_approximate_harmonic_oscillator(
             &mut evolver_implementation,
             &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
         )
     }
 
@@ -577,6 +648,537 @@ mod tests {
         evolver_tests::test_approximate_harmonic_oscillator(
             &mut evolver_implementation,
             &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_single_particle_at_rest_stays_at_rest_with_multithreaded() -> Result<(), String> {
+        let mut evolver_implementation = new_multithreaded_for_test()?;
+        evolver_tests::test_single_particle_at_rest_stays_at_rest(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_single_particle_at_constant_speed_with_multithreaded() -> Result<(), String> {
+        let mut evolver_implementation = new_multithreaded_for_test()?;
+        evolver_tests::test_single_particle_at_constant_speed(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_uncharged_particles_do_not_accelerate_with_multithreaded() -> Result<(), String> {
+        let mut evolver_implementation = new_multithreaded_for_test()?;
+        evolver_tests::test_uncharged_particles_do_not_accelerate(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_immobile_repelling_particles_within_dead_zone_stay_at_rest_with_multithreaded(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_multithreaded_for_test()?;
+        evolver_tests::test_immobile_repelling_particles_within_dead_zone_stay_at_rest(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_fourth_critical_escape_with_multithreaded(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_multithreaded_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_fourth_critical_escape(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_repelling_inverse_fourth_accelerate_away_equally_with_multithreaded(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_multithreaded_for_test()?;
+        evolver_tests::test_equal_masses_repelling_inverse_fourth_accelerate_away_equally(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_square_critical_escape_with_multithreaded(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_multithreaded_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_square_critical_escape(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_square_circular_orbit_with_multithreaded(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_multithreaded_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_square_circular_orbit(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_triangle_at_cancelling_forces_is_stable_with_multithreaded() -> Result<(), String> {
+        let mut evolver_implementation = new_multithreaded_for_test()?;
+        evolver_tests::test_triangle_at_cancelling_forces_is_stable(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+        )
+    }
+
+    #[test]
+    fn test_approximate_harmonic_oscillator_with_multithreaded() -> Result<(), String> {
+        let mut evolver_implementation = new_multithreaded_for_test()?;
+        evolver_tests::test_approximate_harmonic_oscillator(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_many_particles_evolve_identically_when_repeated_with_multithreaded(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_multithreaded_for_test()?;
+        evolver_tests::test_many_particles_evolve_identically_when_repeated(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_single_particle_at_rest_stays_at_rest_with_struct_of_arrays() -> Result<(), String> {
+        let mut evolver_implementation = new_struct_of_arrays_for_test()?;
+        evolver_tests::test_single_particle_at_rest_stays_at_rest(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_single_particle_at_constant_speed_with_struct_of_arrays() -> Result<(), String> {
+        let mut evolver_implementation = new_struct_of_arrays_for_test()?;
+        evolver_tests::test_single_particle_at_constant_speed(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_uncharged_particles_do_not_accelerate_with_struct_of_arrays() -> Result<(), String> {
+        let mut evolver_implementation = new_struct_of_arrays_for_test()?;
+        evolver_tests::test_uncharged_particles_do_not_accelerate(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_immobile_repelling_particles_within_dead_zone_stay_at_rest_with_struct_of_arrays(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_struct_of_arrays_for_test()?;
+        evolver_tests::test_immobile_repelling_particles_within_dead_zone_stay_at_rest(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_fourth_critical_escape_with_struct_of_arrays(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_struct_of_arrays_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_fourth_critical_escape(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_repelling_inverse_fourth_accelerate_away_equally_with_struct_of_arrays(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_struct_of_arrays_for_test()?;
+        evolver_tests::test_equal_masses_repelling_inverse_fourth_accelerate_away_equally(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_square_critical_escape_with_struct_of_arrays(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_struct_of_arrays_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_square_critical_escape(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_equal_masses_attracting_inverse_square_circular_orbit_with_struct_of_arrays(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_struct_of_arrays_for_test()?;
+        evolver_tests::test_equal_masses_attracting_inverse_square_circular_orbit(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_triangle_at_cancelling_forces_is_stable_with_struct_of_arrays() -> Result<(), String> {
+        let mut evolver_implementation = new_struct_of_arrays_for_test()?;
+        evolver_tests::test_triangle_at_cancelling_forces_is_stable(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+        )
+    }
+
+    #[test]
+    fn test_approximate_harmonic_oscillator_with_struct_of_arrays() -> Result<(), String> {
+        let mut evolver_implementation = new_struct_of_arrays_for_test()?;
+        evolver_tests::test_approximate_harmonic_oscillator(
+            &mut evolver_implementation,
+            &TEST_DEAD_ZONE_RADIUS,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_evolution_resumed_from_time_slice_matches_uninterrupted_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_evolution_resumed_from_time_slice_matches_uninterrupted(
+            &mut evolver_implementation,
         )
     }
+
+    #[test]
+    fn test_evolution_with_thermostat_resumed_from_time_slice_matches_uninterrupted_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_evolution_with_thermostat_resumed_from_time_slice_matches_uninterrupted(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_plummer_softened_attraction_passes_through_and_conserves_energy_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_softened_attraction_passes_through_and_conserves_energy(
+            &mut evolver_implementation,
+            configuration_parsing::SofteningKernel::Plummer,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_spline_softened_attraction_passes_through_and_conserves_energy_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_softened_attraction_passes_through_and_conserves_energy(
+            &mut evolver_implementation,
+            configuration_parsing::SofteningKernel::Spline,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_lennard_jones_pair_conserves_energy_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_lennard_jones_pair_conserves_energy(
+            &mut evolver_implementation,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_yukawa_pair_conserves_energy_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_yukawa_pair_conserves_energy(
+            &mut evolver_implementation,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_free_particle_bounces_off_reflecting_walls_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_free_particle_kept_in_box(
+            &mut evolver_implementation,
+            configuration_parsing::BoundaryKind::Reflecting,
+        )
+    }
+
+    #[test]
+    fn test_free_particle_wraps_round_periodic_box_with_maximally_contiguous() -> Result<(), String>
+    {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_free_particle_kept_in_box(
+            &mut evolver_implementation,
+            configuration_parsing::BoundaryKind::Periodic,
+        )
+    }
+
+    #[test]
+    fn test_particle_falls_through_tidal_trap_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_particle_falls_through_tidal_trap(
+            &mut evolver_implementation,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_particle_falls_towards_fixed_source_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_particle_falls_towards_fixed_source(
+            &mut evolver_implementation,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_drag_slows_free_particle_exponentially_with_maximally_contiguous() -> Result<(), String>
+    {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_drag_slows_free_particle_exponentially(
+            &mut evolver_implementation,
+            0.01,
+        )
+    }
+
+    #[test]
+    fn test_thermostat_brings_kinetic_temperature_to_target_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_thermostat_brings_kinetic_temperature_to_target(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_thermostat_kicks_repeat_for_same_seed_with_maximally_contiguous() -> Result<(), String>
+    {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_thermostat_kicks_repeat_for_same_seed(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_approaching_particles_merge_at_center_of_mass_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_approaching_particles_merge_at_center_of_mass(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_approaching_particles_merge_at_center_of_mass_with_contiguous_pointers(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_approaching_particles_merge_at_center_of_mass(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_approaching_particles_merge_at_center_of_mass_with_double_boxed() -> Result<(), String>
+    {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_approaching_particles_merge_at_center_of_mass(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_approaching_particles_merge_at_center_of_mass_with_multithreaded() -> Result<(), String>
+    {
+        let mut evolver_implementation = new_multithreaded_for_test()?;
+        evolver_tests::test_approaching_particles_merge_at_center_of_mass(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_approaching_particles_merge_at_center_of_mass_with_struct_of_arrays(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_struct_of_arrays_for_test()?;
+        evolver_tests::test_approaching_particles_merge_at_center_of_mass(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_head_on_collision_keeps_momentum_and_scales_approach_speed_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_head_on_collision_keeps_momentum_and_scales_approach_speed(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_glancing_elastic_collision_deflects_at_right_angles_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_glancing_elastic_collision_deflects_at_right_angles(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_head_on_collision_keeps_momentum_and_scales_approach_speed_with_contiguous_pointers(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_head_on_collision_keeps_momentum_and_scales_approach_speed(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_head_on_collision_keeps_momentum_and_scales_approach_speed_with_double_boxed(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_head_on_collision_keeps_momentum_and_scales_approach_speed(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_head_on_collision_keeps_momentum_and_scales_approach_speed_with_multithreaded(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_multithreaded_for_test()?;
+        evolver_tests::test_head_on_collision_keeps_momentum_and_scales_approach_speed(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_head_on_collision_keeps_momentum_and_scales_approach_speed_with_struct_of_arrays(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_struct_of_arrays_for_test()?;
+        evolver_tests::test_head_on_collision_keeps_momentum_and_scales_approach_speed(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_bonded_pair_oscillates_at_spring_frequency_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_bonded_pair_oscillates_at_spring_frequency(
+            &mut evolver_implementation,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_damped_bonded_pair_rings_down_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_damped_bonded_pair_rings_down(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_bonded_pair_oscillates_at_spring_frequency_with_contiguous_pointers(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_bonded_pair_oscillates_at_spring_frequency(
+            &mut evolver_implementation,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_bonded_pair_oscillates_at_spring_frequency_with_double_boxed() -> Result<(), String> {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_bonded_pair_oscillates_at_spring_frequency(
+            &mut evolver_implementation,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_bonded_pair_oscillates_at_spring_frequency_with_multithreaded() -> Result<(), String> {
+        let mut evolver_implementation = new_multithreaded_for_test()?;
+        evolver_tests::test_bonded_pair_oscillates_at_spring_frequency(
+            &mut evolver_implementation,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_bonded_pair_oscillates_at_spring_frequency_with_struct_of_arrays() -> Result<(), String>
+    {
+        let mut evolver_implementation = new_struct_of_arrays_for_test()?;
+        evolver_tests::test_bonded_pair_oscillates_at_spring_frequency(
+            &mut evolver_implementation,
+            evolver_tests::TEST_DEFAULT_ENERGY_TOLERANCE,
+        )
+    }
+
+    #[test]
+    fn test_anchored_particle_pulls_like_fixed_source_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_anchored_particle_pulls_like_fixed_source(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_scripted_particles_follow_keyframes_and_ellipse_with_maximally_contiguous(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_scripted_particles_follow_keyframes_and_ellipse(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_scripted_particles_follow_keyframes_and_ellipse_with_contiguous_pointers(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_contiguous_pointers_for_test()?;
+        evolver_tests::test_scripted_particles_follow_keyframes_and_ellipse(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_scripted_particles_follow_keyframes_and_ellipse_with_double_boxed() -> Result<(), String>
+    {
+        let mut evolver_implementation = new_double_boxed_for_test()?;
+        evolver_tests::test_scripted_particles_follow_keyframes_and_ellipse(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_scripted_particles_follow_keyframes_and_ellipse_with_multithreaded(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_multithreaded_for_test()?;
+        evolver_tests::test_scripted_particles_follow_keyframes_and_ellipse(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_scripted_particles_follow_keyframes_and_ellipse_with_struct_of_arrays(
+    ) -> Result<(), String> {
+        let mut evolver_implementation = new_struct_of_arrays_for_test()?;
+        evolver_tests::test_scripted_particles_follow_keyframes_and_ellipse(
+            &mut evolver_implementation,
+        )
+    }
+
+    #[test]
+    fn test_tracers_feel_forces_but_exert_none_with_maximally_contiguous() -> Result<(), String> {
+        let mut evolver_implementation = new_maximally_contiguous_for_test()?;
+        evolver_tests::test_tracers_feel_forces_but_exert_none(&mut evolver_implementation)
+    }
+
+    #[test]
+    fn test_tracers_feel_forces_but_exert_none_with_struct_of_arrays() -> Result<(), String> {
+        let mut evolver_implementation = new_struct_of_arrays_for_test()?;
+        evolver_tests::test_tracers_feel_forces_but_exert_none(&mut evolver_implementation)
+    }
 }
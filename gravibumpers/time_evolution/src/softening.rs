@@ -0,0 +1,191 @@
+/// This module provides the forces and potentials of each softening kernel, for a force going as
+/// the coupling over the separation to some power, per unit of coupling (with the charges of both
+/// particles counted as part of the coupling). The softening length is the dead zone radius of
+/// the configuration.
+///
+/// For a force of 1/r^n, the softened forces are:
+///  - DeadZone: 0 for r < h, and 1/r^n otherwise
+///  - Plummer: r / (r^2 + h^2)^((n + 1) / 2), which is 1/r^n with r^2 replaced by r^2 + h^2 other
+///    than for the factor of r giving the direction
+///  - Spline: for r < h, the cubic polynomial in r with no even powers which matches 1/r^n and its
+///    gradient at h, which is r h^(-n-1) ((n + 3) / 2 - ((n + 1) / 2) (r/h)^2), and 1/r^n otherwise
+///
+/// The potential of each is the integral of the force from r to infinity, so for example the
+/// potential of the Plummer kernel is 1 / ((n - 1) (r^2 + h^2)^((n - 1) / 2)).
+use configuration_parsing::SofteningKernel;
+
+/// This returns the magnitude of the force divided by the separation, so that the separation
+/// vector can be multiplied directly, given the square of the separation.
+pub fn force_magnitude_over_separation(
+    softening_kernel: SofteningKernel,
+    softening_length: f64,
+    power_of_separation: i32,
+    squared_separation: f64,
+) -> f64 {
+    let squared_softening_length = softening_length * softening_length;
+    match softening_kernel {
+        SofteningKernel::DeadZone => {
+            if squared_separation < squared_softening_length {
+                0.0
+            } else {
+                (1.0 / squared_separation.sqrt()).powi(power_of_separation + 1)
+            }
+        }
+        SofteningKernel::Plummer => (1.0 / (squared_separation + squared_softening_length).sqrt())
+            .powi(power_of_separation + 1),
+        SofteningKernel::Spline => {
+            if squared_separation < squared_softening_length {
+                let power_as_float = power_of_separation as f64;
+                (((power_as_float + 3.0)
+                    - ((power_as_float + 1.0) * (squared_separation / squared_softening_length)))
+                    * 0.5)
+                    / softening_length.powi(power_of_separation + 1)
+            } else {
+                (1.0 / squared_separation.sqrt()).powi(power_of_separation + 1)
+            }
+        }
+    }
+}
+
+/// This returns the potential energy per unit of coupling, given the square of the separation.
+/// The power of the separation must be greater than 1 so that the potential at infinity is 0.
+pub fn potential_per_coupling(
+    softening_kernel: SofteningKernel,
+    softening_length: f64,
+    power_of_separation: i32,
+    squared_separation: f64,
+) -> f64 {
+    let squared_softening_length = softening_length * softening_length;
+    let power_as_float = power_of_separation as f64;
+    match softening_kernel {
+        // Within the dead zone there is no force, so the potential is constant there at its value
+        // on the edge of the dead zone.
+        SofteningKernel::DeadZone => {
+            (1.0 / squared_separation.max(squared_softening_length).sqrt())
+                .powi(power_of_separation - 1)
+                / (power_as_float - 1.0)
+        }
+        SofteningKernel::Plummer => {
+            (1.0 / (squared_separation + squared_softening_length).sqrt())
+                .powi(power_of_separation - 1)
+                / (power_as_float - 1.0)
+        }
+        SofteningKernel::Spline => {
+            if squared_separation < squared_softening_length {
+                let squared_scaled_separation = squared_separation / squared_softening_length;
+                ((1.0 / (power_as_float - 1.0))
+                    + (0.25 * (power_as_float + 3.0) * (1.0 - squared_scaled_separation))
+                    - (0.125
+                        * (power_as_float + 1.0)
+                        * (1.0 - (squared_scaled_separation * squared_scaled_separation))))
+                    / softening_length.powi(power_of_separation - 1)
+            } else {
+                (1.0 / squared_separation.sqrt()).powi(power_of_separation - 1)
+                    / (power_as_float - 1.0)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_SOFTENING_LENGTH: f64 = 0.5;
+    const TEST_RELATIVE_TOLERANCE: f64 = 0.000001;
+
+    /// The force should be minus the gradient of the potential, which is checked by a central
+    /// difference of the potential at separations inside and outside the softening length.
+    fn check_force_is_gradient_of_potential(
+        softening_kernel: SofteningKernel,
+    ) -> Result<(), String> {
+        let mut failure_messages: std::vec::Vec<String> = vec![];
+        let difference_step = 0.000001;
+        for power_of_separation in &[2, 4] {
+            for tested_separation in &[0.1, 0.3, 0.45, 0.55, 1.0, 2.5] {
+                let potential_difference = potential_per_coupling(
+                    softening_kernel,
+                    TEST_SOFTENING_LENGTH,
+                    *power_of_separation,
+                    (tested_separation - difference_step) * (tested_separation - difference_step),
+                ) - potential_per_coupling(
+                    softening_kernel,
+                    TEST_SOFTENING_LENGTH,
+                    *power_of_separation,
+                    (tested_separation + difference_step) * (tested_separation + difference_step),
+                );
+                let expected_force = potential_difference / (2.0 * difference_step);
+                let actual_force = tested_separation
+                    * force_magnitude_over_separation(
+                        softening_kernel,
+                        TEST_SOFTENING_LENGTH,
+                        *power_of_separation,
+                        tested_separation * tested_separation,
+                    );
+                if !data_structure::comparison::within_relative_tolerance(
+                    expected_force,
+                    actual_force,
+                    TEST_RELATIVE_TOLERANCE,
+                ) {
+                    failure_messages.push(format!(
+                        "Power {}, separation {}: expected force {}, actual {}",
+                        power_of_separation, tested_separation, expected_force, actual_force
+                    ));
+                }
+            }
+        }
+        if failure_messages.is_empty() {
+            Ok(())
+        } else {
+            Err(failure_messages.join("\n"))
+        }
+    }
+
+    #[test]
+    fn check_plummer_force_is_gradient_of_potential() -> Result<(), String> {
+        check_force_is_gradient_of_potential(SofteningKernel::Plummer)
+    }
+
+    #[test]
+    fn check_spline_force_is_gradient_of_potential() -> Result<(), String> {
+        check_force_is_gradient_of_potential(SofteningKernel::Spline)
+    }
+
+    #[test]
+    fn check_spline_is_continuous_at_softening_length() -> Result<(), String> {
+        let squared_softening_length = TEST_SOFTENING_LENGTH * TEST_SOFTENING_LENGTH;
+        let just_inside = squared_softening_length * (1.0 - 0.000000001);
+        let mut failure_messages: std::vec::Vec<String> = vec![];
+        for power_of_separation in &[2, 4] {
+            for calculation in &[force_magnitude_over_separation, potential_per_coupling] {
+                let inside_value = calculation(
+                    SofteningKernel::Spline,
+                    TEST_SOFTENING_LENGTH,
+                    *power_of_separation,
+                    just_inside,
+                );
+                let outside_value = calculation(
+                    SofteningKernel::Spline,
+                    TEST_SOFTENING_LENGTH,
+                    *power_of_separation,
+                    squared_softening_length,
+                );
+                if !data_structure::comparison::within_relative_tolerance(
+                    outside_value,
+                    inside_value,
+                    TEST_RELATIVE_TOLERANCE,
+                ) {
+                    failure_messages.push(format!(
+                        "Power {}: {} just inside, {} on the edge",
+                        power_of_separation, inside_value, outside_value
+                    ));
+                }
+            }
+        }
+        if failure_messages.is_empty() {
+            Ok(())
+        } else {
+            Err(failure_messages.join("\n"))
+        }
+    }
+}
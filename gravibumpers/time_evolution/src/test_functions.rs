@@ -22,12 +22,55 @@ use data_structure::velocity::DimensionfulVector as VelocityVector;
 use data_structure::velocity::HorizontalUnit as HorizontalVelocityUnit;
 use data_structure::velocity::VerticalUnit as VerticalVelocityUnit;
 
+use super::force_law::PairwiseForceLaw;
+
 const TEST_DEFAULT_TOLERANCE: f64 = 0.01;
+
+/// Implementations which conserve energy better can pass a smaller relative tolerance to the tests
+/// which check energy conservation, but every implementation should manage at least this.
+pub const TEST_DEFAULT_ENERGY_TOLERANCE: f64 = TEST_DEFAULT_TOLERANCE;
 const TEST_DEFAULT_DEAD_ZONE_RADIUS: f64 = 0.01;
 
 const NO_ADDITIONAL_CHECK: Option<fn(&std::vec::Vec<IndividualParticle>) -> Result<(), String>> =
     None;
 
+/// This creates a white particle of the first species with the given mass and charges, at the
+/// given position with the given velocity, which are horizontal then vertical, with no collision
+/// radius and not a tracer, for the tests of this crate which only care about those values.
+pub fn new_test_particle(
+    inertial_mass: f64,
+    inverse_squared_charge: f64,
+    inverse_fourth_charge: f64,
+    position_components: (f64, f64),
+    velocity_components: (f64, f64),
+) -> IndividualParticle {
+    IndividualParticle {
+        intrinsic_values: ParticleIntrinsics {
+            inertial_mass: InertialMassUnit(inertial_mass),
+            inverse_squared_charge: InverseSquaredChargeUnit(inverse_squared_charge),
+            inverse_fourth_charge: InverseFourthChargeUnit(inverse_fourth_charge),
+            species_index: data_structure::charge::SpeciesIndex(0),
+            collision_radius: SpatialSeparationUnit(0.0),
+            is_tracer: false,
+            color_brightness: data_structure::color::new_triplet(
+                RedColorUnit(1.0),
+                GreenColorUnit(1.0),
+                BlueColorUnit(1.0),
+            ),
+        },
+        variable_values: ParticleVariables {
+            position_vector: PositionVector {
+                horizontal_component: HorizontalPositionUnit(position_components.0),
+                vertical_component: VerticalPositionUnit(position_components.1),
+            },
+            velocity_vector: VelocityVector {
+                horizontal_component: HorizontalVelocityUnit(velocity_components.0),
+                vertical_component: VerticalVelocityUnit(velocity_components.1),
+            },
+        },
+    }
+}
+
 fn create_test_tolerance_with_separate_for_values(
     horizontal_position_tolerance: f64,
     vertical_position_tolerance: f64,
@@ -39,6 +82,9 @@ fn create_test_tolerance_with_separate_for_values(
             inertial_mass: InertialMassUnit(TEST_DEFAULT_TOLERANCE),
             inverse_squared_charge: InverseSquaredChargeUnit(TEST_DEFAULT_TOLERANCE),
             inverse_fourth_charge: InverseFourthChargeUnit(TEST_DEFAULT_TOLERANCE),
+            species_index: data_structure::charge::SpeciesIndex(0),
+            collision_radius: data_structure::position::SeparationUnit(0.0),
+            is_tracer: false,
             color_brightness: data_structure::color::new_triplet(
                 RedColorUnit(TEST_DEFAULT_TOLERANCE),
                 GreenColorUnit(TEST_DEFAULT_TOLERANCE),
@@ -68,14 +114,27 @@ fn create_test_tolerances() -> IndividualParticle {
 }
 
 /// It is easiest to work out expected values for whole 1-second time slices, so 1000 milliseconds.
-fn create_test_evolution_configuration(
+/// Tests which need other values override them with struct update syntax, so that each new
+/// configuration field only needs a default here.
+pub fn create_test_evolution_configuration(
     number_of_time_slices: usize,
     dead_zone_radius: f64,
 ) -> super::configuration_parsing::EvolutionConfiguration {
     super::configuration_parsing::EvolutionConfiguration {
         dead_zone_radius: dead_zone_radius,
+        softening_kernel: super::configuration_parsing::SofteningKernel::DeadZone,
+        force_law: super::configuration_parsing::ForceLawConfiguration::InverseSquaredAndFourth,
         inverse_squared_coupling: -1.0,
         inverse_fourth_coupling: 1.0,
+        species_interactions: None,
+        boundary_conditions: None,
+        external_fields: None,
+        drag: None,
+        merging_radius: None,
+        restitution_coefficient: None,
+        spring_bonds: vec![],
+        motion_modes: vec![],
+        barnes_hut_opening_angle: None,
         milliseconds_per_time_slice: 1000,
         number_of_time_slices: number_of_time_slices,
     }
@@ -149,7 +208,9 @@ fn compare_time_slices_to_expected<
 where
     ActualInnerItem: super::ParticleRepresentation,
     ActualInnerIterator: std::iter::ExactSizeIterator<Item = ActualInnerItem>,
-    ActualOuterIterator: std::iter::ExactSizeIterator<Item = ActualInnerIterator>,
+    ActualOuterIterator: std::iter::ExactSizeIterator<
+        Item = Result<ActualInnerIterator, Box<dyn std::error::Error>>,
+    >,
     ExpectedInnerItem: super::ParticleRepresentation,
     ExpectedInnerIterator: std::iter::ExactSizeIterator<Item = ExpectedInnerItem>,
     ExpectedOuterIterator: std::iter::ExactSizeIterator<Item = ExpectedInnerIterator>,
@@ -160,6 +221,10 @@ where
             let number_of_time_slices = expected_sequence.len();
             let actual_sequence = actual_evolution.particle_configurations;
             if actual_sequence.len() == number_of_time_slices {
+                let actual_sequence = actual_sequence
+                    .collect::<Result<std::vec::Vec<ActualInnerIterator>, _>>()
+                    .map_err(|evolution_error| format!("{:?}", evolution_error))?
+                    .into_iter();
                 if let Some(additional_check) = optional_additional_check {
                     return apply_check_then_compare_time_slices(
                         actual_sequence,
@@ -186,52 +251,18 @@ where
     }
 }
 
-trait PotentialEnergyCalculator {
-    fn total_for_both(
-        &self,
-        first_particle: &impl super::ParticleRepresentation,
-        second_particle: &impl super::ParticleRepresentation,
-    ) -> Result<f64, String>;
-}
-
-#[derive(Clone, Copy, Debug)]
-struct InverseSquaredAndFourthPotential {
-    inverse_squared_coupling_constant: f64,
-    inverse_fourth_coupling_constant: f64,
-    dead_zone_radius: SpatialSeparationUnit,
-}
-
-impl PotentialEnergyCalculator for InverseSquaredAndFourthPotential {
-    fn total_for_both(
-        &self,
-        first_particle: &impl super::ParticleRepresentation,
-        second_particle: &impl super::ParticleRepresentation,
-    ) -> Result<f64, String> {
-        let inverse_separation = data_structure::position::get_capped_inverse_separation(
-            &first_particle.read_variables().position_vector,
-            &second_particle.read_variables().position_vector,
-            &self.dead_zone_radius,
-        );
-
-        // The potential energy for the pair is the integral over total separation of the force
-        // felt by one of the particles. Hence for the inverse-fourth part, the 3 factors of the
-        // inverse separation and a division by 3, while the inverse-square part just has a single
-        // inverse power of the separation and no extra factor (because it is 1).
-        // (Equivalently, the potential energy is the sum of the integrals of both forces over the
-        // parts of the separations divided between the particles according to mass.)
-        let inverse_fourth_part = (self.inverse_fourth_coupling_constant
-            * first_particle.read_intrinsics().inverse_fourth_charge.0
-            * second_particle.read_intrinsics().inverse_fourth_charge.0
-            * inverse_separation.get_value()
-            * inverse_separation.get_value()
-            * inverse_separation.get_value())
-            / 3.0;
-        let inverse_square_part = self.inverse_squared_coupling_constant
-            * first_particle.read_intrinsics().inverse_squared_charge.0
-            * second_particle.read_intrinsics().inverse_squared_charge.0
-            * inverse_separation.get_value();
-        Ok(inverse_fourth_part + inverse_square_part)
-    }
+type DefaultForceLaw = super::force_law::WithSpringBonds<
+    super::force_law::InExternalFields<
+        super::force_law::MinimumImage<super::force_law::ConfiguredForceLaw>,
+    >,
+>;
+
+/// The tested evolvers use the default force law, created from the evolution configuration.
+fn create_force_law_for_test(
+    evolution_configuration: &super::configuration_parsing::EvolutionConfiguration,
+) -> Result<DefaultForceLaw, String> {
+    DefaultForceLaw::new_from_configuration(evolution_configuration)
+        .map_err(|construction_error| format!("Force law error: {:?}", construction_error))
 }
 
 fn check_energy_given_potential(
@@ -239,7 +270,7 @@ fn check_energy_given_potential(
     expected_energy_in_implicit_units: f64,
     relative_tolerance: f64,
     particle_list: &std::vec::Vec<impl super::ParticleRepresentation>,
-    potential_energy_of_pair: impl PotentialEnergyCalculator,
+    evolution_configuration: &super::configuration_parsing::EvolutionConfiguration,
 ) -> Result<(), String> {
     if particle_list.len() != expected_number_of_particles {
         return Err(String::from(format!(
@@ -248,23 +279,11 @@ fn check_energy_given_potential(
             particle_list.len()
         )));
     }
-    let mut total_energy = 0.0;
-    for particle_index in 0..expected_number_of_particles {
-        let current_particle = &particle_list[particle_index];
-        let current_variables = current_particle.read_variables();
-        let current_kinetic = 0.5
-            * current_particle.read_intrinsics().inertial_mass.0
-            * ((current_variables.velocity_vector.horizontal_component.0
-                * current_variables.velocity_vector.horizontal_component.0)
-                + (current_variables.velocity_vector.vertical_component.0
-                    * current_variables.velocity_vector.vertical_component.0));
-        total_energy += current_kinetic;
-        for other_index in (particle_index + 1)..expected_number_of_particles {
-            let other_particle = &particle_list[other_index];
-            total_energy +=
-                potential_energy_of_pair.total_for_both(current_particle, other_particle)?;
-        }
-    }
+    let total_energy = super::diagnostics::calculate_for_time_slice(
+        &create_force_law_for_test(evolution_configuration)?,
+        particle_list,
+    )
+    .total_energy();
 
     if !data_structure::comparison::within_relative_tolerance(
         expected_energy_in_implicit_units,
@@ -288,6 +307,9 @@ pub fn test_single_particle_at_rest_stays_at_rest(
             inertial_mass: InertialMassUnit(1.0),
             inverse_squared_charge: InverseSquaredChargeUnit(2.0),
             inverse_fourth_charge: InverseFourthChargeUnit(3.0),
+            species_index: data_structure::charge::SpeciesIndex(0),
+            collision_radius: data_structure::position::SeparationUnit(0.0),
+            is_tracer: false,
             color_brightness: data_structure::color::new_triplet(
                 RedColorUnit(4.0),
                 GreenColorUnit(5.0),
@@ -334,6 +356,9 @@ pub fn test_single_particle_at_constant_speed(
         inertial_mass: InertialMassUnit(1.0),
         inverse_squared_charge: InverseSquaredChargeUnit(2.0),
         inverse_fourth_charge: InverseFourthChargeUnit(3.0),
+        species_index: data_structure::charge::SpeciesIndex(0),
+        collision_radius: data_structure::position::SeparationUnit(0.0),
+        is_tracer: false,
         color_brightness: data_structure::color::new_triplet(
             RedColorUnit(4.0),
             GreenColorUnit(5.0),
@@ -450,6 +475,9 @@ pub fn test_uncharged_particles_do_not_accelerate(
         inertial_mass: InertialMassUnit(1.0),
         inverse_squared_charge: InverseSquaredChargeUnit(0.0),
         inverse_fourth_charge: InverseFourthChargeUnit(0.0),
+        species_index: data_structure::charge::SpeciesIndex(0),
+        collision_radius: data_structure::position::SeparationUnit(0.0),
+        is_tracer: false,
         color_brightness: data_structure::color::new_triplet(
             RedColorUnit(4.0),
             GreenColorUnit(5.0),
@@ -618,6 +646,9 @@ pub fn test_immobile_repelling_particles_within_dead_zone_stay_at_rest(
         inertial_mass: InertialMassUnit(1.0),
         inverse_squared_charge: InverseSquaredChargeUnit(0.0),
         inverse_fourth_charge: InverseFourthChargeUnit(1.0),
+        species_index: data_structure::charge::SpeciesIndex(0),
+        collision_radius: data_structure::position::SeparationUnit(0.0),
+        is_tracer: false,
         color_brightness: data_structure::color::new_triplet(
             RedColorUnit(4.0),
             GreenColorUnit(5.0),
@@ -683,11 +714,15 @@ pub fn test_immobile_repelling_particles_within_dead_zone_stay_at_rest(
 pub fn test_equal_masses_attracting_inverse_fourth_critical_escape(
     tested_implementation: &mut impl super::ParticlesInTimeEvolver,
     dead_zone_radius: &SpatialSeparationUnit,
+    relative_energy_tolerance: f64,
 ) -> Result<(), String> {
     let test_intrinsics = ParticleIntrinsics {
         inertial_mass: InertialMassUnit(1.0),
         inverse_squared_charge: InverseSquaredChargeUnit(0.0),
         inverse_fourth_charge: InverseFourthChargeUnit(1.0),
+        species_index: data_structure::charge::SpeciesIndex(0),
+        collision_radius: data_structure::position::SeparationUnit(0.0),
+        is_tracer: false,
         color_brightness: data_structure::color::new_triplet(
             RedColorUnit(4.0),
             GreenColorUnit(5.0),
@@ -806,21 +841,13 @@ pub fn test_equal_masses_attracting_inverse_fourth_critical_escape(
     // As calculated above, the force each particle experiences is (-96m/25) r^(-4) and each has
     // mass 1.0 in this test.
     let evolution_configuration = super::configuration_parsing::EvolutionConfiguration {
-        dead_zone_radius: dead_zone_radius.0,
         inverse_squared_coupling: 0.0,
         inverse_fourth_coupling: -3.84,
-        milliseconds_per_time_slice: 1000,
-        number_of_time_slices: number_of_time_slices,
+        ..create_test_evolution_configuration(number_of_time_slices, dead_zone_radius.0)
     };
     let evolution_result = tested_implementation
         .create_time_sequence(&evolution_configuration, initial_conditions.into_iter());
 
-    let inverse_fourth_potential_of_pair = InverseSquaredAndFourthPotential {
-        inverse_squared_coupling_constant: 0.0,
-        inverse_fourth_coupling_constant: evolution_configuration.inverse_fourth_coupling,
-        dead_zone_radius: *dead_zone_radius,
-    };
-
     let test_tolerances = create_test_tolerances();
     // The total energy should be 0.0 constantly.
     compare_time_slices_to_expected(
@@ -831,9 +858,9 @@ pub fn test_equal_masses_attracting_inverse_fourth_critical_escape(
             check_energy_given_potential(
                 2,
                 0.0,
-                TEST_DEFAULT_TOLERANCE,
+                relative_energy_tolerance,
                 particle_list,
-                inverse_fourth_potential_of_pair,
+                &evolution_configuration,
             )
         }),
     )
@@ -842,11 +869,15 @@ pub fn test_equal_masses_attracting_inverse_fourth_critical_escape(
 pub fn test_equal_masses_repelling_inverse_fourth_accelerate_away_equally(
     tested_implementation: &mut impl super::ParticlesInTimeEvolver,
     dead_zone_radius: &SpatialSeparationUnit,
+    relative_energy_tolerance: f64,
 ) -> Result<(), String> {
     let left_intrinsics = ParticleIntrinsics {
         inertial_mass: InertialMassUnit(1.0),
         inverse_squared_charge: InverseSquaredChargeUnit(0.0),
         inverse_fourth_charge: InverseFourthChargeUnit(1.0),
+        species_index: data_structure::charge::SpeciesIndex(0),
+        collision_radius: data_structure::position::SeparationUnit(0.0),
+        is_tracer: false,
         color_brightness: data_structure::color::new_triplet(
             RedColorUnit(4.0),
             GreenColorUnit(5.0),
@@ -870,6 +901,9 @@ pub fn test_equal_masses_repelling_inverse_fourth_accelerate_away_equally(
         inertial_mass: InertialMassUnit(1.0),
         inverse_squared_charge: InverseSquaredChargeUnit(0.0),
         inverse_fourth_charge: InverseFourthChargeUnit(2.0),
+        species_index: data_structure::charge::SpeciesIndex(0),
+        collision_radius: data_structure::position::SeparationUnit(0.0),
+        is_tracer: false,
         color_brightness: data_structure::color::new_triplet(
             RedColorUnit(4.0),
             GreenColorUnit(5.0),
@@ -978,24 +1012,16 @@ pub fn test_equal_masses_repelling_inverse_fourth_accelerate_away_equally(
 
     let number_of_time_slices = expected_sequence.len();
     let evolution_configuration = super::configuration_parsing::EvolutionConfiguration {
-        dead_zone_radius: dead_zone_radius.0,
         inverse_squared_coupling: 0.0,
         inverse_fourth_coupling: 100.0,
-        milliseconds_per_time_slice: 1000,
-        number_of_time_slices: number_of_time_slices,
+        ..create_test_evolution_configuration(number_of_time_slices, dead_zone_radius.0)
     };
     let evolution_result = tested_implementation
         .create_time_sequence(&evolution_configuration, initial_conditions.into_iter());
 
     // The potential energy is (r/3)*(force per particle) = 200/81 in total.
-    let inverse_fourth_potential_of_pair = InverseSquaredAndFourthPotential {
-        inverse_squared_coupling_constant: 0.0,
-        inverse_fourth_coupling_constant: evolution_configuration.inverse_fourth_coupling,
-        dead_zone_radius: *dead_zone_radius,
-    };
-
-    let initial_energy =
-        inverse_fourth_potential_of_pair.total_for_both(&left_particle, &right_particle)?;
+    let initial_energy = create_force_law_for_test(&evolution_configuration)?
+        .potential_energy_of_pair(&left_particle, &right_particle);
 
     // The initial potential should be 200/81 in whatever units it works out as (as explained
     // above), and there is zero initial kinetic energy.
@@ -1019,9 +1045,9 @@ pub fn test_equal_masses_repelling_inverse_fourth_accelerate_away_equally(
             check_energy_given_potential(
                 2,
                 expected_initial_energy,
-                TEST_DEFAULT_TOLERANCE,
+                relative_energy_tolerance,
                 particle_list,
-                inverse_fourth_potential_of_pair,
+                &evolution_configuration,
             )
         }),
     )
@@ -1035,11 +1061,15 @@ pub fn test_equal_masses_repelling_inverse_fourth_accelerate_away_equally(
 pub fn test_equal_masses_attracting_inverse_square_critical_escape(
     tested_implementation: &mut impl super::ParticlesInTimeEvolver,
     dead_zone_radius: &SpatialSeparationUnit,
+    relative_energy_tolerance: f64,
 ) -> Result<(), String> {
     let test_intrinsics = ParticleIntrinsics {
         inertial_mass: InertialMassUnit(1.0),
         inverse_squared_charge: InverseSquaredChargeUnit(1.0),
         inverse_fourth_charge: InverseFourthChargeUnit(0.0),
+        species_index: data_structure::charge::SpeciesIndex(0),
+        collision_radius: data_structure::position::SeparationUnit(0.0),
+        is_tracer: false,
         color_brightness: data_structure::color::new_triplet(
             RedColorUnit(4.0),
             GreenColorUnit(5.0),
@@ -1158,21 +1188,13 @@ pub fn test_equal_masses_attracting_inverse_square_critical_escape(
     // As calculated above, the force each particle experiences is (-8m/9) r^(-2) and each has
     // mass 1.0 in this test.
     let evolution_configuration = super::configuration_parsing::EvolutionConfiguration {
-        dead_zone_radius: dead_zone_radius.0,
         inverse_squared_coupling: -8.0 / 9.0,
         inverse_fourth_coupling: 0.0,
-        milliseconds_per_time_slice: 1000,
-        number_of_time_slices: number_of_time_slices,
+        ..create_test_evolution_configuration(number_of_time_slices, dead_zone_radius.0)
     };
     let evolution_result = tested_implementation
         .create_time_sequence(&evolution_configuration, initial_conditions.into_iter());
 
-    let inverse_squared_potential_of_pair = InverseSquaredAndFourthPotential {
-        inverse_squared_coupling_constant: evolution_configuration.inverse_squared_coupling,
-        inverse_fourth_coupling_constant: 0.0,
-        dead_zone_radius: *dead_zone_radius,
-    };
-
     let test_tolerances = create_test_tolerances();
     // The total energy should be 0.0 constantly.
     compare_time_slices_to_expected(
@@ -1183,9 +1205,9 @@ pub fn test_equal_masses_attracting_inverse_square_critical_escape(
             check_energy_given_potential(
                 2,
                 0.0,
-                TEST_DEFAULT_TOLERANCE,
+                relative_energy_tolerance,
                 particle_list,
-                inverse_squared_potential_of_pair,
+                &evolution_configuration,
             )
         }),
     )
@@ -1194,11 +1216,15 @@ pub fn test_equal_masses_attracting_inverse_square_critical_escape(
 pub fn test_equal_masses_attracting_inverse_square_circular_orbit(
     tested_implementation: &mut impl super::ParticlesInTimeEvolver,
     dead_zone_radius: &SpatialSeparationUnit,
+    relative_energy_tolerance: f64,
 ) -> Result<(), String> {
     let red_intrinsics = ParticleIntrinsics {
         inertial_mass: InertialMassUnit(1.0),
         inverse_squared_charge: InverseSquaredChargeUnit(1.0),
         inverse_fourth_charge: InverseFourthChargeUnit(0.0),
+        species_index: data_structure::charge::SpeciesIndex(0),
+        collision_radius: data_structure::position::SeparationUnit(0.0),
+        is_tracer: false,
         color_brightness: data_structure::color::new_triplet(
             RedColorUnit(1.0),
             GreenColorUnit(0.0),
@@ -1209,6 +1235,9 @@ pub fn test_equal_masses_attracting_inverse_square_circular_orbit(
         inertial_mass: InertialMassUnit(1.0),
         inverse_squared_charge: InverseSquaredChargeUnit(1.0),
         inverse_fourth_charge: InverseFourthChargeUnit(0.0),
+        species_index: data_structure::charge::SpeciesIndex(0),
+        collision_radius: data_structure::position::SeparationUnit(0.0),
+        is_tracer: false,
         color_brightness: data_structure::color::new_triplet(
             RedColorUnit(0.0),
             GreenColorUnit(0.0),
@@ -1301,21 +1330,14 @@ pub fn test_equal_masses_attracting_inverse_square_circular_orbit(
     // As mentioned above, for an inverse-squared force of magnitude 1 with a separation of 2,
     // the coupling must be 4.
     let evolution_configuration = super::configuration_parsing::EvolutionConfiguration {
-        dead_zone_radius: dead_zone_radius.0,
         inverse_squared_coupling: -4.0,
         inverse_fourth_coupling: 0.0,
         milliseconds_per_time_slice: 200,
-        number_of_time_slices: number_of_time_slices,
+        ..create_test_evolution_configuration(number_of_time_slices, dead_zone_radius.0)
     };
     let evolution_result = tested_implementation
         .create_time_sequence(&evolution_configuration, initial_conditions.into_iter());
 
-    let inverse_squared_potential_of_pair = InverseSquaredAndFourthPotential {
-        inverse_squared_coupling_constant: evolution_configuration.inverse_squared_coupling,
-        inverse_fourth_coupling_constant: 0.0,
-        dead_zone_radius: *dead_zone_radius,
-    };
-
     let test_tolerances = create_test_tolerances();
     // The total energy is potential plus kinetic.
     // The potential is -coupling/r => -4/2 = -2.
@@ -1329,9 +1351,9 @@ pub fn test_equal_masses_attracting_inverse_square_circular_orbit(
             check_energy_given_potential(
                 2,
                 -1.0,
-                TEST_DEFAULT_TOLERANCE,
+                relative_energy_tolerance,
                 particle_list,
-                inverse_squared_potential_of_pair,
+                &evolution_configuration,
             )
         }),
     )
@@ -1346,6 +1368,9 @@ pub fn test_triangle_at_cancelling_forces_is_stable(
             inertial_mass: InertialMassUnit(1.0),
             inverse_squared_charge: InverseSquaredChargeUnit(1.0),
             inverse_fourth_charge: InverseFourthChargeUnit(1.0),
+            species_index: data_structure::charge::SpeciesIndex(0),
+            collision_radius: data_structure::position::SeparationUnit(0.0),
+            is_tracer: false,
             color_brightness: data_structure::color::new_triplet(
                 RedColorUnit(1.0),
                 GreenColorUnit(0.0),
@@ -1368,6 +1393,9 @@ pub fn test_triangle_at_cancelling_forces_is_stable(
             inertial_mass: InertialMassUnit(1.0),
             inverse_squared_charge: InverseSquaredChargeUnit(1.0),
             inverse_fourth_charge: InverseFourthChargeUnit(2.0),
+            species_index: data_structure::charge::SpeciesIndex(0),
+            collision_radius: data_structure::position::SeparationUnit(0.0),
+            is_tracer: false,
             color_brightness: data_structure::color::new_triplet(
                 RedColorUnit(0.0),
                 GreenColorUnit(0.0),
@@ -1390,6 +1418,9 @@ pub fn test_triangle_at_cancelling_forces_is_stable(
             inertial_mass: InertialMassUnit(1.0),
             inverse_squared_charge: InverseSquaredChargeUnit(1.0),
             inverse_fourth_charge: InverseFourthChargeUnit(2.0),
+            species_index: data_structure::charge::SpeciesIndex(0),
+            collision_radius: data_structure::position::SeparationUnit(0.0),
+            is_tracer: false,
             color_brightness: data_structure::color::new_triplet(
                 RedColorUnit(0.0),
                 GreenColorUnit(1.0),
@@ -1443,11 +1474,9 @@ pub fn test_triangle_at_cancelling_forces_is_stable(
     // and inverse-fourth has
     // charges: 2 * 2, r^-4: 0.25, coupling 0.5 => giving +0.5.
     let evolution_configuration = super::configuration_parsing::EvolutionConfiguration {
-        dead_zone_radius: dead_zone_radius.0,
         inverse_squared_coupling: -1.0,
         inverse_fourth_coupling: 0.5,
-        milliseconds_per_time_slice: 1000,
-        number_of_time_slices: number_of_time_slices,
+        ..create_test_evolution_configuration(number_of_time_slices, dead_zone_radius.0)
     };
 
     let evolution_result = tested_implementation
@@ -1464,11 +1493,15 @@ pub fn test_triangle_at_cancelling_forces_is_stable(
 pub fn test_approximate_harmonic_oscillator(
     tested_implementation: &mut impl super::ParticlesInTimeEvolver,
     dead_zone_radius: &SpatialSeparationUnit,
+    relative_energy_tolerance: f64,
 ) -> Result<(), String> {
     let red_intrinsics = ParticleIntrinsics {
         inertial_mass: InertialMassUnit(1.0),
         inverse_squared_charge: InverseSquaredChargeUnit(1.0),
         inverse_fourth_charge: InverseFourthChargeUnit(1.0),
+        species_index: data_structure::charge::SpeciesIndex(0),
+        collision_radius: data_structure::position::SeparationUnit(0.0),
+        is_tracer: false,
         color_brightness: data_structure::color::new_triplet(
             RedColorUnit(1.0),
             GreenColorUnit(0.0),
@@ -1479,6 +1512,9 @@ pub fn test_approximate_harmonic_oscillator(
         inertial_mass: InertialMassUnit(1.0),
         inverse_squared_charge: InverseSquaredChargeUnit(1.0),
         inverse_fourth_charge: InverseFourthChargeUnit(1.0),
+        species_index: data_structure::charge::SpeciesIndex(0),
+        collision_radius: data_structure::position::SeparationUnit(0.0),
+        is_tracer: false,
         color_brightness: data_structure::color::new_triplet(
             RedColorUnit(0.0),
             GreenColorUnit(0.0),
@@ -1594,21 +1630,14 @@ pub fn test_approximate_harmonic_oscillator(
     // and will undergo simple harmonic motion with the same angular frequency of 1, but each with
     // half the amplitude.
     let evolution_configuration = super::configuration_parsing::EvolutionConfiguration {
-        dead_zone_radius: dead_zone_radius.0,
         inverse_squared_coupling: -0.25,
         inverse_fourth_coupling: 0.25,
         milliseconds_per_time_slice: 200,
-        number_of_time_slices: number_of_time_slices,
+        ..create_test_evolution_configuration(number_of_time_slices, dead_zone_radius.0)
     };
     let evolution_result = tested_implementation
         .create_time_sequence(&evolution_configuration, initial_conditions.into_iter());
 
-    let potential_of_pair = InverseSquaredAndFourthPotential {
-        inverse_squared_coupling_constant: evolution_configuration.inverse_squared_coupling,
-        inverse_fourth_coupling_constant: evolution_configuration.inverse_fourth_coupling,
-        dead_zone_radius: *dead_zone_radius,
-    };
-
     let test_tolerances = create_test_tolerances();
     // There is no initial kinetic energy, so the total is that from the inverse-squared force
     // plus that from the inverse-fourth force, so
@@ -1625,10 +1654,1748 @@ pub fn test_approximate_harmonic_oscillator(
             check_energy_given_potential(
                 2,
                 initial_energy,
-                TEST_DEFAULT_TOLERANCE,
+                relative_energy_tolerance,
+                particle_list,
+                &evolution_configuration,
+            )
+        }),
+    )
+}
+
+/// The positions and velocities of every particle at every time slice are given as the bits of
+/// the numbers, so that evolutions which should be identical can be compared exactly.
+fn collect_variable_bits(
+    particle_configurations: impl std::iter::Iterator<
+        Item = Result<
+            impl std::iter::Iterator<Item = impl super::ParticleRepresentation>,
+            Box<dyn std::error::Error>,
+        >,
+    >,
+) -> Result<std::vec::Vec<std::vec::Vec<[u64; 4]>>, String> {
+    particle_configurations
+        .map(|time_slice| {
+            let evolved_particles = time_slice
+                .map_err(|evolution_error| format!("Evolution error: {:?}", evolution_error))?;
+            Ok(evolved_particles
+                .map(|evolved_particle| {
+                    let particle_variables =
+                        super::ParticleRepresentation::read_variables(&evolved_particle);
+                    let evolved_position = &particle_variables.position_vector;
+                    let evolved_velocity = &particle_variables.velocity_vector;
+                    [
+                        evolved_position.horizontal_component.0.to_bits(),
+                        evolved_position.vertical_component.0.to_bits(),
+                        evolved_velocity.horizontal_component.0.to_bits(),
+                        evolved_velocity.vertical_component.0.to_bits(),
+                    ]
+                })
+                .collect())
+        })
+        .collect()
+}
+
+fn create_particle_grid(grid_side_length: usize) -> std::vec::Vec<IndividualParticle> {
+    (0..(grid_side_length * grid_side_length))
+        .map(|particle_index| {
+            let grid_column = (particle_index % grid_side_length) as f64;
+            let grid_row = (particle_index / grid_side_length) as f64;
+            IndividualParticle {
+                intrinsic_values: ParticleIntrinsics {
+                    inertial_mass: InertialMassUnit(1.0 + (0.1 * grid_column)),
+                    inverse_squared_charge: InverseSquaredChargeUnit(1.0 + (0.1 * grid_row)),
+                    inverse_fourth_charge: InverseFourthChargeUnit(0.5),
+                    species_index: data_structure::charge::SpeciesIndex(0),
+                    collision_radius: data_structure::position::SeparationUnit(0.0),
+                    is_tracer: false,
+                    color_brightness: data_structure::color::new_triplet(
+                        RedColorUnit(1.0),
+                        GreenColorUnit(1.0),
+                        BlueColorUnit(1.0),
+                    ),
+                },
+                variable_values: ParticleVariables {
+                    position_vector: PositionVector {
+                        horizontal_component: HorizontalPositionUnit(
+                            (3.0 * grid_column) + (0.1 * grid_row),
+                        ),
+                        vertical_component: VerticalPositionUnit(3.0 * grid_row),
+                    },
+                    velocity_vector: VelocityVector {
+                        horizontal_component: HorizontalVelocityUnit(0.1 * grid_row),
+                        vertical_component: VerticalVelocityUnit(-0.1 * grid_column),
+                    },
+                },
+            }
+        })
+        .collect()
+}
+
+/// This evolves a grid of particles twice from the same initial conditions, and checks that the
+/// positions and velocities of every particle at every time slice are identical bit for bit,
+/// which is expected even of implementations which use several threads. There are enough
+/// particles for multithreaded collections to split them between their threads.
+pub fn test_many_particles_evolve_identically_when_repeated(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+) -> Result<(), String> {
+    let initial_conditions = create_particle_grid(12);
+    let evolution_configuration =
+        create_test_evolution_configuration(4, TEST_DEFAULT_DEAD_ZONE_RADIUS);
+
+    let mut evolved_variables = vec![];
+    for _ in 0..2 {
+        let particle_set_evolution = tested_implementation
+            .create_time_sequence(&evolution_configuration, initial_conditions.iter())
+            .map_err(|evolution_error| format!("Evolution error: {:?}", evolution_error))?;
+        evolved_variables.push(collect_variable_bits(
+            particle_set_evolution.particle_configurations,
+        )?);
+    }
+
+    if evolved_variables[0] == evolved_variables[1] {
+        Ok(())
+    } else {
+        Err(String::from(
+            "Evolving the same initial conditions twice gave different results",
+        ))
+    }
+}
+
+/// This evolves a grid of particles, then starts a new evolution from one of the intermediate time
+/// slices, as is done when resuming from a checkpoint, and checks that the time slices from there
+/// on are identical bit for bit to those of the uninterrupted evolution. This requires that the
+/// implementation carries nothing from one time slice to the next other than the particles.
+pub fn test_evolution_resumed_from_time_slice_matches_uninterrupted(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+) -> Result<(), String> {
+    check_resumed_evolution_matches_uninterrupted(tested_implementation, None)
+}
+
+/// This is as test_evolution_resumed_from_time_slice_matches_uninterrupted, but with a
+/// thermostat, so the resumed evolution is given the state of the random generator at the time
+/// slice which it is resumed from, as a checkpoint would give it.
+pub fn test_evolution_with_thermostat_resumed_from_time_slice_matches_uninterrupted(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+) -> Result<(), String> {
+    check_resumed_evolution_matches_uninterrupted(
+        tested_implementation,
+        Some(super::configuration_parsing::ThermostatConfiguration {
+            target_temperature: 0.5,
+            random_seed: 12345,
+            generator_state: None,
+        }),
+    )
+}
+
+fn check_resumed_evolution_matches_uninterrupted(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+    thermostat_configuration: Option<super::configuration_parsing::ThermostatConfiguration>,
+) -> Result<(), String> {
+    let initial_conditions = create_particle_grid(3);
+    let number_of_time_slices = 6;
+    let resumed_time_slice_index = 2;
+    let create_drag_configuration =
+        |thermostat_configuration| super::configuration_parsing::DragConfiguration {
+            drag_rate: 2.0,
+            thermostat: Some(thermostat_configuration),
+        };
+    let uninterrupted_configuration = super::configuration_parsing::EvolutionConfiguration {
+        drag: thermostat_configuration.map(create_drag_configuration),
+        ..create_test_evolution_configuration(number_of_time_slices, TEST_DEFAULT_DEAD_ZONE_RADIUS)
+    };
+    let resumed_configuration = super::configuration_parsing::EvolutionConfiguration {
+        drag: thermostat_configuration.map(|thermostat_configuration| {
+            create_drag_configuration(super::configuration_parsing::ThermostatConfiguration {
+                generator_state: Some(super::thermostat::generator_state_after_time_slices(
+                    &thermostat_configuration,
+                    resumed_time_slice_index,
+                )),
+                ..thermostat_configuration
+            })
+        }),
+        ..create_test_evolution_configuration(
+            number_of_time_slices - resumed_time_slice_index,
+            TEST_DEFAULT_DEAD_ZONE_RADIUS,
+        )
+    };
+
+    let uninterrupted_evolution = tested_implementation
+        .create_time_sequence(&uninterrupted_configuration, initial_conditions.iter())
+        .map_err(|evolution_error| format!("Evolution error: {:?}", evolution_error))?;
+    let mut uninterrupted_particles = vec![];
+    for time_slice in uninterrupted_evolution.particle_configurations {
+        uninterrupted_particles.push(
+            time_slice
+                .map_err(|evolution_error| format!("Evolution error: {:?}", evolution_error))?
+                .map(|evolved_particle| {
+                    data_structure::particle::create_individual_from_representation(
+                        &evolved_particle,
+                    )
+                })
+                .collect::<std::vec::Vec<IndividualParticle>>(),
+        );
+    }
+    let uninterrupted_bits = collect_variable_bits(
+        uninterrupted_particles[resumed_time_slice_index..]
+            .iter()
+            .map(|time_slice| Ok(time_slice.iter())),
+    )?;
+
+    let resumed_evolution = tested_implementation
+        .create_time_sequence(
+            &resumed_configuration,
+            uninterrupted_particles[resumed_time_slice_index].iter(),
+        )
+        .map_err(|evolution_error| format!("Evolution error: {:?}", evolution_error))?;
+    let resumed_bits = collect_variable_bits(resumed_evolution.particle_configurations)?;
+
+    if resumed_bits == uninterrupted_bits {
+        Ok(())
+    } else {
+        Err(format!(
+            "Resuming from time slice {} gave {:?}, uninterrupted evolution gave {:?}",
+            resumed_time_slice_index, resumed_bits, uninterrupted_bits
+        ))
+    }
+}
+
+/// This lets two equal masses which attract each other fall together from rest, so that with a
+/// softening kernel they pass through each other rather than being kicked as with a dead zone.
+/// The total energy, with the potential softened in the same way as the force, should be
+/// conserved throughout, and the momentum should stay zero. The particles have different colors
+/// so that they can be told apart whatever order they are emitted in.
+pub fn test_softened_attraction_passes_through_and_conserves_energy(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+    softening_kernel: super::configuration_parsing::SofteningKernel,
+    relative_energy_tolerance: f64,
+) -> Result<(), String> {
+    let softening_length = 0.8;
+    let initial_conditions: std::vec::Vec<IndividualParticle> = [(-1.0, 1.0), (1.0, 0.0)]
+        .iter()
+        .map(|(horizontal_position, red_brightness)| IndividualParticle {
+            intrinsic_values: ParticleIntrinsics {
+                inertial_mass: InertialMassUnit(1.0),
+                inverse_squared_charge: InverseSquaredChargeUnit(1.0),
+                inverse_fourth_charge: InverseFourthChargeUnit(1.0),
+                species_index: data_structure::charge::SpeciesIndex(0),
+                collision_radius: data_structure::position::SeparationUnit(0.0),
+                is_tracer: false,
+                color_brightness: data_structure::color::new_triplet(
+                    RedColorUnit(*red_brightness),
+                    GreenColorUnit(0.0),
+                    BlueColorUnit(1.0 - red_brightness),
+                ),
+            },
+            variable_values: ParticleVariables {
+                position_vector: PositionVector {
+                    horizontal_component: HorizontalPositionUnit(*horizontal_position),
+                    vertical_component: VerticalPositionUnit(0.0),
+                },
+                velocity_vector: VelocityVector {
+                    horizontal_component: HorizontalVelocityUnit(0.0),
+                    vertical_component: VerticalVelocityUnit(0.0),
+                },
+            },
+        })
+        .collect();
+
+    // The inverse-fourth force is repulsive but weak enough that the particles still fall through
+    // each other, so that both kinds of softened force and potential are tested.
+    let evolution_configuration = super::configuration_parsing::EvolutionConfiguration {
+        softening_kernel,
+        inverse_squared_coupling: -1.0,
+        inverse_fourth_coupling: 0.01,
+        milliseconds_per_time_slice: 100,
+        ..create_test_evolution_configuration(30, softening_length)
+    };
+    let force_law = create_force_law_for_test(&evolution_configuration)?;
+    let initial_energy =
+        super::diagnostics::calculate_for_time_slice(&force_law, &initial_conditions)
+            .total_energy();
+
+    let particle_set_evolution = tested_implementation
+        .create_time_sequence(&evolution_configuration, initial_conditions.iter())
+        .map_err(|evolution_error| format!("Evolution error: {:?}", evolution_error))?;
+    let mut has_left_particle_crossed = false;
+    for (time_slice_index, time_slice) in particle_set_evolution.particle_configurations.enumerate()
+    {
+        let particle_list = time_slice
+            .map_err(|evolution_error| format!("Evolution error: {:?}", evolution_error))?
+            .map(|evolved_particle| {
+                data_structure::particle::create_individual_from_representation(&evolved_particle)
+            })
+            .collect::<std::vec::Vec<IndividualParticle>>();
+        check_energy_given_potential(
+            2,
+            initial_energy,
+            relative_energy_tolerance,
+            &particle_list,
+            &evolution_configuration,
+        )
+        .map_err(|energy_error| format!("Time slice {}: {}", time_slice_index, energy_error))?;
+        let frame_diagnostics =
+            super::diagnostics::calculate_for_time_slice(&force_law, &particle_list);
+        if (frame_diagnostics.horizontal_momentum.abs() > relative_energy_tolerance)
+            || (frame_diagnostics.vertical_momentum.abs() > relative_energy_tolerance)
+        {
+            return Err(format!(
+                "Time slice {}: expected zero momentum, actual diagnostics {:?}",
+                time_slice_index, frame_diagnostics
+            ));
+        }
+        has_left_particle_crossed |= particle_list.iter().any(|evolved_particle| {
+            (evolved_particle
+                .intrinsic_values
+                .color_brightness
+                .get_red()
+                .0
+                > 0.5)
+                && (evolved_particle
+                    .variable_values
+                    .position_vector
+                    .horizontal_component
+                    .0
+                    > 0.0)
+        });
+    }
+
+    if has_left_particle_crossed {
+        Ok(())
+    } else {
+        Err(String::from(
+            "Expected the particles to pass through each other",
+        ))
+    }
+}
+
+fn create_equal_masses_at_rest(separation: f64) -> std::vec::Vec<IndividualParticle> {
+    [-0.5 * separation, 0.5 * separation]
+        .iter()
+        .map(|horizontal_position| IndividualParticle {
+            intrinsic_values: ParticleIntrinsics {
+                inertial_mass: InertialMassUnit(1.0),
+                inverse_squared_charge: InverseSquaredChargeUnit(1.0),
+                inverse_fourth_charge: InverseFourthChargeUnit(1.0),
+                species_index: data_structure::charge::SpeciesIndex(0),
+                collision_radius: data_structure::position::SeparationUnit(0.0),
+                is_tracer: false,
+                color_brightness: data_structure::color::new_triplet(
+                    RedColorUnit(1.0),
+                    GreenColorUnit(1.0),
+                    BlueColorUnit(1.0),
+                ),
+            },
+            variable_values: ParticleVariables {
+                position_vector: PositionVector {
+                    horizontal_component: HorizontalPositionUnit(*horizontal_position),
+                    vertical_component: VerticalPositionUnit(0.0),
+                },
+                velocity_vector: VelocityVector {
+                    horizontal_component: HorizontalVelocityUnit(0.0),
+                    vertical_component: VerticalVelocityUnit(0.0),
+                },
+            },
+        })
+        .collect()
+}
+
+/// This evolves the given pair of particles, checking that the total energy matches the expected
+/// energy and that the momentum stays zero in every time slice, and returns the separation of the
+/// pair in each time slice.
+fn evolve_pair_checking_energy(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+    evolution_configuration: &super::configuration_parsing::EvolutionConfiguration,
+    initial_conditions: &std::vec::Vec<IndividualParticle>,
+    expected_energy: f64,
+    relative_energy_tolerance: f64,
+) -> Result<std::vec::Vec<f64>, String> {
+    let force_law = create_force_law_for_test(evolution_configuration)?;
+    check_energy_given_potential(
+        2,
+        expected_energy,
+        relative_energy_tolerance,
+        initial_conditions,
+        evolution_configuration,
+    )
+    .map_err(|energy_error| format!("Initial conditions: {}", energy_error))?;
+    let particle_set_evolution = tested_implementation
+        .create_time_sequence(evolution_configuration, initial_conditions.iter())
+        .map_err(|evolution_error| format!("Evolution error: {:?}", evolution_error))?;
+    let mut separations = vec![];
+    for (time_slice_index, time_slice) in particle_set_evolution.particle_configurations.enumerate()
+    {
+        let particle_list = time_slice
+            .map_err(|evolution_error| format!("Evolution error: {:?}", evolution_error))?
+            .map(|evolved_particle| {
+                data_structure::particle::create_individual_from_representation(&evolved_particle)
+            })
+            .collect::<std::vec::Vec<IndividualParticle>>();
+        check_energy_given_potential(
+            2,
+            expected_energy,
+            relative_energy_tolerance,
+            &particle_list,
+            evolution_configuration,
+        )
+        .map_err(|energy_error| format!("Time slice {}: {}", time_slice_index, energy_error))?;
+        let frame_diagnostics =
+            super::diagnostics::calculate_for_time_slice(&force_law, &particle_list);
+        if (frame_diagnostics.horizontal_momentum.abs() > relative_energy_tolerance)
+            || (frame_diagnostics.vertical_momentum.abs() > relative_energy_tolerance)
+        {
+            return Err(format!(
+                "Time slice {}: expected zero momentum, actual diagnostics {:?}",
+                time_slice_index, frame_diagnostics
+            ));
+        }
+        separations.push(
+            data_structure::position::square_separation_vector(
+                &(particle_list[0].variable_values.position_vector
+                    - particle_list[1].variable_values.position_vector),
+            )
+            .0
+            .sqrt(),
+        );
+    }
+    Ok(separations)
+}
+
+/// This releases two particles from rest outside the minimum of a Lennard-Jones potential with a
+/// cutoff, so that they should fall inwards past the minimum before bouncing off the repulsive
+/// core, with the total energy equal to the shifted potential energy at the start throughout.
+pub fn test_lennard_jones_pair_conserves_energy(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+    relative_energy_tolerance: f64,
+) -> Result<(), String> {
+    let initial_separation = 1.5;
+    let cutoff_separation = 2.5;
+    let evolution_configuration = super::configuration_parsing::EvolutionConfiguration {
+        force_law: super::configuration_parsing::ForceLawConfiguration::LennardJones {
+            length_scale: 1.0,
+            energy_scale: 1.0,
+            cutoff_separation: Some(cutoff_separation),
+        },
+        inverse_squared_coupling: 0.0,
+        inverse_fourth_coupling: 0.0,
+        milliseconds_per_time_slice: 10,
+        ..create_test_evolution_configuration(100, 0.1)
+    };
+    let unshifted_potential = |separation: f64| {
+        let sixth_power_ratio = separation.powi(-6);
+        4.0 * ((sixth_power_ratio * sixth_power_ratio) - sixth_power_ratio)
+    };
+    let expected_energy =
+        unshifted_potential(initial_separation) - unshifted_potential(cutoff_separation);
+
+    let separations = evolve_pair_checking_energy(
+        tested_implementation,
+        &evolution_configuration,
+        &create_equal_masses_at_rest(initial_separation),
+        expected_energy,
+        relative_energy_tolerance,
+    )?;
+    let separation_at_minimum = 2.0_f64.powf(1.0 / 6.0);
+    if separations
+        .iter()
+        .any(|separation| *separation < separation_at_minimum)
+    {
+        Ok(())
+    } else {
+        Err(format!(
+            "Expected the separation to go below {} at some point, actual separations {:?}",
+            separation_at_minimum, separations
+        ))
+    }
+}
+
+/// This releases two particles from rest with a repulsive Yukawa potential between them, so that
+/// they should move apart with the total energy equal to the screened potential energy at the
+/// start throughout.
+pub fn test_yukawa_pair_conserves_energy(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+    relative_energy_tolerance: f64,
+) -> Result<(), String> {
+    let initial_separation = 1.0;
+    let screening_length = 2.0;
+    let evolution_configuration = super::configuration_parsing::EvolutionConfiguration {
+        force_law: super::configuration_parsing::ForceLawConfiguration::Yukawa { screening_length },
+        inverse_squared_coupling: 1.0,
+        inverse_fourth_coupling: 0.0,
+        milliseconds_per_time_slice: 10,
+        ..create_test_evolution_configuration(100, 0.1)
+    };
+    let expected_energy = (-initial_separation / screening_length).exp() / initial_separation;
+
+    let separations = evolve_pair_checking_energy(
+        tested_implementation,
+        &evolution_configuration,
+        &create_equal_masses_at_rest(initial_separation),
+        expected_energy,
+        relative_energy_tolerance,
+    )?;
+    match separations.last() {
+        Some(final_separation) if *final_separation > (initial_separation + 0.1) => Ok(()),
+        _ => Err(format!(
+            "Expected the particles to move apart, actual separations {:?}",
+            separations
+        )),
+    }
+}
+
+/// This gives where a free particle which started at the given position with the given velocity
+/// should be after the given time in a box with the given boundaries along one axis, along with
+/// its velocity then. A reflecting box of width w is equivalent to a periodic box of width 2 w in
+/// which the second half is mirrored.
+fn free_motion_in_box_along_axis(
+    boundary_kind: super::configuration_parsing::BoundaryKind,
+    lower_border: f64,
+    upper_border: f64,
+    initial_position: f64,
+    initial_velocity: f64,
+    elapsed_time: f64,
+) -> (f64, f64) {
+    let box_width = upper_border - lower_border;
+    let distance_past_lower_border =
+        (initial_position + (initial_velocity * elapsed_time)) - lower_border;
+    match boundary_kind {
+        super::configuration_parsing::BoundaryKind::Periodic => (
+            lower_border + distance_past_lower_border.rem_euclid(box_width),
+            initial_velocity,
+        ),
+        super::configuration_parsing::BoundaryKind::Reflecting => {
+            let distance_in_mirrored_box = distance_past_lower_border.rem_euclid(2.0 * box_width);
+            if distance_in_mirrored_box <= box_width {
+                (lower_border + distance_in_mirrored_box, initial_velocity)
+            } else {
+                (
+                    lower_border + (2.0 * box_width) - distance_in_mirrored_box,
+                    -initial_velocity,
+                )
+            }
+        }
+    }
+}
+
+/// This lets a single particle move freely through a box for long enough to cross it several times
+/// in each direction, checking that it is at the same position with the same velocity as it would
+/// be if it bounced off the walls perfectly elastically or wrapped round perfectly. None of the
+/// time slices has the particle exactly on a border, where the direction would be ambiguous.
+pub fn test_free_particle_kept_in_box(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+    boundary_kind: super::configuration_parsing::BoundaryKind,
+) -> Result<(), String> {
+    let boundary_conditions = super::configuration_parsing::BoundaryConditions {
+        boundary_kind,
+        right_border_coordinate: 1.0,
+        upper_border_coordinate: 2.0,
+        left_border_coordinate: -1.0,
+        lower_border_coordinate: -2.0,
+    };
+    let (initial_position, initial_velocity) = ((0.1, 0.0), (2.7, -1.3));
+    let particle_at_time = |elapsed_time| {
+        let (horizontal_position, horizontal_velocity) = free_motion_in_box_along_axis(
+            boundary_kind,
+            boundary_conditions.left_border_coordinate,
+            boundary_conditions.right_border_coordinate,
+            initial_position.0,
+            initial_velocity.0,
+            elapsed_time,
+        );
+        let (vertical_position, vertical_velocity) = free_motion_in_box_along_axis(
+            boundary_kind,
+            boundary_conditions.lower_border_coordinate,
+            boundary_conditions.upper_border_coordinate,
+            initial_position.1,
+            initial_velocity.1,
+            elapsed_time,
+        );
+        IndividualParticle {
+            intrinsic_values: ParticleIntrinsics {
+                inertial_mass: InertialMassUnit(1.0),
+                inverse_squared_charge: InverseSquaredChargeUnit(0.0),
+                inverse_fourth_charge: InverseFourthChargeUnit(0.0),
+                species_index: data_structure::charge::SpeciesIndex(0),
+                collision_radius: data_structure::position::SeparationUnit(0.0),
+                is_tracer: false,
+                color_brightness: data_structure::color::new_triplet(
+                    RedColorUnit(1.0),
+                    GreenColorUnit(1.0),
+                    BlueColorUnit(1.0),
+                ),
+            },
+            variable_values: ParticleVariables {
+                position_vector: PositionVector {
+                    horizontal_component: HorizontalPositionUnit(horizontal_position),
+                    vertical_component: VerticalPositionUnit(vertical_position),
+                },
+                velocity_vector: VelocityVector {
+                    horizontal_component: HorizontalVelocityUnit(horizontal_velocity),
+                    vertical_component: VerticalVelocityUnit(vertical_velocity),
+                },
+            },
+        }
+    };
+
+    let milliseconds_per_time_slice = 100;
+    let number_of_time_slices = 21;
+    let expected_sequence = (0..number_of_time_slices)
+        .map(|time_slice_index| {
+            vec![particle_at_time(
+                (time_slice_index as f64)
+                    * (milliseconds_per_time_slice as f64)
+                    * super::configuration_parsing::SECONDS_PER_MILLISECOND,
+            )]
+            .into_iter()
+        })
+        .collect::<std::vec::Vec<_>>();
+    let evolution_configuration = super::configuration_parsing::EvolutionConfiguration {
+        boundary_conditions: Some(boundary_conditions),
+        milliseconds_per_time_slice,
+        ..create_test_evolution_configuration(number_of_time_slices, TEST_DEFAULT_DEAD_ZONE_RADIUS)
+    };
+    let initial_conditions = vec![particle_at_time(0.0)];
+    let evolution_result = tested_implementation
+        .create_time_sequence(&evolution_configuration, initial_conditions.into_iter());
+    compare_time_slices_to_expected(
+        evolution_result,
+        expected_sequence.into_iter(),
+        &create_test_tolerances(),
+        NO_ADDITIONAL_CHECK,
+    )
+}
+
+/// This lets a single uncharged particle fall under a uniform acceleration through a harmonic trap
+/// which pulls it back towards the center horizontally but pushes it away from the center
+/// vertically, as a tidal field would, so that it should oscillate horizontally while falling ever
+/// faster, with the total energy equal to the energy in the trap at the start throughout.
+pub fn test_particle_falls_through_tidal_trap(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+    relative_energy_tolerance: f64,
+) -> Result<(), String> {
+    let inertial_mass = 2.0;
+    let vertical_acceleration = -1.5;
+    let horizontal_angular_frequency = 0.5 * std::f64::consts::PI;
+    let vertical_growth_rate = 0.5;
+    let harmonic_trap = super::configuration_parsing::HarmonicTrap {
+        horizontal_center: 1.0,
+        vertical_center: 0.0,
+        horizontal_spring_constant: inertial_mass
+            * horizontal_angular_frequency
+            * horizontal_angular_frequency,
+        vertical_spring_constant: -inertial_mass * vertical_growth_rate * vertical_growth_rate,
+    };
+    let horizontal_amplitude = 0.5;
+    // The vertical motion is about the point where the acceleration and the push of the trap
+    // cancel out, starting from rest at the center of the trap.
+    let vertical_offset_from_balance =
+        vertical_acceleration / (vertical_growth_rate * vertical_growth_rate);
+    let particle_at_time = |elapsed_time: f64| IndividualParticle {
+        intrinsic_values: ParticleIntrinsics {
+            inertial_mass: InertialMassUnit(inertial_mass),
+            inverse_squared_charge: InverseSquaredChargeUnit(0.0),
+            inverse_fourth_charge: InverseFourthChargeUnit(0.0),
+            species_index: data_structure::charge::SpeciesIndex(0),
+            collision_radius: data_structure::position::SeparationUnit(0.0),
+            is_tracer: false,
+            color_brightness: data_structure::color::new_triplet(
+                RedColorUnit(1.0),
+                GreenColorUnit(1.0),
+                BlueColorUnit(1.0),
+            ),
+        },
+        variable_values: ParticleVariables {
+            position_vector: PositionVector {
+                horizontal_component: HorizontalPositionUnit(
+                    harmonic_trap.horizontal_center
+                        + (horizontal_amplitude
+                            * (horizontal_angular_frequency * elapsed_time).cos()),
+                ),
+                vertical_component: VerticalPositionUnit(
+                    harmonic_trap.vertical_center
+                        + (vertical_offset_from_balance
+                            * ((vertical_growth_rate * elapsed_time).cosh() - 1.0)),
+                ),
+            },
+            velocity_vector: VelocityVector {
+                horizontal_component: HorizontalVelocityUnit(
+                    -horizontal_amplitude
+                        * horizontal_angular_frequency
+                        * (horizontal_angular_frequency * elapsed_time).sin(),
+                ),
+                vertical_component: VerticalVelocityUnit(
+                    vertical_offset_from_balance
+                        * vertical_growth_rate
+                        * (vertical_growth_rate * elapsed_time).sinh(),
+                ),
+            },
+        },
+    };
+
+    // The comparison is relative, so the last time slice is just before the horizontal velocity
+    // goes back to zero.
+    let milliseconds_per_time_slice = 100;
+    let number_of_time_slices = 20;
+    let expected_sequence = (0..number_of_time_slices)
+        .map(|time_slice_index| {
+            vec![particle_at_time(
+                (time_slice_index as f64)
+                    * (milliseconds_per_time_slice as f64)
+                    * super::configuration_parsing::SECONDS_PER_MILLISECOND,
+            )]
+            .into_iter()
+        })
+        .collect::<std::vec::Vec<_>>();
+    let evolution_configuration = super::configuration_parsing::EvolutionConfiguration {
+        external_fields: Some(super::configuration_parsing::ExternalFields {
+            horizontal_acceleration: 0.0,
+            vertical_acceleration,
+            fixed_sources: vec![],
+            harmonic_trap: Some(harmonic_trap),
+        }),
+        milliseconds_per_time_slice,
+        ..create_test_evolution_configuration(number_of_time_slices, TEST_DEFAULT_DEAD_ZONE_RADIUS)
+    };
+    let expected_energy = 0.5
+        * harmonic_trap.horizontal_spring_constant
+        * horizontal_amplitude
+        * horizontal_amplitude;
+    let initial_conditions = vec![particle_at_time(0.0)];
+    let evolution_result = tested_implementation
+        .create_time_sequence(&evolution_configuration, initial_conditions.into_iter());
+    compare_time_slices_to_expected(
+        evolution_result,
+        expected_sequence.into_iter(),
+        &create_test_tolerances(),
+        Some(|particle_list: &std::vec::Vec<IndividualParticle>| {
+            check_energy_given_potential(
+                1,
+                expected_energy,
+                relative_energy_tolerance,
                 particle_list,
-                potential_of_pair,
+                &evolution_configuration,
             )
         }),
     )
 }
+
+/// This releases a single particle from rest above an attracting fixed source, so that it should
+/// fall straight down towards the source while the source stays where it is, with the total energy
+/// equal to the potential energy at the start throughout.
+pub fn test_particle_falls_towards_fixed_source(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+    relative_energy_tolerance: f64,
+) -> Result<(), String> {
+    let fixed_source = super::configuration_parsing::FixedSource {
+        horizontal_coordinate: 1.0,
+        vertical_coordinate: -1.0,
+        inverse_squared_charge: 2.0,
+        inverse_fourth_charge: 0.0,
+    };
+    let initial_separation = 2.0;
+    let initial_conditions = [IndividualParticle {
+        intrinsic_values: ParticleIntrinsics {
+            inertial_mass: InertialMassUnit(1.0),
+            inverse_squared_charge: InverseSquaredChargeUnit(1.0),
+            inverse_fourth_charge: InverseFourthChargeUnit(0.0),
+            species_index: data_structure::charge::SpeciesIndex(0),
+            collision_radius: data_structure::position::SeparationUnit(0.0),
+            is_tracer: false,
+            color_brightness: data_structure::color::new_triplet(
+                RedColorUnit(1.0),
+                GreenColorUnit(1.0),
+                BlueColorUnit(1.0),
+            ),
+        },
+        variable_values: ParticleVariables {
+            position_vector: PositionVector {
+                horizontal_component: HorizontalPositionUnit(fixed_source.horizontal_coordinate),
+                vertical_component: VerticalPositionUnit(
+                    fixed_source.vertical_coordinate + initial_separation,
+                ),
+            },
+            velocity_vector: VelocityVector {
+                horizontal_component: HorizontalVelocityUnit(0.0),
+                vertical_component: VerticalVelocityUnit(0.0),
+            },
+        },
+    }];
+    let evolution_configuration = super::configuration_parsing::EvolutionConfiguration {
+        external_fields: Some(super::configuration_parsing::ExternalFields {
+            horizontal_acceleration: 0.0,
+            vertical_acceleration: 0.0,
+            fixed_sources: vec![fixed_source],
+            harmonic_trap: None,
+        }),
+        milliseconds_per_time_slice: 10,
+        ..create_test_evolution_configuration(100, TEST_DEFAULT_DEAD_ZONE_RADIUS)
+    };
+    let expected_energy = evolution_configuration.inverse_squared_coupling
+        * fixed_source.inverse_squared_charge
+        / initial_separation;
+
+    let particle_set_evolution = tested_implementation
+        .create_time_sequence(&evolution_configuration, initial_conditions.iter())
+        .map_err(|evolution_error| format!("Evolution error: {:?}", evolution_error))?;
+    let mut final_particle = None;
+    for (time_slice_index, time_slice) in particle_set_evolution.particle_configurations.enumerate()
+    {
+        let particle_list = time_slice
+            .map_err(|evolution_error| format!("Evolution error: {:?}", evolution_error))?
+            .map(|evolved_particle| {
+                data_structure::particle::create_individual_from_representation(&evolved_particle)
+            })
+            .collect::<std::vec::Vec<IndividualParticle>>();
+        check_energy_given_potential(
+            1,
+            expected_energy,
+            relative_energy_tolerance,
+            &particle_list,
+            &evolution_configuration,
+        )
+        .map_err(|energy_error| format!("Time slice {}: {}", time_slice_index, energy_error))?;
+        final_particle = particle_list.into_iter().next();
+    }
+    let final_position = match &final_particle {
+        Some(final_particle) => final_particle.variable_values.position_vector,
+        None => return Err(String::from("Expected at least one time slice")),
+    };
+    if ((final_position.horizontal_component.0 - fixed_source.horizontal_coordinate).abs()
+        < TEST_DEFAULT_TOLERANCE)
+        && (final_position.vertical_component.0
+            < (fixed_source.vertical_coordinate + initial_separation - 0.1))
+        && (final_position.vertical_component.0 > fixed_source.vertical_coordinate)
+    {
+        Ok(())
+    } else {
+        Err(format!(
+            "Expected the particle to have fallen straight towards {:?}, actual final particle {:?}",
+            fixed_source, final_particle
+        ))
+    }
+}
+
+/// This lets a single uncharged particle coast with drag, checking that it slows exponentially and
+/// comes to rest at the distance which its initial velocity over the drag rate would take it. The
+/// drag is applied after each step rather than during it, so implementations which take longer
+/// steps can pass a larger relative tolerance.
+pub fn test_drag_slows_free_particle_exponentially(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+    relative_tolerance: f64,
+) -> Result<(), String> {
+    let drag_rate = 1.5;
+    let initial_velocity = (2.0, -1.0);
+    let particle_at_time = |elapsed_time: f64| {
+        let retained_velocity_fraction = (-drag_rate * elapsed_time).exp();
+        let distance_fraction = (1.0 - retained_velocity_fraction) / drag_rate;
+        IndividualParticle {
+            intrinsic_values: ParticleIntrinsics {
+                inertial_mass: InertialMassUnit(3.0),
+                inverse_squared_charge: InverseSquaredChargeUnit(0.0),
+                inverse_fourth_charge: InverseFourthChargeUnit(0.0),
+                species_index: data_structure::charge::SpeciesIndex(0),
+                collision_radius: data_structure::position::SeparationUnit(0.0),
+                is_tracer: false,
+                color_brightness: data_structure::color::new_triplet(
+                    RedColorUnit(1.0),
+                    GreenColorUnit(1.0),
+                    BlueColorUnit(1.0),
+                ),
+            },
+            variable_values: ParticleVariables {
+                position_vector: PositionVector {
+                    horizontal_component: HorizontalPositionUnit(
+                        initial_velocity.0 * distance_fraction,
+                    ),
+                    vertical_component: VerticalPositionUnit(
+                        initial_velocity.1 * distance_fraction,
+                    ),
+                },
+                velocity_vector: VelocityVector {
+                    horizontal_component: HorizontalVelocityUnit(
+                        initial_velocity.0 * retained_velocity_fraction,
+                    ),
+                    vertical_component: VerticalVelocityUnit(
+                        initial_velocity.1 * retained_velocity_fraction,
+                    ),
+                },
+            },
+        }
+    };
+
+    let milliseconds_per_time_slice = 100;
+    let number_of_time_slices = 21;
+    let expected_sequence = (0..number_of_time_slices)
+        .map(|time_slice_index| {
+            vec![particle_at_time(
+                (time_slice_index as f64)
+                    * (milliseconds_per_time_slice as f64)
+                    * super::configuration_parsing::SECONDS_PER_MILLISECOND,
+            )]
+            .into_iter()
+        })
+        .collect::<std::vec::Vec<_>>();
+    let evolution_configuration = super::configuration_parsing::EvolutionConfiguration {
+        drag: Some(super::configuration_parsing::DragConfiguration {
+            drag_rate,
+            thermostat: None,
+        }),
+        milliseconds_per_time_slice,
+        ..create_test_evolution_configuration(number_of_time_slices, TEST_DEFAULT_DEAD_ZONE_RADIUS)
+    };
+    let initial_conditions = vec![particle_at_time(0.0)];
+    let evolution_result = tested_implementation
+        .create_time_sequence(&evolution_configuration, initial_conditions.into_iter());
+    compare_time_slices_to_expected(
+        evolution_result,
+        expected_sequence.into_iter(),
+        &create_test_tolerance_with_separate_for_values(
+            relative_tolerance,
+            relative_tolerance,
+            relative_tolerance,
+            relative_tolerance,
+        ),
+        NO_ADDITIONAL_CHECK,
+    )
+}
+
+/// The particles are uncharged so that only the thermostat changes their velocities, and have
+/// different masses, as the temperature which they reach should not depend on their masses.
+fn create_uncharged_grid_at_rest(grid_side_length: usize) -> std::vec::Vec<IndividualParticle> {
+    create_particle_grid(grid_side_length)
+        .into_iter()
+        .map(|mut grid_particle| {
+            grid_particle.intrinsic_values.inverse_squared_charge = InverseSquaredChargeUnit(0.0);
+            grid_particle.intrinsic_values.inverse_fourth_charge = InverseFourthChargeUnit(0.0);
+            grid_particle.variable_values.velocity_vector = VelocityVector {
+                horizontal_component: HorizontalVelocityUnit(0.0),
+                vertical_component: VerticalVelocityUnit(0.0),
+            };
+            grid_particle
+        })
+        .collect()
+}
+
+fn create_thermostat_configuration(
+    target_temperature: f64,
+    random_seed: u64,
+    number_of_time_slices: usize,
+) -> super::configuration_parsing::EvolutionConfiguration {
+    super::configuration_parsing::EvolutionConfiguration {
+        drag: Some(super::configuration_parsing::DragConfiguration {
+            drag_rate: 2.0,
+            thermostat: Some(super::configuration_parsing::ThermostatConfiguration {
+                target_temperature,
+                random_seed,
+                generator_state: None,
+            }),
+        }),
+        milliseconds_per_time_slice: 100,
+        ..create_test_evolution_configuration(number_of_time_slices, TEST_DEFAULT_DEAD_ZONE_RADIUS)
+    }
+}
+
+/// This starts a grid of particles at rest with a thermostat, and checks that the kinetic
+/// temperature averaged over the later time slices, long after the time which the drag takes to
+/// bring the temperature to equilibrium, is close to the target. Averaging over several time
+/// slices evens out the fluctuations of the temperature of a finite number of particles.
+pub fn test_thermostat_brings_kinetic_temperature_to_target(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+) -> Result<(), String> {
+    let target_temperature = 0.75;
+    let number_of_time_slices = 60;
+    let number_of_averaged_time_slices = 30;
+    let evolution_configuration =
+        create_thermostat_configuration(target_temperature, 2020, number_of_time_slices);
+    let particle_set_evolution = tested_implementation
+        .create_time_sequence(
+            &evolution_configuration,
+            create_uncharged_grid_at_rest(12).iter(),
+        )
+        .map_err(|evolution_error| format!("Evolution error: {:?}", evolution_error))?;
+    let mut kinetic_temperatures = vec![];
+    for time_slice in particle_set_evolution.particle_configurations {
+        let particle_list = time_slice
+            .map_err(|evolution_error| format!("Evolution error: {:?}", evolution_error))?
+            .map(|evolved_particle| {
+                data_structure::particle::create_individual_from_representation(&evolved_particle)
+            })
+            .collect::<std::vec::Vec<IndividualParticle>>();
+        kinetic_temperatures.push(super::diagnostics::kinetic_temperature(&particle_list));
+    }
+    let averaged_temperatures =
+        &kinetic_temperatures[(number_of_time_slices - number_of_averaged_time_slices)..];
+    let average_temperature =
+        averaged_temperatures.iter().sum::<f64>() / (number_of_averaged_time_slices as f64);
+    if (kinetic_temperatures[0] == 0.0)
+        && data_structure::comparison::within_relative_tolerance(
+            target_temperature,
+            average_temperature,
+            0.05,
+        )
+    {
+        Ok(())
+    } else {
+        Err(format!(
+            "Expected the kinetic temperature to go from 0 to about {}, actual temperatures {:?}",
+            target_temperature, kinetic_temperatures
+        ))
+    }
+}
+
+/// This evolves a grid of particles with a thermostat twice with the same seed and once with a
+/// different seed, and checks that only the same seed gives identical time slices bit for bit.
+pub fn test_thermostat_kicks_repeat_for_same_seed(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+) -> Result<(), String> {
+    let initial_conditions = create_uncharged_grid_at_rest(4);
+    let mut evolved_variables = vec![];
+    for random_seed in &[17, 17, 18] {
+        let particle_set_evolution = tested_implementation
+            .create_time_sequence(
+                &create_thermostat_configuration(1.0, *random_seed, 5),
+                initial_conditions.iter(),
+            )
+            .map_err(|evolution_error| format!("Evolution error: {:?}", evolution_error))?;
+        evolved_variables.push(collect_variable_bits(
+            particle_set_evolution.particle_configurations,
+        )?);
+    }
+
+    if (evolved_variables[0] == evolved_variables[1])
+        && (evolved_variables[0] != evolved_variables[2])
+    {
+        Ok(())
+    } else {
+        Err(String::from(
+            "Expected the same seed to give identical evolutions and a different seed not to",
+        ))
+    }
+}
+
+/// This sends a light particle towards a heavier one at rest, with the couplings set to zero so
+/// that they move freely, and checks that they are separate until they come within the merging
+/// radius, and from then on are a single particle with their total mass, charges, and brightness,
+/// moving along the path of their center of mass. The path of the center of mass does not depend
+/// on when within a time slice the particles merge.
+pub fn test_approaching_particles_merge_at_center_of_mass(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+) -> Result<(), String> {
+    let merging_radius = 0.5;
+    let vertical_position = 1.0;
+    let new_particle = |inertial_mass: f64,
+                        inverse_squared_charge: f64,
+                        inverse_fourth_charge: f64,
+                        color_brightness: (f64, f64, f64),
+                        horizontal_position: f64,
+                        horizontal_velocity: f64| IndividualParticle {
+        intrinsic_values: ParticleIntrinsics {
+            inertial_mass: InertialMassUnit(inertial_mass),
+            inverse_squared_charge: InverseSquaredChargeUnit(inverse_squared_charge),
+            inverse_fourth_charge: InverseFourthChargeUnit(inverse_fourth_charge),
+            species_index: data_structure::charge::SpeciesIndex(0),
+            collision_radius: data_structure::position::SeparationUnit(0.0),
+            is_tracer: false,
+            color_brightness: data_structure::color::new_triplet(
+                RedColorUnit(color_brightness.0),
+                GreenColorUnit(color_brightness.1),
+                BlueColorUnit(color_brightness.2),
+            ),
+        },
+        variable_values: ParticleVariables {
+            position_vector: PositionVector {
+                horizontal_component: HorizontalPositionUnit(horizontal_position),
+                vertical_component: VerticalPositionUnit(vertical_position),
+            },
+            velocity_vector: VelocityVector {
+                horizontal_component: HorizontalVelocityUnit(horizontal_velocity),
+                vertical_component: VerticalVelocityUnit(0.0),
+            },
+        },
+    };
+    // The light particle starts 2 to the left of the heavy particle and closes the gap at 2 per
+    // second, so they are within the merging radius after 0.75 seconds.
+    let particles_at_time = |elapsed_time: f64| {
+        let light_position = 2.0 + (2.0 * elapsed_time);
+        let heavy_position = 4.0;
+        if (heavy_position - light_position) < merging_radius {
+            vec![new_particle(
+                4.0,
+                3.0,
+                -1.0,
+                (1.5, 1.0, 2.0),
+                (light_position + (3.0 * heavy_position)) / 4.0,
+                0.5,
+            )]
+        } else {
+            vec![
+                new_particle(1.0, 1.0, 0.5, (1.0, 0.0, 2.0), light_position, 2.0),
+                new_particle(3.0, 2.0, -1.5, (0.5, 1.0, 0.0), heavy_position, 0.0),
+            ]
+        }
+    };
+
+    let milliseconds_per_time_slice = 100;
+    let number_of_time_slices = 12;
+    let expected_sequence = (0..number_of_time_slices)
+        .map(|time_slice_index| {
+            particles_at_time(
+                (time_slice_index as f64)
+                    * (milliseconds_per_time_slice as f64)
+                    * super::configuration_parsing::SECONDS_PER_MILLISECOND,
+            )
+            .into_iter()
+        })
+        .collect::<std::vec::Vec<_>>();
+    let evolution_configuration = super::configuration_parsing::EvolutionConfiguration {
+        inverse_squared_coupling: 0.0,
+        inverse_fourth_coupling: 0.0,
+        merging_radius: Some(merging_radius),
+        milliseconds_per_time_slice,
+        ..create_test_evolution_configuration(number_of_time_slices, TEST_DEFAULT_DEAD_ZONE_RADIUS)
+    };
+    let evolution_result = tested_implementation
+        .create_time_sequence(&evolution_configuration, particles_at_time(0.0).into_iter());
+    compare_time_slices_to_expected(
+        evolution_result,
+        expected_sequence.into_iter(),
+        &create_test_tolerances(),
+        NO_ADDITIONAL_CHECK,
+    )
+}
+
+/// The particles are given no charges, so they move in straight lines apart from when they
+/// collide. The given positions and velocities are horizontal then vertical.
+fn create_colliding_particle(
+    inertial_mass: f64,
+    collision_radius: f64,
+    position_components: (f64, f64),
+    velocity_components: (f64, f64),
+) -> IndividualParticle {
+    let mut colliding_particle = new_test_particle(
+        inertial_mass,
+        0.0,
+        0.0,
+        position_components,
+        velocity_components,
+    );
+    colliding_particle.intrinsic_values.collision_radius = SpatialSeparationUnit(collision_radius);
+    colliding_particle
+}
+
+fn test_collision_of_two_particles(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+    restitution_coefficient: f64,
+    particles_at_time: &impl Fn(f64) -> std::vec::Vec<IndividualParticle>,
+) -> Result<(), String> {
+    let milliseconds_per_time_slice = 100;
+    let number_of_time_slices = 20;
+    let expected_sequence = (0..number_of_time_slices)
+        .map(|time_slice_index| {
+            particles_at_time(
+                (time_slice_index as f64)
+                    * (milliseconds_per_time_slice as f64)
+                    * super::configuration_parsing::SECONDS_PER_MILLISECOND,
+            )
+            .into_iter()
+        })
+        .collect::<std::vec::Vec<_>>();
+    let evolution_configuration = super::configuration_parsing::EvolutionConfiguration {
+        inverse_squared_coupling: 0.0,
+        inverse_fourth_coupling: 0.0,
+        restitution_coefficient: Some(restitution_coefficient),
+        milliseconds_per_time_slice,
+        ..create_test_evolution_configuration(number_of_time_slices, TEST_DEFAULT_DEAD_ZONE_RADIUS)
+    };
+    let evolution_result = tested_implementation
+        .create_time_sequence(&evolution_configuration, particles_at_time(0.0).into_iter());
+    compare_time_slices_to_expected(
+        evolution_result,
+        expected_sequence.into_iter(),
+        &create_test_tolerances(),
+        NO_ADDITIONAL_CHECK,
+    )
+}
+
+/// The light particle has 1 of mass and the heavy particle has 3, so with a restitution coefficient
+/// of 0.5, the light particle bounces back at an eighth of its speed while the heavy particle moves
+/// off at three eighths of it, keeping the total momentum. The particles touch between
+/// internal steps, so the evolver has to find the moment of contact within a step.
+pub fn test_head_on_collision_keeps_momentum_and_scales_approach_speed(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+) -> Result<(), String> {
+    let contact_time = 0.9375;
+    let particles_at_time = |elapsed_time: f64| {
+        let (light_position, heavy_position, light_velocity, heavy_velocity) =
+            if elapsed_time < contact_time {
+                (2.0 - (2.0 * (contact_time - elapsed_time)), 3.0, 2.0, 0.0)
+            } else {
+                (
+                    2.0 - (0.25 * (elapsed_time - contact_time)),
+                    3.0 + (0.75 * (elapsed_time - contact_time)),
+                    -0.25,
+                    0.75,
+                )
+            };
+        vec![
+            create_colliding_particle(1.0, 0.4, (light_position, 0.0), (light_velocity, 0.0)),
+            create_colliding_particle(3.0, 0.6, (heavy_position, 0.0), (heavy_velocity, 0.0)),
+        ]
+    };
+    test_collision_of_two_particles(tested_implementation, 0.5, &particles_at_time)
+}
+
+/// The moving particle passes the resting particle with its center half of the sum of their radii
+/// away from the line of its motion, so at contact the line between their centers is 30 degrees
+/// from the line of motion. With equal masses and a restitution coefficient of 1, the resting
+/// particle moves off along the line between the centers with the component of the velocity along
+/// it, and the moving particle keeps the perpendicular component, so they move off at right angles
+/// to each other.
+pub fn test_glancing_elastic_collision_deflects_at_right_angles(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+) -> Result<(), String> {
+    let half_root_three = 0.5 * 3.0_f64.sqrt();
+    let contact_position = 3.0 - half_root_three;
+    let contact_time = 0.5 * contact_position;
+    let particles_at_time = |elapsed_time: f64| {
+        if elapsed_time < contact_time {
+            vec![
+                create_colliding_particle(1.0, 0.5, (2.0 * elapsed_time, 0.0), (2.0, 0.0)),
+                create_colliding_particle(1.0, 0.5, (3.0, 0.5), (0.0, 0.0)),
+            ]
+        } else {
+            let time_since_contact = elapsed_time - contact_time;
+            vec![
+                create_colliding_particle(
+                    1.0,
+                    0.5,
+                    (
+                        contact_position + (0.5 * time_since_contact),
+                        -half_root_three * time_since_contact,
+                    ),
+                    (0.5, -half_root_three),
+                ),
+                create_colliding_particle(
+                    1.0,
+                    0.5,
+                    (
+                        3.0 + (1.5 * time_since_contact),
+                        0.5 + (half_root_three * time_since_contact),
+                    ),
+                    (1.5, half_root_three),
+                ),
+            ]
+        }
+    };
+    test_collision_of_two_particles(tested_implementation, 1.0, &particles_at_time)
+}
+
+const BONDED_PAIR_STIFFNESS: f64 = 3.0;
+const BONDED_PAIR_REST_LENGTH: f64 = 2.0;
+const BONDED_PAIR_INITIAL_STRETCH: f64 = 0.5;
+
+/// The light particle has 1 of mass and the heavy particle has 3, so their reduced mass is 0.75
+/// and with a stiffness of 3 the bond would oscillate at an angular frequency of 2 without damping.
+/// The pair is released from rest with the bond stretched, with the center of mass at the origin,
+/// so the separation rings down about the rest length at the damped frequency, and each particle
+/// moves along the bond by its share of the change in separation.
+fn bonded_pair_at_time(
+    damping_coefficient: f64,
+    elapsed_time: f64,
+) -> std::vec::Vec<IndividualParticle> {
+    let reduced_mass = 0.75;
+    let undamped_frequency = (BONDED_PAIR_STIFFNESS / reduced_mass).sqrt();
+    let damping_rate = damping_coefficient / (2.0 * reduced_mass);
+    let damped_frequency =
+        ((undamped_frequency * undamped_frequency) - (damping_rate * damping_rate)).sqrt();
+    let decay_factor = BONDED_PAIR_INITIAL_STRETCH * (-damping_rate * elapsed_time).exp();
+    let separation = BONDED_PAIR_REST_LENGTH
+        + (decay_factor
+            * ((damped_frequency * elapsed_time).cos()
+                + ((damping_rate / damped_frequency) * (damped_frequency * elapsed_time).sin())));
+    let separating_speed = -decay_factor
+        * ((undamped_frequency * undamped_frequency) / damped_frequency)
+        * (damped_frequency * elapsed_time).sin();
+    vec![
+        create_colliding_particle(
+            1.0,
+            0.0,
+            (-0.75 * separation, 0.0),
+            (-0.75 * separating_speed, 0.0),
+        ),
+        create_colliding_particle(
+            3.0,
+            0.0,
+            (0.25 * separation, 0.0),
+            (0.25 * separating_speed, 0.0),
+        ),
+    ]
+}
+
+/// The comparison is relative, so the last time slice is just before the velocities go back to
+/// zero.
+fn create_bonded_pair_configuration_and_expected_sequence(
+    damping_coefficient: f64,
+) -> (
+    super::configuration_parsing::EvolutionConfiguration,
+    std::vec::Vec<std::vec::IntoIter<IndividualParticle>>,
+) {
+    let milliseconds_per_time_slice = 100;
+    let number_of_time_slices = 15;
+    let expected_sequence = (0..number_of_time_slices)
+        .map(|time_slice_index| {
+            bonded_pair_at_time(
+                damping_coefficient,
+                (time_slice_index as f64)
+                    * (milliseconds_per_time_slice as f64)
+                    * super::configuration_parsing::SECONDS_PER_MILLISECOND,
+            )
+            .into_iter()
+        })
+        .collect::<std::vec::Vec<_>>();
+    let evolution_configuration = super::configuration_parsing::EvolutionConfiguration {
+        inverse_squared_coupling: 0.0,
+        inverse_fourth_coupling: 0.0,
+        spring_bonds: vec![super::configuration_parsing::SpringBond {
+            first_particle_index: 0,
+            second_particle_index: 1,
+            rest_length: BONDED_PAIR_REST_LENGTH,
+            stiffness: BONDED_PAIR_STIFFNESS,
+            damping_coefficient,
+        }],
+        milliseconds_per_time_slice,
+        ..create_test_evolution_configuration(number_of_time_slices, TEST_DEFAULT_DEAD_ZONE_RADIUS)
+    };
+    (evolution_configuration, expected_sequence)
+}
+
+/// Without damping, the total energy should stay that of the stretched bond at the start.
+pub fn test_bonded_pair_oscillates_at_spring_frequency(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+    relative_energy_tolerance: f64,
+) -> Result<(), String> {
+    let (evolution_configuration, expected_sequence) =
+        create_bonded_pair_configuration_and_expected_sequence(0.0);
+    let expected_energy =
+        0.5 * BONDED_PAIR_STIFFNESS * BONDED_PAIR_INITIAL_STRETCH * BONDED_PAIR_INITIAL_STRETCH;
+    let evolution_result = tested_implementation.create_time_sequence(
+        &evolution_configuration,
+        bonded_pair_at_time(0.0, 0.0).into_iter(),
+    );
+    compare_time_slices_to_expected(
+        evolution_result,
+        expected_sequence.into_iter(),
+        &create_test_tolerances(),
+        Some(|particle_list: &std::vec::Vec<IndividualParticle>| {
+            check_energy_given_potential(
+                2,
+                expected_energy,
+                relative_energy_tolerance,
+                particle_list,
+                &evolution_configuration,
+            )
+        }),
+    )
+}
+
+pub fn test_damped_bonded_pair_rings_down(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+) -> Result<(), String> {
+    let damping_coefficient = 0.6;
+    let (evolution_configuration, expected_sequence) =
+        create_bonded_pair_configuration_and_expected_sequence(damping_coefficient);
+    let evolution_result = tested_implementation.create_time_sequence(
+        &evolution_configuration,
+        bonded_pair_at_time(damping_coefficient, 0.0).into_iter(),
+    );
+    compare_time_slices_to_expected(
+        evolution_result,
+        expected_sequence.into_iter(),
+        &create_test_tolerances(),
+        NO_ADDITIONAL_CHECK,
+    )
+}
+
+fn collect_time_slices(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+    evolution_configuration: &super::configuration_parsing::EvolutionConfiguration,
+    initial_conditions: &[IndividualParticle],
+) -> Result<std::vec::Vec<std::vec::Vec<IndividualParticle>>, String> {
+    let particle_set_evolution = tested_implementation
+        .create_time_sequence(evolution_configuration, initial_conditions.iter())
+        .map_err(|evolution_error| format!("Evolution error: {:?}", evolution_error))?;
+    particle_set_evolution
+        .particle_configurations
+        .map(|time_slice| {
+            time_slice
+                .map_err(|evolution_error| format!("Evolution error: {:?}", evolution_error))
+                .map(|particle_list| {
+                    particle_list
+                        .map(|evolved_particle| {
+                            data_structure::particle::create_individual_from_representation(
+                                &evolved_particle,
+                            )
+                        })
+                        .collect()
+                })
+        })
+        .collect()
+}
+
+/// A fixed particle with the charge of a fixed source should pull a free particle just as the
+/// fixed source does, while never moving itself however hard the free particle pulls back.
+pub fn test_anchored_particle_pulls_like_fixed_source(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+) -> Result<(), String> {
+    let anchor_position = (1.0, -1.0);
+    let free_particle = IndividualParticle {
+        intrinsic_values: ParticleIntrinsics {
+            inverse_squared_charge: InverseSquaredChargeUnit(1.0),
+            ..create_colliding_particle(1.0, 0.0, (0.0, 0.0), (0.0, 0.0)).intrinsic_values
+        },
+        ..create_colliding_particle(
+            1.0,
+            0.0,
+            (anchor_position.0, anchor_position.1 + 2.0),
+            (0.0, 0.0),
+        )
+    };
+    let anchored_particle = IndividualParticle {
+        intrinsic_values: ParticleIntrinsics {
+            inverse_squared_charge: InverseSquaredChargeUnit(2.0),
+            ..free_particle.intrinsic_values
+        },
+        ..create_colliding_particle(1.0, 0.0, anchor_position, (0.0, 0.0))
+    };
+    let base_configuration = super::configuration_parsing::EvolutionConfiguration {
+        milliseconds_per_time_slice: 10,
+        ..create_test_evolution_configuration(100, TEST_DEFAULT_DEAD_ZONE_RADIUS)
+    };
+    let fixed_source_slices = collect_time_slices(
+        tested_implementation,
+        &super::configuration_parsing::EvolutionConfiguration {
+            external_fields: Some(super::configuration_parsing::ExternalFields {
+                horizontal_acceleration: 0.0,
+                vertical_acceleration: 0.0,
+                fixed_sources: vec![super::configuration_parsing::FixedSource {
+                    horizontal_coordinate: anchor_position.0,
+                    vertical_coordinate: anchor_position.1,
+                    inverse_squared_charge: 2.0,
+                    inverse_fourth_charge: 0.0,
+                }],
+                harmonic_trap: None,
+            }),
+            ..base_configuration.clone()
+        },
+        &[free_particle],
+    )?;
+    let anchored_slices = collect_time_slices(
+        tested_implementation,
+        &super::configuration_parsing::EvolutionConfiguration {
+            motion_modes: vec![
+                super::configuration_parsing::MotionMode::Fixed {
+                    horizontal_coordinate: anchor_position.0,
+                    vertical_coordinate: anchor_position.1,
+                },
+                super::configuration_parsing::MotionMode::Free,
+            ],
+            ..base_configuration
+        },
+        &[anchored_particle, free_particle],
+    )?;
+
+    let expected_anchor_variables = anchored_particle.variable_values;
+    let is_at_anchor = |particle_variables: &ParticleVariables| {
+        (particle_variables.position_vector.horizontal_component.0 == anchor_position.0)
+            && (particle_variables.position_vector.vertical_component.0 == anchor_position.1)
+            && (particle_variables.velocity_vector.horizontal_component.0 == 0.0)
+            && (particle_variables.velocity_vector.vertical_component.0 == 0.0)
+    };
+    for (time_slice_index, (fixed_source_slice, anchored_slice)) in fixed_source_slices
+        .iter()
+        .zip(anchored_slices.iter())
+        .enumerate()
+    {
+        let expected_free_position = fixed_source_slice[0].variable_values.position_vector;
+        let actual_free_position = anchored_slice[1].variable_values.position_vector;
+        if !is_at_anchor(&anchored_slice[0].variable_values)
+            || ((actual_free_position.horizontal_component.0
+                - expected_free_position.horizontal_component.0)
+                .abs()
+                > TEST_DEFAULT_TOLERANCE)
+            || ((actual_free_position.vertical_component.0
+                - expected_free_position.vertical_component.0)
+                .abs()
+                > TEST_DEFAULT_TOLERANCE)
+        {
+            return Err(format!(
+                "Time slice {}: expected anchor at {:?} and free particle at {:?}, got {:?}",
+                time_slice_index, expected_anchor_variables, expected_free_position, anchored_slice
+            ));
+        }
+    }
+    if (fixed_source_slices.len() == 100) && (anchored_slices.len() == 100) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Expected 100 time slices, got {} with the fixed source and {} with the anchor",
+            fixed_source_slices.len(),
+            anchored_slices.len()
+        ))
+    }
+}
+
+/// A source should stay at rest however many tracers surround it, while each tracer falls towards
+/// the source just as a lone particle falls towards a fixed source, ignoring the other tracer.
+pub fn test_tracers_feel_forces_but_exert_none(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+) -> Result<(), String> {
+    let source_particle = IndividualParticle {
+        intrinsic_values: ParticleIntrinsics {
+            inverse_squared_charge: InverseSquaredChargeUnit(2.0),
+            ..create_colliding_particle(1.0, 0.0, (0.0, 0.0), (0.0, 0.0)).intrinsic_values
+        },
+        ..create_colliding_particle(1.0, 0.0, (0.0, 0.0), (0.0, 0.0))
+    };
+    let tracer_particles: std::vec::Vec<IndividualParticle> = [(0.0, 2.0), (0.5, 2.5)]
+        .iter()
+        .map(|tracer_position| IndividualParticle {
+            intrinsic_values: ParticleIntrinsics {
+                inverse_squared_charge: InverseSquaredChargeUnit(1.0),
+                is_tracer: true,
+                ..source_particle.intrinsic_values
+            },
+            ..create_colliding_particle(1.0, 0.0, *tracer_position, (0.0, 0.0))
+        })
+        .collect();
+    let base_configuration = super::configuration_parsing::EvolutionConfiguration {
+        milliseconds_per_time_slice: 10,
+        ..create_test_evolution_configuration(100, TEST_DEFAULT_DEAD_ZONE_RADIUS)
+    };
+    let mut fixed_source_slices = vec![];
+    for tracer_particle in &tracer_particles {
+        let free_particle = IndividualParticle {
+            intrinsic_values: ParticleIntrinsics {
+                is_tracer: false,
+                ..tracer_particle.intrinsic_values
+            },
+            ..*tracer_particle
+        };
+        fixed_source_slices.push(collect_time_slices(
+            tested_implementation,
+            &super::configuration_parsing::EvolutionConfiguration {
+                external_fields: Some(super::configuration_parsing::ExternalFields {
+                    horizontal_acceleration: 0.0,
+                    vertical_acceleration: 0.0,
+                    fixed_sources: vec![super::configuration_parsing::FixedSource {
+                        horizontal_coordinate: 0.0,
+                        vertical_coordinate: 0.0,
+                        inverse_squared_charge: 2.0,
+                        inverse_fourth_charge: 0.0,
+                    }],
+                    harmonic_trap: None,
+                }),
+                ..base_configuration.clone()
+            },
+            &[free_particle],
+        )?);
+    }
+    let mut initial_conditions = vec![source_particle];
+    initial_conditions.extend(tracer_particles.iter().copied());
+    let tracer_slices = collect_time_slices(
+        tested_implementation,
+        &base_configuration,
+        &initial_conditions,
+    )?;
+
+    for (time_slice_index, tracer_slice) in tracer_slices.iter().enumerate() {
+        let source_variables = tracer_slice[0].variable_values;
+        if (source_variables.position_vector.horizontal_component.0 != 0.0)
+            || (source_variables.position_vector.vertical_component.0 != 0.0)
+            || (source_variables.velocity_vector.horizontal_component.0 != 0.0)
+            || (source_variables.velocity_vector.vertical_component.0 != 0.0)
+        {
+            return Err(format!(
+                "Time slice {}: expected source at rest at the origin, got {:?}",
+                time_slice_index, tracer_slice
+            ));
+        }
+        for (tracer_index, fixed_source_slice) in fixed_source_slices.iter().enumerate() {
+            let expected_position = fixed_source_slice[time_slice_index][0]
+                .variable_values
+                .position_vector;
+            let actual_position = tracer_slice[tracer_index + 1]
+                .variable_values
+                .position_vector;
+            if ((actual_position.horizontal_component.0 - expected_position.horizontal_component.0)
+                .abs()
+                > TEST_DEFAULT_TOLERANCE)
+                || ((actual_position.vertical_component.0 - expected_position.vertical_component.0)
+                    .abs()
+                    > TEST_DEFAULT_TOLERANCE)
+            {
+                return Err(format!(
+                    "Time slice {}: expected tracer {} at {:?}, got {:?}",
+                    time_slice_index, tracer_index, expected_position, tracer_slice
+                ));
+            }
+        }
+    }
+    if tracer_slices.len() == 100 {
+        Ok(())
+    } else {
+        Err(format!(
+            "Expected 100 time slices, got {}",
+            tracer_slices.len()
+        ))
+    }
+}
+
+const SCRIPTED_KEYFRAMES: [(f64, (f64, f64)); 3] =
+    [(0.05, (0.0, 0.0)), (1.05, (2.0, 0.0)), (1.55, (2.0, 1.0))];
+const SCRIPTED_ELLIPSE_CENTER: (f64, f64) = (-1.0, 1.0);
+const SCRIPTED_ELLIPSE_SEMI_AXES: (f64, f64) = (2.0, 1.0);
+const SCRIPTED_ELLIPSE_INITIAL_PHASE: f64 = 0.3;
+
+/// The first particle is at rest before the first keyframe and after the last, and moves at
+/// constant velocity between keyframes, none of which fall on a time slice. The second goes around
+/// the ellipse at an angular frequency of 1. Both are charged and heavy with charge, so they would
+/// fly off if they were free.
+fn scripted_particles_at_time(elapsed_time: f64) -> std::vec::Vec<IndividualParticle> {
+    let keyframe_variables = match SCRIPTED_KEYFRAMES
+        .iter()
+        .position(|(keyframe_time, _)| *keyframe_time > elapsed_time)
+    {
+        Some(0) => (SCRIPTED_KEYFRAMES[0].1, (0.0, 0.0)),
+        None => (SCRIPTED_KEYFRAMES[2].1, (0.0, 0.0)),
+        Some(later_index) => {
+            let (earlier_time, earlier_position) = SCRIPTED_KEYFRAMES[later_index - 1];
+            let (later_time, later_position) = SCRIPTED_KEYFRAMES[later_index];
+            let segment_velocity = (
+                (later_position.0 - earlier_position.0) / (later_time - earlier_time),
+                (later_position.1 - earlier_position.1) / (later_time - earlier_time),
+            );
+            (
+                (
+                    earlier_position.0 + (segment_velocity.0 * (elapsed_time - earlier_time)),
+                    earlier_position.1 + (segment_velocity.1 * (elapsed_time - earlier_time)),
+                ),
+                segment_velocity,
+            )
+        }
+    };
+    let ellipse_phase = elapsed_time + SCRIPTED_ELLIPSE_INITIAL_PHASE;
+    let charged_intrinsics = ParticleIntrinsics {
+        inverse_squared_charge: InverseSquaredChargeUnit(5.0),
+        inverse_fourth_charge: InverseFourthChargeUnit(5.0),
+        ..create_colliding_particle(1.0, 0.0, (0.0, 0.0), (0.0, 0.0)).intrinsic_values
+    };
+    vec![
+        IndividualParticle {
+            intrinsic_values: charged_intrinsics,
+            ..create_colliding_particle(1.0, 0.0, keyframe_variables.0, keyframe_variables.1)
+        },
+        IndividualParticle {
+            intrinsic_values: charged_intrinsics,
+            ..create_colliding_particle(
+                1.0,
+                0.0,
+                (
+                    SCRIPTED_ELLIPSE_CENTER.0
+                        + (SCRIPTED_ELLIPSE_SEMI_AXES.0 * ellipse_phase.cos()),
+                    SCRIPTED_ELLIPSE_CENTER.1
+                        + (SCRIPTED_ELLIPSE_SEMI_AXES.1 * ellipse_phase.sin()),
+                ),
+                (
+                    -SCRIPTED_ELLIPSE_SEMI_AXES.0 * ellipse_phase.sin(),
+                    SCRIPTED_ELLIPSE_SEMI_AXES.1 * ellipse_phase.cos(),
+                ),
+            )
+        },
+    ]
+}
+
+pub fn test_scripted_particles_follow_keyframes_and_ellipse(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+) -> Result<(), String> {
+    let milliseconds_per_time_slice = 100;
+    let number_of_time_slices = 20;
+    let expected_sequence = (0..number_of_time_slices)
+        .map(|time_slice_index| {
+            scripted_particles_at_time(
+                (time_slice_index as f64)
+                    * (milliseconds_per_time_slice as f64)
+                    * super::configuration_parsing::SECONDS_PER_MILLISECOND,
+            )
+            .into_iter()
+        })
+        .collect::<std::vec::Vec<_>>();
+    let evolution_configuration = super::configuration_parsing::EvolutionConfiguration {
+        motion_modes: vec![
+            super::configuration_parsing::MotionMode::Keyframes(
+                SCRIPTED_KEYFRAMES
+                    .iter()
+                    .map(|(keyframe_time, keyframe_position)| {
+                        super::configuration_parsing::Keyframe {
+                            time_in_seconds: *keyframe_time,
+                            horizontal_coordinate: keyframe_position.0,
+                            vertical_coordinate: keyframe_position.1,
+                        }
+                    })
+                    .collect(),
+            ),
+            super::configuration_parsing::MotionMode::Ellipse(
+                super::configuration_parsing::EllipticalPath {
+                    horizontal_center: SCRIPTED_ELLIPSE_CENTER.0,
+                    vertical_center: SCRIPTED_ELLIPSE_CENTER.1,
+                    horizontal_semi_axis: SCRIPTED_ELLIPSE_SEMI_AXES.0,
+                    vertical_semi_axis: SCRIPTED_ELLIPSE_SEMI_AXES.1,
+                    angular_frequency: 1.0,
+                    initial_phase: SCRIPTED_ELLIPSE_INITIAL_PHASE,
+                },
+            ),
+        ],
+        milliseconds_per_time_slice,
+        ..create_test_evolution_configuration(number_of_time_slices, TEST_DEFAULT_DEAD_ZONE_RADIUS)
+    };
+    let evolution_result = tested_implementation.create_time_sequence(
+        &evolution_configuration,
+        scripted_particles_at_time(0.0).into_iter(),
+    );
+    compare_time_slices_to_expected(
+        evolution_result,
+        expected_sequence.into_iter(),
+        &create_test_tolerances(),
+        NO_ADDITIONAL_CHECK,
+    )
+}
+
+/// Motion modes which are all free are allowed, as they change nothing.
+pub fn test_prescribed_motion_rejected(
+    tested_implementation: &mut impl super::ParticlesInTimeEvolver,
+) -> Result<(), String> {
+    let initial_conditions = [create_colliding_particle(1.0, 0.0, (0.0, 0.0), (0.0, 0.0))];
+    let free_configuration = super::configuration_parsing::EvolutionConfiguration {
+        motion_modes: vec![super::configuration_parsing::MotionMode::Free],
+        ..create_test_evolution_configuration(2, TEST_DEFAULT_DEAD_ZONE_RADIUS)
+    };
+    let is_free_accepted = tested_implementation
+        .create_time_sequence(&free_configuration, initial_conditions.iter())
+        .is_ok();
+    let fixed_configuration = super::configuration_parsing::EvolutionConfiguration {
+        motion_modes: vec![super::configuration_parsing::MotionMode::Fixed {
+            horizontal_coordinate: 0.0,
+            vertical_coordinate: 0.0,
+        }],
+        ..free_configuration
+    };
+    let is_fixed_rejected = tested_implementation
+        .create_time_sequence(&fixed_configuration, initial_conditions.iter())
+        .is_err();
+    if is_free_accepted && is_fixed_rejected {
+        Ok(())
+    } else {
+        Err(format!(
+            "Expected free motion accepted ({}) and fixed motion rejected ({})",
+            is_free_accepted, is_fixed_rejected
+        ))
+    }
+}
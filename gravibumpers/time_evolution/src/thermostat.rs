@@ -0,0 +1,297 @@
+/// This module provides the drag and the Langevin thermostat which the evolvers apply to the
+/// velocities of the particles after each step, if the configuration gives them. The velocity of
+/// each particle is updated by the exact solution of the drag and the random kicks over the length
+/// of the step, so that the drag cannot overshoot however long the step is. The random numbers come
+/// from a small generator in this module rather than from another crate, so that a given seed
+/// always gives the same kicks. The generator is jumped on by the same amount at the end of every
+/// time slice, so its state at any frame can be calculated from its starting state and written in
+/// a checkpoint, and an evolution resumed from the checkpoint gets the same kicks as an
+/// uninterrupted evolution would.
+use data_structure::particle::WritableInForceField;
+
+/// This is the xoshiro256** generator of Blackman and Vigna, with its state filled from the seed
+/// by the splitmix64 generator as its authors recommend.
+#[derive(Clone, Debug)]
+struct SeededGenerator {
+    generator_state: [u64; 4],
+}
+
+/// These are the constants which jump the generator on by 2^128 numbers, given by its authors.
+const JUMP_CONSTANTS: [u64; 4] = [
+    0x180e_c6d3_3cfd_0aba,
+    0xd5a6_1266_f0c9_392c,
+    0xa958_2618_e03f_c9aa,
+    0x39ab_dc45_29b1_661c,
+];
+
+impl SeededGenerator {
+    /// The generator starts from the given state if there is one, such as when resuming from a
+    /// checkpoint, and otherwise from the seed.
+    fn new_from_configuration(
+        thermostat_configuration: &configuration_parsing::ThermostatConfiguration,
+    ) -> Self {
+        match thermostat_configuration.generator_state {
+            Some(generator_state) => Self { generator_state },
+            None => Self::new(thermostat_configuration.random_seed),
+        }
+    }
+
+    fn new(random_seed: u64) -> Self {
+        let mut splitmix_state = random_seed;
+        let mut next_splitmix = || {
+            splitmix_state = splitmix_state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut mixed_bits = splitmix_state;
+            mixed_bits = (mixed_bits ^ (mixed_bits >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            mixed_bits = (mixed_bits ^ (mixed_bits >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            mixed_bits ^ (mixed_bits >> 31)
+        };
+        Self {
+            generator_state: [
+                next_splitmix(),
+                next_splitmix(),
+                next_splitmix(),
+                next_splitmix(),
+            ],
+        }
+    }
+
+    fn next_bits(&mut self) -> u64 {
+        let state = &mut self.generator_state;
+        let generated_bits = state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let shifted_bits = state[1] << 17;
+        state[2] ^= state[0];
+        state[3] ^= state[1];
+        state[1] ^= state[2];
+        state[0] ^= state[3];
+        state[2] ^= shifted_bits;
+        state[3] = state[3].rotate_left(45);
+        generated_bits
+    }
+
+    /// This gives the same state as drawing 2^128 numbers, which no time slice could draw.
+    fn jump(&mut self) {
+        let mut jumped_state = [0_u64; 4];
+        for jump_constant in &JUMP_CONSTANTS {
+            for bit_index in 0..64 {
+                if (jump_constant & (1_u64 << bit_index)) != 0 {
+                    for (jumped_word, current_word) in
+                        jumped_state.iter_mut().zip(self.generator_state.iter())
+                    {
+                        *jumped_word ^= current_word;
+                    }
+                }
+                self.next_bits();
+            }
+        }
+        self.generator_state = jumped_state;
+    }
+
+    /// The number is in (0, 1] rather than [0, 1) so that its logarithm is always finite.
+    fn next_uniform(&mut self) -> f64 {
+        ((self.next_bits() >> 11) as f64 + 1.0) / ((1_u64 << 53) as f64)
+    }
+
+    /// The Box-Muller transform gives two independent numbers from the standard normal
+    /// distribution for every two uniform numbers, which is one for each component of a kick.
+    fn next_pair_of_normals(&mut self) -> (f64, f64) {
+        let normal_magnitude = (-2.0 * self.next_uniform().ln()).sqrt();
+        let normal_angle = 2.0 * std::f64::consts::PI * self.next_uniform();
+        (
+            normal_magnitude * normal_angle.cos(),
+            normal_magnitude * normal_angle.sin(),
+        )
+    }
+}
+
+/// The kicks of each time slice are drawn from a copy of the generator as it was at the start of
+/// the time slice.
+#[derive(Clone, Debug)]
+struct Thermostat {
+    target_temperature: f64,
+    time_slice_generator: SeededGenerator,
+    random_generator: SeededGenerator,
+}
+
+/// This gives the state of the generator at the start of the time slice which is the given number
+/// of time slices after the start of an evolution with the given thermostat, for a checkpoint of
+/// that time slice.
+pub fn generator_state_after_time_slices(
+    thermostat_configuration: &configuration_parsing::ThermostatConfiguration,
+    number_of_time_slices: usize,
+) -> [u64; 4] {
+    let mut random_generator = SeededGenerator::new_from_configuration(thermostat_configuration);
+    for _ in 0..number_of_time_slices {
+        random_generator.jump();
+    }
+    random_generator.generator_state
+}
+
+/// Each evolver holds one of these for each sequence which it creates, so that the random kicks
+/// carry on from one time slice to the next.
+#[derive(Clone, Debug)]
+pub struct DragAndThermostat {
+    drag_rate: f64,
+    thermostat: Option<Thermostat>,
+}
+
+impl DragAndThermostat {
+    /// This returns None if the configuration has no drag.
+    pub fn new_from_configuration(
+        evolution_configuration: &configuration_parsing::EvolutionConfiguration,
+    ) -> Option<Self> {
+        let drag_configuration = evolution_configuration.drag?;
+        Some(Self {
+            drag_rate: drag_configuration.drag_rate,
+            thermostat: drag_configuration
+                .thermostat
+                .map(|thermostat_configuration| {
+                    let random_generator =
+                        SeededGenerator::new_from_configuration(&thermostat_configuration);
+                    Thermostat {
+                        target_temperature: thermostat_configuration.target_temperature,
+                        time_slice_generator: random_generator.clone(),
+                        random_generator,
+                    }
+                }),
+        })
+    }
+
+    /// This jumps the generator on from its state at the start of the time slice, however many
+    /// numbers were drawn during the time slice, which may differ between evolvers.
+    pub fn finish_time_slice(&mut self) {
+        if let Some(thermostat) = &mut self.thermostat {
+            thermostat.time_slice_generator.jump();
+            thermostat.random_generator = thermostat.time_slice_generator.clone();
+        }
+    }
+
+    /// Over a time t, the drag alone multiplies the velocity by exp(-drag rate * t). The kicks add
+    /// a random velocity from a normal distribution for each component, with the variance which
+    /// brings the variance of each component of the velocity of a particle of mass m towards
+    /// temperature / m.
+    pub fn apply_for_time_interval<ParticleImplementation, ParticleCollection>(
+        &mut self,
+        time_interval: &data_structure::time::IntervalUnit,
+        evolving_particles: &mut ParticleCollection,
+    ) where
+        ParticleImplementation: WritableInForceField,
+        ParticleCollection: data_structure::collection::SingleAndPairwiseFinite<
+            MutableElement = ParticleImplementation,
+        >,
+    {
+        let retained_velocity_fraction = (-self.drag_rate * time_interval.0).exp();
+        match &mut self.thermostat {
+            None => evolving_particles.apply_to_every_single(&mut |particle_with_force| {
+                let velocity_vector = &mut particle_with_force
+                    .write_particle_variables()
+                    .velocity_vector;
+                velocity_vector.horizontal_component.0 *= retained_velocity_fraction;
+                velocity_vector.vertical_component.0 *= retained_velocity_fraction;
+            }),
+            Some(thermostat) => {
+                let kick_variance_per_temperature_over_mass =
+                    1.0 - (retained_velocity_fraction * retained_velocity_fraction);
+                evolving_particles.apply_to_every_single(&mut |particle_with_force| {
+                    let kick_size = (kick_variance_per_temperature_over_mass
+                        * thermostat.target_temperature
+                        / particle_with_force.read_intrinsics().inertial_mass.0)
+                        .sqrt();
+                    let (horizontal_normal, vertical_normal) =
+                        thermostat.random_generator.next_pair_of_normals();
+                    let velocity_vector = &mut particle_with_force
+                        .write_particle_variables()
+                        .velocity_vector;
+                    velocity_vector.horizontal_component.0 = (retained_velocity_fraction
+                        * velocity_vector.horizontal_component.0)
+                        + (kick_size * horizontal_normal);
+                    velocity_vector.vertical_component.0 = (retained_velocity_fraction
+                        * velocity_vector.vertical_component.0)
+                        + (kick_size * vertical_normal);
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_generator_repeats_for_same_seed_only() -> Result<(), String> {
+        let generate_bits = |random_seed| {
+            let mut random_generator = SeededGenerator::new(random_seed);
+            (0..8)
+                .map(|_| random_generator.next_bits())
+                .collect::<std::vec::Vec<u64>>()
+        };
+        let first_sequence = generate_bits(42);
+        if (first_sequence == generate_bits(42)) && (first_sequence != generate_bits(43)) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected only the same seed to give {:?}",
+                first_sequence
+            ))
+        }
+    }
+
+    /// A resumed evolution starts from the state given in its checkpoint, so jumping on from there
+    /// should reach the same state as jumping on from the seed through the whole evolution.
+    #[test]
+    fn check_generator_state_carries_on_from_given_state() -> Result<(), String> {
+        let seeded_configuration = configuration_parsing::ThermostatConfiguration {
+            target_temperature: 1.0,
+            random_seed: 42,
+            generator_state: None,
+        };
+        let resumed_configuration = configuration_parsing::ThermostatConfiguration {
+            generator_state: Some(generator_state_after_time_slices(&seeded_configuration, 2)),
+            ..seeded_configuration
+        };
+        let uninterrupted_state = generator_state_after_time_slices(&seeded_configuration, 5);
+        let resumed_state = generator_state_after_time_slices(&resumed_configuration, 3);
+        if (resumed_state == uninterrupted_state)
+            && (resumed_state != generator_state_after_time_slices(&seeded_configuration, 4))
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected {:?} after 5 time slices, got {:?} after resuming at 2",
+                uninterrupted_state, resumed_state
+            ))
+        }
+    }
+
+    /// The mean and variance of many samples should be close to 0 and 1, and the two numbers of
+    /// each pair should not be correlated.
+    #[test]
+    fn check_normals_have_standard_distribution() -> Result<(), String> {
+        let mut random_generator = SeededGenerator::new(7);
+        let number_of_pairs = 100_000;
+        let mut sum_of_samples = 0.0;
+        let mut sum_of_squares = 0.0;
+        let mut sum_of_products = 0.0;
+        for _ in 0..number_of_pairs {
+            let (first_normal, second_normal) = random_generator.next_pair_of_normals();
+            sum_of_samples += first_normal + second_normal;
+            sum_of_squares += (first_normal * first_normal) + (second_normal * second_normal);
+            sum_of_products += first_normal * second_normal;
+        }
+        let number_of_samples = (2 * number_of_pairs) as f64;
+        let sample_mean = sum_of_samples / number_of_samples;
+        let sample_variance = (sum_of_squares / number_of_samples) - (sample_mean * sample_mean);
+        let sample_correlation = sum_of_products / (number_of_pairs as f64);
+        if (sample_mean.abs() < 0.01)
+            && ((sample_variance - 1.0).abs() < 0.01)
+            && (sample_correlation.abs() < 0.01)
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected mean 0, variance 1, and correlation 0, got {}, {}, and {}",
+                sample_mean, sample_variance, sample_correlation
+            ))
+        }
+    }
+}
@@ -0,0 +1,402 @@
+/// This module provides a wrapper for sequences of time slices which checks each time slice against
+/// the limits of a WatchdogConfiguration as it is evolved, so that an evolution which has blown up
+/// (for example because a particle fell through the dead zone and was flung out at enormous speed)
+/// is stopped with an error rather than carrying on to produce meaningless frames.
+use super::diagnostics::FrameDiagnostics;
+use super::force_law::PairwiseForceLaw;
+use super::EvolutionError;
+use super::ParticleRepresentation;
+
+/// This passes on the frames of the wrapped sequence unchanged until a frame breaks one of the
+/// limits, at which point an error naming the frame and the particles is given in place of that
+/// frame and there are no further frames. The relative energy drift is measured from the first
+/// frame of the sequence.
+pub struct WatchedSequence<'a, FrameSequence, ForceLaw> {
+    frame_sequence: FrameSequence,
+    force_law: &'a ForceLaw,
+    watchdog_configuration: &'a configuration_parsing::WatchdogConfiguration,
+    next_frame_index: usize,
+    initial_diagnostics: Option<FrameDiagnostics>,
+    has_stopped: bool,
+}
+
+/// The first frame index is 0 for a new evolution, and the frame index of the checkpoint for a
+/// resumed evolution, so that the errors name the frame as it would be in the whole evolution. The
+/// force law is only used for the potential energy when checking the relative energy drift.
+pub fn watch_each_frame<'a, FrameSequence, ForceLaw>(
+    frame_sequence: FrameSequence,
+    force_law: &'a ForceLaw,
+    watchdog_configuration: &'a configuration_parsing::WatchdogConfiguration,
+    first_frame_index: usize,
+) -> WatchedSequence<'a, FrameSequence, ForceLaw> {
+    WatchedSequence {
+        frame_sequence,
+        force_law,
+        watchdog_configuration,
+        next_frame_index: first_frame_index,
+        initial_diagnostics: None,
+        has_stopped: false,
+    }
+}
+
+fn get_speed(particle_representation: &impl ParticleRepresentation) -> f64 {
+    let velocity_vector = particle_representation.read_variables().velocity_vector;
+    velocity_vector
+        .horizontal_component
+        .0
+        .hypot(velocity_vector.vertical_component.0)
+}
+
+fn has_only_finite_variables(particle_representation: &impl ParticleRepresentation) -> bool {
+    let particle_variables = particle_representation.read_variables();
+    particle_variables
+        .position_vector
+        .horizontal_component
+        .0
+        .is_finite()
+        && particle_variables
+            .position_vector
+            .vertical_component
+            .0
+            .is_finite()
+        && particle_variables
+            .velocity_vector
+            .horizontal_component
+            .0
+            .is_finite()
+        && particle_variables
+            .velocity_vector
+            .vertical_component
+            .0
+            .is_finite()
+}
+
+impl<'a, FrameSequence, ForceLaw> WatchedSequence<'a, FrameSequence, ForceLaw>
+where
+    ForceLaw: PairwiseForceLaw,
+{
+    /// The particles which have non-finite values are listed before any limit is checked, as
+    /// they would make every other check meaningless.
+    fn check_time_slice(
+        &mut self,
+        frame_index: usize,
+        particles_at_time_slice: &[data_structure::particle::BasicIndividual],
+    ) -> Result<(), EvolutionError> {
+        let non_finite_indices: std::vec::Vec<usize> = particles_at_time_slice
+            .iter()
+            .enumerate()
+            .filter(|(_, particle_at_time_slice)| {
+                !has_only_finite_variables(*particle_at_time_slice)
+            })
+            .map(|(particle_index, _)| particle_index)
+            .collect();
+        if !non_finite_indices.is_empty() {
+            return Err(EvolutionError::new(&format!(
+                "Frame {}: particles {:?} have positions or velocities which are not finite",
+                frame_index, non_finite_indices
+            )));
+        }
+
+        if let Some(maximum_speed) = self.watchdog_configuration.maximum_speed {
+            let too_fast: std::vec::Vec<(usize, f64)> = particles_at_time_slice
+                .iter()
+                .map(get_speed)
+                .enumerate()
+                .filter(|(_, particle_speed)| *particle_speed > maximum_speed)
+                .collect();
+            if !too_fast.is_empty() {
+                return Err(EvolutionError::new(&format!(
+                    "Frame {}: (particle index, speed) pairs {:?} exceed the maximum speed {}",
+                    frame_index, too_fast, maximum_speed
+                )));
+            }
+        }
+
+        if let Some(maximum_relative_energy_drift) =
+            self.watchdog_configuration.maximum_relative_energy_drift
+        {
+            let current_diagnostics = super::diagnostics::calculate_for_time_slice(
+                self.force_law,
+                particles_at_time_slice,
+            );
+            let initial_diagnostics = *self.initial_diagnostics.get_or_insert(current_diagnostics);
+            let relative_energy_drift = super::diagnostics::relative_energy_drift(
+                &initial_diagnostics,
+                &current_diagnostics,
+            );
+            if relative_energy_drift > maximum_relative_energy_drift {
+                // The energy belongs to the whole set of particles, but the fastest particle is
+                // the most likely to have been flung out by a close encounter.
+                let fastest_particle = particles_at_time_slice
+                    .iter()
+                    .map(get_speed)
+                    .enumerate()
+                    .max_by(|(_, first_speed), (_, second_speed)| {
+                        first_speed.total_cmp(second_speed)
+                    });
+                return Err(EvolutionError::new(&format!(
+                    "Frame {}: relative energy drift {} exceeds the maximum {} (total energy {} \
+                    rather than {}, fastest (particle index, speed) {:?})",
+                    frame_index,
+                    relative_energy_drift,
+                    maximum_relative_energy_drift,
+                    current_diagnostics.total_energy(),
+                    initial_diagnostics.total_energy(),
+                    fastest_particle
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, FrameSequence, ForceLaw, ParticleIterator> std::iter::Iterator
+    for WatchedSequence<'a, FrameSequence, ForceLaw>
+where
+    ForceLaw: PairwiseForceLaw,
+    FrameSequence: std::iter::Iterator<Item = Result<ParticleIterator, Box<dyn std::error::Error>>>,
+    ParticleIterator: std::iter::Iterator + Clone,
+    ParticleIterator::Item: ParticleRepresentation,
+{
+    type Item = Result<ParticleIterator, Box<dyn std::error::Error>>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.has_stopped {
+            return None;
+        }
+        let time_slice = self.frame_sequence.next()?;
+        let frame_index = self.next_frame_index;
+        self.next_frame_index += 1;
+        let particles_at_time_slice = match &time_slice {
+            Ok(particles_at_time_slice) => particles_at_time_slice,
+            Err(_) => return Some(time_slice),
+        };
+        let copied_particles: std::vec::Vec<data_structure::particle::BasicIndividual> =
+            particles_at_time_slice
+                .clone()
+                .map(|particle_representation| {
+                    data_structure::particle::create_individual_from_representation(
+                        &particle_representation,
+                    )
+                })
+                .collect();
+        match self.check_time_slice(frame_index, &copied_particles) {
+            Ok(()) => Some(time_slice),
+            Err(watchdog_error) => {
+                self.has_stopped = true;
+                Some(Err(Box::new(watchdog_error)))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.has_stopped {
+            (0, Some(0))
+        } else {
+            self.frame_sequence.size_hint()
+        }
+    }
+}
+
+impl<'a, FrameSequence, ForceLaw, ParticleIterator> std::iter::ExactSizeIterator
+    for WatchedSequence<'a, FrameSequence, ForceLaw>
+where
+    ForceLaw: PairwiseForceLaw,
+    FrameSequence:
+        std::iter::ExactSizeIterator<Item = Result<ParticleIterator, Box<dyn std::error::Error>>>,
+    ParticleIterator: std::iter::Iterator + Clone,
+    ParticleIterator::Item: ParticleRepresentation,
+{
+    fn len(&self) -> usize {
+        if self.has_stopped {
+            0
+        } else {
+            self.frame_sequence.len()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_test_particle(
+        horizontal_position: f64,
+        horizontal_velocity: f64,
+    ) -> data_structure::particle::BasicIndividual {
+        super::super::test_functions::new_test_particle(
+            1.0,
+            1.0,
+            0.0,
+            (horizontal_position, 0.0),
+            (horizontal_velocity, 0.0),
+        )
+    }
+
+    fn new_test_force_law() -> Result<super::super::force_law::InverseSquaredAndFourth, String> {
+        super::super::force_law::InverseSquaredAndFourth::new_from_configuration(
+            &configuration_parsing::EvolutionConfiguration {
+                inverse_squared_coupling: -1.0,
+                inverse_fourth_coupling: 0.0,
+                milliseconds_per_time_slice: 100,
+                ..super::super::test_functions::create_test_evolution_configuration(3, 0.1)
+            },
+        )
+        .map_err(|construction_error| format!("Force law error: {}", construction_error))
+    }
+
+    /// Each frame has two particles, with the second particle of each frame given, and this
+    /// returns the number of frames passed on before the error, and the error message.
+    fn count_frames_before_error(
+        first_frame_index: usize,
+        watchdog_configuration: &configuration_parsing::WatchdogConfiguration,
+        second_particles: &[data_structure::particle::BasicIndividual],
+    ) -> (usize, Option<String>) {
+        let force_law = match new_test_force_law() {
+            Ok(force_law) => force_law,
+            Err(construction_error) => return (0, Some(construction_error)),
+        };
+        let frame_sequence = second_particles.iter().map(
+            |second_particle| -> Result<_, Box<dyn std::error::Error>> {
+                Ok(vec![new_test_particle(0.0, 0.0), *second_particle].into_iter())
+            },
+        );
+        let mut watched_sequence = watch_each_frame(
+            frame_sequence,
+            &force_law,
+            watchdog_configuration,
+            first_frame_index,
+        );
+        let mut number_of_passed_frames = 0;
+        while let Some(time_slice) = watched_sequence.next() {
+            match time_slice {
+                Ok(_) => number_of_passed_frames += 1,
+                Err(watchdog_error) => {
+                    return (
+                        number_of_passed_frames,
+                        Some(format!(
+                            "{} (then {} frames left)",
+                            watchdog_error,
+                            watched_sequence.count()
+                        )),
+                    )
+                }
+            }
+        }
+        (number_of_passed_frames, None)
+    }
+
+    fn check_stopped_at(
+        expected_number_of_passed_frames: usize,
+        expected_message_parts: &[&str],
+        actual_result: (usize, Option<String>),
+    ) -> Result<(), String> {
+        match &actual_result {
+            (actual_number_of_passed_frames, Some(error_message))
+                if (*actual_number_of_passed_frames == expected_number_of_passed_frames)
+                    && expected_message_parts
+                        .iter()
+                        .all(|message_part| error_message.contains(message_part)) =>
+            {
+                Ok(())
+            }
+            _ => Err(format!(
+                "Expected error containing {:?} after {} frames, actually {:?}",
+                expected_message_parts, expected_number_of_passed_frames, actual_result
+            )),
+        }
+    }
+
+    #[test]
+    fn check_unbroken_limits_pass_every_frame() -> Result<(), String> {
+        let watchdog_configuration = configuration_parsing::WatchdogConfiguration {
+            maximum_relative_energy_drift: Some(0.01),
+            maximum_speed: Some(2.0),
+        };
+        let actual_result = count_frames_before_error(
+            0,
+            &watchdog_configuration,
+            &[new_test_particle(1.0, 1.0), new_test_particle(1.0, -1.0)],
+        );
+        if actual_result == (2, None) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected 2 frames without error, actually {:?}",
+                actual_result
+            ))
+        }
+    }
+
+    #[test]
+    fn check_non_finite_values_stop_without_limits() -> Result<(), String> {
+        let watchdog_configuration = configuration_parsing::WatchdogConfiguration {
+            maximum_relative_energy_drift: None,
+            maximum_speed: None,
+        };
+        check_stopped_at(
+            1,
+            &[
+                "Frame 8",
+                "particles [1]",
+                "not finite",
+                "then 0 frames left",
+            ],
+            count_frames_before_error(
+                7,
+                &watchdog_configuration,
+                &[
+                    new_test_particle(1.0, 1.0),
+                    new_test_particle(1.0, f64::NAN),
+                    new_test_particle(1.0, 1.0),
+                ],
+            ),
+        )
+    }
+
+    #[test]
+    fn check_maximum_speed_stops_evolution() -> Result<(), String> {
+        let watchdog_configuration = configuration_parsing::WatchdogConfiguration {
+            maximum_relative_energy_drift: None,
+            maximum_speed: Some(2.0),
+        };
+        check_stopped_at(
+            2,
+            &["Frame 2", "[(1, 3.0)]", "then 0 frames left"],
+            count_frames_before_error(
+                0,
+                &watchdog_configuration,
+                &[
+                    new_test_particle(1.0, 1.0),
+                    new_test_particle(1.0, -2.0),
+                    new_test_particle(1.0, 3.0),
+                    new_test_particle(1.0, 1.0),
+                ],
+            ),
+        )
+    }
+
+    #[test]
+    fn check_maximum_relative_energy_drift_stops_evolution() -> Result<(), String> {
+        let watchdog_configuration = configuration_parsing::WatchdogConfiguration {
+            maximum_relative_energy_drift: Some(0.1),
+            maximum_speed: None,
+        };
+        // The initial energy is 0.5 kinetic and -1 potential, so a scale of 1.5. Moving the second
+        // particle to a separation of 1.1 raises the potential by 1/11, a relative drift of about
+        // 0.06, while a separation of 2 raises it by 1/2, a relative drift of 1/3.
+        check_stopped_at(
+            2,
+            &["Frame 2", "fastest (particle index, speed) Some((1, 1.0))"],
+            count_frames_before_error(
+                0,
+                &watchdog_configuration,
+                &[
+                    new_test_particle(1.0, 1.0),
+                    new_test_particle(1.1, 1.0),
+                    new_test_particle(2.0, 1.0),
+                ],
+            ),
+        )
+    }
+}
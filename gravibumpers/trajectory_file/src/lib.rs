@@ -0,0 +1,75 @@
+/// This crate provides a compact binary format for the sequence of particle collections of an
+/// evolution, along with a writer and a reader for it. The file starts with a header which holds
+/// the configuration of the evolution as JSON, the number of milliseconds between frames, and the
+/// intrinsic values of every particle, since they do not change. After the header, every frame has
+/// the same size: the index of the frame followed by the position and velocity of every particle.
+/// Hence frame n starts at (length of header) + n * (size of frame) bytes, which serves as the
+/// index of the frames, so that any frame can be read without reading those before it.
+///
+/// All numbers are written little-endian. The layout of the header is:
+///  - 8 bytes of FILE_SIGNATURE
+///  - u32 of FORMAT_VERSION
+///  - u16 of the milliseconds between frames
+///  - u64 of the number of bytes of the configuration JSON, followed by those bytes
+///  - u64 of the number of particles
+///  - for each particle, f64s of the inertial mass, inverse-squared charge, and inverse-fourth
+///    charge, a u64 of the species index, an f64 of the collision radius, a u8 which is 1 for a
+///    tracer and 0 otherwise, and f64s of the red, green, and blue brightness
+///
+/// and the layout of each frame is:
+///  - u64 of the frame index
+///  - for each particle, f64s of the horizontal and vertical position, then of the horizontal and
+///    vertical velocity
+///
+/// The number of frames is not written in the header, as the frames are written as they are
+/// evolved, and an evolution may fail part way through. Instead it is deduced from the length of
+/// the file, ignoring an incomplete final frame.
+extern crate data_structure;
+extern crate serde_json;
+pub mod reader;
+pub mod writer;
+use std::error::Error;
+
+pub const FILE_SIGNATURE: [u8; 8] = *b"GRAVBTRJ";
+pub const FORMAT_VERSION: u32 = 2;
+
+const BYTES_PER_NUMBER: u64 = 8;
+const NUMBERS_PER_VARIABLE_PART: u64 = 4;
+
+#[derive(Debug)]
+pub struct TrajectoryFileError {
+    error_message: String,
+}
+
+impl TrajectoryFileError {
+    pub fn new(error_message: &str) -> Self {
+        Self {
+            error_message: error_message.to_string(),
+        }
+    }
+}
+
+impl Error for TrajectoryFileError {
+    fn description(&self) -> &str {
+        &self.error_message
+    }
+}
+
+impl std::fmt::Display for TrajectoryFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Error in trajectory file: {}", self.error_message)
+    }
+}
+
+/// Everything about the trajectory which does not change from frame to frame.
+#[derive(Clone, Debug)]
+pub struct TrajectoryHeader {
+    pub configuration: serde_json::Value,
+    pub milliseconds_per_frame: u16,
+    pub particle_intrinsics: std::vec::Vec<data_structure::particle::IntrinsicPart>,
+}
+
+/// This includes the frame index at the start of the frame.
+pub fn bytes_per_frame(number_of_particles: usize) -> u64 {
+    BYTES_PER_NUMBER * (1 + (NUMBERS_PER_VARIABLE_PART * (number_of_particles as u64)))
+}
@@ -0,0 +1,451 @@
+/// This module provides a reader which reads the header of a trajectory file when it is opened,
+/// and then reads any frame by seeking directly to it.
+use super::TrajectoryFileError;
+use super::TrajectoryHeader;
+use std::convert::TryInto;
+use std::io::Read;
+
+pub struct TrajectoryReader<InputReader: std::io::Read + std::io::Seek> {
+    input_reader: InputReader,
+    trajectory_header: TrajectoryHeader,
+    first_frame_position: u64,
+    number_of_frames: usize,
+}
+
+fn read_u64(input_reader: &mut impl std::io::Read) -> Result<u64, Box<dyn std::error::Error>> {
+    let mut read_bytes = [0u8; 8];
+    input_reader.read_exact(&mut read_bytes)?;
+    Ok(u64::from_le_bytes(read_bytes))
+}
+
+fn read_u8(input_reader: &mut impl std::io::Read) -> Result<u8, Box<dyn std::error::Error>> {
+    let mut read_bytes = [0u8; 1];
+    input_reader.read_exact(&mut read_bytes)?;
+    Ok(read_bytes[0])
+}
+
+fn read_f64(input_reader: &mut impl std::io::Read) -> Result<f64, Box<dyn std::error::Error>> {
+    let mut read_bytes = [0u8; 8];
+    input_reader.read_exact(&mut read_bytes)?;
+    Ok(f64::from_le_bytes(read_bytes))
+}
+
+fn read_intrinsics(
+    input_reader: &mut impl std::io::Read,
+) -> Result<data_structure::particle::IntrinsicPart, Box<dyn std::error::Error>> {
+    let inertial_mass = read_f64(input_reader)?;
+    let inverse_squared_charge = read_f64(input_reader)?;
+    let inverse_fourth_charge = read_f64(input_reader)?;
+    let species_index: usize = read_u64(input_reader)?.try_into()?;
+    let collision_radius = read_f64(input_reader)?;
+    let is_tracer = match read_u8(input_reader)? {
+        0 => false,
+        1 => true,
+        tracer_flag => {
+            return Err(Box::new(TrajectoryFileError::new(&format!(
+                "The tracer flag of a particle is {} rather than 0 or 1",
+                tracer_flag
+            ))))
+        }
+    };
+    let red_brightness = read_f64(input_reader)?;
+    let green_brightness = read_f64(input_reader)?;
+    let blue_brightness = read_f64(input_reader)?;
+    Ok(data_structure::particle::IntrinsicPart {
+        inertial_mass: data_structure::charge::InertialMassUnit(inertial_mass),
+        inverse_squared_charge: data_structure::charge::InverseSquaredChargeUnit(
+            inverse_squared_charge,
+        ),
+        inverse_fourth_charge: data_structure::charge::InverseFourthChargeUnit(
+            inverse_fourth_charge,
+        ),
+        species_index: data_structure::charge::SpeciesIndex(species_index),
+        collision_radius: data_structure::position::SeparationUnit(collision_radius),
+        is_tracer,
+        color_brightness: data_structure::color::new_triplet(
+            data_structure::color::RedUnit(red_brightness),
+            data_structure::color::GreenUnit(green_brightness),
+            data_structure::color::BlueUnit(blue_brightness),
+        ),
+    })
+}
+
+fn read_variables(
+    input_reader: &mut impl std::io::Read,
+) -> Result<data_structure::particle::VariablePart, Box<dyn std::error::Error>> {
+    let horizontal_position = read_f64(input_reader)?;
+    let vertical_position = read_f64(input_reader)?;
+    let horizontal_velocity = read_f64(input_reader)?;
+    let vertical_velocity = read_f64(input_reader)?;
+    Ok(data_structure::particle::VariablePart {
+        position_vector: data_structure::position::DimensionfulVector {
+            horizontal_component: data_structure::position::HorizontalUnit(horizontal_position),
+            vertical_component: data_structure::position::VerticalUnit(vertical_position),
+        },
+        velocity_vector: data_structure::velocity::DimensionfulVector {
+            horizontal_component: data_structure::velocity::HorizontalUnit(horizontal_velocity),
+            vertical_component: data_structure::velocity::VerticalUnit(vertical_velocity),
+        },
+    })
+}
+
+pub fn new<InputReader: std::io::Read + std::io::Seek>(
+    mut input_reader: InputReader,
+) -> Result<TrajectoryReader<InputReader>, Box<dyn std::error::Error>> {
+    let mut file_signature = [0u8; 8];
+    input_reader.read_exact(&mut file_signature)?;
+    if file_signature != super::FILE_SIGNATURE {
+        return Err(Box::new(TrajectoryFileError::new(
+            "The file does not start with the signature of a trajectory file",
+        )));
+    }
+    let mut format_version = [0u8; 4];
+    input_reader.read_exact(&mut format_version)?;
+    let format_version = u32::from_le_bytes(format_version);
+    if format_version != super::FORMAT_VERSION {
+        return Err(Box::new(TrajectoryFileError::new(&format!(
+            "The file has format version {} but only version {} can be read",
+            format_version,
+            super::FORMAT_VERSION
+        ))));
+    }
+    let mut milliseconds_per_frame = [0u8; 2];
+    input_reader.read_exact(&mut milliseconds_per_frame)?;
+    let milliseconds_per_frame = u16::from_le_bytes(milliseconds_per_frame);
+
+    let configuration_length = read_u64(&mut input_reader)?;
+    let mut configuration_bytes = vec![];
+    (&mut input_reader)
+        .take(configuration_length)
+        .read_to_end(&mut configuration_bytes)?;
+    let configuration: serde_json::Value = serde_json::from_slice(&configuration_bytes)?;
+
+    let number_of_particles = read_u64(&mut input_reader)?;
+    let mut particle_intrinsics = vec![];
+    for _ in 0..number_of_particles {
+        particle_intrinsics.push(read_intrinsics(&mut input_reader)?);
+    }
+
+    let first_frame_position = input_reader.stream_position()?;
+    let end_position = input_reader.seek(std::io::SeekFrom::End(0))?;
+    let number_of_frames = ((end_position - first_frame_position)
+        / super::bytes_per_frame(particle_intrinsics.len()))
+    .try_into()?;
+    Ok(TrajectoryReader {
+        input_reader,
+        trajectory_header: TrajectoryHeader {
+            configuration,
+            milliseconds_per_frame,
+            particle_intrinsics,
+        },
+        first_frame_position,
+        number_of_frames,
+    })
+}
+
+pub fn open_file(
+    input_filename: &str,
+) -> Result<TrajectoryReader<std::io::BufReader<std::fs::File>>, Box<dyn std::error::Error>> {
+    new(std::io::BufReader::new(std::fs::File::open(
+        input_filename,
+    )?))
+}
+
+impl<InputReader: std::io::Read + std::io::Seek> TrajectoryReader<InputReader> {
+    pub fn get_header(&self) -> &TrajectoryHeader {
+        &self.trajectory_header
+    }
+
+    pub fn get_number_of_frames(&self) -> usize {
+        self.number_of_frames
+    }
+
+    pub fn read_frame(
+        &mut self,
+        frame_index: usize,
+    ) -> Result<std::vec::Vec<data_structure::particle::BasicIndividual>, Box<dyn std::error::Error>>
+    {
+        if frame_index >= self.number_of_frames {
+            return Err(Box::new(TrajectoryFileError::new(&format!(
+                "Frame {} requested but there are only {} frames",
+                frame_index, self.number_of_frames
+            ))));
+        }
+        let particle_intrinsics = &self.trajectory_header.particle_intrinsics;
+        self.input_reader.seek(std::io::SeekFrom::Start(
+            self.first_frame_position
+                + ((frame_index as u64) * super::bytes_per_frame(particle_intrinsics.len())),
+        ))?;
+        let written_frame_index = read_u64(&mut self.input_reader)?;
+        if written_frame_index != (frame_index as u64) {
+            return Err(Box::new(TrajectoryFileError::new(&format!(
+                "Frame {} requested but frame {} was found in its place",
+                frame_index, written_frame_index
+            ))));
+        }
+        let mut particles_at_frame = std::vec::Vec::with_capacity(particle_intrinsics.len());
+        for intrinsic_values in particle_intrinsics.iter() {
+            particles_at_frame.push(data_structure::particle::BasicIndividual {
+                intrinsic_values: *intrinsic_values,
+                variable_values: read_variables(&mut self.input_reader)?,
+            });
+        }
+        Ok(particles_at_frame)
+    }
+
+    /// The frames are read from the file only as they are requested from the sequence.
+    pub fn into_frame_sequence(self) -> FrameSequence<InputReader> {
+        FrameSequence {
+            trajectory_reader: self,
+            next_frame_index: 0,
+        }
+    }
+}
+
+pub struct FrameSequence<InputReader: std::io::Read + std::io::Seek> {
+    trajectory_reader: TrajectoryReader<InputReader>,
+    next_frame_index: usize,
+}
+
+impl<InputReader: std::io::Read + std::io::Seek> std::iter::Iterator
+    for FrameSequence<InputReader>
+{
+    type Item = Result<
+        std::vec::IntoIter<data_structure::particle::BasicIndividual>,
+        Box<dyn std::error::Error>,
+    >;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.next_frame_index >= self.trajectory_reader.number_of_frames {
+            return None;
+        }
+        let frame_index = self.next_frame_index;
+        self.next_frame_index += 1;
+        Some(
+            self.trajectory_reader
+                .read_frame(frame_index)
+                .map(|particles_at_frame| particles_at_frame.into_iter()),
+        )
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining_frames = self.trajectory_reader.number_of_frames - self.next_frame_index;
+        (remaining_frames, Some(remaining_frames))
+    }
+}
+
+impl<InputReader: std::io::Read + std::io::Seek> std::iter::ExactSizeIterator
+    for FrameSequence<InputReader>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_particle(
+        particle_index: usize,
+        frame_index: usize,
+    ) -> data_structure::particle::BasicIndividual {
+        let particle_number = particle_index as f64;
+        let frame_number = frame_index as f64;
+        data_structure::particle::BasicIndividual {
+            intrinsic_values: data_structure::particle::IntrinsicPart {
+                inertial_mass: data_structure::charge::InertialMassUnit(1.0 + particle_number),
+                inverse_squared_charge: data_structure::charge::InverseSquaredChargeUnit(
+                    -particle_number / 3.0,
+                ),
+                inverse_fourth_charge: data_structure::charge::InverseFourthChargeUnit(0.5),
+                species_index: data_structure::charge::SpeciesIndex(particle_index % 2),
+                collision_radius: data_structure::position::SeparationUnit(0.25 * particle_number),
+                is_tracer: particle_index == 1,
+                color_brightness: data_structure::color::new_triplet(
+                    data_structure::color::RedUnit(particle_number),
+                    data_structure::color::GreenUnit(2.0),
+                    data_structure::color::BlueUnit(0.1),
+                ),
+            },
+            variable_values: data_structure::particle::VariablePart {
+                position_vector: data_structure::position::DimensionfulVector {
+                    horizontal_component: data_structure::position::HorizontalUnit(
+                        particle_number + (frame_number / 7.0),
+                    ),
+                    vertical_component: data_structure::position::VerticalUnit(-frame_number),
+                },
+                velocity_vector: data_structure::velocity::DimensionfulVector {
+                    horizontal_component: data_structure::velocity::HorizontalUnit(
+                        frame_number * 1.0e-10,
+                    ),
+                    vertical_component: data_structure::velocity::VerticalUnit(
+                        particle_number * 1.0e10,
+                    ),
+                },
+            },
+        }
+    }
+
+    fn create_test_frames(
+        number_of_particles: usize,
+        number_of_frames: usize,
+    ) -> std::vec::Vec<std::vec::Vec<data_structure::particle::BasicIndividual>> {
+        (0..number_of_frames)
+            .map(|frame_index| {
+                (0..number_of_particles)
+                    .map(|particle_index| create_test_particle(particle_index, frame_index))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn write_test_frames(
+        test_frames: &[std::vec::Vec<data_structure::particle::BasicIndividual>],
+    ) -> Result<std::vec::Vec<u8>, String> {
+        let trajectory_header = TrajectoryHeader {
+            configuration: serde_json::json!({ "numberOfFrames": test_frames.len() }),
+            milliseconds_per_frame: 40,
+            particle_intrinsics: test_frames[0]
+                .iter()
+                .map(|test_particle| test_particle.intrinsic_values)
+                .collect(),
+        };
+        let mut trajectory_writer = super::super::writer::new(vec![], &trajectory_header)
+            .map_err(|writing_error| format!("Could not write header: {:?}", writing_error))?;
+        for test_frame in test_frames {
+            trajectory_writer
+                .write_frame(test_frame.iter())
+                .map_err(|writing_error| format!("Could not write frame: {:?}", writing_error))?;
+        }
+        trajectory_writer
+            .finish()
+            .map_err(|writing_error| format!("Could not finish: {:?}", writing_error))
+    }
+
+    #[test]
+    fn check_read_any_frame_as_written() -> Result<(), String> {
+        let test_frames = create_test_frames(3, 5);
+        let written_bytes = write_test_frames(&test_frames)?;
+        let mut trajectory_reader = new(std::io::Cursor::new(written_bytes))
+            .map_err(|reading_error| format!("Could not read header: {:?}", reading_error))?;
+
+        let read_header = trajectory_reader.get_header();
+        if (read_header.milliseconds_per_frame != 40)
+            || (read_header.configuration["numberOfFrames"] != 5)
+            || (trajectory_reader.get_number_of_frames() != 5)
+        {
+            return Err(format!(
+                "Expected 40ms, 5 frames in configuration and file, got {:?} with {} frames",
+                trajectory_reader.get_header(),
+                trajectory_reader.get_number_of_frames()
+            ));
+        }
+
+        // The frames are read out of order to check that reading one does not depend on having
+        // read the one before.
+        for frame_index in [3, 0, 4, 1, 2].iter() {
+            let read_frame = trajectory_reader
+                .read_frame(*frame_index)
+                .map_err(|reading_error| format!("Could not read frame: {:?}", reading_error))?;
+            if format!("{:?}", read_frame) != format!("{:?}", test_frames[*frame_index]) {
+                return Err(format!(
+                    "Frame {}: expected {:?}, read {:?}",
+                    frame_index, test_frames[*frame_index], read_frame
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn check_frame_sequence_reads_every_frame() -> Result<(), String> {
+        let test_frames = create_test_frames(2, 4);
+        let written_bytes = write_test_frames(&test_frames)?;
+        let frame_sequence = new(std::io::Cursor::new(written_bytes))
+            .map_err(|reading_error| format!("Could not read header: {:?}", reading_error))?
+            .into_frame_sequence();
+        if frame_sequence.len() != 4 {
+            return Err(format!("Expected 4 frames, got {}", frame_sequence.len()));
+        }
+        let read_frames = frame_sequence
+            .map(|read_frame| {
+                read_frame
+                    .map(|read_particles| read_particles.collect::<std::vec::Vec<_>>())
+                    .map_err(|reading_error| format!("Could not read frame: {:?}", reading_error))
+            })
+            .collect::<Result<std::vec::Vec<_>, String>>()?;
+        if format!("{:?}", read_frames) == format!("{:?}", test_frames) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected {:?}, read {:?}",
+                test_frames, read_frames
+            ))
+        }
+    }
+
+    #[test]
+    fn check_incomplete_final_frame_is_ignored() -> Result<(), String> {
+        let test_frames = create_test_frames(2, 3);
+        let mut written_bytes = write_test_frames(&test_frames)?;
+        written_bytes.truncate(written_bytes.len() - 1);
+        let mut trajectory_reader = new(std::io::Cursor::new(written_bytes))
+            .map_err(|reading_error| format!("Could not read header: {:?}", reading_error))?;
+        if trajectory_reader.get_number_of_frames() != 2 {
+            return Err(format!(
+                "Expected 2 complete frames, got {}",
+                trajectory_reader.get_number_of_frames()
+            ));
+        }
+        if trajectory_reader.read_frame(2).is_ok() {
+            return Err(String::from(
+                "Did not get an error for the incomplete frame",
+            ));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn check_reject_wrong_signature() -> Result<(), String> {
+        let mut written_bytes = write_test_frames(&create_test_frames(1, 1))?;
+        written_bytes[0] = b'X';
+        if new(std::io::Cursor::new(written_bytes)).is_err() {
+            Ok(())
+        } else {
+            Err(String::from("Did not get an error"))
+        }
+    }
+
+    #[test]
+    fn check_reject_other_format_version() -> Result<(), String> {
+        let mut written_bytes = write_test_frames(&create_test_frames(1, 1))?;
+        let version_start = super::super::FILE_SIGNATURE.len();
+        written_bytes[version_start..(version_start + 4)]
+            .copy_from_slice(&(super::super::FORMAT_VERSION - 1).to_le_bytes());
+        if new(std::io::Cursor::new(written_bytes)).is_err() {
+            Ok(())
+        } else {
+            Err(String::from("Did not get an error"))
+        }
+    }
+
+    #[test]
+    fn check_reject_frame_with_wrong_number_of_particles() -> Result<(), String> {
+        let test_frames = create_test_frames(2, 1);
+        let trajectory_header = TrajectoryHeader {
+            configuration: serde_json::json!({}),
+            milliseconds_per_frame: 40,
+            particle_intrinsics: test_frames[0]
+                .iter()
+                .map(|test_particle| test_particle.intrinsic_values)
+                .collect(),
+        };
+        let mut trajectory_writer = super::super::writer::new(vec![], &trajectory_header)
+            .map_err(|writing_error| format!("Could not write header: {:?}", writing_error))?;
+        if trajectory_writer
+            .write_frame(test_frames[0][..1].iter())
+            .is_err()
+        {
+            Ok(())
+        } else {
+            Err(String::from("Did not get an error"))
+        }
+    }
+}
@@ -0,0 +1,167 @@
+/// This module provides a writer which writes the header of a trajectory file and then each frame
+/// as it is given.
+use super::TrajectoryFileError;
+use super::TrajectoryHeader;
+
+pub struct TrajectoryWriter<OutputWriter: std::io::Write> {
+    output_writer: OutputWriter,
+    number_of_particles: usize,
+    number_of_written_frames: u64,
+}
+
+fn write_f64(
+    output_writer: &mut impl std::io::Write,
+    written_number: f64,
+) -> Result<(), Box<dyn std::error::Error>> {
+    output_writer.write_all(&written_number.to_le_bytes())?;
+    Ok(())
+}
+
+pub fn new<OutputWriter: std::io::Write>(
+    mut output_writer: OutputWriter,
+    trajectory_header: &TrajectoryHeader,
+) -> Result<TrajectoryWriter<OutputWriter>, Box<dyn std::error::Error>> {
+    let configuration_bytes = serde_json::to_vec(&trajectory_header.configuration)?;
+    output_writer.write_all(&super::FILE_SIGNATURE)?;
+    output_writer.write_all(&super::FORMAT_VERSION.to_le_bytes())?;
+    output_writer.write_all(&trajectory_header.milliseconds_per_frame.to_le_bytes())?;
+    output_writer.write_all(&(configuration_bytes.len() as u64).to_le_bytes())?;
+    output_writer.write_all(&configuration_bytes)?;
+    output_writer.write_all(&(trajectory_header.particle_intrinsics.len() as u64).to_le_bytes())?;
+    for particle_intrinsics in trajectory_header.particle_intrinsics.iter() {
+        write_f64(&mut output_writer, particle_intrinsics.inertial_mass.0)?;
+        write_f64(
+            &mut output_writer,
+            particle_intrinsics.inverse_squared_charge.0,
+        )?;
+        write_f64(
+            &mut output_writer,
+            particle_intrinsics.inverse_fourth_charge.0,
+        )?;
+        output_writer.write_all(&(particle_intrinsics.species_index.0 as u64).to_le_bytes())?;
+        write_f64(&mut output_writer, particle_intrinsics.collision_radius.0)?;
+        output_writer.write_all(&[particle_intrinsics.is_tracer as u8])?;
+        write_f64(
+            &mut output_writer,
+            particle_intrinsics.color_brightness.get_red().0,
+        )?;
+        write_f64(
+            &mut output_writer,
+            particle_intrinsics.color_brightness.get_green().0,
+        )?;
+        write_f64(
+            &mut output_writer,
+            particle_intrinsics.color_brightness.get_blue().0,
+        )?;
+    }
+    Ok(TrajectoryWriter {
+        output_writer,
+        number_of_particles: trajectory_header.particle_intrinsics.len(),
+        number_of_written_frames: 0,
+    })
+}
+
+impl<OutputWriter: std::io::Write> TrajectoryWriter<OutputWriter> {
+    /// Only the variables of the particles are written, as their intrinsic values are in the
+    /// header, so the particles must be in the same order in every frame.
+    pub fn write_frame(
+        &mut self,
+        particles_at_frame: impl std::iter::ExactSizeIterator<
+            Item = impl data_structure::particle::IndividualRepresentation,
+        >,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if particles_at_frame.len() != self.number_of_particles {
+            return Err(Box::new(TrajectoryFileError::new(&format!(
+                "Frame {} has {} particles but the header has {}",
+                self.number_of_written_frames,
+                particles_at_frame.len(),
+                self.number_of_particles
+            ))));
+        }
+        self.output_writer
+            .write_all(&self.number_of_written_frames.to_le_bytes())?;
+        for particle_at_frame in particles_at_frame {
+            let particle_variables = particle_at_frame.read_variables();
+            write_f64(
+                &mut self.output_writer,
+                particle_variables.position_vector.horizontal_component.0,
+            )?;
+            write_f64(
+                &mut self.output_writer,
+                particle_variables.position_vector.vertical_component.0,
+            )?;
+            write_f64(
+                &mut self.output_writer,
+                particle_variables.velocity_vector.horizontal_component.0,
+            )?;
+            write_f64(
+                &mut self.output_writer,
+                particle_variables.velocity_vector.vertical_component.0,
+            )?;
+        }
+        self.number_of_written_frames += 1;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<OutputWriter, Box<dyn std::error::Error>> {
+        self.output_writer.flush()?;
+        Ok(self.output_writer)
+    }
+}
+
+/// This takes the intrinsic values of the particles for the header from the first frame, then
+/// writes each frame as it is taken from the sequence. If a frame fails, the frames before it are
+/// still written to the file before the error is returned.
+pub fn write_sequence_to_file(
+    mut particle_map_sequence: impl std::iter::Iterator<
+        Item = Result<
+            impl std::iter::ExactSizeIterator<
+                Item = impl data_structure::particle::IndividualRepresentation,
+            >,
+            Box<dyn std::error::Error>,
+        >,
+    >,
+    configuration: &serde_json::Value,
+    milliseconds_per_frame: u16,
+    output_filename: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let first_particle_map: std::vec::Vec<data_structure::particle::BasicIndividual> =
+        match particle_map_sequence.next() {
+            Some(first_particle_map) => first_particle_map?
+                .map(|particle_representation| {
+                    data_structure::particle::create_individual_from_representation(
+                        &particle_representation,
+                    )
+                })
+                .collect(),
+            None => {
+                return Err(Box::new(TrajectoryFileError::new(
+                    "Cannot write a trajectory without any frames",
+                )))
+            }
+        };
+    let trajectory_header = TrajectoryHeader {
+        configuration: configuration.clone(),
+        milliseconds_per_frame,
+        particle_intrinsics: first_particle_map
+            .iter()
+            .map(|first_particle| first_particle.intrinsic_values)
+            .collect(),
+    };
+
+    let mut trajectory_writer = new(
+        std::io::BufWriter::new(std::fs::File::create(output_filename)?),
+        &trajectory_header,
+    )?;
+    trajectory_writer.write_frame(first_particle_map.iter())?;
+    for particle_map in particle_map_sequence {
+        let frame_result = particle_map
+            .and_then(|particles_at_frame| trajectory_writer.write_frame(particles_at_frame));
+        if let Err(frame_error) = frame_result {
+            trajectory_writer.finish()?;
+            return Err(frame_error);
+        }
+    }
+    trajectory_writer.finish()?;
+    Ok(())
+}
@@ -39,8 +39,11 @@ impl<T: ParticleToPixelMapper> SequenceAnimator for ApngAnimator<T> {
     fn animate_sequence(
         &self,
         particle_map_sequence: impl std::iter::ExactSizeIterator<
-            Item = impl std::iter::ExactSizeIterator<
-                Item = impl data_structure::particle::IndividualRepresentation,
+            Item = Result<
+                impl std::iter::ExactSizeIterator<
+                    Item = impl data_structure::particle::IndividualRepresentation,
+                >,
+                Box<dyn std::error::Error>,
             >,
         >,
         milliseconds_per_frame: u16,
@@ -76,13 +79,18 @@ impl<T: ParticleToPixelMapper> SequenceAnimator for ApngAnimator<T> {
         let mut output_encoder =
             apng_encoder::Encoder::create(&mut output_file, meta_information).unwrap();
 
-        let matrix_sequence = self
-            .particle_to_pixel_mapper
-            .aggregate_particle_colors_to_pixels(particle_map_sequence)?;
-
-        for pixel_matrix in matrix_sequence.colored_pixel_matrices {
-            let flattened_color_bytes =
-                &flattened_color_bytes_from(pixel_matrix, &matrix_sequence.maximum_brightness)?;
+        // Each frame is written as soon as it is mapped to pixels, so that only one frame of
+        // particles and pixels is held in memory at a time. Each frame is normalized to its own
+        // brightest pixel, which does not change the bytes because the fractions are multiplied
+        // back by the same brightness.
+        for (frame_index, particle_map) in particle_map_sequence.enumerate() {
+            let pixel_frame = self
+                .particle_to_pixel_mapper
+                .aggregate_particle_colors_to_pixels(frame_index, particle_map?)?;
+            let flattened_color_bytes = &flattened_color_bytes_from(
+                pixel_frame.colored_pixel_matrix,
+                &pixel_frame.maximum_brightness,
+            )?;
             output_encoder
                 .write_frame(
                     flattened_color_bytes,
@@ -3,7 +3,7 @@
 /// rounding particle co-ordinates to pixel co-ordinates, aggregating the color brightnesses
 /// which land in each pixel.
 use super::color::FractionTriplet as ColorFraction;
-use super::particles_to_pixels::ColoredPixelMatrixSequence as PixelMatrixSequence;
+use super::particles_to_pixels::ColoredPixelMatrixFrame as PixelMatrixFrame;
 use super::HorizontalPixelAmount;
 use super::OutOfBoundsError;
 use super::VerticalPixelAmount;
@@ -141,28 +141,15 @@ impl super::particles_to_pixels::ParticleToPixelMapper for PixelBrightnessAggreg
     type Output = AggregatedBrightnessMatrix;
     fn aggregate_particle_colors_to_pixels(
         &self,
-        particle_map_sequence: impl std::iter::ExactSizeIterator<
-            Item = impl std::iter::ExactSizeIterator<Item = impl ParticleRepresentation>,
-        >,
-    ) -> Result<PixelMatrixSequence<Self::Output>, Box<dyn std::error::Error>> {
-        let mut aggregated_brightnesses: PixelMatrixSequence<AggregatedBrightnessMatrix> =
-            PixelMatrixSequence {
-                colored_pixel_matrices: vec![],
-                maximum_brightness: AbsoluteColorUnit(0.0),
-            };
-
-        for particle_map in particle_map_sequence {
-            let (aggregated_brightnesses_in_map, maximum_brightness_in_map) =
-                self.aggregate_over_particle_iterator(particle_map);
-            aggregated_brightnesses
-                .colored_pixel_matrices
-                .push(aggregated_brightnesses_in_map);
-            aggregated_brightnesses
-                .maximum_brightness
-                .update_to_other_if_brighter(&maximum_brightness_in_map);
-        }
-
-        Ok(aggregated_brightnesses)
+        _frame_index: usize,
+        particle_map: impl std::iter::ExactSizeIterator<Item = impl ParticleRepresentation>,
+    ) -> Result<PixelMatrixFrame<Self::Output>, Box<dyn std::error::Error>> {
+        let (aggregated_brightnesses, maximum_brightness_in_map) =
+            self.aggregate_over_particle_iterator(particle_map);
+        Ok(PixelMatrixFrame {
+            colored_pixel_matrix: aggregated_brightnesses,
+            maximum_brightness: maximum_brightness_in_map,
+        })
     }
 
     fn width_in_pixels(&self) -> &HorizontalPixelAmount {
@@ -369,6 +356,9 @@ mod tests {
             inertial_mass: data_structure::charge::InertialMassUnit(1.2),
             inverse_squared_charge: data_structure::charge::InverseSquaredChargeUnit(-3.4),
             inverse_fourth_charge: data_structure::charge::InverseFourthChargeUnit(5.6),
+            species_index: data_structure::charge::SpeciesIndex(0),
+            collision_radius: data_structure::position::SeparationUnit(0.0),
+            is_tracer: false,
             color_brightness: *color_fraction * &new_reference_brightness(),
         }
     }
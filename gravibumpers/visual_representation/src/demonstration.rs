@@ -236,23 +236,17 @@ impl super::particles_to_pixels::ParticleToPixelMapper for DemonstrationMapper {
     type Output = DemonstrationPixelMatrix;
     fn aggregate_particle_colors_to_pixels(
         &self,
-        particle_map_sequence: impl std::iter::ExactSizeIterator<
-            Item = impl std::iter::ExactSizeIterator<
-                Item = impl data_structure::particle::IndividualRepresentation,
-            >,
+        frame_index: usize,
+        _particle_map: impl std::iter::ExactSizeIterator<
+            Item = impl data_structure::particle::IndividualRepresentation,
         >,
     ) -> Result<
-        super::particles_to_pixels::ColoredPixelMatrixSequence<Self::Output>,
+        super::particles_to_pixels::ColoredPixelMatrixFrame<Self::Output>,
         Box<dyn std::error::Error>,
     > {
-        let mut matrix_sequence: Vec<DemonstrationPixelMatrix> = Vec::new();
-        for (time_index, _) in particle_map_sequence.enumerate() {
-            matrix_sequence.push(new_pixel_matrix(4 * time_index as i32))
-        }
-
         Ok(
-            super::particles_to_pixels::ColoredPixelMatrixSequence::<DemonstrationPixelMatrix> {
-                colored_pixel_matrices: matrix_sequence,
+            super::particles_to_pixels::ColoredPixelMatrixFrame::<DemonstrationPixelMatrix> {
+                colored_pixel_matrix: new_pixel_matrix(4 * frame_index as i32),
                 maximum_brightness: data_structure::color::AbsoluteUnit(1.0),
             },
         )
@@ -1,11 +1,12 @@
 /// This crate provides structs, traits, and functions for turning sequences of particle
-/// collections into an animated visual representation.
+/// collections into an animated visual representation, or into text for analysis elsewhere.
 extern crate data_structure;
 pub mod apng;
 pub mod brightness_aggregator;
 pub mod color;
 pub mod demonstration;
 pub mod particles_to_pixels;
+pub mod text_trajectory;
 use std::error::Error;
 
 #[derive(Debug)]
@@ -33,12 +34,17 @@ impl std::fmt::Display for OutOfBoundsError {
     }
 }
 
+/// The particle maps may be calculated only as they are requested from the sequence, so each is
+/// given as a result, and the animation stops with the error of the first which failed.
 pub trait SequenceAnimator {
     fn animate_sequence(
         &self,
         particle_map_sequence: impl std::iter::ExactSizeIterator<
-            Item = impl std::iter::ExactSizeIterator<
-                Item = impl data_structure::particle::IndividualRepresentation,
+            Item = Result<
+                impl std::iter::ExactSizeIterator<
+                    Item = impl data_structure::particle::IndividualRepresentation,
+                >,
+                Box<dyn std::error::Error>,
             >,
         >,
         milliseconds_per_frame: u16,
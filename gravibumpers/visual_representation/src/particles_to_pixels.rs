@@ -4,21 +4,22 @@ use super::ColoredPixelMatrix;
 use super::HorizontalPixelAmount;
 use super::VerticalPixelAmount;
 
-pub struct ColoredPixelMatrixSequence<T: ColoredPixelMatrix> {
-    pub colored_pixel_matrices: Vec<T>,
+pub struct ColoredPixelMatrixFrame<T: ColoredPixelMatrix> {
+    pub colored_pixel_matrix: T,
     pub maximum_brightness: data_structure::color::AbsoluteUnit,
 }
 
+/// Each frame is mapped on its own so that a sequence of frames can be processed one frame at a
+/// time, with the index of the frame in the sequence given for mappers which depend on it.
 pub trait ParticleToPixelMapper {
     type Output: ColoredPixelMatrix;
     fn aggregate_particle_colors_to_pixels(
         &self,
-        particle_map_sequence: impl std::iter::ExactSizeIterator<
-            Item = impl std::iter::ExactSizeIterator<
-                Item = impl data_structure::particle::IndividualRepresentation,
-            >,
+        frame_index: usize,
+        particle_map: impl std::iter::ExactSizeIterator<
+            Item = impl data_structure::particle::IndividualRepresentation,
         >,
-    ) -> Result<ColoredPixelMatrixSequence<Self::Output>, Box<dyn std::error::Error>>;
+    ) -> Result<ColoredPixelMatrixFrame<Self::Output>, Box<dyn std::error::Error>>;
 
     fn width_in_pixels(&self) -> &HorizontalPixelAmount;
     fn height_in_pixels(&self) -> &VerticalPixelAmount;
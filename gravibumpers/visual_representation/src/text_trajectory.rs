@@ -0,0 +1,342 @@
+/// This module provides an implementation of SequenceAnimator which, rather than drawing the
+/// particles, writes out their numbers as text, with a row for each particle in each frame, so
+/// that the evolution can be analysed by other tools.
+extern crate data_structure;
+extern crate serde_json;
+
+use super::SequenceAnimator;
+
+const FRAME_INDEX_LABEL: &str = "frameIndex";
+const TIME_IN_SECONDS_LABEL: &str = "timeInSeconds";
+const PARTICLE_INDEX_LABEL: &str = "particleIndex";
+const HORIZONTAL_POSITION_LABEL: &str = "horizontalPosition";
+const VERTICAL_POSITION_LABEL: &str = "verticalPosition";
+const HORIZONTAL_VELOCITY_LABEL: &str = "horizontalVelocity";
+const VERTICAL_VELOCITY_LABEL: &str = "verticalVelocity";
+const INERTIAL_MASS_LABEL: &str = "inertialMass";
+const INVERSE_SQUARED_CHARGE_LABEL: &str = "inverseSquaredCharge";
+const INVERSE_FOURTH_CHARGE_LABEL: &str = "inverseFourthCharge";
+const RED_BRIGHTNESS_LABEL: &str = "redBrightness";
+const GREEN_BRIGHTNESS_LABEL: &str = "greenBrightness";
+const BLUE_BRIGHTNESS_LABEL: &str = "blueBrightness";
+
+const SECONDS_PER_MILLISECOND: f64 = 0.001;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TrajectoryFormat {
+    CommaSeparatedValues,
+    JsonLines,
+}
+
+pub fn parse_format(format_name: &str) -> Result<TrajectoryFormat, Box<dyn std::error::Error>> {
+    match format_name {
+        "csv" => Ok(TrajectoryFormat::CommaSeparatedValues),
+        "jsonl" => Ok(TrajectoryFormat::JsonLines),
+        _ => Err(Box::new(super::OutOfBoundsError::new(&format!(
+            "Trajectory format \"{}\" is unknown (expected \"csv\" or \"jsonl\")",
+            format_name
+        )))),
+    }
+}
+
+pub struct TextTrajectoryWriter {
+    trajectory_format: TrajectoryFormat,
+}
+
+pub fn new(trajectory_format: TrajectoryFormat) -> TextTrajectoryWriter {
+    TextTrajectoryWriter { trajectory_format }
+}
+
+/// The numbers in the CSV format are written with the debug formatting of f64, which is the
+/// shortest text which reads back as exactly the same number, and which uses an exponent for very
+/// large or very small numbers rather than writing out hundreds of zeroes.
+fn write_comma_separated_row(
+    output_writer: &mut impl std::io::Write,
+    frame_index: usize,
+    time_in_seconds: f64,
+    particle_index: usize,
+    particle_representation: &impl data_structure::particle::IndividualRepresentation,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let particle_intrinsics = particle_representation.read_intrinsics();
+    let particle_variables = particle_representation.read_variables();
+    writeln!(
+        output_writer,
+        "{},{:?},{},{:?},{:?},{:?},{:?},{:?},{:?},{:?},{:?},{:?},{:?}",
+        frame_index,
+        time_in_seconds,
+        particle_index,
+        particle_variables.position_vector.horizontal_component.0,
+        particle_variables.position_vector.vertical_component.0,
+        particle_variables.velocity_vector.horizontal_component.0,
+        particle_variables.velocity_vector.vertical_component.0,
+        particle_intrinsics.inertial_mass.0,
+        particle_intrinsics.inverse_squared_charge.0,
+        particle_intrinsics.inverse_fourth_charge.0,
+        particle_intrinsics.color_brightness.get_red().0,
+        particle_intrinsics.color_brightness.get_green().0,
+        particle_intrinsics.color_brightness.get_blue().0
+    )?;
+    Ok(())
+}
+
+fn write_json_line(
+    output_writer: &mut impl std::io::Write,
+    frame_index: usize,
+    time_in_seconds: f64,
+    particle_index: usize,
+    particle_representation: &impl data_structure::particle::IndividualRepresentation,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let particle_intrinsics = particle_representation.read_intrinsics();
+    let particle_variables = particle_representation.read_variables();
+    let particle_line = serde_json::json!(
+        {
+            FRAME_INDEX_LABEL: frame_index,
+            TIME_IN_SECONDS_LABEL: time_in_seconds,
+            PARTICLE_INDEX_LABEL: particle_index,
+            HORIZONTAL_POSITION_LABEL: particle_variables.position_vector.horizontal_component.0,
+            VERTICAL_POSITION_LABEL: particle_variables.position_vector.vertical_component.0,
+            HORIZONTAL_VELOCITY_LABEL: particle_variables.velocity_vector.horizontal_component.0,
+            VERTICAL_VELOCITY_LABEL: particle_variables.velocity_vector.vertical_component.0,
+            INERTIAL_MASS_LABEL: particle_intrinsics.inertial_mass.0,
+            INVERSE_SQUARED_CHARGE_LABEL: particle_intrinsics.inverse_squared_charge.0,
+            INVERSE_FOURTH_CHARGE_LABEL: particle_intrinsics.inverse_fourth_charge.0,
+            RED_BRIGHTNESS_LABEL: particle_intrinsics.color_brightness.get_red().0,
+            GREEN_BRIGHTNESS_LABEL: particle_intrinsics.color_brightness.get_green().0,
+            BLUE_BRIGHTNESS_LABEL: particle_intrinsics.color_brightness.get_blue().0
+        }
+    );
+    serde_json::to_writer(&mut *output_writer, &particle_line)?;
+    writeln!(output_writer)?;
+    Ok(())
+}
+
+/// The rows are written as each frame is taken from the sequence, so the rows of every frame
+/// before a failed frame are still written before the error is returned.
+pub fn write_trajectory(
+    particle_map_sequence: impl std::iter::Iterator<
+        Item = Result<
+            impl std::iter::Iterator<Item = impl data_structure::particle::IndividualRepresentation>,
+            Box<dyn std::error::Error>,
+        >,
+    >,
+    milliseconds_per_frame: u16,
+    trajectory_format: TrajectoryFormat,
+    output_writer: &mut impl std::io::Write,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if trajectory_format == TrajectoryFormat::CommaSeparatedValues {
+        writeln!(
+            output_writer,
+            "{}",
+            [
+                FRAME_INDEX_LABEL,
+                TIME_IN_SECONDS_LABEL,
+                PARTICLE_INDEX_LABEL,
+                HORIZONTAL_POSITION_LABEL,
+                VERTICAL_POSITION_LABEL,
+                HORIZONTAL_VELOCITY_LABEL,
+                VERTICAL_VELOCITY_LABEL,
+                INERTIAL_MASS_LABEL,
+                INVERSE_SQUARED_CHARGE_LABEL,
+                INVERSE_FOURTH_CHARGE_LABEL,
+                RED_BRIGHTNESS_LABEL,
+                GREEN_BRIGHTNESS_LABEL,
+                BLUE_BRIGHTNESS_LABEL,
+            ]
+            .join(",")
+        )?;
+    }
+
+    for (frame_index, particle_map) in particle_map_sequence.enumerate() {
+        let time_in_seconds =
+            (frame_index as f64) * (milliseconds_per_frame as f64) * SECONDS_PER_MILLISECOND;
+        for (particle_index, particle_representation) in particle_map?.enumerate() {
+            match trajectory_format {
+                TrajectoryFormat::CommaSeparatedValues => write_comma_separated_row(
+                    output_writer,
+                    frame_index,
+                    time_in_seconds,
+                    particle_index,
+                    &particle_representation,
+                )?,
+                TrajectoryFormat::JsonLines => write_json_line(
+                    output_writer,
+                    frame_index,
+                    time_in_seconds,
+                    particle_index,
+                    &particle_representation,
+                )?,
+            }
+        }
+    }
+    output_writer.flush()?;
+    Ok(())
+}
+
+impl SequenceAnimator for TextTrajectoryWriter {
+    fn animate_sequence(
+        &self,
+        particle_map_sequence: impl std::iter::ExactSizeIterator<
+            Item = Result<
+                impl std::iter::ExactSizeIterator<
+                    Item = impl data_structure::particle::IndividualRepresentation,
+                >,
+                Box<dyn std::error::Error>,
+            >,
+        >,
+        milliseconds_per_frame: u16,
+        output_filename: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut output_writer = std::io::BufWriter::new(std::fs::File::create(output_filename)?);
+        write_trajectory(
+            particle_map_sequence,
+            milliseconds_per_frame,
+            self.trajectory_format,
+            &mut output_writer,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_particle(
+        horizontal_position: f64,
+        vertical_velocity: f64,
+    ) -> data_structure::particle::BasicIndividual {
+        data_structure::particle::BasicIndividual {
+            intrinsic_values: data_structure::particle::IntrinsicPart {
+                inertial_mass: data_structure::charge::InertialMassUnit(2.0),
+                inverse_squared_charge: data_structure::charge::InverseSquaredChargeUnit(-1.5),
+                inverse_fourth_charge: data_structure::charge::InverseFourthChargeUnit(0.25),
+                species_index: data_structure::charge::SpeciesIndex(0),
+                collision_radius: data_structure::position::SeparationUnit(0.0),
+                is_tracer: false,
+                color_brightness: data_structure::color::new_triplet(
+                    data_structure::color::RedUnit(1.0),
+                    data_structure::color::GreenUnit(0.0),
+                    data_structure::color::BlueUnit(100.0),
+                ),
+            },
+            variable_values: data_structure::particle::VariablePart {
+                position_vector: data_structure::position::DimensionfulVector {
+                    horizontal_component: data_structure::position::HorizontalUnit(
+                        horizontal_position,
+                    ),
+                    vertical_component: data_structure::position::VerticalUnit(-3.0),
+                },
+                velocity_vector: data_structure::velocity::DimensionfulVector {
+                    horizontal_component: data_structure::velocity::HorizontalUnit(0.0),
+                    vertical_component: data_structure::velocity::VerticalUnit(vertical_velocity),
+                },
+            },
+        }
+    }
+
+    fn create_test_sequence(
+    ) -> std::vec::Vec<std::vec::Vec<data_structure::particle::BasicIndividual>> {
+        vec![
+            vec![
+                create_test_particle(1.0, 0.5),
+                create_test_particle(-1.0, 1.0e-20),
+            ],
+            vec![
+                create_test_particle(1.5, 0.5),
+                create_test_particle(0.1, -2.0),
+            ],
+        ]
+    }
+
+    fn write_test_sequence(trajectory_format: TrajectoryFormat) -> Result<String, String> {
+        let test_sequence = create_test_sequence();
+        let mut written_bytes: std::vec::Vec<u8> = vec![];
+        write_trajectory(
+            test_sequence
+                .iter()
+                .map(|particle_map| Ok::<_, Box<dyn std::error::Error>>(particle_map.iter())),
+            250,
+            trajectory_format,
+            &mut written_bytes,
+        )
+        .map_err(|writing_error| format!("Could not write: {:?}", writing_error))?;
+        String::from_utf8(written_bytes)
+            .map_err(|conversion_error| format!("Not UTF-8: {:?}", conversion_error))
+    }
+
+    #[test]
+    fn check_comma_separated_values() -> Result<(), String> {
+        let expected_text = "frameIndex,timeInSeconds,particleIndex,horizontalPosition,\
+            verticalPosition,horizontalVelocity,verticalVelocity,inertialMass,\
+            inverseSquaredCharge,inverseFourthCharge,redBrightness,greenBrightness,blueBrightness\n\
+            0,0.0,0,1.0,-3.0,0.0,0.5,2.0,-1.5,0.25,1.0,0.0,100.0\n\
+            0,0.0,1,-1.0,-3.0,0.0,1e-20,2.0,-1.5,0.25,1.0,0.0,100.0\n\
+            1,0.25,0,1.5,-3.0,0.0,0.5,2.0,-1.5,0.25,1.0,0.0,100.0\n\
+            1,0.25,1,0.1,-3.0,0.0,-2.0,2.0,-1.5,0.25,1.0,0.0,100.0\n";
+        let written_text = write_test_sequence(TrajectoryFormat::CommaSeparatedValues)?;
+        if written_text == expected_text {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected\n{}\nwrote\n{}",
+                expected_text, written_text
+            ))
+        }
+    }
+
+    #[test]
+    fn check_json_lines() -> Result<(), String> {
+        let written_text = write_test_sequence(TrajectoryFormat::JsonLines)?;
+        let written_lines: std::vec::Vec<serde_json::Value> = written_text
+            .lines()
+            .map(|written_line| {
+                serde_json::from_str(written_line)
+                    .map_err(|parsing_error| format!("Could not parse: {:?}", parsing_error))
+            })
+            .collect::<Result<_, String>>()?;
+        let test_sequence = create_test_sequence();
+        let expected_rows: std::vec::Vec<(usize, f64, usize, f64, f64)> = vec![
+            (0, 0.0, 0, 1.0, 0.5),
+            (0, 0.0, 1, -1.0, 1.0e-20),
+            (1, 0.25, 0, 1.5, 0.5),
+            (1, 0.25, 1, 0.1, -2.0),
+        ];
+        let actual_rows: std::vec::Vec<(usize, f64, usize, f64, f64)> = written_lines
+            .iter()
+            .map(|written_line| {
+                (
+                    written_line[FRAME_INDEX_LABEL].as_u64().unwrap_or(99) as usize,
+                    written_line[TIME_IN_SECONDS_LABEL].as_f64().unwrap_or(-1.0),
+                    written_line[PARTICLE_INDEX_LABEL].as_u64().unwrap_or(99) as usize,
+                    written_line[HORIZONTAL_POSITION_LABEL]
+                        .as_f64()
+                        .unwrap_or(-1.0),
+                    written_line[VERTICAL_VELOCITY_LABEL]
+                        .as_f64()
+                        .unwrap_or(-1.0),
+                )
+            })
+            .collect();
+        if (actual_rows == expected_rows)
+            && (written_lines.len() == test_sequence.len() * test_sequence[0].len())
+            && written_lines
+                .iter()
+                .all(|written_line| written_line[BLUE_BRIGHTNESS_LABEL] == 100.0)
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "Expected rows {:?}, wrote\n{}",
+                expected_rows, written_text
+            ))
+        }
+    }
+
+    #[test]
+    fn check_reject_unknown_format() -> Result<(), String> {
+        if parse_format("xml").is_err() {
+            Ok(())
+        } else {
+            Err(String::from("Did not get an error"))
+        }
+    }
+}